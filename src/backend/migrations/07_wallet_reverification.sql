@@ -0,0 +1,14 @@
+-- Policies that require linked wallets to periodically re-prove control
+CREATE TABLE IF NOT EXISTS wallet_reverification_policies (
+    id TEXT PRIMARY KEY,
+    tribe TEXT, -- NULL applies the policy to every tribe (and to unaffiliated wallets)
+    admin_only BOOLEAN NOT NULL DEFAULT FALSE,
+    interval_days INTEGER NOT NULL,
+    enforce BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Set when a wallet misses its re-verification deadline, cleared on re-sign
+ALTER TABLE wallets ADD COLUMN stale_at DATETIME;
+
+CREATE INDEX IF NOT EXISTS idx_wallets_stale_at ON wallets(stale_at);
@@ -0,0 +1,3 @@
+-- Cached on-chain character (rider) name for each wallet
+ALTER TABLE wallets ADD COLUMN character_name TEXT;
+ALTER TABLE wallets ADD COLUMN character_name_refreshed_at DATETIME;
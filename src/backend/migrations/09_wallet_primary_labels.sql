@@ -0,0 +1,26 @@
+-- User-controlled wallet presentation: one primary wallet per user per network,
+-- an optional label and an explicit display order
+ALTER TABLE wallets ADD COLUMN is_primary BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE wallets ADD COLUMN label TEXT;
+ALTER TABLE wallets ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0;
+
+-- Existing wallets keep their link order
+UPDATE wallets SET sort_order = (
+    SELECT COUNT(*) FROM wallets w2
+    WHERE w2.user_id = wallets.user_id
+      AND (w2.verified_at < wallets.verified_at OR (w2.verified_at = wallets.verified_at AND w2.id < wallets.id))
+);
+
+-- The earliest active wallet on each network becomes primary
+UPDATE wallets SET is_primary = TRUE
+WHERE deleted_at IS NULL
+  AND NOT EXISTS (
+    SELECT 1 FROM wallets w2
+    WHERE w2.user_id = wallets.user_id
+      AND w2.network = wallets.network
+      AND w2.deleted_at IS NULL
+      AND w2.sort_order < wallets.sort_order
+  );
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_wallets_primary_per_network
+    ON wallets(user_id, network) WHERE is_primary AND deleted_at IS NULL;
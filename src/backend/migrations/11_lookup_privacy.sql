@@ -0,0 +1,5 @@
+-- Who can see a user through the key-authenticated identity lookup:
+--   public: any key with verify:read sees verification state and all tribes
+--   tribes: tribe memberships only shown to keys scoped to that tribe
+--   hidden: only keys scoped to one of the user's tribes can find them at all
+ALTER TABLE users ADD COLUMN lookup_visibility TEXT NOT NULL DEFAULT 'tribes';
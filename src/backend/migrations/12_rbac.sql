@@ -0,0 +1,96 @@
+-- Roles and permissions
+CREATE TABLE IF NOT EXISTS roles (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    is_builtin BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE IF NOT EXISTS role_permissions (
+    role_id TEXT NOT NULL,
+    permission TEXT NOT NULL,
+    PRIMARY KEY(role_id, permission),
+    FOREIGN KEY(role_id) REFERENCES roles(id) ON DELETE CASCADE
+);
+
+-- Role assignments; a NULL tribe applies the role in every tribe the user belongs to
+CREATE TABLE IF NOT EXISTS user_roles (
+    id TEXT PRIMARY KEY,
+    user_id INTEGER NOT NULL,
+    role_id TEXT NOT NULL,
+    tribe TEXT,
+    granted_by INTEGER,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY(role_id) REFERENCES roles(id) ON DELETE CASCADE,
+    FOREIGN KEY(granted_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_user_roles_assignment ON user_roles(user_id, role_id, COALESCE(tribe, ''));
+CREATE INDEX IF NOT EXISTS idx_user_roles_tribe ON user_roles(tribe);
+
+-- Built-in roles
+INSERT OR IGNORE INTO roles (id, name, description, is_builtin) VALUES
+    ('admin', 'Admin', 'Full tribe administration', TRUE),
+    ('recruiter', 'Recruiter', 'Read the roster and manage notes', TRUE),
+    ('viewer', 'Viewer', 'Read the roster', TRUE);
+
+INSERT OR IGNORE INTO role_permissions (role_id, permission) VALUES
+    ('admin', 'roster.read'),
+    ('admin', 'audit.read'),
+    ('admin', 'notes.read'),
+    ('admin', 'notes.write'),
+    ('admin', 'admin.grant'),
+    ('recruiter', 'roster.read'),
+    ('recruiter', 'notes.read'),
+    ('recruiter', 'notes.write'),
+    ('viewer', 'roster.read');
+
+-- Map the existing admin flags onto the built-in admin role
+INSERT OR IGNORE INTO user_roles (id, user_id, role_id, tribe)
+SELECT lower(hex(randomblob(16))), id, 'admin', NULL FROM users WHERE is_admin = TRUE;
+
+INSERT OR IGNORE INTO user_roles (id, user_id, role_id, tribe)
+SELECT lower(hex(randomblob(16))), user_id, 'admin', tribe FROM user_tribes WHERE is_admin = TRUE;
+
+-- Keep the admin role in step with the legacy flags, which are still written by
+-- login bootstrap, grant-admin and the super admin user editor
+CREATE TRIGGER IF NOT EXISTS trg_users_admin_role_insert
+AFTER INSERT ON users WHEN NEW.is_admin = TRUE
+BEGIN
+    INSERT OR IGNORE INTO user_roles (id, user_id, role_id, tribe)
+    VALUES (lower(hex(randomblob(16))), NEW.id, 'admin', NULL);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_users_admin_role_update
+AFTER UPDATE OF is_admin ON users
+BEGIN
+    INSERT OR IGNORE INTO user_roles (id, user_id, role_id, tribe)
+    SELECT lower(hex(randomblob(16))), NEW.id, 'admin', NULL WHERE NEW.is_admin = TRUE;
+    DELETE FROM user_roles
+    WHERE user_id = NEW.id AND role_id = 'admin' AND tribe IS NULL AND COALESCE(NEW.is_admin, FALSE) = FALSE;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_user_tribes_admin_role_insert
+AFTER INSERT ON user_tribes WHEN NEW.is_admin = TRUE
+BEGIN
+    INSERT OR IGNORE INTO user_roles (id, user_id, role_id, tribe)
+    VALUES (lower(hex(randomblob(16))), NEW.user_id, 'admin', NEW.tribe);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_user_tribes_admin_role_update
+AFTER UPDATE OF is_admin ON user_tribes
+BEGIN
+    INSERT OR IGNORE INTO user_roles (id, user_id, role_id, tribe)
+    SELECT lower(hex(randomblob(16))), NEW.user_id, 'admin', NEW.tribe WHERE NEW.is_admin = TRUE;
+    DELETE FROM user_roles
+    WHERE user_id = NEW.user_id AND role_id = 'admin' AND tribe = NEW.tribe AND COALESCE(NEW.is_admin, FALSE) = FALSE;
+END;
+
+-- Leaving a tribe drops every role held in it
+CREATE TRIGGER IF NOT EXISTS trg_user_tribes_roles_delete
+AFTER DELETE ON user_tribes
+BEGIN
+    DELETE FROM user_roles WHERE user_id = OLD.user_id AND tribe = OLD.tribe;
+END;
@@ -0,0 +1,7 @@
+-- Super admin registry, seeded from SUPER_ADMIN_DISCORD_IDS while empty.
+-- Keyed by Discord ID so admins can be nominated before their first login.
+CREATE TABLE IF NOT EXISTS super_admins (
+    discord_id TEXT PRIMARY KEY,
+    nominated_by TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
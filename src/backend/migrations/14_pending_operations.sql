@@ -0,0 +1,17 @@
+-- Super admin actions held for a second super admin's approval ("four eyes")
+CREATE TABLE IF NOT EXISTS pending_operations (
+    id TEXT PRIMARY KEY,
+    action TEXT NOT NULL,
+    payload TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    target_id INTEGER,
+    requested_by TEXT NOT NULL,
+    requested_at DATETIME NOT NULL,
+    expires_at DATETIME NOT NULL,
+    -- PENDING, APPROVED, REJECTED, EXPIRED or FAILED
+    status TEXT NOT NULL DEFAULT 'PENDING',
+    decided_by TEXT,
+    decided_at DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_pending_operations_status ON pending_operations(status, expires_at);
@@ -0,0 +1,33 @@
+-- TOTP second factor; the secret is encrypted with TOTP_ENCRYPTION_KEY
+CREATE TABLE IF NOT EXISTS user_totp (
+    user_id INTEGER PRIMARY KEY,
+    secret_ciphertext TEXT NOT NULL,
+    -- NULL until the user proves they can generate codes
+    confirmed_at DATETIME,
+    -- Last accepted time step, so a code can't be replayed
+    last_used_step INTEGER,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS totp_recovery_codes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    code_hash TEXT NOT NULL,
+    used_at DATETIME,
+    FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_totp_recovery_codes_user ON totp_recovery_codes(user_id);
+
+-- Step-up policy per role id, plus the pseudo-role 'super_admin'
+CREATE TABLE IF NOT EXISTS step_up_policies (
+    role TEXT PRIMARY KEY,
+    required BOOLEAN NOT NULL DEFAULT FALSE,
+    max_age_minutes INTEGER NOT NULL DEFAULT 15,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT OR IGNORE INTO step_up_policies (role, required, max_age_minutes) VALUES
+    ('super_admin', FALSE, 15),
+    ('admin', FALSE, 15);
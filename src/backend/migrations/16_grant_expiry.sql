@@ -0,0 +1,6 @@
+-- Optional end dates for tribe memberships and tribe-admin grants; NULL means permanent
+ALTER TABLE user_tribes ADD COLUMN expires_at DATETIME;
+ALTER TABLE user_tribes ADD COLUMN admin_expires_at DATETIME;
+
+CREATE INDEX IF NOT EXISTS idx_user_tribes_expires_at ON user_tribes(expires_at);
+CREATE INDEX IF NOT EXISTS idx_user_tribes_admin_expires_at ON user_tribes(admin_expires_at);
@@ -0,0 +1,58 @@
+-- Every tribe membership interval; user_tribes only holds the current state.
+-- Timestamps are stored as ISO-8601 UTC so they compare correctly as text.
+CREATE TABLE IF NOT EXISTS tribe_membership_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL,
+    tribe TEXT NOT NULL,
+    source TEXT NOT NULL DEFAULT 'MANUAL',
+    joined_at TEXT NOT NULL,
+    left_at TEXT,
+    joined_by INTEGER,
+    left_by INTEGER,
+    FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY(joined_by) REFERENCES users(id) ON DELETE SET NULL,
+    FOREIGN KEY(left_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_membership_history_tribe ON tribe_membership_history(tribe, joined_at);
+CREATE INDEX IF NOT EXISTS idx_membership_history_left ON tribe_membership_history(tribe, left_at);
+CREATE INDEX IF NOT EXISTS idx_membership_history_user ON tribe_membership_history(user_id, tribe);
+
+-- Open an interval for every current membership
+INSERT INTO tribe_membership_history (user_id, tribe, source, joined_at)
+SELECT user_id, tribe, source, strftime('%Y-%m-%dT%H:%M:%fZ', COALESCE(created_at, 'now'))
+FROM user_tribes;
+
+-- Maintained by triggers so no membership write can skip it. The acting user is
+-- not visible here; handlers fill in joined_by / left_by afterwards.
+CREATE TRIGGER IF NOT EXISTS trg_user_tribes_history_insert
+AFTER INSERT ON user_tribes
+BEGIN
+    INSERT INTO tribe_membership_history (user_id, tribe, source, joined_at)
+    VALUES (NEW.user_id, NEW.tribe, NEW.source, strftime('%Y-%m-%dT%H:%M:%fZ', 'now'));
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_user_tribes_history_delete
+AFTER DELETE ON user_tribes
+BEGIN
+    UPDATE tribe_membership_history
+    SET left_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    WHERE user_id = OLD.user_id AND tribe = OLD.tribe AND left_at IS NULL;
+END;
+
+-- A tribe rename carries the whole history over to the new name
+CREATE TRIGGER IF NOT EXISTS trg_user_tribes_history_rename
+AFTER UPDATE OF tribe ON user_tribes WHEN NEW.tribe <> OLD.tribe
+BEGIN
+    UPDATE tribe_membership_history
+    SET tribe = NEW.tribe
+    WHERE user_id = NEW.user_id AND tribe = OLD.tribe;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_user_tribes_history_source
+AFTER UPDATE OF source ON user_tribes WHEN NEW.source <> OLD.source
+BEGIN
+    UPDATE tribe_membership_history
+    SET source = NEW.source
+    WHERE user_id = NEW.user_id AND tribe = NEW.tribe AND left_at IS NULL;
+END;
@@ -0,0 +1,10 @@
+-- Soft deletion, pinning, visibility and categories for notes
+ALTER TABLE notes ADD COLUMN deleted_at DATETIME;
+ALTER TABLE notes ADD COLUMN deleted_by INTEGER REFERENCES users(id) ON DELETE SET NULL;
+ALTER TABLE notes ADD COLUMN pinned BOOLEAN NOT NULL DEFAULT FALSE;
+-- TRIBE_ADMINS (anyone with notes.read in the tribe), AUTHOR or SUPER_ADMINS
+ALTER TABLE notes ADD COLUMN visibility TEXT NOT NULL DEFAULT 'TRIBE_ADMINS';
+-- RECRUITMENT, WARNING, COMMENDATION or GENERAL
+ALTER TABLE notes ADD COLUMN category TEXT NOT NULL DEFAULT 'GENERAL';
+
+CREATE INDEX IF NOT EXISTS idx_notes_target_tribe ON notes(target_user_id, tribe, deleted_at);
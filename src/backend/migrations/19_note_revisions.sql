@@ -0,0 +1,16 @@
+-- Every superseded version of a note. The live version stays in notes; revision
+-- numbers count up from 1 for the original text.
+CREATE TABLE IF NOT EXISTS note_revisions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    note_id TEXT NOT NULL,
+    revision INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    category TEXT NOT NULL,
+    visibility TEXT NOT NULL,
+    -- Who wrote this version, and when
+    editor_id INTEGER,
+    created_at DATETIME NOT NULL,
+    FOREIGN KEY(note_id) REFERENCES notes(id) ON DELETE CASCADE,
+    FOREIGN KEY(editor_id) REFERENCES users(id) ON DELETE SET NULL,
+    UNIQUE(note_id, revision)
+);
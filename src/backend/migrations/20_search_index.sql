@@ -0,0 +1,93 @@
+-- Full-text search over note content and member identities. Both indexes are
+-- kept in step by triggers; visibility and tribe scoping happen at query time.
+
+-- External-content index over notes; soft-deleted notes stay indexed and are
+-- filtered out by the search query
+CREATE VIRTUAL TABLE IF NOT EXISTS notes_fts USING fts5(
+    content,
+    content = 'notes',
+    content_rowid = 'rowid',
+    tokenize = 'unicode61'
+);
+
+INSERT INTO notes_fts (rowid, content) SELECT rowid, content FROM notes;
+
+CREATE TRIGGER IF NOT EXISTS trg_notes_fts_insert
+AFTER INSERT ON notes
+BEGIN
+    INSERT INTO notes_fts (rowid, content) VALUES (NEW.rowid, NEW.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_notes_fts_delete
+AFTER DELETE ON notes
+BEGIN
+    INSERT INTO notes_fts (notes_fts, rowid, content) VALUES ('delete', OLD.rowid, OLD.content);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_notes_fts_update
+AFTER UPDATE OF content ON notes
+BEGIN
+    INSERT INTO notes_fts (notes_fts, rowid, content) VALUES ('delete', OLD.rowid, OLD.content);
+    INSERT INTO notes_fts (rowid, content) VALUES (NEW.rowid, NEW.content);
+END;
+
+-- One row per user (rowid = users.id) with their active wallets' addresses and
+-- cached character names, space separated
+CREATE VIRTUAL TABLE IF NOT EXISTS members_fts USING fts5(
+    username,
+    addresses,
+    characters,
+    tokenize = 'unicode61'
+);
+
+INSERT INTO members_fts (rowid, username, addresses, characters)
+SELECT u.id, u.username,
+    COALESCE((SELECT group_concat(w.address, ' ') FROM wallets w WHERE w.user_id = u.id AND w.deleted_at IS NULL), ''),
+    COALESCE((SELECT group_concat(w.character_name, ' ') FROM wallets w WHERE w.user_id = u.id AND w.deleted_at IS NULL), '')
+FROM users u;
+
+CREATE TRIGGER IF NOT EXISTS trg_members_fts_user_insert
+AFTER INSERT ON users
+BEGIN
+    INSERT INTO members_fts (rowid, username, addresses, characters) VALUES (NEW.id, NEW.username, '', '');
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_members_fts_user_update
+AFTER UPDATE OF username ON users
+BEGIN
+    UPDATE members_fts SET username = NEW.username WHERE rowid = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_members_fts_user_delete
+AFTER DELETE ON users
+BEGIN
+    DELETE FROM members_fts WHERE rowid = OLD.id;
+END;
+
+-- Any wallet change rebuilds the owner's wallet columns
+CREATE TRIGGER IF NOT EXISTS trg_members_fts_wallet_insert
+AFTER INSERT ON wallets
+BEGIN
+    UPDATE members_fts SET
+        addresses = COALESCE((SELECT group_concat(w.address, ' ') FROM wallets w WHERE w.user_id = NEW.user_id AND w.deleted_at IS NULL), ''),
+        characters = COALESCE((SELECT group_concat(w.character_name, ' ') FROM wallets w WHERE w.user_id = NEW.user_id AND w.deleted_at IS NULL), '')
+    WHERE rowid = NEW.user_id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_members_fts_wallet_update
+AFTER UPDATE OF address, character_name, deleted_at, user_id ON wallets
+BEGIN
+    UPDATE members_fts SET
+        addresses = COALESCE((SELECT group_concat(w.address, ' ') FROM wallets w WHERE w.user_id = members_fts.rowid AND w.deleted_at IS NULL), ''),
+        characters = COALESCE((SELECT group_concat(w.character_name, ' ') FROM wallets w WHERE w.user_id = members_fts.rowid AND w.deleted_at IS NULL), '')
+    WHERE rowid IN (OLD.user_id, NEW.user_id);
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_members_fts_wallet_delete
+AFTER DELETE ON wallets
+BEGIN
+    UPDATE members_fts SET
+        addresses = COALESCE((SELECT group_concat(w.address, ' ') FROM wallets w WHERE w.user_id = OLD.user_id AND w.deleted_at IS NULL), ''),
+        characters = COALESCE((SELECT group_concat(w.character_name, ' ') FROM wallets w WHERE w.user_id = OLD.user_id AND w.deleted_at IS NULL), '')
+    WHERE rowid = OLD.user_id;
+END;
@@ -0,0 +1,31 @@
+-- Field-level encryption for note content. key_id names the key that sealed a
+-- row; NULL means the content is still plaintext and will be sealed by the
+-- background re-encryption pass once keys are configured.
+ALTER TABLE notes ADD COLUMN key_id TEXT;
+ALTER TABLE note_revisions ADD COLUMN key_id TEXT;
+
+-- Which search key built the note's index entry; NULL means not indexed yet
+ALTER TABLE notes ADD COLUMN search_key_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_notes_key_id ON notes(key_id);
+CREATE INDEX IF NOT EXISTS idx_note_revisions_key_id ON note_revisions(key_id);
+
+-- The note index can no longer read content from notes, so it becomes a
+-- contentless index fed by the application with (optionally keyed) word hashes
+DROP TRIGGER IF EXISTS trg_notes_fts_insert;
+DROP TRIGGER IF EXISTS trg_notes_fts_update;
+DROP TRIGGER IF EXISTS trg_notes_fts_delete;
+DROP TABLE IF EXISTS notes_fts;
+
+CREATE VIRTUAL TABLE IF NOT EXISTS notes_fts USING fts5(
+    terms,
+    content = '',
+    contentless_delete = 1,
+    tokenize = 'unicode61'
+);
+
+CREATE TRIGGER IF NOT EXISTS trg_notes_fts_delete
+AFTER DELETE ON notes
+BEGIN
+    DELETE FROM notes_fts WHERE rowid = OLD.rowid;
+END;
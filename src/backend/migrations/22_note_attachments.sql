@@ -0,0 +1,40 @@
+-- Files attached to notes. The bytes live in the attachment store under
+-- storage_key, sealed with the note keys when key_id is set.
+CREATE TABLE IF NOT EXISTS note_attachments (
+    id TEXT PRIMARY KEY,
+    note_id TEXT NOT NULL,
+    uploader_id INTEGER,
+    file_name TEXT NOT NULL,
+    content_type TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    sha256 TEXT NOT NULL,
+    storage_key TEXT NOT NULL UNIQUE,
+    key_id TEXT,
+    created_at DATETIME NOT NULL,
+    FOREIGN KEY(note_id) REFERENCES notes(id) ON DELETE CASCADE,
+    FOREIGN KEY(uploader_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_note_attachments_note_id ON note_attachments(note_id);
+CREATE INDEX IF NOT EXISTS idx_note_attachments_key_id ON note_attachments(key_id);
+
+-- Stored files whose row is gone, waiting to be removed from the store
+CREATE TABLE IF NOT EXISTS attachment_purge_queue (
+    storage_key TEXT PRIMARY KEY,
+    queued_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- However a row disappears (attachment removed, note deleted, account deleted),
+-- its file is queued for removal
+CREATE TRIGGER IF NOT EXISTS trg_note_attachments_purge
+AFTER DELETE ON note_attachments
+BEGIN
+    INSERT OR IGNORE INTO attachment_purge_queue (storage_key) VALUES (OLD.storage_key);
+END;
+
+-- Soft-deleting a note drops its attachments
+CREATE TRIGGER IF NOT EXISTS trg_notes_soft_delete_attachments
+AFTER UPDATE OF deleted_at ON notes WHEN NEW.deleted_at IS NOT NULL AND OLD.deleted_at IS NULL
+BEGIN
+    DELETE FROM note_attachments WHERE note_id = NEW.id;
+END;
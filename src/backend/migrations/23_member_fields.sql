@@ -0,0 +1,44 @@
+-- Custom member fields defined by each tribe
+CREATE TABLE IF NOT EXISTS tribe_fields (
+    id TEXT PRIMARY KEY,
+    tribe TEXT NOT NULL,
+    key TEXT NOT NULL,
+    label TEXT NOT NULL,
+    -- TEXT, NUMBER, DATE, ENUM or TAGS
+    field_type TEXT NOT NULL,
+    -- JSON array of allowed values for ENUM and TAGS; empty allows any tag
+    options TEXT NOT NULL DEFAULT '[]',
+    sort_order INTEGER NOT NULL DEFAULT 0,
+    created_by INTEGER,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(tribe, key),
+    FOREIGN KEY(created_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+-- One value per member and field. TAGS values are JSON arrays, NUMBER values
+-- decimal text, DATE values YYYY-MM-DD
+CREATE TABLE IF NOT EXISTS member_field_values (
+    field_id TEXT NOT NULL,
+    user_id INTEGER NOT NULL,
+    value TEXT NOT NULL,
+    updated_by INTEGER,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(field_id, user_id),
+    FOREIGN KEY(field_id) REFERENCES tribe_fields(id) ON DELETE CASCADE,
+    FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY(updated_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_member_field_values_user ON member_field_values(user_id);
+
+-- Leaving a tribe drops the member's values for its fields
+CREATE TRIGGER IF NOT EXISTS trg_user_tribes_field_values_delete
+AFTER DELETE ON user_tribes
+BEGIN
+    DELETE FROM member_field_values
+    WHERE user_id = OLD.user_id
+      AND field_id IN (SELECT id FROM tribe_fields WHERE tribe = OLD.tribe);
+END;
+
+-- Tribe admins manage fields and values
+INSERT OR IGNORE INTO role_permissions (role_id, permission) VALUES ('admin', 'roster.write');
@@ -0,0 +1,53 @@
+-- Ordered rank ladder per tribe; position 0 is the lowest rank. A rank may
+-- carry a role, granted in the tribe to holders of that rank or any above it.
+CREATE TABLE IF NOT EXISTS tribe_ranks (
+    id TEXT PRIMARY KEY,
+    tribe TEXT NOT NULL,
+    key TEXT NOT NULL,
+    name TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    role_id TEXT,
+    mumble_group TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(tribe, key),
+    FOREIGN KEY(role_id) REFERENCES roles(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_tribe_ranks_tribe ON tribe_ranks(tribe, position);
+
+-- A member's rank lives on the membership, so leaving the tribe drops it
+ALTER TABLE user_tribes ADD COLUMN rank_id TEXT REFERENCES tribe_ranks(id) ON DELETE SET NULL;
+
+-- Squads (or wings) within a tribe
+CREATE TABLE IF NOT EXISTS tribe_squads (
+    id TEXT PRIMARY KEY,
+    tribe TEXT NOT NULL,
+    name TEXT NOT NULL,
+    mumble_group TEXT,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(tribe, name)
+);
+
+CREATE TABLE IF NOT EXISTS squad_members (
+    squad_id TEXT NOT NULL,
+    user_id INTEGER NOT NULL,
+    added_by INTEGER,
+    added_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(squad_id, user_id),
+    FOREIGN KEY(squad_id) REFERENCES tribe_squads(id) ON DELETE CASCADE,
+    FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY(added_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_squad_members_user ON squad_members(user_id);
+
+-- Leaving a tribe drops the member from its squads
+CREATE TRIGGER IF NOT EXISTS trg_user_tribes_squads_delete
+AFTER DELETE ON user_tribes
+BEGIN
+    DELETE FROM squad_members
+    WHERE user_id = OLD.user_id
+      AND squad_id IN (SELECT id FROM tribe_squads WHERE tribe = OLD.tribe);
+END;
+
+INSERT OR IGNORE INTO role_permissions (role_id, permission) VALUES ('admin', 'ranks.manage');
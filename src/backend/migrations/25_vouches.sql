@@ -0,0 +1,19 @@
+-- Members vouching for a user (usually an applicant) in one of their tribes.
+-- Retracting a vouch deletes it; the audit log keeps the history.
+CREATE TABLE IF NOT EXISTS vouches (
+    id TEXT PRIMARY KEY,
+    tribe TEXT NOT NULL,
+    voucher_id INTEGER NOT NULL,
+    vouchee_id INTEGER NOT NULL,
+    justification TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE(tribe, voucher_id, vouchee_id),
+    FOREIGN KEY(voucher_id) REFERENCES users(id) ON DELETE CASCADE,
+    FOREIGN KEY(vouchee_id) REFERENCES users(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_vouches_vouchee ON vouches(tribe, vouchee_id);
+CREATE INDEX IF NOT EXISTS idx_vouches_voucher ON vouches(voucher_id);
+
+-- Vouches from current members a user needs before being added to the tribe
+ALTER TABLE tribes ADD COLUMN min_vouches INTEGER NOT NULL DEFAULT 0;
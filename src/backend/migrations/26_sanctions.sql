@@ -0,0 +1,65 @@
+-- Kill-on-sight / sanctions entries, each naming one Discord ID or one wallet
+-- address (stored lowercase). Shared entries are visible to the owning tribe's allies.
+CREATE TABLE IF NOT EXISTS sanctions (
+    id TEXT PRIMARY KEY,
+    tribe TEXT NOT NULL,
+    discord_id TEXT,
+    wallet_address TEXT,
+    reason TEXT NOT NULL,
+    shared BOOLEAN NOT NULL DEFAULT FALSE,
+    expires_at DATETIME,
+    created_by INTEGER,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CHECK ((discord_id IS NULL) <> (wallet_address IS NULL)),
+    FOREIGN KEY(created_by) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sanctions_tribe ON sanctions(tribe);
+CREATE INDEX IF NOT EXISTS idx_sanctions_discord ON sanctions(discord_id);
+CREATE INDEX IF NOT EXISTS idx_sanctions_wallet ON sanctions(wallet_address);
+
+-- Tribes a tribe shares its shared entries with
+CREATE TABLE IF NOT EXISTS sanction_allies (
+    tribe TEXT NOT NULL,
+    ally TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY(tribe, ally)
+);
+
+-- Matches found at login, wallet link and tribe join. Rows with no alerted_at
+-- are waiting for the backend to raise their alert.
+CREATE TABLE IF NOT EXISTS sanction_hits (
+    id TEXT PRIMARY KEY,
+    sanction_id TEXT NOT NULL,
+    user_id INTEGER,
+    context TEXT NOT NULL,
+    -- The tribe joined, for TRIBE_JOIN hits
+    tribe TEXT,
+    created_at TEXT NOT NULL,
+    alerted_at TEXT,
+    FOREIGN KEY(sanction_id) REFERENCES sanctions(id) ON DELETE CASCADE,
+    FOREIGN KEY(user_id) REFERENCES users(id) ON DELETE SET NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_sanction_hits_sanction ON sanction_hits(sanction_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_sanction_hits_pending ON sanction_hits(alerted_at);
+
+-- Screen every tribe join, however the membership is written, against the
+-- active entries visible to the joined tribe
+CREATE TRIGGER IF NOT EXISTS trg_user_tribes_sanction_screen
+AFTER INSERT ON user_tribes
+BEGIN
+    INSERT INTO sanction_hits (id, sanction_id, user_id, context, tribe, created_at)
+    SELECT lower(hex(randomblob(16))), s.id, NEW.user_id, 'TRIBE_JOIN', NEW.tribe,
+           strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+    FROM sanctions s
+    WHERE (s.expires_at IS NULL OR julianday(s.expires_at) > julianday('now'))
+      AND (s.tribe = NEW.tribe
+           OR (s.shared AND EXISTS (
+               SELECT 1 FROM sanction_allies a WHERE a.tribe = s.tribe AND a.ally = NEW.tribe)))
+      AND (s.discord_id = (SELECT discord_id FROM users WHERE id = NEW.user_id)
+           OR s.wallet_address IN (
+               SELECT address FROM wallets WHERE user_id = NEW.user_id AND deleted_at IS NULL));
+END;
+
+INSERT OR IGNORE INTO role_permissions (role_id, permission) VALUES ('admin', 'sanctions.manage');
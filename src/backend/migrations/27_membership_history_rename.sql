@@ -0,0 +1,3 @@
+-- Tribe renames now carry the history over in the rename itself, including the
+-- intervals of members who have since left; the per-membership trigger missed those.
+DROP TRIGGER IF EXISTS trg_user_tribes_history_rename;
@@ -1,18 +1,35 @@
 use crate::{
+    api_keys::SCOPE_ROSTER_READ_PREFIX,
+    approvals::{request_approval, requires_approval, ApprovalQuery, GuardedAction},
     audit::{alert_admin_action, AuditAction},
     middleware::admin::RequireSuperAdmin,
     models::User,
     state::AppState,
 };
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
 
-async fn get_admin_id(db: &crate::db::DbPool, discord_id: &str) -> i64 {
+/// Columns outside `user_tribes` and `user_roles` that hold a tribe name and
+/// follow a tribe rename, as (table, column)
+const TRIBE_NAME_COLUMNS: &[(&str, &str)] = &[
+    ("wallet_reverification_policies", "tribe"),
+    ("tribe_membership_history", "tribe"),
+    ("tribe_fields", "tribe"),
+    ("tribe_ranks", "tribe"),
+    ("tribe_squads", "tribe"),
+    ("vouches", "tribe"),
+    ("sanctions", "tribe"),
+    ("sanction_allies", "tribe"),
+    ("sanction_allies", "ally"),
+    ("sanction_hits", "tribe"),
+];
+
+pub(crate) async fn get_admin_id(db: &crate::db::DbPool, discord_id: &str) -> i64 {
     sqlx::query_scalar("SELECT id FROM users WHERE discord_id = ?")
         .bind(discord_id)
         .fetch_one(db)
@@ -104,9 +121,17 @@ pub async fn list_users(
                 deleted_at: flat.deleted_at,
                 tribes: flat.tribe.map(|t| vec![t]).unwrap_or_default(),
                 network: flat.network,
+                stale_at: flat.stale_at,
+                character_name: flat.character_name,
+                is_primary: flat.is_primary,
+                label: flat.label,
+                sort_order: flat.sort_order,
             });
         }
     }
+    for wallets in wallets_by_user.values_mut() {
+        crate::models::sort_wallets(wallets);
+    }
 
     // Group tribes by user_id in memory
     let mut tribes_by_user: std::collections::HashMap<i64, Vec<String>> =
@@ -131,13 +156,14 @@ pub async fn list_users(
         }
     }
 
-    let super_admin_ids_str = std::env::var("SUPER_ADMIN_DISCORD_IDS").unwrap_or_default();
-    let super_admin_ids: Vec<&str> = super_admin_ids_str.split(',').map(|s| s.trim()).collect();
+    let super_admin_ids = crate::super_admins::super_admin_ids(&state.db)
+        .await
+        .unwrap_or_default();
 
     let response: Vec<UserResponse> = users
         .into_iter()
         .map(|user| {
-            let is_super_admin = super_admin_ids.contains(&user.discord_id.as_str());
+            let is_super_admin = super_admin_ids.contains(&user.discord_id);
 
             UserResponse {
                 id: user.id.to_string(),
@@ -161,7 +187,7 @@ pub async fn list_users(
     Json(response).into_response()
 }
 
-#[derive(Deserialize, utoipa::ToSchema)]
+#[derive(Serialize, Deserialize, utoipa::ToSchema)]
 pub struct UpdateUserRequest {
     pub is_admin: bool,
     pub username: String,
@@ -174,11 +200,13 @@ pub struct UpdateUserRequest {
     path = "/api/admin/users/{id}",
     tag = "Admin",
     params(
-        ("id" = i64, Path, description = "User ID")
+        ("id" = i64, Path, description = "User ID"),
+        ApprovalQuery
     ),
     request_body = UpdateUserRequest,
     responses(
         (status = 200, description = "User updated successfully"),
+        (status = 202, description = "Global admin grant awaiting approval", body = crate::approvals::PendingOperation),
         (status = 404, description = "User not found"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "User is not super admin"),
@@ -188,8 +216,42 @@ pub async fn update_user(
     State(state): State<AppState>,
     admin: RequireSuperAdmin,
     Path(user_id): Path<i64>,
+    Query(approval): Query<ApprovalQuery>,
     Json(payload): Json<UpdateUserRequest>,
 ) -> impl IntoResponse {
+    // Granting global admin may need a second super admin's approval
+    if payload.is_admin && requires_approval(GuardedAction::GrantGlobalAdmin) {
+        let was_admin: Option<bool> = sqlx::query_scalar("SELECT is_admin FROM users WHERE id = ?")
+            .bind(user_id)
+            .fetch_optional(&state.db)
+            .await
+            .unwrap_or(None);
+        match was_admin {
+            None => return StatusCode::NOT_FOUND.into_response(),
+            Some(false) => {
+                return request_approval(
+                    &state,
+                    &admin,
+                    GuardedAction::GrantGlobalAdmin,
+                    Some(user_id),
+                    serde_json::json!({ "user_id": user_id, "update": payload }),
+                    approval.reason,
+                )
+                .await
+            }
+            Some(true) => {}
+        }
+    }
+
+    apply_update_user(&state, &admin, user_id, payload).await
+}
+
+pub(crate) async fn apply_update_user(
+    state: &AppState,
+    admin: &RequireSuperAdmin,
+    user_id: i64,
+    payload: UpdateUserRequest,
+) -> Response {
     let mut tx = match state.db.begin().await {
         Ok(tx) => tx,
         Err(e) => {
@@ -234,11 +296,14 @@ pub async fn update_user(
     }
 
     // Update tribe admin status
-    // 1. Reset all admin flags for this user in user_tribes
-    let reset_res = sqlx::query("UPDATE user_tribes SET is_admin = FALSE WHERE user_id = ?")
-        .bind(user_id)
-        .execute(&mut *tx)
-        .await;
+    // 1. Reset all admin flags for this user in user_tribes. The editor has no expiry,
+    // so grants it re-sets are permanent and revoked ones leave no expiry behind.
+    let reset_res = sqlx::query(
+        "UPDATE user_tribes SET is_admin = FALSE, admin_expires_at = NULL WHERE user_id = ?",
+    )
+    .bind(user_id)
+    .execute(&mut *tx)
+    .await;
 
     if let Err(e) = reset_res {
         eprintln!("Failed to reset tribe admin status: {}", e);
@@ -433,11 +498,13 @@ pub async fn create_tribe(
     path = "/api/admin/tribes/{id}",
     tag = "Admin",
     params(
-        ("id" = String, Path, description = "Tribe name")
+        ("id" = String, Path, description = "Tribe name"),
+        ApprovalQuery
     ),
     request_body = CreateTribeRequest,
     responses(
         (status = 200, description = "Tribe updated successfully"),
+        (status = 202, description = "Rename awaiting approval", body = crate::approvals::PendingOperation),
         (status = 400, description = "Invalid tribe name"),
         (status = 409, description = "Tribe name already exists"),
         (status = 401, description = "Unauthorized"),
@@ -448,12 +515,36 @@ pub async fn update_tribe(
     State(state): State<AppState>,
     admin: RequireSuperAdmin,
     Path(tribe_name): Path<String>,
+    Query(approval): Query<ApprovalQuery>,
     Json(payload): Json<CreateTribeRequest>, // reusing struct for name update
 ) -> impl IntoResponse {
     if payload.name.trim().is_empty() {
         return StatusCode::BAD_REQUEST.into_response();
     }
 
+    if requires_approval(GuardedAction::UpdateTribe) {
+        return request_approval(
+            &state,
+            &admin,
+            GuardedAction::UpdateTribe,
+            None,
+            serde_json::json!({ "tribe": tribe_name, "name": payload.name }),
+            approval.reason,
+        )
+        .await;
+    }
+
+    apply_update_tribe(&state, &admin, tribe_name, payload.name).await
+}
+
+pub(crate) async fn apply_update_tribe(
+    state: &AppState,
+    admin: &RequireSuperAdmin,
+    tribe_name: String,
+    new_name: String,
+) -> Response {
+    let payload = CreateTribeRequest { name: new_name };
+
     let mut tx = match state.db.begin().await {
         Ok(tx) => tx,
         Err(e) => {
@@ -493,6 +584,64 @@ pub async fn update_tribe(
         return StatusCode::INTERNAL_SERVER_ERROR.into_response();
     }
 
+    // Tribe-scoped role assignments follow the rename
+    let update_user_roles_res = sqlx::query("UPDATE user_roles SET tribe = ? WHERE tribe = ?")
+        .bind(&payload.name)
+        .bind(&tribe_name)
+        .execute(&mut *tx)
+        .await;
+
+    if let Err(e) = update_user_roles_res {
+        eprintln!(
+            "Failed to update tribe name in user_roles in update_tribe: {}",
+            e
+        );
+        let _ = tx.rollback().await;
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    // Per-tribe settings and records kept by name follow it too
+    for (table, column) in TRIBE_NAME_COLUMNS {
+        let res = sqlx::query(&format!(
+            "UPDATE {} SET {} = ? WHERE {} = ?",
+            table, column, column
+        ))
+        .bind(&payload.name)
+        .bind(&tribe_name)
+        .execute(&mut *tx)
+        .await;
+
+        if let Err(e) = res {
+            eprintln!(
+                "Failed to update tribe name in {} in update_tribe: {}",
+                table, e
+            );
+            let _ = tx.rollback().await;
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+
+    // API keys scoped to the tribe's roster keep working under the new name
+    let old_scope = format!("{}{}", SCOPE_ROSTER_READ_PREFIX, tribe_name);
+    let new_scope = format!("{}{}", SCOPE_ROSTER_READ_PREFIX, payload.name);
+    let update_scopes_res = sqlx::query(
+        "UPDATE api_keys SET scopes = TRIM(REPLACE(' ' || scopes || ' ', ' ' || ? || ' ', ' ' || ? || ' ')) WHERE instr(' ' || scopes || ' ', ' ' || ? || ' ') > 0",
+    )
+    .bind(&old_scope)
+    .bind(&new_scope)
+    .bind(&old_scope)
+    .execute(&mut *tx)
+    .await;
+
+    if let Err(e) = update_scopes_res {
+        eprintln!(
+            "Failed to update tribe name in api_keys in update_tribe: {}",
+            e
+        );
+        let _ = tx.rollback().await;
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
     // Audit
     let audit_res = sqlx::query(
         "INSERT INTO audit_logs (id, action, actor_id, target_id, details, created_at) VALUES (?, ?, ?, ?, ?, ?)"
@@ -529,6 +678,9 @@ pub async fn update_tribe(
 #[derive(Deserialize, utoipa::ToSchema)]
 pub struct AddUserToTribeRequest {
     pub username: String,
+    /// When the membership lapses; permanent when absent
+    #[serde(default)]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 #[utoipa::path(
@@ -541,7 +693,7 @@ pub struct AddUserToTribeRequest {
     request_body = AddUserToTribeRequest,
     responses(
         (status = 200, description = "User added to tribe successfully"),
-        (status = 400, description = "Tribe does not exist or user already in tribe"),
+        (status = 400, description = "Tribe does not exist, expiry is not in the future, or too few vouches"),
         (status = 404, description = "User not found"),
         (status = 409, description = "User already in tribe"),
         (status = 401, description = "Unauthorized"),
@@ -564,6 +716,11 @@ pub async fn add_user_to_tribe(
 
     let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
 
+    if let Err(msg) = crate::expiry::validate_expiry(payload.expires_at) {
+        let _ = tx.rollback().await;
+        return (StatusCode::BAD_REQUEST, msg).into_response();
+    }
+
     // Validate username length
     if payload.username.len() > 100 {
         let _ = tx.rollback().await;
@@ -631,14 +788,46 @@ pub async fn add_user_to_tribe(
         return (StatusCode::BAD_REQUEST, "Tribe does not exist").into_response();
     }
 
-    // 4. Add to user_tribes
+    // Tribes may require vouches from their current members before admission
+    match crate::vouches::vouch_standing(&mut *tx, &tribe_name, user.id).await {
+        Ok((vouches, required)) if vouches < required => {
+            let _ = tx.rollback().await;
+            return (
+                StatusCode::BAD_REQUEST,
+                "User does not have enough vouches from tribe members",
+            )
+                .into_response();
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Failed to check vouches in add_user_to_tribe: {}", e);
+            let _ = tx.rollback().await;
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+
+    // 4. Add to user_tribes, attributed to the user's primary wallet
+    let wallet_id = match crate::wallet::primary_wallet_id(&mut *tx, user.id).await {
+        Ok(w) => w,
+        Err(e) => {
+            eprintln!(
+                "Failed to look up primary wallet in add_user_to_tribe: {}",
+                e
+            );
+            let _ = tx.rollback().await;
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
     let res = sqlx::query(
-        "INSERT INTO user_tribes (user_id, tribe, is_admin, created_at, source) VALUES (?, ?, ?, ?, 'MANUAL')",
+        "INSERT INTO user_tribes (user_id, tribe, wallet_id, is_admin, created_at, expires_at, source) VALUES (?, ?, ?, ?, ?, ?, 'MANUAL')",
     )
     .bind(user.id)
     .bind(&tribe_name)
+    .bind(&wallet_id)
     .bind(false)
     .bind(chrono::Utc::now())
+    .bind(payload.expires_at)
     .execute(&mut *tx)
     .await;
 
@@ -648,7 +837,27 @@ pub async fn add_user_to_tribe(
         return StatusCode::INTERNAL_SERVER_ERROR.into_response();
     }
 
+    if let Err(e) =
+        crate::membership::attribute_join(&mut *tx, user.id, &tribe_name, admin_id).await
+    {
+        eprintln!(
+            "Failed to record membership history in add_user_to_tribe: {}",
+            e
+        );
+        let _ = tx.rollback().await;
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
     // Audit
+    let details = match payload.expires_at {
+        Some(at) => format!(
+            "Added User '{}' to Tribe '{}' until {}",
+            user.username,
+            tribe_name,
+            at.to_rfc3339()
+        ),
+        None => format!("Added User '{}' to Tribe '{}'", user.username, tribe_name),
+    };
     let audit_id = uuid::Uuid::new_v4().to_string();
     let audit_res = sqlx::query(
         "INSERT INTO audit_logs (id, action, actor_id, target_id, details, created_at) VALUES (?, ?, ?, ?, ?, ?)"
@@ -657,7 +866,7 @@ pub async fn add_user_to_tribe(
     .bind(AuditAction::SuperAdminUpdateTribe.as_str()) // Reusing action or create new
     .bind(admin_id)
     .bind(user.id)
-    .bind(format!("Added User '{}' to Tribe '{}'", user.username, tribe_name))
+    .bind(&details)
     .bind(chrono::Utc::now())
     .execute(&mut *tx)
     .await;
@@ -676,8 +885,9 @@ pub async fn add_user_to_tribe(
     alert_admin_action(
         format!("SuperAdmin ({})", admin.discord_id),
         AuditAction::SuperAdminUpdateTribe,
-        format!("Added User '{}' to Tribe '{}'", user.username, tribe_name),
+        details,
     );
+    crate::sanctions::raise_alerts(&state.db).await;
 
     StatusCode::OK.into_response()
 }
@@ -689,10 +899,12 @@ pub async fn add_user_to_tribe(
     path = "/api/admin/wallets/{id}",
     tag = "Admin",
     params(
-        ("id" = String, Path, description = "Wallet ID")
+        ("id" = String, Path, description = "Wallet ID"),
+        ApprovalQuery
     ),
     responses(
         (status = 200, description = "Wallet deleted successfully"),
+        (status = 202, description = "Deletion awaiting approval", body = crate::approvals::PendingOperation),
         (status = 404, description = "Wallet not found or already deleted"),
         (status = 401, description = "Unauthorized"),
         (status = 403, description = "User is not super admin"),
@@ -702,7 +914,37 @@ pub async fn delete_wallet(
     State(state): State<AppState>,
     admin: RequireSuperAdmin,
     Path(wallet_id): Path<String>,
+    Query(approval): Query<ApprovalQuery>,
 ) -> impl IntoResponse {
+    if requires_approval(GuardedAction::DeleteWallet) {
+        let owner_id: Option<i64> =
+            sqlx::query_scalar("SELECT user_id FROM wallets WHERE id = ? AND deleted_at IS NULL")
+                .bind(&wallet_id)
+                .fetch_optional(&state.db)
+                .await
+                .unwrap_or(None);
+        let Some(owner_id) = owner_id else {
+            return (StatusCode::NOT_FOUND, "Wallet not found or already deleted").into_response();
+        };
+        return request_approval(
+            &state,
+            &admin,
+            GuardedAction::DeleteWallet,
+            Some(owner_id),
+            serde_json::json!({ "wallet_id": wallet_id }),
+            approval.reason,
+        )
+        .await;
+    }
+
+    apply_delete_wallet(&state, &admin, wallet_id).await
+}
+
+pub(crate) async fn apply_delete_wallet(
+    state: &AppState,
+    admin: &RequireSuperAdmin,
+    wallet_id: String,
+) -> Response {
     let mut tx = match state.db.begin().await {
         Ok(tx) => tx,
         Err(e) => {
@@ -747,7 +989,7 @@ pub async fn delete_wallet(
     }
 
     let del_res = match sqlx::query(
-        "UPDATE wallets SET deleted_at = CURRENT_TIMESTAMP WHERE id = ? AND deleted_at IS NULL",
+        "UPDATE wallets SET deleted_at = CURRENT_TIMESTAMP, is_primary = FALSE WHERE id = ? AND deleted_at IS NULL",
     )
     .bind(&wallet_id)
     .execute(&mut *tx)
@@ -767,6 +1009,20 @@ pub async fn delete_wallet(
         return (StatusCode::NOT_FOUND, "Wallet not found or already deleted").into_response();
     }
 
+    // Hand the owner's primary flag to their next wallet on the same network
+    let owner_id: Option<i64> = sqlx::query_scalar("SELECT user_id FROM wallets WHERE id = ?")
+        .bind(&wallet_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .unwrap_or(None);
+    if let Some(owner_id) = owner_id {
+        if let Err(e) = crate::wallet::ensure_primary_wallets(&mut *tx, owner_id).await {
+            eprintln!("Failed to promote primary wallet in delete_wallet: {}", e);
+            let _ = tx.rollback().await;
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+
     if let Err(e) = tx.commit().await {
         eprintln!("Transaction commit failed for delete_wallet: {}", e);
         return StatusCode::INTERNAL_SERVER_ERROR.into_response();
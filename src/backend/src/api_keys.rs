@@ -0,0 +1,948 @@
+use crate::{
+    admin::get_admin_id,
+    audit::{alert_admin_action, log_audit, AuditAction},
+    auth::AuthenticatedUser,
+    db::DbPool,
+    helpers::{require_admin_in_tribe, ApiResult},
+    middleware::admin::RequireSuperAdmin,
+    models::i64_as_string,
+    rbac::{permission_grants, Permission},
+    state::AppState,
+};
+use axum::{
+    extract::{FromRef, FromRequestParts, Path, Request, State},
+    http::{request::Parts, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use rand::{distr::Alphanumeric, RngExt};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Header carrying the API key
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
+/// Read the roster of one tribe: `roster:read:<tribe>`
+pub const SCOPE_ROSTER_READ_PREFIX: &str = "roster:read:";
+/// Look up whether identities are verified
+pub const SCOPE_VERIFY_READ: &str = "verify:read";
+/// Authenticate Mumble logins on behalf of the Murmur authenticator
+pub const SCOPE_MUMBLE_AUTH: &str = "mumble:auth";
+
+/// Prefix of every issued key, so leaked keys are easy to recognise
+const KEY_PREFIX: &str = "veid_";
+/// Characters of the key kept in clear so owners can tell keys apart
+const DISPLAY_PREFIX_LEN: usize = 12;
+const MAX_NAME_LENGTH: usize = 100;
+const MAX_LIFETIME_DAYS: i64 = 3650;
+
+pub fn roster_read_scope(tribe: &str) -> String {
+    format!("{}{}", SCOPE_ROSTER_READ_PREFIX, tribe)
+}
+
+/// SHA-256 of a raw key: what we store, and what identifies a key for rate limiting.
+pub fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+fn generate_key() -> String {
+    let random: String = rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(40)
+        .map(char::from)
+        .collect();
+    format!("{}{}", KEY_PREFIX, random)
+}
+
+/// Check a scope is one we know how to enforce.
+pub fn validate_scope(scope: &str) -> Result<(), &'static str> {
+    match scope {
+        SCOPE_VERIFY_READ | SCOPE_MUMBLE_AUTH => Ok(()),
+        s => match s.strip_prefix(SCOPE_ROSTER_READ_PREFIX) {
+            Some(tribe) if !tribe.trim().is_empty() => Ok(()),
+            _ => Err("Unknown scope"),
+        },
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ApiKeyRow {
+    id: String,
+    name: String,
+    key_prefix: String,
+    scopes: String,
+    created_by: i64,
+    created_at: DateTime<Utc>,
+    expires_at: Option<DateTime<Utc>>,
+    last_used_at: Option<DateTime<Utc>>,
+    revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyResponse {
+    pub id: String,
+    pub name: String,
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    #[serde(with = "i64_as_string")]
+    #[schema(value_type = String)]
+    pub created_by: i64,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<ApiKeyRow> for ApiKeyResponse {
+    fn from(row: ApiKeyRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            key_prefix: row.key_prefix,
+            scopes: row.scopes.split_whitespace().map(str::to_string).collect(),
+            created_by: row.created_by,
+            created_at: row.created_at,
+            expires_at: row.expires_at,
+            last_used_at: row.last_used_at,
+            revoked_at: row.revoked_at,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+    /// Days until the key expires; keys without one live until revoked
+    pub expires_in_days: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedApiKeyResponse {
+    /// The full key. Only returned once; store it securely.
+    pub secret: String,
+    pub api_key: ApiKeyResponse,
+}
+
+/// A caller authenticated with an API key from the `X-Api-Key` header.
+#[derive(Clone, Debug)]
+pub struct ApiKey {
+    pub id: String,
+    pub name: String,
+    /// User who issued the key; recorded as the actor of audited key actions
+    pub created_by: i64,
+    pub scopes: Vec<String>,
+}
+
+impl ApiKey {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|s| s == scope)
+    }
+
+    pub fn require_scope(&self, scope: &str) -> ApiResult<()> {
+        if self.has_scope(scope) {
+            Ok(())
+        } else {
+            Err((StatusCode::FORBIDDEN, "API key lacks the required scope"))
+        }
+    }
+}
+
+/// Resolve a raw key to an active API key, recording when it was last used.
+pub async fn authenticate(db: &DbPool, raw_key: &str) -> ApiResult<ApiKey> {
+    let row = sqlx::query_as::<_, ApiKeyRow>(
+        "SELECT * FROM api_keys WHERE key_hash = ? AND revoked_at IS NULL",
+    )
+    .bind(hash_key(raw_key))
+    .fetch_optional(db)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+    .ok_or((StatusCode::UNAUTHORIZED, "Invalid API Key"))?;
+
+    let now = Utc::now();
+    if row.expires_at.is_some_and(|t| t <= now) {
+        return Err((StatusCode::UNAUTHORIZED, "API Key expired"));
+    }
+
+    // Only touch the row once a minute so busy keys don't turn every request into a write
+    if row
+        .last_used_at
+        .is_none_or(|t| now - t > Duration::minutes(1))
+    {
+        if let Err(e) = sqlx::query("UPDATE api_keys SET last_used_at = ? WHERE id = ?")
+            .bind(now)
+            .bind(&row.id)
+            .execute(db)
+            .await
+        {
+            eprintln!("Failed to record API key use for {}: {}", row.id, e);
+        }
+    }
+
+    let scopes = current_scopes(db, row.created_by, &row.scopes)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    Ok(ApiKey {
+        scopes,
+        id: row.id,
+        name: row.name,
+        created_by: row.created_by,
+    })
+}
+
+/// A key's scopes, minus roster access to tribes where the issuer no longer holds
+/// `roster.read`, so demoting or removing a tribe admin also cuts off their keys.
+/// Keys issued by a current super admin keep all their scopes.
+async fn current_scopes(
+    db: &DbPool,
+    issuer: i64,
+    scopes: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    let scopes: Vec<String> = scopes.split_whitespace().map(str::to_string).collect();
+    if !scopes
+        .iter()
+        .any(|s| s.starts_with(SCOPE_ROSTER_READ_PREFIX))
+    {
+        return Ok(scopes);
+    }
+
+    let issuer_discord_id: Option<String> =
+        sqlx::query_scalar("SELECT discord_id FROM users WHERE id = ?")
+            .bind(issuer)
+            .fetch_optional(db)
+            .await?;
+    if let Some(discord_id) = issuer_discord_id {
+        if crate::super_admins::is_super_admin(db, &discord_id).await? {
+            return Ok(scopes);
+        }
+    }
+
+    let grants = permission_grants(db, issuer, Permission::RosterRead).await?;
+    Ok(scopes
+        .into_iter()
+        .filter(|s| {
+            s.strip_prefix(SCOPE_ROSTER_READ_PREFIX)
+                .is_none_or(|tribe| grants.allows(tribe))
+        })
+        .collect())
+}
+
+impl<S> FromRequestParts<S> for ApiKey
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if let Some(key) = parts.extensions.get::<ApiKey>() {
+            return Ok(key.clone());
+        }
+
+        let raw_key = parts
+            .headers
+            .get(API_KEY_HEADER)
+            .and_then(|h| h.to_str().ok())
+            .ok_or((StatusCode::UNAUTHORIZED, "Missing API Key"))?;
+
+        let state = AppState::from_ref(state);
+        authenticate(&state.db, raw_key).await
+    }
+}
+
+/// Authenticate `X-Api-Key` ahead of per-key rate limiting, so only real keys get a
+/// bucket. The key is left in the request extensions for the `ApiKey` extractor.
+pub async fn require_api_key(
+    State(state): State<AppState>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let Some(raw_key) = req
+        .headers()
+        .get(API_KEY_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .map(str::to_string)
+    else {
+        return (StatusCode::UNAUTHORIZED, "Missing API Key").into_response();
+    };
+
+    match authenticate(&state.db, &raw_key).await {
+        Ok(key) => {
+            req.extensions_mut().insert(key);
+            next.run(req).await
+        }
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Either a logged-in user or an API key. Requests carrying `X-Api-Key` are
+/// authenticated as the key, everything else falls back to the user JWT.
+#[derive(Clone)]
+pub enum Caller {
+    User(AuthenticatedUser),
+    ApiKey(ApiKey),
+}
+
+impl From<AuthenticatedUser> for Caller {
+    fn from(user: AuthenticatedUser) -> Self {
+        Caller::User(user)
+    }
+}
+
+impl<S> FromRequestParts<S> for Caller
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if parts.headers.contains_key(API_KEY_HEADER) {
+            ApiKey::from_request_parts(parts, state)
+                .await
+                .map(Caller::ApiKey)
+        } else {
+            AuthenticatedUser::from_request_parts(parts, state)
+                .await
+                .map(Caller::User)
+        }
+    }
+}
+
+/// Validate and store a new key, returning the secret alongside its metadata.
+async fn insert_key(
+    db: &DbPool,
+    created_by: i64,
+    payload: CreateApiKeyRequest,
+) -> ApiResult<CreatedApiKeyResponse> {
+    let name = payload.name.trim().to_string();
+    if name.is_empty() || name.len() > MAX_NAME_LENGTH {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Name must be between 1 and 100 characters",
+        ));
+    }
+    if payload.scopes.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "At least one scope is required"));
+    }
+    for scope in &payload.scopes {
+        validate_scope(scope).map_err(|msg| (StatusCode::BAD_REQUEST, msg))?;
+        if let Some(tribe) = scope.strip_prefix(SCOPE_ROSTER_READ_PREFIX) {
+            let tribe_exists: bool =
+                sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM tribes WHERE name = ?)")
+                    .bind(tribe)
+                    .fetch_one(db)
+                    .await
+                    .unwrap_or(false);
+            if !tribe_exists {
+                return Err((StatusCode::BAD_REQUEST, "Tribe does not exist"));
+            }
+        }
+    }
+
+    let now = Utc::now();
+    let expires_at = match payload.expires_in_days {
+        Some(days) if (1..=MAX_LIFETIME_DAYS).contains(&days) => Some(now + Duration::days(days)),
+        Some(_) => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                "expires_in_days must be between 1 and 3650",
+            ))
+        }
+        None => None,
+    };
+
+    let mut scopes = payload.scopes;
+    scopes.sort();
+    scopes.dedup();
+
+    let secret = generate_key();
+    let row = ApiKeyRow {
+        id: Uuid::new_v4().to_string(),
+        name,
+        key_prefix: secret[..DISPLAY_PREFIX_LEN].to_string(),
+        scopes: scopes.join(" "),
+        created_by,
+        created_at: now,
+        expires_at,
+        last_used_at: None,
+        revoked_at: None,
+    };
+
+    sqlx::query(
+        "INSERT INTO api_keys (id, name, key_prefix, key_hash, scopes, created_by, created_at, expires_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&row.id)
+    .bind(&row.name)
+    .bind(&row.key_prefix)
+    .bind(hash_key(&secret))
+    .bind(&row.scopes)
+    .bind(row.created_by)
+    .bind(row.created_at)
+    .bind(row.expires_at)
+    .execute(db)
+    .await
+    .map_err(|e| {
+        eprintln!("Failed to insert API key: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, "Database error")
+    })?;
+
+    Ok(CreatedApiKeyResponse {
+        secret,
+        api_key: row.into(),
+    })
+}
+
+async fn revoke_key(db: &DbPool, id: &str, created_by: Option<i64>) -> ApiResult<()> {
+    let res = sqlx::query(
+        "UPDATE api_keys SET revoked_at = ? WHERE id = ? AND revoked_at IS NULL AND (? IS NULL OR created_by = ?)",
+    )
+    .bind(Utc::now())
+    .bind(id)
+    .bind(created_by)
+    .bind(created_by)
+    .execute(db)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    if res.rows_affected() == 0 {
+        return Err((
+            StatusCode::NOT_FOUND,
+            "API key not found or already revoked",
+        ));
+    }
+    Ok(())
+}
+
+// --- Tribe admin endpoints ---
+
+#[utoipa::path(
+    get,
+    path = "/api/api-keys",
+    responses(
+        (status = 200, description = "API keys issued by the current user", body = Vec<ApiKeyResponse>)
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn list_my_api_keys(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let keys = sqlx::query_as::<_, ApiKeyRow>(
+        "SELECT * FROM api_keys WHERE created_by = ? ORDER BY created_at DESC",
+    )
+    .bind(auth_user.user_id)
+    .fetch_all(&state.db)
+    .await;
+
+    match keys {
+        Ok(rows) => Json(
+            rows.into_iter()
+                .map(ApiKeyResponse::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(e) => {
+            eprintln!("Failed to list API keys: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/api-keys",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 201, description = "API key created", body = CreatedApiKeyResponse),
+        (status = 400, description = "Invalid name, scope or expiry"),
+        (status = 403, description = "Scope outside the tribes the user administers")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn create_my_api_key(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<CreateApiKeyRequest>,
+) -> impl IntoResponse {
+    // Tribe admins may only issue roster access to the tribes they administer
+    for scope in &payload.scopes {
+        let Some(tribe) = scope.strip_prefix(SCOPE_ROSTER_READ_PREFIX) else {
+            return (
+                StatusCode::FORBIDDEN,
+                "Only super admins can issue this scope",
+            )
+                .into_response();
+        };
+        if let Err(e) = require_admin_in_tribe(&state.db, auth_user.user_id, Some(tribe)).await {
+            return e.into_response();
+        }
+    }
+
+    let created = match insert_key(&state.db, auth_user.user_id, payload).await {
+        Ok(c) => c,
+        Err(e) => return e.into_response(),
+    };
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::ApiKeyCreate,
+        auth_user.user_id,
+        None,
+        &format!(
+            "Created API key '{}' ({}) with scopes {}",
+            created.api_key.name,
+            created.api_key.id,
+            created.api_key.scopes.join(" ")
+        ),
+    )
+    .await;
+
+    (StatusCode::CREATED, Json(created)).into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/api-keys/{id}",
+    params(
+        ("id" = String, Path, description = "API key ID")
+    ),
+    responses(
+        (status = 200, description = "API key revoked"),
+        (status = 404, description = "API key not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn revoke_my_api_key(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = revoke_key(&state.db, &id, Some(auth_user.user_id)).await {
+        return e.into_response();
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::ApiKeyRevoke,
+        auth_user.user_id,
+        None,
+        &format!("Revoked API key {}", id),
+    )
+    .await;
+
+    StatusCode::OK.into_response()
+}
+
+// --- Super admin endpoints ---
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/api-keys",
+    tag = "Admin",
+    responses(
+        (status = 200, description = "All API keys", body = Vec<ApiKeyResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn list_api_keys(
+    State(state): State<AppState>,
+    _admin: RequireSuperAdmin,
+) -> impl IntoResponse {
+    let keys = sqlx::query_as::<_, ApiKeyRow>("SELECT * FROM api_keys ORDER BY created_at DESC")
+        .fetch_all(&state.db)
+        .await;
+
+    match keys {
+        Ok(rows) => Json(
+            rows.into_iter()
+                .map(ApiKeyResponse::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(e) => {
+            eprintln!("Failed to list API keys: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/api-keys",
+    tag = "Admin",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 201, description = "API key created", body = CreatedApiKeyResponse),
+        (status = 400, description = "Invalid name, scope or expiry"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    admin: RequireSuperAdmin,
+    Json(payload): Json<CreateApiKeyRequest>,
+) -> impl IntoResponse {
+    let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+
+    let created = match insert_key(&state.db, admin_id, payload).await {
+        Ok(c) => c,
+        Err(e) => return e.into_response(),
+    };
+
+    let details = format!(
+        "Created API key '{}' ({}) with scopes {}",
+        created.api_key.name,
+        created.api_key.id,
+        created.api_key.scopes.join(" ")
+    );
+    let _ = log_audit(
+        &state.db,
+        AuditAction::ApiKeyCreate,
+        admin_id,
+        None,
+        &details,
+    )
+    .await;
+
+    alert_admin_action(
+        format!("SuperAdmin ({})", admin.discord_id),
+        AuditAction::ApiKeyCreate,
+        details,
+    );
+
+    (StatusCode::CREATED, Json(created)).into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/api-keys/{id}",
+    tag = "Admin",
+    params(
+        ("id" = String, Path, description = "API key ID")
+    ),
+    responses(
+        (status = 200, description = "API key revoked"),
+        (status = 404, description = "API key not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn revoke_api_key(
+    State(state): State<AppState>,
+    admin: RequireSuperAdmin,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = revoke_key(&state.db, &id, None).await {
+        return e.into_response();
+    }
+
+    let details = format!("Revoked API key {}", id);
+    let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+    let _ = log_audit(
+        &state.db,
+        AuditAction::ApiKeyRevoke,
+        admin_id,
+        None,
+        &details,
+    )
+    .await;
+
+    alert_admin_action(
+        format!("SuperAdmin ({})", admin.discord_id),
+        AuditAction::ApiKeyRevoke,
+        details,
+    );
+
+    StatusCode::OK.into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{add_members, insert_users, setup_db};
+
+    async fn setup() -> DbPool {
+        let pool = setup_db().await;
+        insert_users(&pool, &[(1001, "admin-discord-id", "TribeAdmin")]).await;
+        sqlx::query("INSERT INTO tribes (name) VALUES ('Fire'), ('Water')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        add_members(&pool, &[(1001, "Fire", true)]).await;
+        pool
+    }
+
+    fn request(scopes: &[&str], expires_in_days: Option<i64>) -> CreateApiKeyRequest {
+        CreateApiKeyRequest {
+            name: "Roster bot".to_string(),
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+            expires_in_days,
+        }
+    }
+
+    #[test]
+    fn test_validate_scope() {
+        assert!(validate_scope("roster:read:Fire").is_ok());
+        assert!(validate_scope(SCOPE_VERIFY_READ).is_ok());
+        assert!(validate_scope(SCOPE_MUMBLE_AUTH).is_ok());
+        assert!(validate_scope("roster:read:").is_err());
+        assert!(validate_scope("roster:write:Fire").is_err());
+        assert!(validate_scope("admin").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_roster_scopes_follow_tribe_rename() {
+        let db = setup().await;
+        add_members(&db, &[(1001, "Water", true)]).await;
+        let created = insert_key(
+            &db,
+            1001,
+            request(&["roster:read:Fire", "roster:read:Water"], None),
+        )
+        .await
+        .unwrap();
+
+        let res = crate::admin::apply_update_tribe(
+            &AppState::new(db.clone()),
+            &crate::middleware::admin::RequireSuperAdmin {
+                discord_id: "admin-discord-id".to_string(),
+            },
+            "Fire".to_string(),
+            "Flame".to_string(),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let key = authenticate(&db, &created.secret).await.unwrap();
+        assert!(key.require_scope("roster:read:Flame").is_ok());
+        assert!(key.require_scope("roster:read:Fire").is_err());
+        assert!(key.require_scope("roster:read:Water").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_key_is_stored_hashed_and_authenticates() {
+        let db = setup().await;
+        let created = insert_key(&db, 1001, request(&["roster:read:Fire"], None))
+            .await
+            .unwrap();
+        assert!(created.secret.starts_with(KEY_PREFIX));
+        assert!(created.secret.starts_with(&created.api_key.key_prefix));
+
+        let stored: String = sqlx::query_scalar("SELECT key_hash FROM api_keys WHERE id = ?")
+            .bind(&created.api_key.id)
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_ne!(stored, created.secret);
+        assert_eq!(stored, hash_key(&created.secret));
+
+        let key = authenticate(&db, &created.secret).await.unwrap();
+        assert_eq!(key.created_by, 1001);
+        assert!(key.require_scope("roster:read:Fire").is_ok());
+        assert!(key.require_scope("roster:read:Water").is_err());
+
+        let last_used: Option<DateTime<Utc>> =
+            sqlx::query_scalar("SELECT last_used_at FROM api_keys WHERE id = ?")
+                .bind(&created.api_key.id)
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert!(last_used.is_some());
+
+        assert_eq!(
+            authenticate(&db, "veid_wrong").await.unwrap_err().0,
+            StatusCode::UNAUTHORIZED
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expired_and_revoked_keys_rejected() {
+        let db = setup().await;
+        let created = insert_key(&db, 1001, request(&[SCOPE_VERIFY_READ], Some(30)))
+            .await
+            .unwrap();
+
+        sqlx::query("UPDATE api_keys SET expires_at = ? WHERE id = ?")
+            .bind(Utc::now() - Duration::minutes(1))
+            .bind(&created.api_key.id)
+            .execute(&db)
+            .await
+            .unwrap();
+        assert_eq!(
+            authenticate(&db, &created.secret).await.unwrap_err().1,
+            "API Key expired"
+        );
+
+        let created = insert_key(&db, 1001, request(&[SCOPE_VERIFY_READ], None))
+            .await
+            .unwrap();
+        revoke_key(&db, &created.api_key.id, Some(1001))
+            .await
+            .unwrap();
+        assert!(authenticate(&db, &created.secret).await.is_err());
+        assert!(revoke_key(&db, &created.api_key.id, None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_insert_key_validation() {
+        let db = setup().await;
+        assert!(insert_key(&db, 1001, request(&[], None)).await.is_err());
+        assert!(insert_key(&db, 1001, request(&["roster:read:Earth"], None))
+            .await
+            .is_err());
+        assert!(
+            insert_key(&db, 1001, request(&[SCOPE_VERIFY_READ], Some(0)))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tribe_admin_scope_restrictions() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+        let user = || AuthenticatedUser { user_id: 1001 };
+
+        let res = create_my_api_key(
+            user(),
+            State(state.clone()),
+            Json(request(&["roster:read:Fire"], Some(90))),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::CREATED);
+
+        let res = create_my_api_key(
+            user(),
+            State(state.clone()),
+            Json(request(&["roster:read:Water"], None)),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        let res = create_my_api_key(
+            user(),
+            State(state),
+            Json(request(&[SCOPE_MUMBLE_AUTH], None)),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_roster_read_via_api_key() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+        let created = insert_key(&db, 1001, request(&["roster:read:Fire"], None))
+            .await
+            .unwrap();
+        let key = authenticate(&db, &created.secret).await.unwrap();
+
+        let query = |tribe: Option<&str>| crate::roster::RosterQuery {
+            tribe: tribe.map(str::to_string),
+            sort: None,
+            order: None,
+            search: None,
+            fields: None,
+            as_of: None,
+            limit: None,
+            cursor: None,
+            format: None,
+        };
+
+        let res = crate::roster::get_roster(
+            Caller::ApiKey(key.clone()),
+            axum::extract::Query(query(Some("Fire"))),
+            State(state.clone()),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = crate::roster::get_roster(
+            Caller::ApiKey(key.clone()),
+            axum::extract::Query(query(Some("Water"))),
+            State(state.clone()),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        let res = crate::roster::get_roster(
+            Caller::ApiKey(key),
+            axum::extract::Query(query(None)),
+            State(state),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let audit: String =
+            sqlx::query_scalar("SELECT details FROM audit_logs WHERE action = 'VIEW_ROSTER'")
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert!(audit.contains("via API key 'Roster bot'"));
+    }
+
+    #[tokio::test]
+    async fn test_roster_key_stops_working_when_issuer_demoted() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+        let created = insert_key(&db, 1001, request(&["roster:read:Fire"], None))
+            .await
+            .unwrap();
+
+        sqlx::query("UPDATE user_tribes SET is_admin = FALSE WHERE user_id = 1001")
+            .execute(&db)
+            .await
+            .unwrap();
+        let key = authenticate(&db, &created.secret).await.unwrap();
+        assert!(key.scopes.is_empty());
+
+        let res = crate::roster::get_roster(
+            Caller::ApiKey(key),
+            axum::extract::Query(crate::roster::RosterQuery {
+                tribe: Some("Fire".to_string()),
+                sort: None,
+                order: None,
+                search: None,
+                fields: None,
+                as_of: None,
+                limit: None,
+                cursor: None,
+                format: None,
+            }),
+            State(state),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        // A super admin's keys don't depend on their tribe roles
+        sqlx::query("INSERT INTO super_admins (discord_id) VALUES ('admin-discord-id')")
+            .execute(&db)
+            .await
+            .unwrap();
+        let key = authenticate(&db, &created.secret).await.unwrap();
+        assert_eq!(key.scopes, vec!["roster:read:Fire"]);
+    }
+}
@@ -0,0 +1,761 @@
+use crate::{
+    admin::{apply_delete_wallet, apply_update_tribe, apply_update_user, get_admin_id},
+    audit::{alert_admin_action, log_audit, AuditAction},
+    db::DbPool,
+    middleware::admin::RequireSuperAdmin,
+    state::AppState,
+    super_admins::is_super_admin,
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+const MAX_REASON_LENGTH: usize = 500;
+
+/// Super admin actions that can be configured to need a second approver
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardedAction {
+    DeleteWallet,
+    GrantGlobalAdmin,
+    UpdateTribe,
+}
+
+impl GuardedAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GuardedAction::DeleteWallet => "delete_wallet",
+            GuardedAction::GrantGlobalAdmin => "grant_global_admin",
+            GuardedAction::UpdateTribe => "update_tribe",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        [
+            GuardedAction::DeleteWallet,
+            GuardedAction::GrantGlobalAdmin,
+            GuardedAction::UpdateTribe,
+        ]
+        .into_iter()
+        .find(|a| a.as_str() == value)
+    }
+}
+
+/// Whether `FOUR_EYES_ACTIONS` (comma-separated) lists the action.
+pub fn requires_approval(action: GuardedAction) -> bool {
+    std::env::var("FOUR_EYES_ACTIONS")
+        .unwrap_or_default()
+        .split(',')
+        .any(|a| a.trim() == action.as_str())
+}
+
+/// How long a pending operation can wait for approval, from `FOUR_EYES_EXPIRY_HOURS`.
+fn expiry_hours() -> i64 {
+    std::env::var("FOUR_EYES_EXPIRY_HOURS")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(24)
+        .clamp(1, 720)
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ApprovalQuery {
+    /// Why the action is needed; required when it has to be approved
+    pub reason: Option<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct PendingOperationsQuery {
+    /// PENDING, APPROVED, REJECTED, EXPIRED or FAILED
+    pub status: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct PendingOperationRow {
+    id: String,
+    action: String,
+    payload: String,
+    reason: String,
+    target_id: Option<i64>,
+    requested_by: String,
+    requested_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+    status: String,
+    decided_by: Option<String>,
+    decided_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingOperation {
+    pub id: String,
+    pub action: String,
+    /// Arguments the action will run with once approved
+    #[schema(value_type = Object)]
+    pub payload: serde_json::Value,
+    pub reason: String,
+    #[serde(with = "crate::models::option_i64_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub target_id: Option<i64>,
+    /// Discord ID of the requesting super admin
+    pub requested_by: String,
+    pub requested_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub status: String,
+    pub decided_by: Option<String>,
+    pub decided_at: Option<DateTime<Utc>>,
+}
+
+impl From<PendingOperationRow> for PendingOperation {
+    fn from(row: PendingOperationRow) -> Self {
+        Self {
+            payload: serde_json::from_str(&row.payload).unwrap_or(serde_json::Value::Null),
+            id: row.id,
+            action: row.action,
+            reason: row.reason,
+            target_id: row.target_id,
+            requested_by: row.requested_by,
+            requested_at: row.requested_at,
+            expires_at: row.expires_at,
+            status: row.status,
+            decided_by: row.decided_by,
+            decided_at: row.decided_at,
+        }
+    }
+}
+
+/// Hold an action for approval instead of running it. Responds 202 with the pending operation.
+pub(crate) async fn request_approval(
+    state: &AppState,
+    admin: &RequireSuperAdmin,
+    action: GuardedAction,
+    target_id: Option<i64>,
+    payload: serde_json::Value,
+    reason: Option<String>,
+) -> Response {
+    let reason = reason.unwrap_or_default().trim().to_string();
+    if reason.is_empty() || reason.len() > MAX_REASON_LENGTH {
+        return (
+            StatusCode::BAD_REQUEST,
+            "A reason (up to 500 characters) is required for actions needing approval",
+        )
+            .into_response();
+    }
+
+    let now = Utc::now();
+    let op = PendingOperation {
+        id: Uuid::new_v4().to_string(),
+        action: action.as_str().to_string(),
+        payload,
+        reason,
+        target_id,
+        requested_by: admin.discord_id.clone(),
+        requested_at: now,
+        expires_at: now + Duration::hours(expiry_hours()),
+        status: "PENDING".to_string(),
+        decided_by: None,
+        decided_at: None,
+    };
+
+    let result = sqlx::query(
+        "INSERT INTO pending_operations (id, action, payload, reason, target_id, requested_by, requested_at, expires_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&op.id)
+    .bind(&op.action)
+    .bind(op.payload.to_string())
+    .bind(&op.reason)
+    .bind(op.target_id)
+    .bind(&op.requested_by)
+    .bind(op.requested_at)
+    .bind(op.expires_at)
+    .execute(&state.db)
+    .await;
+
+    if let Err(e) = result {
+        eprintln!("Failed to create pending operation: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    let details = format!(
+        "Requested {} ({}) {}, awaiting approval until {}: {}",
+        op.action, op.id, op.payload, op.expires_at, op.reason
+    );
+    let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+    let _ = log_audit(
+        &state.db,
+        AuditAction::PendingOperationCreate,
+        admin_id,
+        op.target_id,
+        &details,
+    )
+    .await;
+    alert_admin_action(
+        format!("SuperAdmin ({})", admin.discord_id),
+        AuditAction::PendingOperationCreate,
+        details,
+    );
+
+    (StatusCode::ACCEPTED, Json(op)).into_response()
+}
+
+/// Mark overdue operations as expired, auditing each against its requester.
+pub async fn expire_stale_operations(db: &DbPool) -> Result<(), sqlx::Error> {
+    let expired: Vec<(String, String, String, Option<i64>)> = sqlx::query_as(
+        "SELECT id, action, requested_by, target_id FROM pending_operations WHERE status = 'PENDING' AND expires_at <= ?",
+    )
+    .bind(Utc::now())
+    .fetch_all(db)
+    .await?;
+
+    for (id, action, requested_by, target_id) in expired {
+        let updated = sqlx::query(
+            "UPDATE pending_operations SET status = 'EXPIRED' WHERE id = ? AND status = 'PENDING'",
+        )
+        .bind(&id)
+        .execute(db)
+        .await?;
+        if updated.rows_affected() == 0 {
+            continue;
+        }
+
+        let requester: Option<i64> =
+            sqlx::query_scalar("SELECT id FROM users WHERE discord_id = ?")
+                .bind(&requested_by)
+                .fetch_optional(db)
+                .await?;
+        if let Some(requester) = requester {
+            let _ = log_audit(
+                db,
+                AuditAction::PendingOperationExpire,
+                requester,
+                target_id,
+                &format!("Pending {} ({}) expired without approval", action, id),
+            )
+            .await;
+        }
+    }
+    Ok(())
+}
+
+/// Reject every operation still pending from `requested_by` once they are no
+/// longer a super admin, auditing each against the deciding admin.
+pub async fn reject_operations_of(
+    db: &DbPool,
+    requested_by: &str,
+    decided_by: &str,
+    decided_by_id: i64,
+) -> Result<(), sqlx::Error> {
+    let pending: Vec<(String, String, Option<i64>)> = sqlx::query_as(
+        "SELECT id, action, target_id FROM pending_operations WHERE requested_by = ? AND status = 'PENDING'",
+    )
+    .bind(requested_by)
+    .fetch_all(db)
+    .await?;
+
+    for (id, action, target_id) in pending {
+        let updated = sqlx::query(
+            "UPDATE pending_operations SET status = 'REJECTED', decided_by = ?, decided_at = ? WHERE id = ? AND status = 'PENDING'",
+        )
+        .bind(decided_by)
+        .bind(Utc::now())
+        .bind(&id)
+        .execute(db)
+        .await?;
+        if updated.rows_affected() == 0 {
+            continue;
+        }
+
+        let _ = log_audit(
+            db,
+            AuditAction::PendingOperationReject,
+            decided_by_id,
+            target_id,
+            &format!(
+                "Rejected {} ({}) requested by {}: requester is no longer a super admin",
+                action, id, requested_by
+            ),
+        )
+        .await;
+    }
+    Ok(())
+}
+
+async fn fetch_operation(
+    db: &DbPool,
+    id: &str,
+) -> Result<Option<PendingOperationRow>, sqlx::Error> {
+    sqlx::query_as::<_, PendingOperationRow>("SELECT * FROM pending_operations WHERE id = ?")
+        .bind(id)
+        .fetch_optional(db)
+        .await
+}
+
+/// Run an approved operation with the requester as the acting super admin.
+async fn execute(state: &AppState, op: &PendingOperationRow) -> Response {
+    let requester = RequireSuperAdmin {
+        discord_id: op.requested_by.clone(),
+    };
+    let payload: serde_json::Value = match serde_json::from_str(&op.payload) {
+        Ok(p) => p,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Invalid operation").into_response(),
+    };
+
+    match GuardedAction::parse(&op.action) {
+        Some(GuardedAction::DeleteWallet) => match payload["wallet_id"].as_str() {
+            Some(wallet_id) => apply_delete_wallet(state, &requester, wallet_id.to_string()).await,
+            None => (StatusCode::INTERNAL_SERVER_ERROR, "Invalid operation").into_response(),
+        },
+        Some(GuardedAction::GrantGlobalAdmin) => {
+            let user_id = payload["user_id"].as_i64();
+            let update = serde_json::from_value(payload["update"].clone()).ok();
+            match (user_id, update) {
+                (Some(user_id), Some(update)) => {
+                    apply_update_user(state, &requester, user_id, update).await
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "Invalid operation").into_response(),
+            }
+        }
+        Some(GuardedAction::UpdateTribe) => {
+            match (payload["tribe"].as_str(), payload["name"].as_str()) {
+                (Some(tribe), Some(name)) => {
+                    apply_update_tribe(state, &requester, tribe.to_string(), name.to_string()).await
+                }
+                _ => (StatusCode::INTERNAL_SERVER_ERROR, "Invalid operation").into_response(),
+            }
+        }
+        None => (StatusCode::INTERNAL_SERVER_ERROR, "Invalid operation").into_response(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/pending-operations",
+    tag = "Admin",
+    params(PendingOperationsQuery),
+    responses(
+        (status = 200, description = "Pending and decided operations, newest first", body = Vec<PendingOperation>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn list_pending_operations(
+    State(state): State<AppState>,
+    _admin: RequireSuperAdmin,
+    Query(query): Query<PendingOperationsQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = expire_stale_operations(&state.db).await {
+        eprintln!("Failed to expire pending operations: {}", e);
+    }
+
+    let result = sqlx::query_as::<_, PendingOperationRow>(
+        "SELECT * FROM pending_operations WHERE (? IS NULL OR status = ?) ORDER BY requested_at DESC LIMIT 100",
+    )
+    .bind(&query.status)
+    .bind(&query.status)
+    .fetch_all(&state.db)
+    .await;
+
+    match result {
+        Ok(rows) => Json(
+            rows.into_iter()
+                .map(PendingOperation::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(e) => {
+            eprintln!("Failed to list pending operations: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/pending-operations/{id}/approve",
+    tag = "Admin",
+    params(
+        ("id" = String, Path, description = "Pending operation ID")
+    ),
+    responses(
+        (status = 200, description = "Approved and applied; the response is that of the action"),
+        (status = 403, description = "Requester cannot approve their own operation"),
+        (status = 404, description = "Operation not found"),
+        (status = 409, description = "Operation is no longer pending, or its requester is no longer a super admin"),
+        (status = 401, description = "Unauthorized"),
+    )
+)]
+pub async fn approve_operation(
+    State(state): State<AppState>,
+    admin: RequireSuperAdmin,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = expire_stale_operations(&state.db).await {
+        eprintln!("Failed to expire pending operations: {}", e);
+    }
+
+    let op = match fetch_operation(&state.db, &id).await {
+        Ok(Some(op)) => op,
+        Ok(None) => return (StatusCode::NOT_FOUND, "Operation not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+
+    if op.requested_by == admin.discord_id {
+        return (
+            StatusCode::FORBIDDEN,
+            "A different super admin must approve this operation",
+        )
+            .into_response();
+    }
+
+    // The operation is applied as its requester, so they must still hold the role
+    match is_super_admin(&state.db, &op.requested_by).await {
+        Ok(true) => {}
+        Ok(false) => {
+            let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+            if let Err(e) =
+                reject_operations_of(&state.db, &op.requested_by, &admin.discord_id, admin_id).await
+            {
+                eprintln!(
+                    "Failed to reject operations of a removed super admin: {}",
+                    e
+                );
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+            }
+            return (StatusCode::CONFLICT, "Requester is no longer a super admin").into_response();
+        }
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    }
+
+    // Claim the operation so it can only be applied once
+    let claimed = sqlx::query(
+        "UPDATE pending_operations SET status = 'APPROVED', decided_by = ?, decided_at = ? WHERE id = ? AND status = 'PENDING'",
+    )
+    .bind(&admin.discord_id)
+    .bind(Utc::now())
+    .bind(&id)
+    .execute(&state.db)
+    .await;
+
+    match claimed {
+        Ok(r) if r.rows_affected() == 1 => {}
+        Ok(_) => return (StatusCode::CONFLICT, "Operation is no longer pending").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    }
+
+    let details = format!(
+        "Approved {} ({}) requested by {}: {}",
+        op.action, op.id, op.requested_by, op.reason
+    );
+    let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+    let _ = log_audit(
+        &state.db,
+        AuditAction::PendingOperationApprove,
+        admin_id,
+        op.target_id,
+        &details,
+    )
+    .await;
+    alert_admin_action(
+        format!("SuperAdmin ({})", admin.discord_id),
+        AuditAction::PendingOperationApprove,
+        details,
+    );
+
+    let response = execute(&state, &op).await;
+    if !response.status().is_success() {
+        let _ = sqlx::query("UPDATE pending_operations SET status = 'FAILED' WHERE id = ?")
+            .bind(&id)
+            .execute(&state.db)
+            .await;
+    }
+    response
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/pending-operations/{id}/reject",
+    tag = "Admin",
+    params(
+        ("id" = String, Path, description = "Pending operation ID")
+    ),
+    responses(
+        (status = 200, description = "Operation rejected (or withdrawn by its requester)"),
+        (status = 404, description = "Operation not found"),
+        (status = 409, description = "Operation is no longer pending"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn reject_operation(
+    State(state): State<AppState>,
+    admin: RequireSuperAdmin,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    if let Err(e) = expire_stale_operations(&state.db).await {
+        eprintln!("Failed to expire pending operations: {}", e);
+    }
+
+    let op = match fetch_operation(&state.db, &id).await {
+        Ok(Some(op)) => op,
+        Ok(None) => return (StatusCode::NOT_FOUND, "Operation not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+
+    let rejected = sqlx::query(
+        "UPDATE pending_operations SET status = 'REJECTED', decided_by = ?, decided_at = ? WHERE id = ? AND status = 'PENDING'",
+    )
+    .bind(&admin.discord_id)
+    .bind(Utc::now())
+    .bind(&id)
+    .execute(&state.db)
+    .await;
+
+    match rejected {
+        Ok(r) if r.rows_affected() == 1 => {}
+        Ok(_) => return (StatusCode::CONFLICT, "Operation is no longer pending").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    }
+
+    let details = format!(
+        "Rejected {} ({}) requested by {}",
+        op.action, op.id, op.requested_by
+    );
+    let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+    let _ = log_audit(
+        &state.db,
+        AuditAction::PendingOperationReject,
+        admin_id,
+        op.target_id,
+        &details,
+    )
+    .await;
+    alert_admin_action(
+        format!("SuperAdmin ({})", admin.discord_id),
+        AuditAction::PendingOperationReject,
+        details,
+    );
+
+    StatusCode::OK.into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        admin::delete_wallet,
+        test_support::{insert_users, setup_db},
+    };
+
+    async fn setup() -> DbPool {
+        let pool = setup_db().await;
+        insert_users(
+            &pool,
+            &[
+                (1, "111", "User1"),
+                (2, "222", "User2"),
+                (3, "333", "User3"),
+            ],
+        )
+        .await;
+        for discord_id in ["111", "222", "333"] {
+            sqlx::query("INSERT INTO super_admins (discord_id) VALUES (?)")
+                .bind(discord_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO wallets (id, user_id, address, verified_at) VALUES ('w1', 3, '0xabc', ?)",
+        )
+        .bind(Utc::now())
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool
+    }
+
+    fn as_admin(discord_id: &str) -> RequireSuperAdmin {
+        RequireSuperAdmin {
+            discord_id: discord_id.to_string(),
+        }
+    }
+
+    async fn wallet_deleted(db: &DbPool) -> bool {
+        let deleted_at: Option<DateTime<Utc>> =
+            sqlx::query_scalar("SELECT deleted_at FROM wallets WHERE id = 'w1'")
+                .fetch_one(db)
+                .await
+                .unwrap();
+        deleted_at.is_some()
+    }
+
+    #[tokio::test]
+    async fn test_four_eyes_delete_wallet() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+        std::env::set_var("FOUR_EYES_ACTIONS", "delete_wallet, update_tribe");
+
+        // A reason is mandatory
+        let res = delete_wallet(
+            State(state.clone()),
+            as_admin("111"),
+            Path("w1".to_string()),
+            Query(ApprovalQuery { reason: None }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let res = delete_wallet(
+            State(state.clone()),
+            as_admin("111"),
+            Path("w1".to_string()),
+            Query(ApprovalQuery {
+                reason: Some("Compromised wallet".to_string()),
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::ACCEPTED);
+        assert!(!wallet_deleted(&db).await);
+
+        let id: String = sqlx::query_scalar("SELECT id FROM pending_operations")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+
+        // The requester cannot approve their own operation
+        let res = approve_operation(State(state.clone()), as_admin("111"), Path(id.clone()))
+            .await
+            .into_response();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        let res = approve_operation(State(state.clone()), as_admin("222"), Path(id.clone()))
+            .await
+            .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(wallet_deleted(&db).await);
+
+        // Already decided
+        let res = approve_operation(State(state), as_admin("222"), Path(id.clone()))
+            .await
+            .into_response();
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+
+        let actions: Vec<String> =
+            sqlx::query_scalar("SELECT action FROM audit_logs ORDER BY created_at")
+                .fetch_all(&db)
+                .await
+                .unwrap();
+        assert_eq!(
+            actions,
+            vec![
+                "PENDING_OPERATION_CREATE",
+                "PENDING_OPERATION_APPROVE",
+                "SUPER_ADMIN_DELETE_WALLET"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expired_operation_cannot_be_approved() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+
+        sqlx::query("INSERT INTO pending_operations (id, action, payload, reason, requested_by, requested_at, expires_at) VALUES ('op1', 'delete_wallet', '{\"wallet_id\":\"w1\"}', 'cleanup', '111', ?, ?)")
+            .bind(Utc::now() - Duration::hours(48))
+            .bind(Utc::now() - Duration::hours(24))
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let res = approve_operation(State(state), as_admin("222"), Path("op1".to_string()))
+            .await
+            .into_response();
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+        assert!(!wallet_deleted(&db).await);
+
+        let status: String =
+            sqlx::query_scalar("SELECT status FROM pending_operations WHERE id = 'op1'")
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(status, "EXPIRED");
+
+        let expiry_audits: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM audit_logs WHERE action = 'PENDING_OPERATION_EXPIRE' AND actor_id = 1",
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(expiry_audits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_reject_operation() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+
+        sqlx::query("INSERT INTO pending_operations (id, action, payload, reason, requested_by, requested_at, expires_at) VALUES ('op2', 'update_tribe', '{\"tribe\":\"Fire\",\"name\":\"Ice\"}', 'rebrand', '111', ?, ?)")
+            .bind(Utc::now())
+            .bind(Utc::now() + Duration::hours(24))
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let res = reject_operation(State(state.clone()), as_admin("222"), Path("op2".into()))
+            .await
+            .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = approve_operation(State(state), as_admin("333"), Path("op2".into()))
+            .await
+            .into_response();
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_operation_of_removed_super_admin_is_rejected() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+
+        sqlx::query("INSERT INTO pending_operations (id, action, payload, reason, requested_by, requested_at, expires_at) VALUES ('op3', 'delete_wallet', '{\"wallet_id\":\"w1\"}', 'cleanup', '111', ?, ?)")
+            .bind(Utc::now())
+            .bind(Utc::now() + Duration::hours(24))
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM super_admins WHERE discord_id = '111'")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let res = approve_operation(State(state), as_admin("222"), Path("op3".into()))
+            .await
+            .into_response();
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+        assert!(!wallet_deleted(&db).await);
+
+        let (status, decided_by): (String, Option<String>) =
+            sqlx::query_as("SELECT status, decided_by FROM pending_operations WHERE id = 'op3'")
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(status, "REJECTED");
+        assert_eq!(decided_by.as_deref(), Some("222"));
+
+        let rejections: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM audit_logs WHERE action = 'PENDING_OPERATION_REJECT' AND actor_id = 2",
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(rejections, 1);
+    }
+}
@@ -0,0 +1,880 @@
+use crate::{
+    audit::{log_audit, AuditAction},
+    auth::AuthenticatedUser,
+    db::DbPool,
+    helpers::{attachment_disposition, get_user_by_id},
+    note_crypto::{self, NoteKeys},
+    notes::note_for_action,
+    rbac::{has_permission_in_tribe, Permission},
+    state::AppState,
+    storage::BlobStore,
+    totp::StepUp,
+};
+use axum::{
+    extract::{Multipart, Path, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+const DEFAULT_MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+pub const MAX_ATTACHMENTS_PER_NOTE: i64 = 10;
+/// Room for multipart boundaries and headers on top of the file itself
+pub const MULTIPART_OVERHEAD_BYTES: usize = 64 * 1024;
+const MAX_FILE_NAME_CHARS: usize = 200;
+const PURGE_BATCH_SIZE: i64 = 200;
+/// How often files left behind by deleted notes and accounts are removed
+const PURGE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Largest accepted file, from `ATTACHMENT_MAX_BYTES` (default 10 MiB).
+pub fn max_attachment_bytes() -> usize {
+    std::env::var("ATTACHMENT_MAX_BYTES")
+        .ok()
+        .and_then(|v| v.trim().parse().ok())
+        .filter(|&n: &usize| n > 0)
+        .unwrap_or(DEFAULT_MAX_ATTACHMENT_BYTES)
+}
+
+/// The content type of an accepted file, judged from its bytes rather than
+/// what the client claims. Screenshots, PDFs and plain-text logs are allowed.
+fn detect_content_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(b"\xff\xd8\xff") {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if bytes.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else if !bytes.contains(&0) && std::str::from_utf8(bytes).is_ok() {
+        Some("text/plain; charset=utf-8")
+    } else {
+        None
+    }
+}
+
+/// Keep the last path component, drop control characters and quotes, and cap the length.
+fn clean_file_name(name: Option<&str>) -> String {
+    let base = name
+        .unwrap_or_default()
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default();
+    let cleaned: String = base
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"')
+        .take(MAX_FILE_NAME_CHARS)
+        .collect();
+    let cleaned = cleaned.trim();
+    if cleaned.is_empty() || cleaned == "." || cleaned == ".." {
+        "attachment".to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteAttachment {
+    pub id: String,
+    pub note_id: String,
+    #[serde(with = "crate::models::option_i64_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub uploader_id: Option<i64>,
+    pub file_name: String,
+    pub content_type: String,
+    pub size: i64,
+    /// Hex SHA-256 of the file as uploaded
+    pub sha256: String,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub storage_key: String,
+    #[serde(skip)]
+    pub key_id: Option<String>,
+}
+
+async fn fetch_attachment(
+    db: &DbPool,
+    note_id: &str,
+    attachment_id: &str,
+) -> Result<Option<NoteAttachment>, sqlx::Error> {
+    sqlx::query_as("SELECT * FROM note_attachments WHERE id = ? AND note_id = ?")
+        .bind(attachment_id)
+        .bind(note_id)
+        .fetch_optional(db)
+        .await
+}
+
+/// Remove files whose attachment rows are gone. Files that fail to delete stay
+/// queued for the next run.
+pub async fn purge_deleted(db: &DbPool, store: &dyn BlobStore) -> Result<u64, sqlx::Error> {
+    let mut purged = 0;
+    let mut after = String::new();
+    loop {
+        let keys: Vec<String> = sqlx::query_scalar(
+            "SELECT storage_key FROM attachment_purge_queue WHERE storage_key > ? ORDER BY storage_key LIMIT ?",
+        )
+        .bind(&after)
+        .bind(PURGE_BATCH_SIZE)
+        .fetch_all(db)
+        .await?;
+        let Some(last) = keys.last() else { break };
+        after = last.clone();
+
+        for key in keys {
+            if let Err(e) = store.delete(&key).await {
+                eprintln!("Failed to remove attachment file {}: {}", key, e);
+                continue;
+            }
+            sqlx::query("DELETE FROM attachment_purge_queue WHERE storage_key = ?")
+                .bind(&key)
+                .execute(db)
+                .await?;
+            purged += 1;
+        }
+    }
+    Ok(purged)
+}
+
+/// Purge now, logging rather than failing; used after deletions.
+pub async fn purge_deleted_now(db: &DbPool, store: &dyn BlobStore) {
+    if let Err(e) = purge_deleted(db, store).await {
+        eprintln!("Attachment purge failed: {}", e);
+    }
+}
+
+pub fn spawn_attachment_purger(db: DbPool, store: std::sync::Arc<dyn BlobStore>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            match purge_deleted(&db, store.as_ref()).await {
+                Ok(0) => {}
+                Ok(n) => println!("Removed {} deleted attachment file(s)", n),
+                Err(e) => eprintln!("Attachment purge failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Move attachments on older keys (or still plaintext) to the current key.
+/// Each is written under a new storage key before the row switches over, so a
+/// crash never leaves a row pointing at a file it can't open. Returns the
+/// number moved and the number that couldn't be opened.
+pub async fn reseal_attachments(
+    db: &DbPool,
+    store: &dyn BlobStore,
+    keys: Option<&NoteKeys>,
+) -> Result<(u64, u64), sqlx::Error> {
+    let current = keys.map(|k| k.current_key_id().to_string());
+    let (mut moved, mut failed) = (0, 0);
+    let mut after = String::new();
+    loop {
+        let batch: Vec<NoteAttachment> = sqlx::query_as(
+            "SELECT * FROM note_attachments WHERE id > ? AND key_id IS NOT ? ORDER BY id LIMIT ?",
+        )
+        .bind(&after)
+        .bind(&current)
+        .bind(PURGE_BATCH_SIZE)
+        .fetch_all(db)
+        .await?;
+        let Some(last) = batch.last() else { break };
+        after = last.id.clone();
+
+        for attachment in batch {
+            let bytes = match store.get(&attachment.storage_key).await {
+                Ok(Some(stored)) => note_crypto::open_bytes(
+                    keys,
+                    &attachment.id,
+                    attachment.key_id.as_deref(),
+                    stored,
+                ),
+                Ok(None) => None,
+                Err(e) => {
+                    eprintln!("Failed to read attachment {}: {}", attachment.id, e);
+                    None
+                }
+            };
+            let Some(bytes) = bytes else {
+                eprintln!(
+                    "Attachment {} could not be opened with the configured keys",
+                    attachment.id
+                );
+                failed += 1;
+                continue;
+            };
+
+            let (sealed, key_id) = note_crypto::seal_bytes(keys, &attachment.id, &bytes);
+            let storage_key = Uuid::new_v4().to_string();
+            if let Err(e) = store.put(&storage_key, sealed).await {
+                eprintln!("Failed to write attachment {}: {}", attachment.id, e);
+                failed += 1;
+                continue;
+            }
+            let mut tx = db.begin().await?;
+            sqlx::query("UPDATE note_attachments SET storage_key = ?, key_id = ? WHERE id = ?")
+                .bind(&storage_key)
+                .bind(&key_id)
+                .bind(&attachment.id)
+                .execute(&mut *tx)
+                .await?;
+            sqlx::query("INSERT OR IGNORE INTO attachment_purge_queue (storage_key) VALUES (?)")
+                .bind(&attachment.storage_key)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            moved += 1;
+        }
+    }
+    purge_deleted_now(db, store).await;
+    Ok((moved, failed))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/notes/{note_id}/attachments",
+    params(
+        ("note_id" = String, Path, description = "Note ID")
+    ),
+    request_body(content_type = "multipart/form-data", description = "A single `file` part"),
+    responses(
+        (status = 201, description = "Attachment stored", body = NoteAttachment),
+        (status = 400, description = "Missing file, empty file or too many attachments"),
+        (status = 403, description = "Forbidden: Missing notes.write in the note's tribe"),
+        (status = 404, description = "Note not found"),
+        (status = 413, description = "File too large"),
+        (status = 415, description = "File type not allowed")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn upload_attachment(
+    Path(note_id): Path<String>,
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    mut multipart: Multipart,
+) -> impl IntoResponse {
+    let user = match get_user_by_id(&state.db, auth_user.user_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return (StatusCode::NOT_FOUND, "User not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+
+    let note = match note_for_action(&state, &note_id, &user, Permission::NotesWrite).await {
+        Ok(n) => n,
+        Err(e) => return e.into_response(),
+    };
+
+    // Cheap early refusal; the insert below enforces the cap atomically
+    let existing: i64 =
+        match sqlx::query_scalar("SELECT COUNT(*) FROM note_attachments WHERE note_id = ?")
+            .bind(&note_id)
+            .fetch_one(&state.db)
+            .await
+        {
+            Ok(n) => n,
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+        };
+    if existing >= MAX_ATTACHMENTS_PER_NOTE {
+        return (
+            StatusCode::BAD_REQUEST,
+            "Note already has the maximum number of attachments",
+        )
+            .into_response();
+    }
+
+    let max_bytes = max_attachment_bytes();
+    let mut upload = None;
+    loop {
+        match multipart.next_field().await {
+            Ok(Some(field)) if field.name() == Some("file") => {
+                let file_name = clean_file_name(field.file_name());
+                match field.bytes().await {
+                    Ok(bytes) => upload = Some((file_name, bytes)),
+                    Err(_) => {
+                        return (StatusCode::PAYLOAD_TOO_LARGE, "File too large").into_response()
+                    }
+                }
+                break;
+            }
+            Ok(Some(_)) => continue,
+            Ok(None) => break,
+            Err(_) => return (StatusCode::BAD_REQUEST, "Invalid multipart body").into_response(),
+        }
+    }
+    let Some((file_name, bytes)) = upload else {
+        return (StatusCode::BAD_REQUEST, "Missing file").into_response();
+    };
+    if bytes.is_empty() {
+        return (StatusCode::BAD_REQUEST, "File is empty").into_response();
+    }
+    if bytes.len() > max_bytes {
+        return (StatusCode::PAYLOAD_TOO_LARGE, "File too large").into_response();
+    }
+    let Some(content_type) = detect_content_type(&bytes) else {
+        return (
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "Only PNG, JPEG, GIF, WebP, PDF and plain text files can be attached",
+        )
+            .into_response();
+    };
+
+    let id = Uuid::new_v4().to_string();
+    let storage_key = Uuid::new_v4().to_string();
+    let (sealed, key_id) = note_crypto::seal_bytes(state.note_keys.as_deref(), &id, &bytes);
+    if let Err(e) = state.attachment_store.put(&storage_key, sealed).await {
+        eprintln!("Failed to store attachment: {}", e);
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to store attachment",
+        )
+            .into_response();
+    }
+
+    let attachment = NoteAttachment {
+        id,
+        note_id: note_id.clone(),
+        uploader_id: Some(user.id),
+        file_name,
+        content_type: content_type.to_string(),
+        size: bytes.len() as i64,
+        sha256: hex::encode(Sha256::digest(&bytes)),
+        created_at: Utc::now(),
+        storage_key,
+        key_id,
+    };
+    let result = sqlx::query(
+        r#"
+        INSERT INTO note_attachments (id, note_id, uploader_id, file_name, content_type, size, sha256, storage_key, key_id, created_at)
+        SELECT ?, ?, ?, ?, ?, ?, ?, ?, ?, ?
+        WHERE (SELECT COUNT(*) FROM note_attachments WHERE note_id = ?) < ?
+        "#,
+    )
+    .bind(&attachment.id)
+    .bind(&attachment.note_id)
+    .bind(attachment.uploader_id)
+    .bind(&attachment.file_name)
+    .bind(&attachment.content_type)
+    .bind(attachment.size)
+    .bind(&attachment.sha256)
+    .bind(&attachment.storage_key)
+    .bind(&attachment.key_id)
+    .bind(attachment.created_at)
+    .bind(&attachment.note_id)
+    .bind(MAX_ATTACHMENTS_PER_NOTE)
+    .execute(&state.db)
+    .await;
+    match result {
+        Ok(r) if r.rows_affected() == 0 => {
+            // Another upload filled the last slot while this one was stored
+            let _ = state.attachment_store.delete(&attachment.storage_key).await;
+            return (
+                StatusCode::BAD_REQUEST,
+                "Note already has the maximum number of attachments",
+            )
+                .into_response();
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Failed to record attachment: {}", e);
+            let _ = state.attachment_store.delete(&attachment.storage_key).await;
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to store attachment",
+            )
+                .into_response();
+        }
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::NoteEdit,
+        user.id,
+        Some(note.target_user_id),
+        &format!(
+            "Attached {} ({} bytes, sha256 {}) to note {} in tribe {}",
+            attachment.content_type, attachment.size, attachment.sha256, note.id, note.tribe
+        ),
+    )
+    .await;
+
+    (StatusCode::CREATED, Json(attachment)).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/notes/{note_id}/attachments",
+    params(
+        ("note_id" = String, Path, description = "Note ID")
+    ),
+    responses(
+        (status = 200, description = "Attachments on the note, oldest first", body = Vec<NoteAttachment>),
+        (status = 403, description = "Forbidden: Missing notes.read in the note's tribe"),
+        (status = 404, description = "Note not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn list_attachments(
+    Path(note_id): Path<String>,
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+) -> impl IntoResponse {
+    let user = match get_user_by_id(&state.db, auth_user.user_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return (StatusCode::NOT_FOUND, "User not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+    if let Err(e) = note_for_action(&state, &note_id, &user, Permission::NotesRead).await {
+        return e.into_response();
+    }
+
+    match sqlx::query_as::<_, NoteAttachment>(
+        "SELECT * FROM note_attachments WHERE note_id = ? ORDER BY created_at, id",
+    )
+    .bind(&note_id)
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(attachments) => Json(attachments).into_response(),
+        Err(e) => {
+            eprintln!("Failed to list attachments: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/notes/{note_id}/attachments/{attachment_id}",
+    params(
+        ("note_id" = String, Path, description = "Note ID"),
+        ("attachment_id" = String, Path, description = "Attachment ID")
+    ),
+    responses(
+        (status = 200, description = "The file, as a download"),
+        (status = 403, description = "Forbidden: Missing notes.read in the note's tribe"),
+        (status = 404, description = "Note or attachment not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn download_attachment(
+    Path((note_id, attachment_id)): Path<(String, String)>,
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+) -> impl IntoResponse {
+    let user = match get_user_by_id(&state.db, auth_user.user_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return (StatusCode::NOT_FOUND, "User not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+    // Same audience as the note itself
+    if let Err(e) = note_for_action(&state, &note_id, &user, Permission::NotesRead).await {
+        return e.into_response();
+    }
+
+    let attachment = match fetch_attachment(&state.db, &note_id, &attachment_id).await {
+        Ok(Some(a)) => a,
+        Ok(None) => return (StatusCode::NOT_FOUND, "Attachment not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+
+    let stored = match state.attachment_store.get(&attachment.storage_key).await {
+        Ok(Some(bytes)) => bytes,
+        Ok(None) => {
+            eprintln!("Attachment {} has no stored file", attachment.id);
+            return (StatusCode::NOT_FOUND, "Attachment not found").into_response();
+        }
+        Err(e) => {
+            eprintln!("Failed to read attachment {}: {}", attachment.id, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "Failed to read attachment",
+            )
+                .into_response();
+        }
+    };
+    let Some(bytes) = note_crypto::open_bytes(
+        state.note_keys.as_deref(),
+        &attachment.id,
+        attachment.key_id.as_deref(),
+        stored,
+    ) else {
+        eprintln!("Failed to decrypt attachment {}", attachment.id);
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to decrypt attachment",
+        )
+            .into_response();
+    };
+
+    (
+        [
+            (header::CONTENT_TYPE, attachment.content_type),
+            (
+                header::CONTENT_DISPOSITION,
+                attachment_disposition(&attachment.file_name),
+            ),
+            (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+            (header::CACHE_CONTROL, "private, no-store".to_string()),
+        ],
+        bytes,
+    )
+        .into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/notes/{note_id}/attachments/{attachment_id}",
+    params(
+        ("note_id" = String, Path, description = "Note ID"),
+        ("attachment_id" = String, Path, description = "Attachment ID")
+    ),
+    responses(
+        (status = 200, description = "Attachment removed"),
+        (status = 403, description = "Forbidden: Not the uploader or a tribe admin, or step-up required"),
+        (status = 404, description = "Note or attachment not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn delete_attachment(
+    Path((note_id, attachment_id)): Path<(String, String)>,
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    _step_up: StepUp,
+) -> impl IntoResponse {
+    let user = match get_user_by_id(&state.db, auth_user.user_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return (StatusCode::NOT_FOUND, "User not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+    let note = match note_for_action(&state, &note_id, &user, Permission::NotesWrite).await {
+        Ok(n) => n,
+        Err(e) => return e.into_response(),
+    };
+    let attachment = match fetch_attachment(&state.db, &note_id, &attachment_id).await {
+        Ok(Some(a)) => a,
+        Ok(None) => return (StatusCode::NOT_FOUND, "Attachment not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+
+    // Uploaders can remove their own files; tribe admins can remove anyone's
+    if attachment.uploader_id != Some(user.id) {
+        match has_permission_in_tribe(&state.db, user.id, &note.tribe, Permission::AdminGrant).await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                return (
+                    StatusCode::FORBIDDEN,
+                    "Only the uploader or a tribe admin can remove this attachment",
+                )
+                    .into_response()
+            }
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+        }
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM note_attachments WHERE id = ?")
+        .bind(&attachment.id)
+        .execute(&state.db)
+        .await
+    {
+        eprintln!("Failed to delete attachment: {}", e);
+        return (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Failed to delete attachment",
+        )
+            .into_response();
+    }
+    purge_deleted_now(&state.db, state.attachment_store.as_ref()).await;
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::NoteEdit,
+        user.id,
+        Some(note.target_user_id),
+        &format!(
+            "Removed attachment {} (sha256 {}) from note {} in tribe {}",
+            attachment.id, attachment.sha256, note.id, note.tribe
+        ),
+    )
+    .await;
+
+    StatusCode::OK.into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::FsBlobStore;
+    use axum::{body::Body, extract::FromRequest, http::Request};
+    use sqlx::sqlite::SqlitePoolOptions;
+    use std::sync::Arc;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\nfake image data";
+
+    async fn setup() -> (AppState, std::path::PathBuf) {
+        // One connection so concurrent handlers share the in-memory database
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+
+        for (id, discord_id, username) in [
+            (1, "111", "Chief"),
+            (2, "222", "Target"),
+            (3, "333", "Recruiter"),
+            (4, "444", "Outsider"),
+        ] {
+            sqlx::query("INSERT INTO users (id, discord_id, username, discriminator) VALUES (?, ?, ?, '0000')")
+                .bind(id)
+                .bind(discord_id)
+                .bind(username)
+                .execute(&db)
+                .await
+                .unwrap();
+        }
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe, is_admin) VALUES (1, 'Fire', TRUE), (3, 'Fire', FALSE), (4, 'Water', TRUE)")
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO user_roles (id, user_id, role_id, tribe) VALUES ('r1', 3, 'recruiter', 'Fire')")
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO notes (id, target_user_id, author_id, tribe, content) VALUES ('n1', 2, 1, 'Fire', 'Killmail attached')")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let root = std::env::temp_dir().join(format!("void-eid-attachments-{}", Uuid::new_v4()));
+        let mut state = AppState::new(db);
+        state.attachment_store = Arc::new(FsBlobStore::new(&root));
+        state.note_keys = Some(Arc::new(
+            NoteKeys::new("k1", [("k1".to_string(), [3u8; 32])], [4u8; 32]).unwrap(),
+        ));
+        (state, root)
+    }
+
+    async fn multipart(file_name: &str, bytes: &[u8]) -> Multipart {
+        let boundary = "void-eid-boundary";
+        let mut body = format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\nContent-Type: image/png\r\n\r\n"
+        )
+        .into_bytes();
+        body.extend_from_slice(bytes);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+        let req = Request::builder()
+            .method("POST")
+            .header(
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .body(Body::from(body))
+            .unwrap();
+        Multipart::from_request(req, &()).await.unwrap()
+    }
+
+    async fn upload(
+        state: &AppState,
+        user_id: i64,
+        file_name: &str,
+        bytes: &[u8],
+    ) -> axum::response::Response {
+        upload_attachment(
+            Path("n1".to_string()),
+            State(state.clone()),
+            AuthenticatedUser { user_id },
+            multipart(file_name, bytes).await,
+        )
+        .await
+        .into_response()
+    }
+
+    #[test]
+    fn test_content_type_and_file_name() {
+        assert_eq!(detect_content_type(PNG), Some("image/png"));
+        assert_eq!(detect_content_type(b"%PDF-1.7"), Some("application/pdf"));
+        assert_eq!(
+            detect_content_type("[12:00] chat log".as_bytes()),
+            Some("text/plain; charset=utf-8")
+        );
+        assert_eq!(detect_content_type(b"MZ\x90\x00\x03"), None);
+
+        assert_eq!(clean_file_name(Some("C:\\shots\\kill.png")), "kill.png");
+        assert_eq!(clean_file_name(Some("../../a\"b\r\n.png")), "ab.png");
+        assert_eq!(clean_file_name(Some("..")), "attachment");
+        assert_eq!(clean_file_name(None), "attachment");
+    }
+
+    #[tokio::test]
+    async fn test_attachment_lifecycle() {
+        let (state, root) = setup().await;
+
+        let res = upload(&state, 3, "kill.png", PNG).await;
+        assert_eq!(res.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let id = json["id"].as_str().unwrap().to_string();
+        assert_eq!(json["contentType"], "image/png");
+        assert_eq!(json["fileName"], "kill.png");
+
+        // Sealed in the store
+        let key: String = sqlx::query_scalar("SELECT storage_key FROM note_attachments")
+            .fetch_one(&state.db)
+            .await
+            .unwrap();
+        let stored = state.attachment_store.get(&key).await.unwrap().unwrap();
+        assert!(!stored.windows(4).any(|w| w == b"fake"));
+
+        // Executables are refused whatever the client claims
+        let res = upload(&state, 3, "tool.png", b"MZ\x90\x00\x03\x00").await;
+        assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let download = |user_id: i64| {
+            download_attachment(
+                Path(("n1".to_string(), id.clone())),
+                State(state.clone()),
+                AuthenticatedUser { user_id },
+            )
+        };
+        let res = download(1).await.into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "image/png");
+        assert_eq!(
+            res.headers()[header::CONTENT_DISPOSITION],
+            "attachment; filename=\"kill.png\"; filename*=UTF-8''kill.png"
+        );
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], PNG);
+        // Admins of another tribe can't fetch it
+        assert_eq!(
+            download(4).await.into_response().status(),
+            StatusCode::FORBIDDEN
+        );
+
+        // Only the uploader or a tribe admin may remove it
+        let remove = |user_id: i64| {
+            delete_attachment(
+                Path(("n1".to_string(), id.clone())),
+                State(state.clone()),
+                AuthenticatedUser { user_id },
+                StepUp,
+            )
+        };
+        assert_eq!(
+            remove(4).await.into_response().status(),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(remove(1).await.into_response().status(), StatusCode::OK);
+        assert_eq!(state.attachment_store.get(&key).await.unwrap(), None);
+
+        // Deleting the note, softly or with the account, clears its files
+        let res = upload(&state, 1, "log.txt", b"[12:00] spy talk").await;
+        assert_eq!(res.status(), StatusCode::CREATED);
+        let key: String = sqlx::query_scalar("SELECT storage_key FROM note_attachments")
+            .fetch_one(&state.db)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE notes SET deleted_at = CURRENT_TIMESTAMP WHERE id = 'n1'")
+            .execute(&state.db)
+            .await
+            .unwrap();
+        assert_eq!(
+            purge_deleted(&state.db, state.attachment_store.as_ref())
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(state.attachment_store.get(&key).await.unwrap(), None);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn test_attachment_reseal() {
+        let (mut state, root) = setup().await;
+        state.note_keys = None;
+        let res = upload(&state, 1, "kill.png", PNG).await;
+        assert_eq!(res.status(), StatusCode::CREATED);
+
+        // Plaintext files are sealed once keys are configured
+        let keys = NoteKeys::new("k2", [("k2".to_string(), [5u8; 32])], [4u8; 32]).unwrap();
+        let (moved, failed) =
+            reseal_attachments(&state.db, state.attachment_store.as_ref(), Some(&keys))
+                .await
+                .unwrap();
+        assert_eq!((moved, failed), (1, 0));
+        let (id, key, key_id): (String, String, Option<String>) =
+            sqlx::query_as("SELECT id, storage_key, key_id FROM note_attachments")
+                .fetch_one(&state.db)
+                .await
+                .unwrap();
+        assert_eq!(key_id.as_deref(), Some("k2"));
+        let stored = state.attachment_store.get(&key).await.unwrap().unwrap();
+        assert_eq!(
+            note_crypto::open_bytes(Some(&keys), &id, Some("k2"), stored).as_deref(),
+            Some(PNG)
+        );
+        // The plaintext copy is gone
+        let queued: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM attachment_purge_queue")
+            .fetch_one(&state.db)
+            .await
+            .unwrap();
+        assert_eq!(queued, 0);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+
+    #[tokio::test]
+    async fn test_attachment_cap_holds_under_concurrent_uploads() {
+        let (state, root) = setup().await;
+        for i in 1..MAX_ATTACHMENTS_PER_NOTE {
+            sqlx::query("INSERT INTO note_attachments (id, note_id, file_name, content_type, size, sha256, storage_key, created_at) VALUES (?, 'n1', 'old.png', 'image/png', 1, '', ?, CURRENT_TIMESTAMP)")
+                .bind(format!("a{i}"))
+                .bind(format!("k{i}"))
+                .execute(&state.db)
+                .await
+                .unwrap();
+        }
+
+        // Every upload passes the early count check before any of them is recorded
+        let (a, b, c) = tokio::join!(
+            upload(&state, 1, "a.png", PNG),
+            upload(&state, 1, "b.png", PNG),
+            upload(&state, 1, "c.png", PNG)
+        );
+        let created = [a, b, c]
+            .iter()
+            .filter(|r| r.status() == StatusCode::CREATED)
+            .count();
+        assert_eq!(created, 1);
+        let count: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM note_attachments WHERE note_id = 'n1'")
+                .fetch_one(&state.db)
+                .await
+                .unwrap();
+        assert_eq!(count, MAX_ATTACHMENTS_PER_NOTE);
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -17,13 +17,56 @@ pub enum AuditAction {
     TribeLeave,
     NoteCreate,
     NoteEdit,
+    NoteDelete,
+    NoteViewRevisions,
     MumbleCreateAccount,
     MumbleLogin,
     SuperAdminUpdateUser,
     SuperAdminCreateTribe,
     SuperAdminUpdateTribe,
     SuperAdminDeleteWallet,
+    SuperAdminUpdateReverifyPolicy,
     DeleteUser,
+    WalletReverify,
+    WalletStale,
+    WalletUpdate,
+    ApiKeyCreate,
+    ApiKeyRevoke,
+    IdentityLookup,
+    RoleCreate,
+    RoleDelete,
+    RoleAssign,
+    RoleUnassign,
+    SuperAdminNominate,
+    SuperAdminRemove,
+    PendingOperationCreate,
+    PendingOperationApprove,
+    PendingOperationReject,
+    PendingOperationExpire,
+    TotpEnroll,
+    TotpDisable,
+    TotpReset,
+    TotpRecoveryCodesRegenerate,
+    StepUp,
+    StepUpPolicyUpdate,
+    ViewOverlapReport,
+    ExportRoster,
+    Search,
+    NoteKeyRotation,
+    MemberFieldDefine,
+    MemberFieldSet,
+    RankLadderUpdate,
+    RankPromote,
+    RankDemote,
+    SquadUpdate,
+    MemberKick,
+    VouchCreate,
+    VouchRetract,
+    VouchPolicyUpdate,
+    SanctionAdd,
+    SanctionRemove,
+    SanctionAlliesUpdate,
+    SanctionHit,
 }
 
 impl AuditAction {
@@ -40,13 +83,56 @@ impl AuditAction {
             AuditAction::TribeLeave => "TRIBE_LEAVE",
             AuditAction::NoteCreate => "NOTE_CREATE",
             AuditAction::NoteEdit => "NOTE_EDIT",
+            AuditAction::NoteDelete => "NOTE_DELETE",
+            AuditAction::NoteViewRevisions => "NOTE_VIEW_REVISIONS",
             AuditAction::MumbleCreateAccount => "MUMBLE_CREATE_ACCOUNT",
             AuditAction::MumbleLogin => "MUMBLE_LOGIN",
             AuditAction::SuperAdminUpdateUser => "SUPER_ADMIN_UPDATE_USER",
             AuditAction::SuperAdminCreateTribe => "SUPER_ADMIN_CREATE_TRIBE",
             AuditAction::SuperAdminUpdateTribe => "SUPER_ADMIN_UPDATE_TRIBE",
             AuditAction::SuperAdminDeleteWallet => "SUPER_ADMIN_DELETE_WALLET",
+            AuditAction::SuperAdminUpdateReverifyPolicy => "SUPER_ADMIN_UPDATE_REVERIFY_POLICY",
             AuditAction::DeleteUser => "DELETE_USER",
+            AuditAction::WalletReverify => "WALLET_REVERIFY",
+            AuditAction::WalletStale => "WALLET_STALE",
+            AuditAction::WalletUpdate => "WALLET_UPDATE",
+            AuditAction::ApiKeyCreate => "API_KEY_CREATE",
+            AuditAction::ApiKeyRevoke => "API_KEY_REVOKE",
+            AuditAction::IdentityLookup => "IDENTITY_LOOKUP",
+            AuditAction::RoleCreate => "ROLE_CREATE",
+            AuditAction::RoleDelete => "ROLE_DELETE",
+            AuditAction::RoleAssign => "ROLE_ASSIGN",
+            AuditAction::RoleUnassign => "ROLE_UNASSIGN",
+            AuditAction::SuperAdminNominate => "SUPER_ADMIN_NOMINATE",
+            AuditAction::SuperAdminRemove => "SUPER_ADMIN_REMOVE",
+            AuditAction::PendingOperationCreate => "PENDING_OPERATION_CREATE",
+            AuditAction::PendingOperationApprove => "PENDING_OPERATION_APPROVE",
+            AuditAction::PendingOperationReject => "PENDING_OPERATION_REJECT",
+            AuditAction::PendingOperationExpire => "PENDING_OPERATION_EXPIRE",
+            AuditAction::TotpEnroll => "TOTP_ENROLL",
+            AuditAction::TotpDisable => "TOTP_DISABLE",
+            AuditAction::TotpReset => "TOTP_RESET",
+            AuditAction::TotpRecoveryCodesRegenerate => "TOTP_RECOVERY_CODES_REGENERATE",
+            AuditAction::StepUp => "STEP_UP",
+            AuditAction::StepUpPolicyUpdate => "STEP_UP_POLICY_UPDATE",
+            AuditAction::ViewOverlapReport => "VIEW_OVERLAP_REPORT",
+            AuditAction::ExportRoster => "EXPORT_ROSTER",
+            AuditAction::Search => "SEARCH",
+            AuditAction::NoteKeyRotation => "NOTE_KEY_ROTATION",
+            AuditAction::MemberFieldDefine => "MEMBER_FIELD_DEFINE",
+            AuditAction::MemberFieldSet => "MEMBER_FIELD_SET",
+            AuditAction::RankLadderUpdate => "RANK_LADDER_UPDATE",
+            AuditAction::RankPromote => "RANK_PROMOTE",
+            AuditAction::RankDemote => "RANK_DEMOTE",
+            AuditAction::SquadUpdate => "SQUAD_UPDATE",
+            AuditAction::MemberKick => "MEMBER_KICK",
+            AuditAction::VouchCreate => "VOUCH_CREATE",
+            AuditAction::VouchRetract => "VOUCH_RETRACT",
+            AuditAction::VouchPolicyUpdate => "VOUCH_POLICY_UPDATE",
+            AuditAction::SanctionAdd => "SANCTION_ADD",
+            AuditAction::SanctionRemove => "SANCTION_REMOVE",
+            AuditAction::SanctionAlliesUpdate => "SANCTION_ALLIES_UPDATE",
+            AuditAction::SanctionHit => "SANCTION_HIT",
         }
     }
 }
@@ -180,5 +266,35 @@ mod tests {
         assert_eq!(AuditAction::TribeLeave.as_str(), "TRIBE_LEAVE");
         assert_eq!(AuditAction::NoteCreate.as_str(), "NOTE_CREATE");
         assert_eq!(AuditAction::NoteEdit.as_str(), "NOTE_EDIT");
+        assert_eq!(AuditAction::NoteDelete.as_str(), "NOTE_DELETE");
+        assert_eq!(
+            AuditAction::NoteViewRevisions.as_str(),
+            "NOTE_VIEW_REVISIONS"
+        );
+        assert_eq!(AuditAction::Search.as_str(), "SEARCH");
+        assert_eq!(AuditAction::NoteKeyRotation.as_str(), "NOTE_KEY_ROTATION");
+        assert_eq!(
+            AuditAction::MemberFieldDefine.as_str(),
+            "MEMBER_FIELD_DEFINE"
+        );
+        assert_eq!(AuditAction::MemberFieldSet.as_str(), "MEMBER_FIELD_SET");
+        assert_eq!(AuditAction::RankLadderUpdate.as_str(), "RANK_LADDER_UPDATE");
+        assert_eq!(AuditAction::RankPromote.as_str(), "RANK_PROMOTE");
+        assert_eq!(AuditAction::RankDemote.as_str(), "RANK_DEMOTE");
+        assert_eq!(AuditAction::SquadUpdate.as_str(), "SQUAD_UPDATE");
+        assert_eq!(AuditAction::MemberKick.as_str(), "MEMBER_KICK");
+        assert_eq!(AuditAction::VouchCreate.as_str(), "VOUCH_CREATE");
+        assert_eq!(AuditAction::VouchRetract.as_str(), "VOUCH_RETRACT");
+        assert_eq!(
+            AuditAction::VouchPolicyUpdate.as_str(),
+            "VOUCH_POLICY_UPDATE"
+        );
+        assert_eq!(AuditAction::SanctionAdd.as_str(), "SANCTION_ADD");
+        assert_eq!(AuditAction::SanctionRemove.as_str(), "SANCTION_REMOVE");
+        assert_eq!(
+            AuditAction::SanctionAlliesUpdate.as_str(),
+            "SANCTION_ALLIES_UPDATE"
+        );
+        assert_eq!(AuditAction::SanctionHit.as_str(), "SANCTION_HIT");
     }
 }
@@ -1,11 +1,12 @@
 use crate::{
     audit::{log_audit, AuditAction},
+    character::resolve_character_name,
     models::{LinkedWallet, User},
     state::AppState,
 };
 use axum::{
     extract::{FromRequestParts, Query, State},
-    http::{header, request::Parts, StatusCode},
+    http::{header, request::Parts, HeaderMap, StatusCode},
     response::{IntoResponse, Redirect, Response},
     Json,
 };
@@ -17,6 +18,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sha2::{Digest, Sha256};
 use std::env;
+use subtle::ConstantTimeEq;
 use uuid::Uuid;
 
 use utoipa::{IntoParams, ToSchema};
@@ -41,6 +43,9 @@ pub struct Claims {
     pub discord_id: String,
     pub username: String,
     pub exp: usize,
+    /// Unix time of the last TOTP step-up, absent on plain login tokens
+    #[serde(rename = "stepUpAt", default, skip_serializing_if = "Option::is_none")]
+    pub step_up_at: Option<usize>,
 }
 
 #[utoipa::path(
@@ -295,6 +300,7 @@ pub async fn discord_callback(
         &format!("User {} logged in via Discord", user.username),
     )
     .await;
+    crate::sanctions::screen_login(&state.db, user.id).await;
 
     // Audit log for admin grant (if applicable)
     if admin_granted {
@@ -319,6 +325,7 @@ pub async fn discord_callback(
         discord_id: user.discord_id,
         username: user.username,
         exp: expiration,
+        step_up_at: None,
     };
 
     let token = encode(
@@ -387,6 +394,30 @@ pub async fn exchange_code(
     Ok(Json(ExchangeResponse { token }))
 }
 
+/// Decode and validate the bearer JWT from the request headers.
+pub fn decode_bearer(headers: &HeaderMap) -> Result<Claims, (StatusCode, &'static str)> {
+    let auth_header = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .ok_or((StatusCode::UNAUTHORIZED, "Missing Auth Header"))?;
+
+    let token = auth_header
+        .strip_prefix("Bearer ")
+        .ok_or((StatusCode::UNAUTHORIZED, "Invalid Auth Header"))?;
+
+    let secret = env::var("JWT_SECRET")
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "JWT Config Error"))?;
+
+    let token_data = decode::<Claims>(
+        token,
+        &DecodingKey::from_secret(secret.as_bytes()),
+        &Validation::default(),
+    )
+    .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid Token"))?;
+
+    Ok(token_data.claims)
+}
+
 #[derive(Clone)]
 pub struct AuthenticatedUser {
     pub user_id: i64,
@@ -399,28 +430,7 @@ where
     type Rejection = (StatusCode, &'static str);
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        let auth_header = parts
-            .headers
-            .get(header::AUTHORIZATION)
-            .and_then(|h| h.to_str().ok())
-            .ok_or((StatusCode::UNAUTHORIZED, "Missing Auth Header"))?;
-
-        let token = auth_header
-            .strip_prefix("Bearer ")
-            .ok_or((StatusCode::UNAUTHORIZED, "Invalid Auth Header"))?;
-
-        let secret = env::var("JWT_SECRET")
-            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "JWT Config Error"))?;
-
-        let token_data = decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(secret.as_bytes()),
-            &Validation::default(),
-        )
-        .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid Token"))?;
-
-        let user_id = token_data
-            .claims
+        let user_id = decode_bearer(&parts.headers)?
             .id
             .parse::<i64>()
             .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid User ID in Token"))?;
@@ -460,6 +470,17 @@ pub async fn get_me(
         .await
         .unwrap_or_default();
 
+    // Refresh character names for the user's own wallets (cached on the wallet row)
+    let mut character_names: std::collections::HashMap<String, Option<String>> =
+        std::collections::HashMap::new();
+    for flat in &flat_wallets {
+        if !character_names.contains_key(&flat.id) {
+            let name =
+                resolve_character_name(&state.db, state.character_client.as_ref(), flat).await;
+            character_names.insert(flat.id.clone(), name);
+        }
+    }
+
     // Group flat results into LinkedWallet with tribes Vec
     let mut wallet_map: std::collections::BTreeMap<String, LinkedWallet> =
         std::collections::BTreeMap::new();
@@ -467,12 +488,17 @@ pub async fn get_me(
         let entry = wallet_map
             .entry(flat.id.clone())
             .or_insert_with(|| LinkedWallet {
+                character_name: character_names.get(&flat.id).cloned().flatten(),
                 id: flat.id,
                 user_id: flat.user_id,
                 address: flat.address,
                 verified_at: flat.verified_at,
                 deleted_at: flat.deleted_at,
                 network: flat.network,
+                stale_at: flat.stale_at,
+                is_primary: flat.is_primary,
+                label: flat.label,
+                sort_order: flat.sort_order,
                 tribes: Vec::new(),
             });
         if let Some(t) = flat.tribe {
@@ -481,7 +507,8 @@ pub async fn get_me(
             }
         }
     }
-    let wallets: Vec<LinkedWallet> = wallet_map.into_values().collect();
+    let mut wallets: Vec<LinkedWallet> = wallet_map.into_values().collect();
+    crate::models::sort_wallets(&mut wallets);
 
     // Fetch all tribes for the user, distinguishing admin ones
     let user_tribes = sqlx::query_as::<_, crate::models::UserTribe>(
@@ -506,10 +533,22 @@ pub async fn get_me(
         .map(|ut| ut.tribe.clone())
         .collect();
 
-    // Re-validate super admin status from environment (don't trust JWT claim)
-    let super_admin_ids_str = std::env::var("SUPER_ADMIN_DISCORD_IDS").unwrap_or_default();
-    let super_admin_ids: Vec<&str> = super_admin_ids_str.split(',').map(|s| s.trim()).collect();
-    let is_super_admin = super_admin_ids.contains(&user.discord_id.as_str());
+    let tribe_permissions =
+        crate::rbac::permissions_by_tribe(&state.db, auth_user.user_id, &tribes)
+            .await
+            .unwrap_or_default();
+
+    // Re-validate super admin status against the registry (don't trust JWT claim)
+    let is_super_admin = crate::super_admins::is_super_admin(&state.db, &user.discord_id)
+        .await
+        .unwrap_or(false);
+
+    let lookup_visibility: String =
+        sqlx::query_scalar("SELECT lookup_visibility FROM users WHERE id = ?")
+            .bind(auth_user.user_id)
+            .fetch_one(&state.db)
+            .await
+            .unwrap_or_else(|_| "tribes".to_string());
 
     Json(serde_json::json!({
         "id": user.id.to_string(),
@@ -519,9 +558,11 @@ pub async fn get_me(
         "avatar": user.avatar,
         "tribes": tribes,
         "adminTribes": admin_tribes,
+        "tribePermissions": tribe_permissions,
         "isAdmin": user.is_admin, // Keep for legacy/global support if valid
         "isSuperAdmin": is_super_admin,
         "lastLoginAt": user.last_login_at,
+        "lookupVisibility": lookup_visibility,
         "wallets": wallets
     }))
     .into_response()
@@ -625,6 +666,9 @@ pub async fn delete_me(
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    // Files attached to the deleted notes
+    crate::attachments::purge_deleted_now(&state.db, state.attachment_store.as_ref()).await;
+
     // 7. Audit Log (After commit to avoid SQLite deadlock)
     let _ = log_audit(
         &state.db,
@@ -652,6 +696,7 @@ mod tests {
             discord_id: "discord123".to_string(),
             username: "TestUser".to_string(),
             exp: 1234567890,
+            step_up_at: None,
         };
 
         let json = serde_json::to_string(&claims).expect("Failed to serialize");
@@ -680,6 +725,7 @@ mod tests {
             discord_id: "987654321".to_string(),
             username: "RoundtripUser".to_string(),
             exp: 9999999999,
+            step_up_at: None,
         };
 
         let json = serde_json::to_string(&original).expect("Serialize failed");
@@ -701,6 +747,7 @@ mod tests {
             discord_id: "discord_id_123".to_string(),
             username: "JwtTestUser".to_string(),
             exp: expiration,
+            step_up_at: None,
         };
 
         // Encode
@@ -738,6 +785,7 @@ mod tests {
             discord_id: "discord123".to_string(),
             username: "TestUser".to_string(),
             exp: expiration,
+            step_up_at: None,
         };
 
         let token = encode(
@@ -767,6 +815,7 @@ mod tests {
             discord_id: "discord123".to_string(),
             username: "ExpiredUser".to_string(),
             exp: expired,
+            step_up_at: None,
         };
 
         let token = encode(
@@ -803,6 +852,7 @@ mod tests {
             discord_id: "discord123".to_string(),
             username: "TestUser".to_string(),
             exp: 9999999999,
+            step_up_at: None,
         };
 
         let user_id: i64 = claims.id.parse().expect("Failed to parse user_id");
@@ -816,6 +866,7 @@ mod tests {
             discord_id: "discord123".to_string(),
             username: "TestUser".to_string(),
             exp: 9999999999,
+            step_up_at: None,
         };
 
         let result: Result<i64, _> = claims.id.parse();
@@ -965,8 +1016,11 @@ where
 
         let configured_secret = env::var("INTERNAL_SECRET").expect("INTERNAL_SECRET must be set");
 
+        // Constant-time comparison so the secret can't be recovered through response timing
         match secret_header {
-            Some(s) if s == configured_secret => Ok(InternalSecret(s.to_string())),
+            Some(s) if bool::from(s.as_bytes().ct_eq(configured_secret.as_bytes())) => {
+                Ok(InternalSecret(s.to_string()))
+            }
             _ => Err((StatusCode::UNAUTHORIZED, "Invalid Internal Secret")),
         }
     }
@@ -12,7 +12,7 @@ use sqlx::SqlitePool;
 use std::{env, net::SocketAddr};
 use tower_http::cors::CorsLayer;
 use uuid::Uuid;
-use void_eid_backend::{auth::Claims, db::init_db, state::AppState, wallet};
+use void_eid_backend::{auth::Claims, db::init_db, lookup, state::AppState, wallet};
 
 #[derive(Deserialize)]
 struct StubLoginParams {
@@ -50,6 +50,7 @@ async fn stub_login(
         discord_id: user.discord_id,
         username: user.username,
         exp: expiration,
+        step_up_at: None,
     };
 
     let token = encode(
@@ -169,6 +170,12 @@ async fn main() -> anyhow::Result<()> {
     if env::var("FRONTEND_URL").is_err() {
         env::set_var("FRONTEND_URL", "http://localhost:5173");
     }
+    if env::var("MOCK_CHARACTER_NAMES").is_err() {
+        env::set_var(
+            "MOCK_CHARACTER_NAMES",
+            "0xregularwallet987654321=Regular Rider,0xadminwallet123456789=Admin Rider",
+        );
+    }
 
     let db_pool = init_db().await?;
     seed_db(&db_pool).await;
@@ -190,6 +197,8 @@ async fn main() -> anyhow::Result<()> {
         // Wallet routes (not rate-limited in stub)
         .route("/api/wallets/link-nonce", post(wallet::link_nonce))
         .route("/api/wallets/link-verify", post(wallet::link_verify))
+        .route("/api/wallets/{id}/reverify", post(wallet::reverify_wallet))
+        .route("/api/lookup", get(lookup::lookup_identity))
         // Mock the original login route to redirect to stub login?
         // Or just let the frontend call stub-login directly if in test mode.
         // Let's redirect /api/auth/discord/login to a page that auto-logs in as admin for convenience?
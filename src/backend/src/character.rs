@@ -0,0 +1,276 @@
+use crate::{db::DbPool, models::FlatLinkedWallet};
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+use std::{collections::HashMap, env, future::Future, pin::Pin, sync::Arc};
+
+/// How long a resolved character name is trusted before asking the chain again
+const CACHE_TTL_HOURS: i64 = 24;
+
+pub type CharacterFuture<'a> =
+    Pin<Box<dyn Future<Output = anyhow::Result<Option<String>>> + Send + 'a>>;
+
+/// Looks up the in-game character (rider) bound to a wallet address.
+/// `Ok(None)` means the wallet has no character; `Err` means the lookup itself failed.
+pub trait CharacterClient: Send + Sync {
+    fn character_name<'a>(&'a self, address: &'a str) -> CharacterFuture<'a>;
+}
+
+/// Resolves character names from the character profile object owned by the wallet,
+/// via the Sui JSON-RPC `suix_getOwnedObjects` method.
+pub struct SuiCharacterClient {
+    http: reqwest::Client,
+    rpc_url: String,
+    profile_type: String,
+}
+
+impl SuiCharacterClient {
+    pub fn new(rpc_url: String, profile_type: String) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            rpc_url,
+            profile_type,
+        }
+    }
+}
+
+impl CharacterClient for SuiCharacterClient {
+    fn character_name<'a>(&'a self, address: &'a str) -> CharacterFuture<'a> {
+        Box::pin(async move {
+            let body = json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "suix_getOwnedObjects",
+                "params": [
+                    address,
+                    {
+                        "filter": { "StructType": self.profile_type },
+                        "options": { "showContent": true }
+                    },
+                    null,
+                    1
+                ]
+            });
+
+            let res: Value = self
+                .http
+                .post(&self.rpc_url)
+                .json(&body)
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+
+            if let Some(err) = res.get("error") {
+                anyhow::bail!("Sui RPC error: {}", err);
+            }
+
+            Ok(extract_character_name(&res))
+        })
+    }
+}
+
+/// Pull the character name out of a `suix_getOwnedObjects` response.
+/// Profiles carry the name either directly or inside a `metadata` struct.
+fn extract_character_name(res: &Value) -> Option<String> {
+    let fields = res.pointer("/result/data/0/data/content/fields")?;
+    fields
+        .get("name")
+        .or_else(|| fields.pointer("/metadata/fields/name"))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+}
+
+/// Fixed address -> name mapping, for local development, the stub API and tests.
+#[derive(Default)]
+pub struct StaticCharacterClient {
+    names: HashMap<String, String>,
+}
+
+impl StaticCharacterClient {
+    pub fn new(names: HashMap<String, String>) -> Self {
+        Self {
+            names: names
+                .into_iter()
+                .map(|(address, name)| (address.to_lowercase(), name))
+                .collect(),
+        }
+    }
+
+    /// Parse `0xaddr=Name,0xother=Other Name`
+    pub fn from_spec(spec: &str) -> Self {
+        Self::new(
+            spec.split(',')
+                .filter_map(|pair| pair.split_once('='))
+                .map(|(address, name)| (address.trim().to_string(), name.trim().to_string()))
+                .filter(|(address, name)| !address.is_empty() && !name.is_empty())
+                .collect(),
+        )
+    }
+}
+
+impl CharacterClient for StaticCharacterClient {
+    fn character_name<'a>(&'a self, address: &'a str) -> CharacterFuture<'a> {
+        let name = self.names.get(&address.to_lowercase()).cloned();
+        Box::pin(async move { Ok(name) })
+    }
+}
+
+/// Build the character client from the environment: the Sui client when
+/// `CHARACTER_RPC_URL` and `CHARACTER_PROFILE_TYPE` are set, otherwise a static
+/// client seeded from `MOCK_CHARACTER_NAMES`.
+pub fn character_client_from_env() -> Arc<dyn CharacterClient> {
+    match (
+        env::var("CHARACTER_RPC_URL"),
+        env::var("CHARACTER_PROFILE_TYPE"),
+    ) {
+        (Ok(rpc_url), Ok(profile_type)) if !rpc_url.is_empty() && !profile_type.is_empty() => {
+            Arc::new(SuiCharacterClient::new(rpc_url, profile_type))
+        }
+        _ => Arc::new(StaticCharacterClient::from_spec(
+            &env::var("MOCK_CHARACTER_NAMES").unwrap_or_default(),
+        )),
+    }
+}
+
+/// Return the character name for a wallet, refreshing the value cached on the `wallets`
+/// row when it is older than the cache TTL. Lookup failures fall back to the cached name.
+pub async fn resolve_character_name(
+    db: &DbPool,
+    client: &dyn CharacterClient,
+    wallet: &FlatLinkedWallet,
+) -> Option<String> {
+    let is_fresh = wallet
+        .character_name_refreshed_at
+        .is_some_and(|t| Utc::now() - t < Duration::hours(CACHE_TTL_HOURS));
+    if is_fresh {
+        return wallet.character_name.clone();
+    }
+
+    match client.character_name(&wallet.address).await {
+        Ok(name) => {
+            if let Err(e) = sqlx::query(
+                "UPDATE wallets SET character_name = ?, character_name_refreshed_at = ? WHERE id = ?",
+            )
+            .bind(&name)
+            .bind(Utc::now())
+            .bind(&wallet.id)
+            .execute(db)
+            .await
+            {
+                eprintln!("Failed to cache character name for {}: {}", wallet.id, e);
+            }
+            name
+        }
+        Err(e) => {
+            eprintln!("Character lookup failed for {}: {}", wallet.address, e);
+            wallet.character_name.clone()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{insert_users, setup_db};
+
+    struct FailingCharacterClient;
+
+    impl CharacterClient for FailingCharacterClient {
+        fn character_name<'a>(&'a self, _address: &'a str) -> CharacterFuture<'a> {
+            Box::pin(async { anyhow::bail!("chain unavailable") })
+        }
+    }
+
+    async fn setup() -> DbPool {
+        let pool = setup_db().await;
+        insert_users(&pool, &[(1001, "test-discord-id", "TestUser")]).await;
+        sqlx::query("INSERT INTO wallets (id, user_id, address, verified_at) VALUES ('w1', ?, '0xrider', ?)")
+            .bind(1001_i64)
+            .bind(Utc::now())
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    async fn fetch_wallet(db: &DbPool) -> FlatLinkedWallet {
+        sqlx::query_as("SELECT *, NULL as tribe FROM wallets WHERE id = 'w1'")
+            .fetch_one(db)
+            .await
+            .unwrap()
+    }
+
+    #[test]
+    fn test_extract_character_name_direct() {
+        let res = json!({"result": {"data": [{"data": {"content": {"fields": {"name": "Friendly Rider"}}}}]}});
+        assert_eq!(
+            extract_character_name(&res),
+            Some("Friendly Rider".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_character_name_metadata() {
+        let res = json!({"result": {"data": [{"data": {"content": {"fields": {"metadata": {"fields": {"name": "Nested Rider"}}}}}}]}});
+        assert_eq!(
+            extract_character_name(&res),
+            Some("Nested Rider".to_string())
+        );
+    }
+
+    #[test]
+    fn test_extract_character_name_missing() {
+        assert!(extract_character_name(&json!({"result": {"data": []}})).is_none());
+        let blank =
+            json!({"result": {"data": [{"data": {"content": {"fields": {"name": "  "}}}}]}});
+        assert!(extract_character_name(&blank).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_static_client_from_spec() {
+        let client = StaticCharacterClient::from_spec("0xABC=Rider One, 0xdef = Rider Two,broken");
+        assert_eq!(
+            client.character_name("0xabc").await.unwrap(),
+            Some("Rider One".to_string())
+        );
+        assert_eq!(
+            client.character_name("0xDEF").await.unwrap(),
+            Some("Rider Two".to_string())
+        );
+        assert!(client.character_name("0x123").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_resolve_caches_on_wallet_row() {
+        let db = setup().await;
+        let client = StaticCharacterClient::from_spec("0xrider=Friendly Rider");
+
+        let name = resolve_character_name(&db, &client, &fetch_wallet(&db).await).await;
+        assert_eq!(name, Some("Friendly Rider".to_string()));
+
+        let wallet = fetch_wallet(&db).await;
+        assert_eq!(wallet.character_name, Some("Friendly Rider".to_string()));
+        assert!(wallet.character_name_refreshed_at.is_some());
+
+        // A fresh cache entry is served without consulting the client
+        let name = resolve_character_name(&db, &FailingCharacterClient, &wallet).await;
+        assert_eq!(name, Some("Friendly Rider".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_resolve_falls_back_to_cache_on_error() {
+        let db = setup().await;
+        sqlx::query("UPDATE wallets SET character_name = 'Old Name', character_name_refreshed_at = ? WHERE id = 'w1'")
+            .bind(Utc::now() - Duration::days(7))
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let name =
+            resolve_character_name(&db, &FailingCharacterClient, &fetch_wallet(&db).await).await;
+        assert_eq!(name, Some("Old Name".to_string()));
+    }
+}
@@ -20,6 +20,7 @@ pub async fn init_db() -> Result<DbPool, sqlx::Error> {
         .await?;
 
     sqlx::migrate!("./migrations").run(&pool).await?;
+    crate::super_admins::seed_from_env(&pool).await?;
 
     Ok(pool)
 }
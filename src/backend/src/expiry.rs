@@ -0,0 +1,271 @@
+use crate::{
+    audit::{alert_admin_action, log_audit, AuditAction},
+    db::DbPool,
+};
+use chrono::{DateTime, Utc};
+
+/// How often the background sweep looks for expired grants
+const SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Name shown in alerts for revocations made by the sweep rather than an admin
+const SWEEP_ACTOR: &str = "System (grant expiry)";
+
+#[derive(sqlx::FromRow)]
+struct ExpiredGrant {
+    user_id: i64,
+    username: String,
+    tribe: String,
+}
+
+/// Check a requested expiry lies in the future.
+pub fn validate_expiry(expires_at: Option<DateTime<Utc>>) -> Result<(), &'static str> {
+    match expires_at {
+        Some(at) if at <= Utc::now() => Err("Expiry must be in the future"),
+        _ => Ok(()),
+    }
+}
+
+async fn expired(
+    db: &DbPool,
+    column: &str,
+    now: DateTime<Utc>,
+) -> Result<Vec<ExpiredGrant>, sqlx::Error> {
+    sqlx::query_as::<_, ExpiredGrant>(&format!(
+        r#"
+        SELECT ut.user_id, u.username, ut.tribe
+        FROM user_tribes ut
+        JOIN users u ON u.id = ut.user_id
+        WHERE ut.{column} IS NOT NULL AND ut.{column} <= ?
+        "#
+    ))
+    .bind(now)
+    .fetch_all(db)
+    .await
+}
+
+/// Remove tribe memberships and tribe-admin grants whose expiry has passed.
+/// Returns the number of (memberships, admin grants) revoked.
+pub async fn revoke_expired_grants(db: &DbPool) -> Result<(u64, u64), sqlx::Error> {
+    let now = Utc::now();
+
+    // Memberships first: leaving the tribe also ends any admin grant in it
+    let mut memberships = 0;
+    for grant in expired(db, "expires_at", now).await? {
+        let result = sqlx::query(
+            "DELETE FROM user_tribes WHERE user_id = ? AND tribe = ? AND expires_at <= ?",
+        )
+        .bind(grant.user_id)
+        .bind(&grant.tribe)
+        .bind(now)
+        .execute(db)
+        .await?;
+        if result.rows_affected() == 0 {
+            continue;
+        }
+        memberships += 1;
+
+        let details = format!(
+            "Membership of {} in tribe {} expired",
+            grant.username, grant.tribe
+        );
+        // No human actor: the member is recorded as both actor and target
+        let _ = log_audit(
+            db,
+            AuditAction::TribeLeave,
+            grant.user_id,
+            Some(grant.user_id),
+            &details,
+        )
+        .await;
+        alert_admin_action(SWEEP_ACTOR.to_string(), AuditAction::TribeLeave, details);
+    }
+
+    let mut admins = 0;
+    for grant in expired(db, "admin_expires_at", now).await? {
+        let result = sqlx::query(
+            "UPDATE user_tribes SET is_admin = FALSE, admin_expires_at = NULL WHERE user_id = ? AND tribe = ? AND admin_expires_at <= ?",
+        )
+        .bind(grant.user_id)
+        .bind(&grant.tribe)
+        .bind(now)
+        .execute(db)
+        .await?;
+        if result.rows_affected() == 0 {
+            continue;
+        }
+        admins += 1;
+
+        let details = format!(
+            "Admin grant of {} in tribe {} expired",
+            grant.username, grant.tribe
+        );
+        let _ = log_audit(
+            db,
+            AuditAction::AdminRevoke,
+            grant.user_id,
+            Some(grant.user_id),
+            &details,
+        )
+        .await;
+        alert_admin_action(SWEEP_ACTOR.to_string(), AuditAction::AdminRevoke, details);
+    }
+
+    Ok((memberships, admins))
+}
+
+/// Run `revoke_expired_grants` on startup and then every minute.
+pub fn spawn_grant_expiry_sweeper(db: DbPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            match revoke_expired_grants(&db).await {
+                Ok((0, 0)) => {}
+                Ok((m, a)) => println!(
+                    "Revoked {} expired membership(s) and {} expired admin grant(s)",
+                    m, a
+                ),
+                Err(e) => eprintln!("Grant expiry sweep failed: {}", e),
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{insert_users, setup_db};
+    use chrono::Duration;
+
+    async fn setup() -> DbPool {
+        let pool = setup_db().await;
+        insert_users(&pool, &[(1, "111", "Officer"), (2, "222", "Recruit")]).await;
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_expired_grants_are_revoked() {
+        let db = setup().await;
+        let past = Utc::now() - Duration::minutes(5);
+        let future = Utc::now() + Duration::days(7);
+
+        // Temporary officer in Fire, permanent admin in Water
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe, is_admin, admin_expires_at) VALUES (1, 'Fire', TRUE, ?), (1, 'Water', TRUE, NULL)")
+            .bind(past)
+            .execute(&db)
+            .await
+            .unwrap();
+        // Expired and still-running memberships
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe, expires_at) VALUES (2, 'Fire', ?), (2, 'Water', ?)")
+            .bind(past)
+            .bind(future)
+            .execute(&db)
+            .await
+            .unwrap();
+
+        assert_eq!(revoke_expired_grants(&db).await.unwrap(), (1, 1));
+        // Nothing left to do on the next run
+        assert_eq!(revoke_expired_grants(&db).await.unwrap(), (0, 0));
+
+        let officer: Vec<(String, bool)> = sqlx::query_as(
+            "SELECT tribe, is_admin FROM user_tribes WHERE user_id = 1 ORDER BY tribe",
+        )
+        .fetch_all(&db)
+        .await
+        .unwrap();
+        assert_eq!(
+            officer,
+            vec![("Fire".to_string(), false), ("Water".to_string(), true)]
+        );
+
+        let roles: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM user_roles WHERE user_id = 1 AND role_id = 'admin'",
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(roles, 1);
+
+        let recruit: Vec<String> =
+            sqlx::query_scalar("SELECT tribe FROM user_tribes WHERE user_id = 2")
+                .fetch_all(&db)
+                .await
+                .unwrap();
+        assert_eq!(recruit, vec!["Water"]);
+
+        let actions: Vec<String> =
+            sqlx::query_scalar("SELECT action FROM audit_logs ORDER BY action")
+                .fetch_all(&db)
+                .await
+                .unwrap();
+        assert_eq!(actions, vec!["ADMIN_REVOKE", "TRIBE_LEAVE"]);
+    }
+
+    #[tokio::test]
+    async fn test_expired_admin_grant_stops_counting_before_sweep() {
+        use crate::rbac::{has_permission_in_tribe, Permission};
+
+        let db = setup().await;
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe, is_admin, admin_expires_at) VALUES (1, 'Fire', TRUE, ?), (1, 'Water', TRUE, ?)")
+            .bind(Utc::now() - Duration::seconds(1))
+            .bind(Utc::now() + Duration::days(7))
+            .execute(&db)
+            .await
+            .unwrap();
+
+        assert!(
+            !has_permission_in_tribe(&db, 1, "Fire", Permission::AdminGrant)
+                .await
+                .unwrap()
+        );
+        assert!(
+            has_permission_in_tribe(&db, 1, "Water", Permission::AdminGrant)
+                .await
+                .unwrap()
+        );
+
+        // Re-granting through the user editor, which has no expiry, makes it permanent
+        let state = crate::state::AppState::new(db.clone());
+        let res = crate::admin::apply_update_user(
+            &state,
+            &crate::middleware::admin::RequireSuperAdmin {
+                discord_id: "222".to_string(),
+            },
+            1,
+            crate::admin::UpdateUserRequest {
+                is_admin: false,
+                username: "Officer".to_string(),
+                discriminator: "0000".to_string(),
+                admin_tribes: vec!["Fire".to_string()],
+            },
+        )
+        .await;
+        assert_eq!(res.status(), axum::http::StatusCode::OK);
+
+        let expiries: Vec<(String, bool, Option<DateTime<Utc>>)> = sqlx::query_as(
+            "SELECT tribe, is_admin, admin_expires_at FROM user_tribes WHERE user_id = 1 ORDER BY tribe",
+        )
+        .fetch_all(&db)
+        .await
+        .unwrap();
+        assert_eq!(
+            expiries,
+            vec![
+                ("Fire".to_string(), true, None),
+                ("Water".to_string(), false, None)
+            ]
+        );
+        assert!(
+            has_permission_in_tribe(&db, 1, "Fire", Permission::AdminGrant)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_validate_expiry() {
+        assert!(validate_expiry(None).is_ok());
+        assert!(validate_expiry(Some(Utc::now() + Duration::hours(1))).is_ok());
+        assert!(validate_expiry(Some(Utc::now() - Duration::hours(1))).is_err());
+    }
+}
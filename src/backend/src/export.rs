@@ -0,0 +1,475 @@
+use crate::{
+    audit::{log_audit, AuditAction},
+    helpers::{csv_response, xlsx_response},
+    member_fields::{self, MemberField},
+    notes::VISIBLE_NOTES_SQL,
+    rbac::{has_permission_in_tribe, perm, Permission, TribeAccess},
+    roster::{build_members, fetch_members, RosterFilter, RosterQuery},
+    state::AppState,
+    super_admins::is_super_admin,
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use utoipa::IntoParams;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportColumn {
+    Username,
+    DiscordId,
+    Wallets,
+    Network,
+    LastLogin,
+    Admin,
+    NoteCount,
+    Rank,
+    Squads,
+    /// A custom member field of the tribe, by key
+    Field(String),
+}
+
+impl ExportColumn {
+    const ALL: [ExportColumn; 9] = [
+        ExportColumn::Username,
+        ExportColumn::DiscordId,
+        ExportColumn::Wallets,
+        ExportColumn::Network,
+        ExportColumn::LastLogin,
+        ExportColumn::Admin,
+        ExportColumn::NoteCount,
+        ExportColumn::Rank,
+        ExportColumn::Squads,
+    ];
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            ExportColumn::Username => "username",
+            ExportColumn::DiscordId => "discord_id",
+            ExportColumn::Wallets => "wallets",
+            ExportColumn::Network => "network",
+            ExportColumn::LastLogin => "last_login",
+            ExportColumn::Admin => "admin",
+            ExportColumn::NoteCount => "note_count",
+            ExportColumn::Rank => "rank",
+            ExportColumn::Squads => "squads",
+            ExportColumn::Field(key) => key,
+        }
+    }
+
+    /// Whether `name` is a built-in column; custom field keys may not shadow one
+    pub fn is_builtin(name: &str) -> bool {
+        Self::ALL.iter().any(|c| c.as_str() == name)
+    }
+
+    fn parse(name: &str, fields: &[MemberField]) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|c| c.as_str() == name)
+            .or_else(|| {
+                fields
+                    .iter()
+                    .any(|f| f.key == name)
+                    .then(|| ExportColumn::Field(name.to_string()))
+            })
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ExportQuery {
+    /// Comma-separated columns: username, discord_id, wallets, network, last_login,
+    /// admin, note_count, rank, squads, or a custom field key. Every column the caller may see
+    /// when absent
+    pub columns: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/roster/export",
+    params(RosterQuery, ExportQuery),
+    responses(
+        (status = 200, description = "Roster as a CSV (`format=csv`, default) or XLSX (`format=xlsx`) attachment"),
+        (status = 400, description = "Invalid format or column"),
+        (status = 403, description = "Forbidden: Missing roster.read, or notes.read for note_count")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn export_roster(
+    access: TribeAccess<perm::RosterRead>,
+    Query(query): Query<RosterQuery>,
+    Query(export): Query<ExportQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let xlsx = match query.format.as_deref() {
+        None | Some("csv") => false,
+        Some("xlsx") => true,
+        Some(_) => return (StatusCode::BAD_REQUEST, "Invalid format").into_response(),
+    };
+
+    // Note counts reveal that notes exist, so they need notes.read
+    let can_read_notes = match has_permission_in_tribe(
+        &state.db,
+        access.user.id,
+        &access.tribe,
+        Permission::NotesRead,
+    )
+    .await
+    {
+        Ok(allowed) => allowed,
+        Err(e) => {
+            eprintln!("Failed to check notes permission for export: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response();
+        }
+    };
+
+    let fields = match member_fields::tribe_fields(&state.db, &access.tribe).await {
+        Ok(fields) => fields,
+        Err(e) => {
+            eprintln!("Failed to fetch member fields for export: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response();
+        }
+    };
+
+    let columns: Vec<ExportColumn> = match export.columns.as_deref() {
+        None | Some("") => ExportColumn::ALL
+            .into_iter()
+            .filter(|c| *c != ExportColumn::NoteCount || can_read_notes)
+            .chain(fields.iter().map(|f| ExportColumn::Field(f.key.clone())))
+            .collect(),
+        Some(list) => {
+            let mut columns = Vec::new();
+            for name in list.split(',').map(str::trim).filter(|n| !n.is_empty()) {
+                match ExportColumn::parse(name, &fields) {
+                    Some(c) if !columns.contains(&c) => columns.push(c),
+                    Some(_) => {}
+                    None => return (StatusCode::BAD_REQUEST, "Invalid column").into_response(),
+                }
+            }
+            if columns.contains(&ExportColumn::NoteCount) && !can_read_notes {
+                return (
+                    StatusCode::FORBIDDEN,
+                    "Access denied: notes.read required for note_count",
+                )
+                    .into_response();
+            }
+            columns
+        }
+    };
+
+    let filter = RosterFilter::new(&query, access.tribe.clone());
+    if let Err(e) = filter.check_fields(&state.db).await {
+        return e.into_response();
+    }
+    let rows = match fetch_members(&state.db, &filter, None, None).await {
+        Ok(rows) => rows,
+        Err(e) => {
+            eprintln!("Database error exporting roster: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response();
+        }
+    };
+    let user_ids: Vec<i64> = rows.iter().map(|r| r.user.id).collect();
+    let members = build_members(
+        &state.db,
+        &access.tribe,
+        rows.into_iter().map(|r| r.user).collect(),
+    )
+    .await;
+
+    let admins: HashSet<String> = if columns.contains(&ExportColumn::Admin) {
+        // A grant past its expiry no longer counts, even before the sweeper clears it
+        match sqlx::query_scalar(
+            "SELECT u.discord_id FROM user_tribes ut JOIN users u ON u.id = ut.user_id WHERE ut.tribe = ? AND ut.is_admin = TRUE AND (ut.admin_expires_at IS NULL OR ut.admin_expires_at > ?)",
+        )
+        .bind(&access.tribe)
+        .bind(chrono::Utc::now())
+        .fetch_all(&state.db)
+        .await
+        {
+            Ok(ids) => ids.into_iter().collect(),
+            Err(e) => {
+                eprintln!("Failed to fetch tribe admins for export: {}", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+                    .into_response();
+            }
+        }
+    } else {
+        HashSet::new()
+    };
+
+    let note_counts: HashMap<String, i64> = if columns.contains(&ExportColumn::NoteCount) {
+        let super_admin = is_super_admin(&state.db, &access.user.discord_id)
+            .await
+            .unwrap_or(false);
+        match sqlx::query_as::<_, (String, i64)>(&format!(
+            "SELECT u.discord_id, COUNT(*) FROM notes n JOIN users u ON u.id = n.target_user_id WHERE n.tribe = ? AND n.deleted_at IS NULL AND {} GROUP BY u.discord_id",
+            VISIBLE_NOTES_SQL
+        ))
+        .bind(&access.tribe)
+        .bind(access.user.id)
+        .bind(super_admin)
+        .fetch_all(&state.db)
+        .await
+        {
+            Ok(counts) => counts.into_iter().collect(),
+            Err(e) => {
+                eprintln!("Failed to count notes for export: {}", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+                    .into_response();
+            }
+        }
+    } else {
+        HashMap::new()
+    };
+
+    let cells: HashMap<(i64, String), String> =
+        if columns.iter().any(|c| matches!(c, ExportColumn::Field(_))) {
+            match member_fields::cells_for(&state.db, &access.tribe, &user_ids).await {
+                Ok(cells) => cells,
+                Err(e) => {
+                    eprintln!("Failed to fetch member field values for export: {}", e);
+                    return (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+                        .into_response();
+                }
+            }
+        } else {
+            HashMap::new()
+        };
+
+    let rows: Vec<Vec<String>> = members
+        .iter()
+        .zip(&user_ids)
+        .map(|(m, user_id)| {
+            let wallets: Vec<_> = m
+                .wallets
+                .iter()
+                .filter(|w| w.deleted_at.is_none())
+                .collect();
+            columns
+                .iter()
+                .map(|c| match c {
+                    ExportColumn::Username => m.username.clone(),
+                    ExportColumn::DiscordId => m.discord_id.clone(),
+                    ExportColumn::Wallets => wallets
+                        .iter()
+                        .map(|w| w.address.as_str())
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                    ExportColumn::Network => wallets
+                        .iter()
+                        .map(|w| w.network.as_str())
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                    ExportColumn::LastLogin => {
+                        m.last_login_at.map(|t| t.to_rfc3339()).unwrap_or_default()
+                    }
+                    ExportColumn::Admin => admins.contains(&m.discord_id).to_string(),
+                    ExportColumn::NoteCount => note_counts
+                        .get(&m.discord_id)
+                        .copied()
+                        .unwrap_or(0)
+                        .to_string(),
+                    ExportColumn::Rank => m.rank.clone().unwrap_or_default(),
+                    ExportColumn::Squads => m.squads.join("; "),
+                    ExportColumn::Field(key) => cells
+                        .get(&(*user_id, key.clone()))
+                        .cloned()
+                        .unwrap_or_default(),
+                })
+                .collect()
+        })
+        .collect();
+    let header: Vec<&str> = columns.iter().map(|c| c.as_str()).collect();
+
+    // Every export is logged, unlike debounced roster views
+    let mut details = format!(
+        "Exported {} members of tribe {} as {} (columns: {})",
+        rows.len(),
+        access.tribe,
+        if xlsx { "XLSX" } else { "CSV" },
+        header.join(", ")
+    );
+    if let Some(as_of) = query.as_of {
+        details.push_str(&format!(", as of {}", as_of.to_rfc3339()));
+    }
+    let _ = log_audit(
+        &state.db,
+        AuditAction::ExportRoster,
+        access.user.id,
+        None,
+        &details,
+    )
+    .await;
+
+    if xlsx {
+        xlsx_response(
+            &format!("roster-{}.xlsx", access.tribe),
+            "Roster",
+            &header,
+            &rows,
+        )
+    } else {
+        csv_response(&format!("roster-{}.csv", access.tribe), &header, &rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::DbPool,
+        test_support::{access, add_members, insert_users, setup_db},
+    };
+
+    async fn setup() -> DbPool {
+        let pool = setup_db().await;
+        insert_users(&pool, &[(1, "111", "Chief"), (2, "222", "Scout")]).await;
+        add_members(&pool, &[(1, "Fire", true), (2, "Fire", false)]).await;
+        sqlx::query("INSERT INTO wallets (id, user_id, address, verified_at, network, sort_order) VALUES ('w1', 2, '0xabc', CURRENT_TIMESTAMP, 'mainnet', 0), ('w2', 2, '0xdef', CURRENT_TIMESTAMP, 'testnet', 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO notes (id, target_user_id, author_id, tribe, content) VALUES ('n1', 2, 1, 'Fire', 'Reliable')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        pool
+    }
+
+    fn roster_query(format: Option<&str>) -> RosterQuery {
+        RosterQuery {
+            tribe: Some("Fire".to_string()),
+            sort: None,
+            order: None,
+            search: None,
+            fields: None,
+            as_of: None,
+            limit: None,
+            cursor: None,
+            format: format.map(str::to_string),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_export_csv_columns() {
+        let db = setup().await;
+        let res = export_roster(
+            access(&db, 1, "Fire").await,
+            Query(roster_query(None)),
+            Query(ExportQuery {
+                columns: Some("username,wallets,network,admin,note_count".to_string()),
+            }),
+            State(AppState::new(db.clone())),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let csv = String::from_utf8(body.to_vec()).unwrap();
+        assert_eq!(
+            csv,
+            "username,wallets,network,admin,note_count\r\n\
+             Chief,,,true,0\r\n\
+             Scout,0xabc; 0xdef,mainnet; testnet,false,1\r\n"
+        );
+
+        let action: String = sqlx::query_scalar("SELECT action FROM audit_logs WHERE actor_id = 1")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(action, "EXPORT_ROSTER");
+    }
+
+    #[tokio::test]
+    async fn test_export_admin_column_skips_expired_grants() {
+        let db = setup().await;
+        sqlx::query(
+            "UPDATE user_tribes SET is_admin = TRUE, admin_expires_at = ? WHERE user_id = 2",
+        )
+        .bind(chrono::Utc::now() - chrono::Duration::hours(1))
+        .execute(&db)
+        .await
+        .unwrap();
+
+        let res = export_roster(
+            access(&db, 1, "Fire").await,
+            Query(roster_query(None)),
+            Query(ExportQuery {
+                columns: Some("username,admin".to_string()),
+            }),
+            State(AppState::new(db.clone())),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(body.to_vec()).unwrap(),
+            "username,admin\r\nChief,true\r\nScout,false\r\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_note_count_needs_notes_read() {
+        let db = setup().await;
+        // Scout can read the roster but not notes
+        sqlx::query("INSERT INTO user_roles (id, user_id, role_id, tribe) VALUES ('r1', 2, 'viewer', 'Fire')")
+            .execute(&db)
+            .await
+            .unwrap();
+        let state = AppState::new(db.clone());
+
+        let res = export_roster(
+            access(&db, 2, "Fire").await,
+            Query(roster_query(Some("xlsx"))),
+            Query(ExportQuery {
+                columns: Some("username,note_count".to_string()),
+            }),
+            State(state.clone()),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        // Without explicit columns the note count is simply left out
+        let res = export_roster(
+            access(&db, 2, "Fire").await,
+            Query(roster_query(Some("xlsx"))),
+            Query(ExportQuery { columns: None }),
+            State(state),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        // The sheet name is fixed rather than derived from the tribe
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(body.to_vec())).unwrap();
+        let mut workbook = String::new();
+        std::io::Read::read_to_string(
+            &mut archive.by_name("xl/workbook.xml").unwrap(),
+            &mut workbook,
+        )
+        .unwrap();
+        assert!(workbook.contains(r#"<sheet name="Roster""#));
+        let details: String =
+            sqlx::query_scalar("SELECT details FROM audit_logs WHERE action = 'EXPORT_ROSTER'")
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert!(details.contains("as XLSX"));
+        assert!(!details.contains("note_count"));
+    }
+}
@@ -1,12 +1,23 @@
 use crate::{
     db::DbPool,
     models::{User, UserTribe},
+    rbac::{permission_grants, Permission},
+    reverification::stale_enforced_tribes,
+};
+use axum::{
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
 };
-use axum::http::StatusCode;
 
 /// Result type for helper functions that can fail with HTTP errors
 pub type ApiResult<T> = Result<T, (StatusCode, &'static str)>;
 
+pub(crate) const STALE_WALLET_MESSAGE: &str =
+    "Access denied: Wallet re-verification required for this tribe";
+
+/// Member IDs per `IN (...)` lookup, well under SQLite's bound-parameter limit
+pub(crate) const ID_BATCH_SIZE: usize = 500;
+
 /// Fetch a user by their internal UUID
 pub async fn get_user_by_id(db: &DbPool, id: i64) -> Result<Option<User>, sqlx::Error> {
     sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = ?")
@@ -37,21 +48,32 @@ pub async fn get_user_tribes(db: &DbPool, user_id: i64) -> Result<Vec<String>, s
 }
 
 /// Require that a user exists, is an admin, and is in a tribe.
+/// Shorthand for [`require_permission_in_tribe`] with the tribe admin permission.
+pub async fn require_admin_in_tribe(
+    db: &DbPool,
+    user_id: i64,
+    tribe: Option<&str>,
+) -> ApiResult<(User, String, Vec<String>)> {
+    require_permission_in_tribe(db, user_id, tribe, Permission::AdminGrant).await
+}
+
+/// Require that a user exists, is in a tribe, and holds `permission` there through a role.
 /// If tribe parameter is provided, verifies user belongs to that specific tribe.
-/// If tribe is None and user has exactly one tribe, uses that tribe.
-/// If tribe is None and user has multiple tribes, returns error.
+/// If tribe is None and user holds the permission in exactly one tribe, uses that tribe.
+/// If tribe is None and user holds it in multiple tribes, returns error.
 /// Returns (User, selected_tribe, all_tribes) on success, or an HTTP error tuple on failure.
-pub async fn require_admin_in_tribe(
+pub async fn require_permission_in_tribe(
     db: &DbPool,
     user_id: i64,
     tribe: Option<&str>,
+    permission: Permission,
 ) -> ApiResult<(User, String, Vec<String>)> {
     let user = get_user_by_id(db, user_id)
         .await
         .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
         .ok_or((StatusCode::UNAUTHORIZED, "User not found"))?;
 
-    // Fetch full UserTribe info to check is_admin per tribe
+    // Fetch memberships; roles only apply in tribes the user belongs to
     let user_tribes_full =
         sqlx::query_as::<_, UserTribe>("SELECT * FROM user_tribes WHERE user_id = ?")
             .bind(user_id)
@@ -68,18 +90,31 @@ pub async fn require_admin_in_tribe(
         ));
     }
 
+    let grants = permission_grants(db, user_id, permission)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
+    // Memberships backed by a stale wallet under an enforcing policy don't count
+    let stale_tribes = stale_enforced_tribes(db, user_id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+
     let selected_tribe = match tribe {
         Some(t) => {
+            if stale_tribes.iter().any(|st| st == t) {
+                return Err((StatusCode::FORBIDDEN, STALE_WALLET_MESSAGE));
+            }
+
             // Find the tribe entry
             let tribe_entry = user_tribes_full.iter().find(|ut| ut.tribe == t);
 
             match tribe_entry {
                 Some(ut) => {
-                    // Check Admin Permission: Global Admin OR Tribe Admin
-                    if !user.is_admin && !ut.is_admin {
+                    // Check the permission through a global or tribe-scoped role
+                    if !grants.allows(&ut.tribe) {
                         return Err((
                             StatusCode::FORBIDDEN,
-                            "Access denied: You are not an admin of this tribe",
+                            "Access denied: You don't have permission in this tribe",
                         ));
                     }
                     t.to_string()
@@ -93,22 +128,25 @@ pub async fn require_admin_in_tribe(
             }
         }
         None => {
-            // Filter to tribes where the user IS an admin (Global or Tribe)
-            let admin_tribes: Vec<&UserTribe> = user_tribes_full
+            // Filter to tribes where the user holds the permission (Global or Tribe role)
+            let permitted_tribes: Vec<&UserTribe> = user_tribes_full
                 .iter()
-                .filter(|ut| user.is_admin || ut.is_admin)
+                .filter(|ut| !stale_tribes.contains(&ut.tribe))
+                .filter(|ut| grants.allows(&ut.tribe))
                 .collect();
 
-            if admin_tribes.is_empty() {
-                // User is in tribes, but admin of none
+            if permitted_tribes.is_empty() && !stale_tribes.is_empty() {
+                return Err((StatusCode::FORBIDDEN, STALE_WALLET_MESSAGE));
+            } else if permitted_tribes.is_empty() {
+                // User is in tribes, but holds the permission in none
                 return Err((
                     StatusCode::FORBIDDEN,
-                    "Access denied: You are not an admin of any tribe",
+                    "Access denied: You don't have permission in any tribe",
                 ));
-            } else if admin_tribes.len() == 1 {
-                admin_tribes[0].tribe.clone()
+            } else if permitted_tribes.len() == 1 {
+                permitted_tribes[0].tribe.clone()
             } else {
-                // Admin of multiple tribes -> Require specification
+                // Permitted in multiple tribes -> Require specification
                 return Err((
                     StatusCode::BAD_REQUEST,
                     "Please specify a tribe - you manage multiple tribes",
@@ -120,6 +158,183 @@ pub async fn require_admin_in_tribe(
     Ok((user, selected_tribe, all_tribe_names))
 }
 
+/// Quote a CSV field when needed. Fields that a spreadsheet would read as a formula
+/// are prefixed with `'`, since usernames and notes are user-controlled.
+pub fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+/// `Content-Disposition` for a download named `filename`: an ASCII `filename`
+/// for older clients plus the full UTF-8 name as an RFC 5987 `filename*`.
+/// Quotes, backslashes and control characters are dropped from both.
+pub fn attachment_disposition(filename: &str) -> String {
+    let cleaned: String = filename
+        .chars()
+        .filter(|c| !c.is_control() && *c != '"' && *c != '\\')
+        .collect();
+    let ascii: String = cleaned
+        .chars()
+        .map(|c| if c.is_ascii() { c } else { '_' })
+        .collect();
+    format!(
+        "attachment; filename=\"{}\"; filename*=UTF-8''{}",
+        ascii,
+        urlencoding::encode(&cleaned)
+    )
+}
+
+/// Render a header and rows as a CSV attachment.
+pub fn csv_response(filename: &str, header: &[&str], rows: &[Vec<String>]) -> Response {
+    let mut body = header.join(",");
+    body.push_str("\r\n");
+    for row in rows {
+        let fields: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+        body.push_str(&fields.join(","));
+        body.push_str("\r\n");
+    }
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                attachment_disposition(filename),
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+/// Escape text for an XML element, dropping control characters XML 1.0 cannot carry.
+fn xml_text(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\t' | '\n' | '\r' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Spreadsheet column letters for a zero-based index: A, B, ..., Z, AA, ...
+fn xlsx_column(mut index: usize) -> String {
+    let mut name = Vec::new();
+    loop {
+        name.push(b'A' + (index % 26) as u8);
+        if index < 26 {
+            break;
+        }
+        index = index / 26 - 1;
+    }
+    name.reverse();
+    String::from_utf8(name).unwrap_or_default()
+}
+
+/// Build a single-sheet XLSX workbook. Every cell is an inline string, so values
+/// are never evaluated as formulas.
+pub fn xlsx_workbook(
+    sheet: &str,
+    header: &[&str],
+    rows: &[Vec<String>],
+) -> zip::result::ZipResult<Vec<u8>> {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    let mut sheet_xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main"><sheetData>"#,
+    );
+    let header: Vec<String> = header.iter().map(|h| h.to_string()).collect();
+    for (r, row) in std::iter::once(&header).chain(rows).enumerate() {
+        sheet_xml.push_str(&format!(r#"<row r="{}">"#, r + 1));
+        for (c, value) in row.iter().enumerate() {
+            sheet_xml.push_str(&format!(
+                r#"<c r="{}{}" t="inlineStr"><is><t xml:space="preserve">{}</t></is></c>"#,
+                xlsx_column(c),
+                r + 1,
+                xml_text(value)
+            ));
+        }
+        sheet_xml.push_str("</row>");
+    }
+    sheet_xml.push_str("</sheetData></worksheet>");
+
+    let parts = [
+        (
+            "[Content_Types].xml",
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types"><Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/><Default Extension="xml" ContentType="application/xml"/><Override PartName="/xl/workbook.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml"/><Override PartName="/xl/worksheets/sheet1.xml" ContentType="application/vnd.openxmlformats-officedocument.spreadsheetml.worksheet+xml"/></Types>"#.to_string(),
+        ),
+        (
+            "_rels/.rels",
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/officeDocument" Target="xl/workbook.xml"/></Relationships>"#.to_string(),
+        ),
+        (
+            "xl/workbook.xml",
+            format!(
+                r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets><sheet name="{}" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+                xml_text(sheet)
+            ),
+        ),
+        (
+            "xl/_rels/workbook.xml.rels",
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?><Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships"><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet1.xml"/></Relationships>"#.to_string(),
+        ),
+        ("xl/worksheets/sheet1.xml", sheet_xml),
+    ];
+
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, content) in parts {
+        zip.start_file(name, SimpleFileOptions::default())?;
+        zip.write_all(content.as_bytes())?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+/// Render a header and rows as an XLSX attachment. `sheet` must be a fixed, valid
+/// sheet name (at most 31 characters, none of `[]:*?/\`), never user input.
+pub fn xlsx_response(
+    filename: &str,
+    sheet: &str,
+    header: &[&str],
+    rows: &[Vec<String>],
+) -> Response {
+    match xlsx_workbook(sheet, header, rows) {
+        Ok(bytes) => (
+            [
+                (
+                    header::CONTENT_TYPE,
+                    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet".to_string(),
+                ),
+                (
+                    header::CONTENT_DISPOSITION,
+                    attachment_disposition(filename),
+                ),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(e) => {
+            eprintln!("Failed to build XLSX workbook: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -139,6 +354,46 @@ mod tests {
         pool
     }
 
+    #[test]
+    fn test_csv_field_quoting() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("=SUM(A1)"), "'=SUM(A1)");
+    }
+
+    #[test]
+    fn test_attachment_disposition() {
+        assert_eq!(
+            attachment_disposition("roster.csv"),
+            "attachment; filename=\"roster.csv\"; filename*=UTF-8''roster.csv"
+        );
+        assert_eq!(
+            attachment_disposition("a\"b\r\nX-Evil: 1\\ñ.png"),
+            "attachment; filename=\"abX-Evil: 1_.png\"; filename*=UTF-8''abX-Evil%3A%201%C3%B1.png"
+        );
+    }
+
+    #[test]
+    fn test_xlsx_workbook() {
+        assert_eq!(xlsx_column(0), "A");
+        assert_eq!(xlsx_column(25), "Z");
+        assert_eq!(xlsx_column(26), "AA");
+
+        let bytes =
+            xlsx_workbook("Roster", &["username"], &[vec!["<b>&=SUM(A1)".to_string()]]).unwrap();
+        let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).unwrap();
+        let mut sheet = String::new();
+        std::io::Read::read_to_string(
+            &mut archive.by_name("xl/worksheets/sheet1.xml").unwrap(),
+            &mut sheet,
+        )
+        .unwrap();
+        assert!(sheet.contains(
+            r#"<c r="A2" t="inlineStr"><is><t xml:space="preserve">&lt;b&gt;&amp;=SUM(A1)</t>"#
+        ));
+    }
+
     #[tokio::test]
     async fn test_get_user_by_id_not_found() {
         let db = setup_db().await;
@@ -315,4 +570,54 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap().1, "Earth");
     }
+
+    #[tokio::test]
+    async fn test_require_admin_in_tribe_stale_wallet_enforced() {
+        let db = setup_db().await;
+
+        sqlx::query("INSERT INTO users (id, discord_id, username, discriminator, is_admin) VALUES (?, ?, ?, ?, ?)")
+            .bind(606_i64)
+            .bind("303")
+            .bind("StaleAdmin")
+            .bind("0000")
+            .bind(false)
+            .execute(&db)
+            .await
+            .unwrap();
+
+        sqlx::query("INSERT INTO wallets (id, user_id, address, verified_at, stale_at) VALUES ('w-stale', ?, '0xstale', ?, ?)")
+            .bind(606_i64)
+            .bind(chrono::Utc::now() - chrono::Duration::days(120))
+            .bind(chrono::Utc::now())
+            .execute(&db)
+            .await
+            .unwrap();
+
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe, wallet_id, is_admin) VALUES (?, 'Earth', 'w-stale', TRUE)")
+            .bind(606_i64)
+            .execute(&db)
+            .await
+            .unwrap();
+
+        // Without an enforcing policy the stale wallet is informational only
+        let result = require_admin_in_tribe(&db, 606, Some("Earth")).await;
+        assert!(result.is_ok());
+
+        sqlx::query("INSERT INTO wallet_reverification_policies (id, tribe, admin_only, interval_days, enforce) VALUES ('p1', NULL, TRUE, 90, TRUE)")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let result = require_admin_in_tribe(&db, 606, Some("Earth")).await;
+        assert_eq!(
+            result.unwrap_err(),
+            (StatusCode::FORBIDDEN, STALE_WALLET_MESSAGE)
+        );
+
+        let result = require_admin_in_tribe(&db, 606, None).await;
+        assert_eq!(
+            result.unwrap_err(),
+            (StatusCode::FORBIDDEN, STALE_WALLET_MESSAGE)
+        );
+    }
 }
@@ -1,34 +1,156 @@
 use axum::{
+    extract::DefaultBodyLimit,
     routing::{delete, get, post, put},
     Router,
 };
 use state::AppState;
 
 pub mod admin;
+pub mod api_keys;
+pub mod approvals;
+pub mod attachments;
 pub mod audit;
 pub mod auth;
+pub mod character;
 pub mod db;
+pub mod expiry;
+pub mod export;
 pub mod helpers;
+pub mod lookup;
+pub mod member_fields;
+pub mod membership;
 pub mod middleware;
 pub mod models;
 pub mod mumble;
+pub mod note_crypto;
 pub mod notes;
+pub mod overlap;
+pub mod ranks;
+pub mod rbac;
+pub mod reverification;
 pub mod roster;
+pub mod sanctions;
+pub mod search;
 pub mod state;
+pub mod storage;
+pub mod super_admins;
+#[cfg(test)]
+mod test_support;
+pub mod totp;
+pub mod tribes;
+pub mod vouches;
 
 pub mod wallet;
 
 pub fn get_common_router() -> Router<AppState> {
     Router::new()
         .route("/api/me", get(auth::get_me).delete(auth::delete_me))
-        .route("/api/wallets/{id}", delete(wallet::unlink_wallet))
+        .route("/api/me/privacy", put(lookup::update_my_privacy))
+        .route("/api/me/tribes", get(tribes::list_my_tribes))
+        .route("/api/me/tribes/{tribe}", delete(tribes::leave_tribe))
+        .route("/api/me/vouches", get(vouches::list_my_vouches))
+        .route("/api/vouches", post(vouches::create_vouch))
+        .route("/api/vouches/{id}", delete(vouches::retract_vouch))
+        .route(
+            "/api/wallets/{id}",
+            delete(wallet::unlink_wallet).patch(wallet::update_wallet),
+        )
+        .route("/api/wallets/order", put(wallet::reorder_wallets))
+        .route(
+            "/api/wallets/reverification",
+            get(reverification::get_my_reverification_status),
+        )
+        .route(
+            "/api/api-keys",
+            get(api_keys::list_my_api_keys).post(api_keys::create_my_api_key),
+        )
+        .route("/api/api-keys/{id}", delete(api_keys::revoke_my_api_key))
         .route("/api/roster", get(roster::get_roster))
-        .route("/api/roster/{discord_id}", get(roster::get_roster_member))
+        .route("/api/roster/overview", get(roster::get_roster_overview))
+        .route("/api/roster/overlap", get(overlap::get_overlap_report))
+        .route(
+            "/api/roster/changes",
+            get(membership::get_membership_changes),
+        )
+        .route("/api/roster/export", get(export::export_roster))
+        .route("/api/roster/search", get(search::search_tribe))
+        .route(
+            "/api/roster/fields",
+            get(member_fields::list_fields).post(member_fields::create_field),
+        )
+        .route(
+            "/api/roster/fields/{key}",
+            put(member_fields::update_field).delete(member_fields::delete_field),
+        )
+        .route(
+            "/api/roster/ranks",
+            get(ranks::list_ranks).put(ranks::set_ranks),
+        )
+        .route(
+            "/api/roster/squads",
+            get(ranks::list_squads).post(ranks::create_squad),
+        )
+        .route("/api/roster/squads/{squad_id}", delete(ranks::delete_squad))
+        .route(
+            "/api/roster/squads/{squad_id}/members/{discord_id}",
+            put(ranks::add_squad_member).delete(ranks::remove_squad_member),
+        )
+        .route("/api/roster/vouches", get(vouches::list_tribe_vouches))
+        .route("/api/roster/vouches/policy", put(vouches::set_vouch_policy))
+        .route(
+            "/api/roster/sanctions",
+            get(sanctions::list_sanctions).post(sanctions::create_sanction),
+        )
+        .route(
+            "/api/roster/sanctions/allies",
+            get(sanctions::list_allies).put(sanctions::set_allies),
+        )
+        .route("/api/roster/sanctions/hits", get(sanctions::list_hits))
+        .route(
+            "/api/roster/sanctions/{id}",
+            delete(sanctions::delete_sanction),
+        )
+        .route(
+            "/api/roster/{discord_id}",
+            get(roster::get_roster_member).delete(roster::kick_member),
+        )
         .route(
             "/api/roster/{discord_id}/grant-admin",
             post(roster::grant_admin),
         )
+        .route(
+            "/api/roster/{discord_id}/promote",
+            post(ranks::promote_member),
+        )
+        .route(
+            "/api/roster/{discord_id}/demote",
+            post(ranks::demote_member),
+        )
+        .route(
+            "/api/roster/{discord_id}/fields",
+            put(member_fields::set_member_fields),
+        )
         .route("/api/roster/{discord_id}/notes", get(notes::get_notes))
         .route("/api/roster/{discord_id}/notes", post(notes::create_note))
-        .route("/api/notes/{note_id}", put(notes::edit_note))
+        .route(
+            "/api/notes/{note_id}",
+            put(notes::edit_note).delete(notes::delete_note),
+        )
+        .route("/api/notes/{note_id}/pin", put(notes::pin_note))
+        .route(
+            "/api/notes/{note_id}/revisions",
+            get(notes::get_note_revisions),
+        )
+        .route(
+            "/api/notes/{note_id}/attachments",
+            get(attachments::list_attachments)
+                .post(attachments::upload_attachment)
+                .layer(DefaultBodyLimit::max(
+                    attachments::max_attachment_bytes() + attachments::MULTIPART_OVERHEAD_BYTES,
+                )),
+        )
+        .route(
+            "/api/notes/{note_id}/attachments/{attachment_id}",
+            get(attachments::download_attachment).delete(attachments::delete_attachment),
+        )
 }
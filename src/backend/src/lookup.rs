@@ -0,0 +1,424 @@
+use crate::{
+    api_keys::{roster_read_scope, ApiKey, SCOPE_VERIFY_READ},
+    audit::{log_audit, AuditAction},
+    auth::AuthenticatedUser,
+    helpers::get_user_by_discord_id,
+    models::User,
+    state::AppState,
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Returned for unknown and hidden identities alike, so hidden users can't be probed
+const NOT_FOUND_MESSAGE: &str = "Identity not found";
+
+/// How much of a user the identity lookup may reveal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LookupVisibility {
+    /// Verification state and all tribes, to any key with `verify:read`
+    Public,
+    /// Tribe memberships only to keys scoped to that tribe
+    Tribes,
+    /// Only findable by keys scoped to one of the user's tribes
+    Hidden,
+}
+
+impl LookupVisibility {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LookupVisibility::Public => "public",
+            LookupVisibility::Tribes => "tribes",
+            LookupVisibility::Hidden => "hidden",
+        }
+    }
+
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "public" => LookupVisibility::Public,
+            "hidden" => LookupVisibility::Hidden,
+            // Anything unexpected gets the default rather than more exposure
+            _ => LookupVisibility::Tribes,
+        }
+    }
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct LookupQuery {
+    pub discord_id: Option<String>,
+    pub wallet: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LookupResponse {
+    pub discord_id: String,
+    /// Has at least one active wallet that isn't awaiting re-verification
+    pub verified: bool,
+    pub wallet_count: i64,
+    /// Tribes the key is allowed to see
+    pub tribes: Vec<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdatePrivacyRequest {
+    pub lookup_visibility: LookupVisibility,
+}
+
+/// Tribes a key may see for a user, or `None` if the user is hidden from it.
+fn visible_tribes(
+    key: &ApiKey,
+    visibility: LookupVisibility,
+    tribes: Vec<String>,
+) -> Option<Vec<String>> {
+    if visibility == LookupVisibility::Public {
+        return Some(tribes);
+    }
+
+    let scoped: Vec<String> = tribes
+        .into_iter()
+        .filter(|t| key.has_scope(&roster_read_scope(t)))
+        .collect();
+
+    if visibility == LookupVisibility::Hidden && scoped.is_empty() {
+        None
+    } else {
+        Some(scoped)
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/lookup",
+    params(LookupQuery),
+    responses(
+        (status = 200, description = "Verification state of the identity", body = LookupResponse),
+        (status = 400, description = "Exactly one of discord_id or wallet is required"),
+        (status = 401, description = "Missing or invalid API key"),
+        (status = 403, description = "API key lacks the verify:read scope"),
+        (status = 404, description = "Identity not found"),
+        (status = 429, description = "Rate limit exceeded for this key")
+    ),
+    security(
+        ("api_key" = [])
+    )
+)]
+pub async fn lookup_identity(
+    key: ApiKey,
+    Query(query): Query<LookupQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    if let Err(e) = key.require_scope(SCOPE_VERIFY_READ) {
+        return e.into_response();
+    }
+
+    // 1. Resolve the user from exactly one identifier
+    let user = match (&query.discord_id, &query.wallet) {
+        (Some(discord_id), None) => get_user_by_discord_id(&state.db, discord_id).await,
+        (None, Some(wallet)) => {
+            sqlx::query_as::<_, User>(
+                "SELECT u.* FROM users u JOIN wallets w ON w.user_id = u.id WHERE w.address = ? AND w.deleted_at IS NULL",
+            )
+            .bind(wallet.to_lowercase())
+            .fetch_optional(&state.db)
+            .await
+        }
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                "Provide exactly one of discord_id or wallet",
+            )
+                .into_response()
+        }
+    };
+
+    let user = match user {
+        Ok(Some(u)) => u,
+        Ok(None) => return (StatusCode::NOT_FOUND, NOT_FOUND_MESSAGE).into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+
+    // 2. Apply the user's privacy setting to what the key can see
+    let visibility: String =
+        match sqlx::query_scalar("SELECT lookup_visibility FROM users WHERE id = ?")
+            .bind(user.id)
+            .fetch_one(&state.db)
+            .await
+        {
+            Ok(v) => v,
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+        };
+
+    let tribes = match crate::helpers::get_user_tribes(&state.db, user.id).await {
+        Ok(t) => t,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+
+    let Some(tribes) = visible_tribes(&key, LookupVisibility::parse(&visibility), tribes) else {
+        return (StatusCode::NOT_FOUND, NOT_FOUND_MESSAGE).into_response();
+    };
+
+    // 3. Verification state from active wallets
+    let (wallet_count, fresh_count): (i64, i64) = match sqlx::query_as(
+        "SELECT COUNT(*), COALESCE(SUM(stale_at IS NULL), 0) FROM wallets WHERE user_id = ? AND deleted_at IS NULL",
+    )
+    .bind(user.id)
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(counts) => counts,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::IdentityLookup,
+        key.created_by,
+        Some(user.id),
+        &format!("Looked up {} via API key '{}'", user.discord_id, key.name),
+    )
+    .await;
+
+    Json(LookupResponse {
+        discord_id: user.discord_id,
+        verified: fresh_count > 0,
+        wallet_count,
+        tribes,
+    })
+    .into_response()
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/me/privacy",
+    request_body = UpdatePrivacyRequest,
+    responses(
+        (status = 200, description = "Privacy settings updated"),
+        (status = 401, description = "Unauthorized")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn update_my_privacy(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<UpdatePrivacyRequest>,
+) -> impl IntoResponse {
+    let res = sqlx::query("UPDATE users SET lookup_visibility = ? WHERE id = ?")
+        .bind(payload.lookup_visibility.as_str())
+        .bind(auth_user.user_id)
+        .execute(&state.db)
+        .await;
+
+    match res {
+        Ok(r) if r.rows_affected() > 0 => {
+            Json(serde_json::json!({ "lookupVisibility": payload.lookup_visibility }))
+                .into_response()
+        }
+        Ok(_) => (StatusCode::UNAUTHORIZED, "User not found").into_response(),
+        Err(e) => {
+            eprintln!("Failed to update privacy settings: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::DbPool,
+        test_support::{insert_users, setup_db},
+    };
+    use chrono::Utc;
+
+    async fn setup() -> DbPool {
+        let pool = setup_db().await;
+        insert_users(
+            &pool,
+            &[
+                (1001, "rider-discord", "Rider"),
+                (1002, "bot-owner", "Owner"),
+            ],
+        )
+        .await;
+        sqlx::query("INSERT INTO wallets (id, user_id, address, verified_at) VALUES ('w1', 1001, '0xrider', ?)")
+            .bind(Utc::now())
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe, wallet_id) VALUES (1001, 'Fire', 'w1'), (1001, 'Water', NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    fn key(scopes: &[&str]) -> ApiKey {
+        ApiKey {
+            id: "key-1".to_string(),
+            name: "Partner bot".to_string(),
+            created_by: 1002,
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    async fn lookup(
+        db: &DbPool,
+        key: ApiKey,
+        discord_id: Option<&str>,
+        wallet: Option<&str>,
+    ) -> axum::response::Response {
+        lookup_identity(
+            key,
+            Query(LookupQuery {
+                discord_id: discord_id.map(str::to_string),
+                wallet: wallet.map(str::to_string),
+            }),
+            State(AppState::new(db.clone())),
+        )
+        .await
+        .into_response()
+    }
+
+    async fn body(res: axum::response::Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn test_visible_tribes() {
+        let tribes = || vec!["Fire".to_string(), "Water".to_string()];
+        let fire_key = key(&["roster:read:Fire"]);
+        let plain_key = key(&[SCOPE_VERIFY_READ]);
+
+        assert_eq!(
+            visible_tribes(&plain_key, LookupVisibility::Public, tribes()),
+            Some(tribes())
+        );
+        assert_eq!(
+            visible_tribes(&fire_key, LookupVisibility::Tribes, tribes()),
+            Some(vec!["Fire".to_string()])
+        );
+        assert_eq!(
+            visible_tribes(&plain_key, LookupVisibility::Tribes, tribes()),
+            Some(vec![])
+        );
+        assert_eq!(
+            visible_tribes(&plain_key, LookupVisibility::Hidden, tribes()),
+            None
+        );
+        assert!(visible_tribes(&fire_key, LookupVisibility::Hidden, tribes()).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_lookup_requires_scope_and_single_identifier() {
+        let db = setup().await;
+        let res = lookup(&db, key(&["roster:read:Fire"]), Some("rider-discord"), None).await;
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        let res = lookup(&db, key(&[SCOPE_VERIFY_READ]), None, None).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let res = lookup(
+            &db,
+            key(&[SCOPE_VERIFY_READ]),
+            Some("rider-discord"),
+            Some("0xrider"),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_lookup_by_wallet_filters_tribes_by_scope() {
+        let db = setup().await;
+        let res = lookup(
+            &db,
+            key(&[SCOPE_VERIFY_READ, "roster:read:Fire"]),
+            None,
+            Some("0xRIDER"),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let json = body(res).await;
+        assert_eq!(json["discordId"], "rider-discord");
+        assert_eq!(json["verified"], true);
+        assert_eq!(json["walletCount"], 1);
+        assert_eq!(json["tribes"], serde_json::json!(["Fire"]));
+
+        let audits: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM audit_logs WHERE action = 'IDENTITY_LOOKUP' AND actor_id = 1002 AND target_id = 1001",
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(audits, 1);
+    }
+
+    #[tokio::test]
+    async fn test_lookup_respects_privacy_settings() {
+        let db = setup().await;
+
+        update_my_privacy(
+            AuthenticatedUser { user_id: 1001 },
+            State(AppState::new(db.clone())),
+            Json(UpdatePrivacyRequest {
+                lookup_visibility: LookupVisibility::Public,
+            }),
+        )
+        .await;
+        let res = lookup(&db, key(&[SCOPE_VERIFY_READ]), Some("rider-discord"), None).await;
+        assert_eq!(
+            body(res).await["tribes"],
+            serde_json::json!(["Fire", "Water"])
+        );
+
+        update_my_privacy(
+            AuthenticatedUser { user_id: 1001 },
+            State(AppState::new(db.clone())),
+            Json(UpdatePrivacyRequest {
+                lookup_visibility: LookupVisibility::Hidden,
+            }),
+        )
+        .await;
+        let res = lookup(&db, key(&[SCOPE_VERIFY_READ]), Some("rider-discord"), None).await;
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        let res = lookup(
+            &db,
+            key(&[SCOPE_VERIFY_READ, "roster:read:Water"]),
+            Some("rider-discord"),
+            None,
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let json = body(res).await;
+        assert_eq!(json["tribes"], serde_json::json!(["Water"]));
+    }
+
+    #[tokio::test]
+    async fn test_stale_wallets_are_not_verified() {
+        let db = setup().await;
+        sqlx::query("UPDATE wallets SET stale_at = ? WHERE id = 'w1'")
+            .bind(Utc::now())
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let res = lookup(&db, key(&[SCOPE_VERIFY_READ]), Some("rider-discord"), None).await;
+        let json = body(res).await;
+        assert_eq!(json["verified"], false);
+        assert_eq!(json["walletCount"], 1);
+    }
+}
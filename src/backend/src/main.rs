@@ -1,7 +1,7 @@
 use axum::{
     extract::ConnectInfo,
     http::StatusCode,
-    routing::{delete, get, patch, post},
+    routing::{delete, get, patch, post, put},
     Router,
 };
 use std::{net::SocketAddr, sync::Arc};
@@ -15,7 +15,11 @@ use tower_http::cors::CorsLayer;
 use void_eid_backend::db::init_db;
 use void_eid_backend::state::AppState;
 
-use void_eid_backend::{admin, auth, models, mumble, notes, roster, wallet};
+use void_eid_backend::{
+    admin, api_keys, approvals, attachments, auth, expiry, export, lookup, member_fields,
+    membership, models, mumble, note_crypto, notes, overlap, ranks, rbac, reverification, roster,
+    sanctions, search, super_admins, totp, tribes, vouches, wallet,
+};
 
 use utoipa::OpenApi;
 use utoipa_scalar::{Scalar, Servable};
@@ -45,6 +49,23 @@ impl KeyExtractor for FallbackIpKeyExtractor {
     }
 }
 
+/// Rate-limit key for API key callers: the ID of the key authenticated by
+/// `api_keys::require_api_key`, so each real key gets its own bucket and made-up
+/// keys never get one.
+#[derive(Clone)]
+struct ApiKeyExtractor;
+
+impl KeyExtractor for ApiKeyExtractor {
+    type Key = String;
+
+    fn extract<T>(&self, req: &axum::http::Request<T>) -> Result<Self::Key, GovernorError> {
+        req.extensions()
+            .get::<api_keys::ApiKey>()
+            .map(|key| format!("key:{}", key.id))
+            .ok_or(GovernorError::UnableToExtractKey)
+    }
+}
+
 #[derive(OpenApi)]
 #[openapi(
     paths(
@@ -53,9 +74,23 @@ impl KeyExtractor for FallbackIpKeyExtractor {
         auth::exchange_code,
         auth::get_me,
         auth::delete_me,
+        totp::get_totp_status,
+        totp::enroll_totp,
+        totp::confirm_totp,
+        totp::disable_totp,
+        totp::regenerate_recovery_codes,
+        totp::step_up,
         wallet::link_nonce,
         wallet::link_verify,
         wallet::unlink_wallet,
+        wallet::reverify_wallet,
+        wallet::update_wallet,
+        wallet::reorder_wallets,
+        reverification::get_my_reverification_status,
+        lookup::lookup_identity,
+        lookup::update_my_privacy,
+        tribes::list_my_tribes,
+        tribes::leave_tribe,
 
         admin::list_users,
         admin::update_user,
@@ -64,14 +99,78 @@ impl KeyExtractor for FallbackIpKeyExtractor {
         admin::update_tribe,
         admin::add_user_to_tribe,
         admin::delete_wallet,
+        reverification::list_policies,
+        reverification::create_policy,
+        reverification::delete_policy,
+        api_keys::list_api_keys,
+        api_keys::create_api_key,
+        api_keys::revoke_api_key,
+        api_keys::list_my_api_keys,
+        api_keys::create_my_api_key,
+        api_keys::revoke_my_api_key,
+        rbac::list_roles,
+        rbac::create_role,
+        rbac::delete_role,
+        rbac::list_user_roles,
+        rbac::assign_role,
+        rbac::unassign_role,
+        super_admins::list_super_admins,
+        super_admins::nominate_super_admin,
+        super_admins::remove_super_admin,
+        approvals::list_pending_operations,
+        approvals::approve_operation,
+        approvals::reject_operation,
+        totp::list_step_up_policies,
+        totp::update_step_up_policy,
+        totp::reset_user_totp,
 
         roster::get_roster,
+        roster::get_roster_overview,
+        overlap::get_overlap_report,
+        membership::get_membership_changes,
+        search::search_tribe,
+        member_fields::list_fields,
+        member_fields::create_field,
+        member_fields::update_field,
+        member_fields::delete_field,
+        member_fields::set_member_fields,
+        ranks::list_ranks,
+        ranks::set_ranks,
+        ranks::promote_member,
+        ranks::demote_member,
+        ranks::list_squads,
+        ranks::create_squad,
+        ranks::delete_squad,
+        ranks::add_squad_member,
+        ranks::remove_squad_member,
+        roster::kick_member,
+        vouches::create_vouch,
+        vouches::list_my_vouches,
+        vouches::retract_vouch,
+        vouches::list_tribe_vouches,
+        vouches::set_vouch_policy,
+        sanctions::list_sanctions,
+        sanctions::create_sanction,
+        sanctions::delete_sanction,
+        sanctions::list_allies,
+        sanctions::set_allies,
+        sanctions::list_hits,
+        note_crypto::get_encryption_status,
+        note_crypto::rotate_note_keys,
+        export::export_roster,
         roster::get_roster_member,
         roster::grant_admin,
 
         notes::get_notes,
         notes::create_note,
-        notes::edit_note
+        notes::edit_note,
+        notes::delete_note,
+        notes::pin_note,
+        notes::get_note_revisions,
+        attachments::upload_attachment,
+        attachments::list_attachments,
+        attachments::download_attachment,
+        attachments::delete_attachment
     ),
     components(
         schemas(
@@ -80,6 +179,38 @@ impl KeyExtractor for FallbackIpKeyExtractor {
             wallet::NonceRequest,
             wallet::NonceResponse,
             wallet::VerifyRequest,
+            wallet::ReverifyRequest,
+            wallet::UpdateWalletRequest,
+            wallet::ReorderWalletsRequest,
+            reverification::ReverificationPolicy,
+            reverification::CreatePolicyRequest,
+            reverification::WalletReverificationStatus,
+            api_keys::ApiKeyResponse,
+            api_keys::CreateApiKeyRequest,
+            api_keys::CreatedApiKeyResponse,
+            rbac::Permission,
+            rbac::RoleResponse,
+            rbac::CreateRoleRequest,
+            rbac::RoleAssignment,
+            rbac::AssignRoleRequest,
+            super_admins::SuperAdminResponse,
+            super_admins::NominateSuperAdminRequest,
+            approvals::PendingOperation,
+            totp::TotpStatus,
+            totp::TotpEnrollment,
+            totp::RecoveryCodesResponse,
+            totp::TotpCodeRequest,
+            totp::StepUpRequest,
+            totp::StepUpResponse,
+            totp::StepUpPolicy,
+            totp::UpdateStepUpPolicyRequest,
+            totp::TotpResetResponse,
+            lookup::LookupVisibility,
+            lookup::LookupResponse,
+            lookup::UpdatePrivacyRequest,
+            tribes::MyTribe,
+            tribes::MembershipWallet,
+            tribes::TribeAdmin,
             auth::CallbackParams,
             auth::Claims,
             auth::ExchangeRequest,
@@ -89,11 +220,53 @@ impl KeyExtractor for FallbackIpKeyExtractor {
             admin::CreateTribeRequest,
             admin::AddUserToTribeRequest,
             roster::RosterMember,
+            roster::RosterPage,
             roster::GrantAdminRequest,
+            roster::RosterOverview,
+            roster::TribeSummary,
+            roster::OverviewMember,
+            overlap::OverlapFinding,
+            overlap::FindingKind,
+            overlap::Severity,
+            membership::MembershipChange,
+            membership::MembershipChanges,
+            search::SearchResults,
+            member_fields::MemberField,
+            member_fields::FieldType,
+            member_fields::CreateFieldRequest,
+            member_fields::UpdateFieldRequest,
+            member_fields::SetFieldValuesRequest,
+            ranks::TribeRank,
+            ranks::RankDefinition,
+            ranks::SetRanksRequest,
+            ranks::RankChangeRequest,
+            ranks::RankChange,
+            ranks::Squad,
+            ranks::CreateSquadRequest,
+            vouches::Vouch,
+            vouches::CreateVouchRequest,
+            vouches::TribeVouches,
+            vouches::VouchPolicyRequest,
+            sanctions::Sanction,
+            sanctions::CreateSanctionRequest,
+            sanctions::SetAlliesRequest,
+            sanctions::SanctionHit,
+            sanctions::HitContext,
+            search::MemberHit,
+            search::NoteHit,
+            note_crypto::NoteEncryptionStatus,
+            note_crypto::KeyUsage,
             notes::Note,
             notes::NoteWithAuthor,
             notes::CreateNoteRequest,
-            notes::EditNoteRequest
+            notes::EditNoteRequest,
+            notes::PinNoteRequest,
+            notes::NoteVisibility,
+            notes::NoteCategory,
+            notes::NoteRevision,
+            notes::DiffLine,
+            notes::DiffOp,
+            attachments::NoteAttachment
         )
     ),
     tags(
@@ -122,7 +295,15 @@ impl utoipa::Modify for SecurityAddon {
                         .bearer_format("JWT")
                         .build(),
                 ),
-            )
+            );
+            components.add_security_scheme(
+                "api_key",
+                utoipa::openapi::security::SecurityScheme::ApiKey(
+                    utoipa::openapi::security::ApiKey::Header(
+                        utoipa::openapi::security::ApiKeyValue::new(api_keys::API_KEY_HEADER),
+                    ),
+                ),
+            );
         }
     }
 }
@@ -137,7 +318,16 @@ async fn main() -> anyhow::Result<()> {
     dotenvy::dotenv().ok();
 
     let db_pool = init_db().await?;
+    reverification::spawn_stale_wallet_sweeper(db_pool.clone());
+    expiry::spawn_grant_expiry_sweeper(db_pool.clone());
+    sanctions::spawn_sanction_alerter(db_pool.clone());
     let state = AppState::new(db_pool);
+    note_crypto::spawn_note_resealer(
+        state.db.clone(),
+        state.attachment_store.clone(),
+        state.note_keys.clone(),
+    );
+    attachments::spawn_attachment_purger(state.db.clone(), state.attachment_store.clone());
 
     // CORS Configuration - Restrict to allowed origins
     let frontend_url =
@@ -162,6 +352,7 @@ async fn main() -> anyhow::Result<()> {
         .allow_headers([
             axum::http::header::AUTHORIZATION,
             axum::http::header::CONTENT_TYPE,
+            axum::http::HeaderName::from_static("x-api-key"),
         ]);
 
     // Rate limiting configuration for sensitive endpoints
@@ -174,6 +365,7 @@ async fn main() -> anyhow::Result<()> {
             .finish()
             .expect("Failed to create rate limit config"),
     );
+    let ip_limiter = governor_conf.limiter().clone();
     let rate_limit_layer = GovernorLayer::new(governor_conf);
 
     // Rate-limited authentication routes
@@ -181,15 +373,60 @@ async fn main() -> anyhow::Result<()> {
         .route("/api/auth/discord/login", get(auth::discord_login))
         .route("/api/auth/discord/callback", get(auth::discord_callback))
         .route("/api/auth/exchange", post(auth::exchange_code))
+        .route("/api/auth/step-up", post(totp::step_up))
+        .layer(rate_limit_layer.clone());
+
+    // Rate-limited TOTP routes (every one of them checks or issues codes)
+    let totp_routes = Router::new()
+        .route(
+            "/api/me/totp",
+            get(totp::get_totp_status).delete(totp::disable_totp),
+        )
+        .route("/api/me/totp/enroll", post(totp::enroll_totp))
+        .route("/api/me/totp/confirm", post(totp::confirm_totp))
+        .route(
+            "/api/me/totp/recovery-codes",
+            post(totp::regenerate_recovery_codes),
+        )
         .layer(rate_limit_layer.clone());
 
     // Rate-limited wallet routes
     let wallet_routes = Router::new()
         .route("/api/wallets/link-nonce", post(wallet::link_nonce))
         .route("/api/wallets/link-verify", post(wallet::link_verify))
+        .route("/api/wallets/{id}/reverify", post(wallet::reverify_wallet))
         .layer(rate_limit_layer.clone());
 
-    // Internal routes (NO rate limiting - protected by INTERNAL_SECRET instead)
+    // Identity lookup, rate-limited per API key rather than per IP
+    let lookup_governor_conf = Arc::new(
+        GovernorConfigBuilder::default()
+            .per_millisecond(500)
+            .burst_size(20)
+            .key_extractor(ApiKeyExtractor)
+            .finish()
+            .expect("Failed to create lookup rate limit config"),
+    );
+    // Drop idle buckets every minute so the limiters' state stays bounded
+    let key_limiter = lookup_governor_conf.limiter().clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(60));
+        loop {
+            interval.tick().await;
+            ip_limiter.retain_recent();
+            key_limiter.retain_recent();
+        }
+    });
+
+    // The key is authenticated before the limiter, which then keys on its ID
+    let lookup_routes = Router::new()
+        .route("/api/lookup", get(lookup::lookup_identity))
+        .layer(GovernorLayer::new(lookup_governor_conf))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            api_keys::require_api_key,
+        ));
+
+    // Internal routes (NO rate limiting - protected by INTERNAL_SECRET or a mumble:auth API key instead)
     let internal_routes =
         Router::new().route("/api/internal/mumble/verify", post(mumble::verify_login));
 
@@ -197,6 +434,8 @@ async fn main() -> anyhow::Result<()> {
         .route("/ping", get(ping))
         .merge(auth_routes)
         .merge(wallet_routes)
+        .merge(totp_routes)
+        .merge(lookup_routes)
         .merge(internal_routes)
         // Admin Routes
         .route("/api/admin/users", get(admin::list_users))
@@ -211,6 +450,69 @@ async fn main() -> anyhow::Result<()> {
             post(admin::add_user_to_tribe),
         )
         .route("/api/admin/wallets/{id}", delete(admin::delete_wallet))
+        .route(
+            "/api/admin/notes/encryption",
+            get(note_crypto::get_encryption_status),
+        )
+        .route(
+            "/api/admin/notes/encryption/rotate",
+            post(note_crypto::rotate_note_keys),
+        )
+        .route(
+            "/api/admin/reverification-policies",
+            get(reverification::list_policies).post(reverification::create_policy),
+        )
+        .route(
+            "/api/admin/reverification-policies/{id}",
+            delete(reverification::delete_policy),
+        )
+        .route(
+            "/api/admin/api-keys",
+            get(api_keys::list_api_keys).post(api_keys::create_api_key),
+        )
+        .route("/api/admin/api-keys/{id}", delete(api_keys::revoke_api_key))
+        .route(
+            "/api/admin/roles",
+            get(rbac::list_roles).post(rbac::create_role),
+        )
+        .route("/api/admin/roles/{id}", delete(rbac::delete_role))
+        .route(
+            "/api/admin/users/{id}/roles",
+            get(rbac::list_user_roles).post(rbac::assign_role),
+        )
+        .route(
+            "/api/admin/users/{id}/roles/{assignment_id}",
+            delete(rbac::unassign_role),
+        )
+        .route(
+            "/api/admin/super-admins",
+            get(super_admins::list_super_admins).post(super_admins::nominate_super_admin),
+        )
+        .route(
+            "/api/admin/super-admins/{discord_id}",
+            delete(super_admins::remove_super_admin),
+        )
+        .route(
+            "/api/admin/pending-operations",
+            get(approvals::list_pending_operations),
+        )
+        .route(
+            "/api/admin/pending-operations/{id}/approve",
+            post(approvals::approve_operation),
+        )
+        .route(
+            "/api/admin/pending-operations/{id}/reject",
+            post(approvals::reject_operation),
+        )
+        .route(
+            "/api/admin/step-up-policies",
+            get(totp::list_step_up_policies),
+        )
+        .route(
+            "/api/admin/step-up-policies/{role}",
+            put(totp::update_step_up_policy),
+        )
+        .route("/api/admin/users/{id}/totp", delete(totp::reset_user_totp))
         // Mumble routes
         .route("/api/mumble/account", post(mumble::create_account))
         .route("/api/mumble/status", get(mumble::get_status))
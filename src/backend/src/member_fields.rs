@@ -0,0 +1,1135 @@
+use crate::{
+    audit::{log_audit, AuditAction},
+    db::DbPool,
+    export::ExportColumn,
+    helpers::{get_user_by_discord_id, ID_BATCH_SIZE},
+    rbac::{perm, TribeAccess},
+    state::AppState,
+    totp::StepUp,
+};
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::SqliteConnection;
+use std::collections::{BTreeMap, HashMap};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+const MAX_FIELDS_PER_TRIBE: i64 = 50;
+const MAX_KEY_CHARS: usize = 32;
+const MAX_LABEL_CHARS: usize = 64;
+const MAX_TEXT_CHARS: usize = 500;
+const MAX_OPTIONS: usize = 50;
+/// Longest enum option or tag
+const MAX_OPTION_CHARS: usize = 50;
+const MAX_TAGS: usize = 20;
+/// Numbers are kept within this magnitude so they sort correctly as text
+const MAX_NUMBER: f64 = 1e12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FieldType {
+    Text,
+    Number,
+    /// Calendar date as `YYYY-MM-DD`
+    Date,
+    /// One of the field's options
+    Enum,
+    /// Any number of tags, limited to the field's options when it has any
+    Tags,
+}
+
+impl FieldType {
+    /// Check a value supplied by a client and return its stored form.
+    /// `Ok(None)` clears the value.
+    fn normalize(&self, options: &[String], value: &Value) -> Result<Option<String>, &'static str> {
+        match self {
+            FieldType::Text => {
+                let text = value.as_str().ok_or("Expected a string")?.trim();
+                if text.chars().count() > MAX_TEXT_CHARS {
+                    return Err("Text value is too long");
+                }
+                Ok((!text.is_empty()).then(|| text.to_string()))
+            }
+            FieldType::Number => {
+                let n = value.as_f64().ok_or("Expected a number")?;
+                if !n.is_finite() || n.abs() > MAX_NUMBER {
+                    return Err("Number is out of range");
+                }
+                Ok(Some(value.to_string()))
+            }
+            FieldType::Date => {
+                let text = value.as_str().ok_or("Expected a date")?.trim();
+                let date = NaiveDate::parse_from_str(text, "%Y-%m-%d")
+                    .map_err(|_| "Dates must be YYYY-MM-DD")?;
+                Ok(Some(date.format("%Y-%m-%d").to_string()))
+            }
+            FieldType::Enum => {
+                let text = value.as_str().ok_or("Expected a string")?.trim();
+                if !options.iter().any(|o| o == text) {
+                    return Err("Value is not one of the field's options");
+                }
+                Ok(Some(text.to_string()))
+            }
+            FieldType::Tags => {
+                let items = value.as_array().ok_or("Expected a list of tags")?;
+                let mut tags: Vec<String> = Vec::new();
+                for item in items {
+                    let tag = item.as_str().ok_or("Tags must be strings")?.trim();
+                    if tag.is_empty() || tag.chars().count() > MAX_OPTION_CHARS {
+                        return Err("Tags must be 1 to 50 characters");
+                    }
+                    if !options.is_empty() && !options.iter().any(|o| o == tag) {
+                        return Err("Tag is not one of the field's options");
+                    }
+                    if !tags.iter().any(|t| t == tag) {
+                        tags.push(tag.to_string());
+                    }
+                }
+                if tags.len() > MAX_TAGS {
+                    return Err("Too many tags");
+                }
+                Ok((!tags.is_empty()).then(|| Value::from(tags).to_string()))
+            }
+        }
+    }
+
+    /// A stored value as returned to clients
+    fn to_json(self, stored: &str) -> Value {
+        match self {
+            FieldType::Number | FieldType::Tags => {
+                serde_json::from_str(stored).unwrap_or_else(|_| Value::from(stored))
+            }
+            _ => Value::from(stored),
+        }
+    }
+
+    /// A stored value as a single export cell; tags are joined like wallets
+    fn to_cell(self, stored: &str) -> String {
+        match self.to_json(stored) {
+            Value::String(s) => s,
+            Value::Array(tags) => tags
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join("; "),
+            other => other.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MemberField {
+    pub key: String,
+    pub label: String,
+    pub field_type: FieldType,
+    /// Allowed values for `ENUM` and `TAGS` fields
+    pub options: Vec<String>,
+    pub sort_order: i64,
+    pub created_at: DateTime<Utc>,
+    #[serde(skip)]
+    pub id: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct FieldRow {
+    id: String,
+    key: String,
+    label: String,
+    field_type: FieldType,
+    options: String,
+    sort_order: i64,
+    created_at: DateTime<Utc>,
+}
+
+impl From<FieldRow> for MemberField {
+    fn from(row: FieldRow) -> Self {
+        MemberField {
+            options: serde_json::from_str(&row.options).unwrap_or_default(),
+            id: row.id,
+            key: row.key,
+            label: row.label,
+            field_type: row.field_type,
+            sort_order: row.sort_order,
+            created_at: row.created_at,
+        }
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateFieldRequest {
+    /// Lowercase letters, digits and underscores, starting with a letter
+    pub key: String,
+    pub label: String,
+    pub field_type: FieldType,
+    /// Required for `ENUM`; optional for `TAGS`
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateFieldRequest {
+    pub label: Option<String>,
+    /// Replaces the options; values no longer allowed are cleared
+    pub options: Option<Vec<String>>,
+    pub sort_order: Option<i64>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SetFieldValuesRequest {
+    /// Field key to new value; `null` clears the value
+    #[schema(value_type = Object)]
+    pub values: HashMap<String, Option<Value>>,
+}
+
+/// Field values by key, as included in roster entries
+pub type FieldValues = BTreeMap<String, Value>;
+
+pub(crate) async fn tribe_fields(
+    db: &DbPool,
+    tribe: &str,
+) -> Result<Vec<MemberField>, sqlx::Error> {
+    let rows: Vec<FieldRow> = sqlx::query_as(
+        "SELECT id, key, label, field_type, options, sort_order, created_at FROM tribe_fields WHERE tribe = ? ORDER BY sort_order, key",
+    )
+    .bind(tribe)
+    .fetch_all(db)
+    .await?;
+    Ok(rows.into_iter().map(MemberField::from).collect())
+}
+
+#[derive(sqlx::FromRow)]
+struct ValueRow {
+    user_id: i64,
+    key: String,
+    field_type: FieldType,
+    value: String,
+}
+
+async fn stored_values(
+    db: &DbPool,
+    tribe: &str,
+    user_ids: &[i64],
+) -> Result<Vec<ValueRow>, sqlx::Error> {
+    let mut rows = Vec::new();
+    for chunk in user_ids.chunks(ID_BATCH_SIZE) {
+        let placeholders = chunk.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+        let sql = format!(
+            "SELECT v.user_id, f.key, f.field_type, v.value FROM member_field_values v JOIN tribe_fields f ON f.id = v.field_id WHERE f.tribe = ? AND v.user_id IN ({})",
+            placeholders
+        );
+        let mut q = sqlx::query_as::<_, ValueRow>(&sql).bind(tribe);
+        for id in chunk {
+            q = q.bind(*id);
+        }
+        rows.extend(q.fetch_all(db).await?);
+    }
+    Ok(rows)
+}
+
+/// Custom field values of the given members in a tribe
+pub(crate) async fn values_for(
+    db: &DbPool,
+    tribe: &str,
+    user_ids: &[i64],
+) -> Result<HashMap<i64, FieldValues>, sqlx::Error> {
+    let mut values: HashMap<i64, FieldValues> = HashMap::new();
+    for row in stored_values(db, tribe, user_ids).await? {
+        values
+            .entry(row.user_id)
+            .or_default()
+            .insert(row.key, row.field_type.to_json(&row.value));
+    }
+    Ok(values)
+}
+
+/// Custom field values as export cells, by user ID and field key
+pub(crate) async fn cells_for(
+    db: &DbPool,
+    tribe: &str,
+    user_ids: &[i64],
+) -> Result<HashMap<(i64, String), String>, sqlx::Error> {
+    Ok(stored_values(db, tribe, user_ids)
+        .await?
+        .into_iter()
+        .map(|row| ((row.user_id, row.key), row.field_type.to_cell(&row.value)))
+        .collect())
+}
+
+/// Sort key for roster ordering by a field: the stored value, with numbers
+/// offset and zero-padded so they compare correctly as text. Members without a
+/// value sort first. Bind the tribe and the field key.
+pub(crate) const FIELD_SORT_KEY_SQL: &str = "COALESCE((SELECT CASE f.field_type \
+     WHEN 'NUMBER' THEN printf('%023.6f', CAST(v.value AS REAL) + 1e12) ELSE v.value END \
+     FROM member_field_values v JOIN tribe_fields f ON f.id = v.field_id \
+     WHERE f.tribe = ? AND f.key = ? AND v.user_id = u.id), '')";
+
+/// Roster condition matching members whose field equals a value, or carries
+/// the tag for tag fields. Bind the tribe, the field key and the value three times.
+pub(crate) const FIELD_FILTER_SQL: &str = "EXISTS (SELECT 1 FROM member_field_values v \
+     JOIN tribe_fields f ON f.id = v.field_id \
+     WHERE f.tribe = ? AND f.key = ? AND v.user_id = u.id AND CASE f.field_type \
+     WHEN 'TAGS' THEN EXISTS (SELECT 1 FROM json_each(v.value) t WHERE t.value = ? COLLATE NOCASE) \
+     WHEN 'NUMBER' THEN CAST(v.value AS REAL) = CAST(? AS REAL) \
+     ELSE v.value = ? COLLATE NOCASE END)";
+
+fn valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    key.len() <= MAX_KEY_CHARS
+        && chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        // Field keys double as export column names
+        && !ExportColumn::is_builtin(key)
+}
+
+fn clean_label(label: &str) -> Result<String, &'static str> {
+    let label = label.trim();
+    if label.is_empty() || label.chars().count() > MAX_LABEL_CHARS {
+        return Err("Label must be 1 to 64 characters");
+    }
+    Ok(label.to_string())
+}
+
+fn clean_options(field_type: FieldType, options: &[String]) -> Result<Vec<String>, &'static str> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for option in options {
+        let option = option.trim();
+        if option.is_empty() || option.chars().count() > MAX_OPTION_CHARS {
+            return Err("Options must be 1 to 50 characters");
+        }
+        if !cleaned.iter().any(|o| o == option) {
+            cleaned.push(option.to_string());
+        }
+    }
+    if cleaned.len() > MAX_OPTIONS {
+        return Err("Too many options");
+    }
+    match field_type {
+        FieldType::Enum if cleaned.is_empty() => Err("Enum fields need at least one option"),
+        FieldType::Text | FieldType::Number | FieldType::Date if !cleaned.is_empty() => {
+            Err("Only enum and tag fields take options")
+        }
+        _ => Ok(cleaned),
+    }
+}
+
+/// Re-check every value of a field against new options, clearing enum values
+/// and dropping tags that are no longer allowed. Returns the number changed.
+async fn prune_values(
+    conn: &mut SqliteConnection,
+    field: &MemberField,
+) -> Result<u64, sqlx::Error> {
+    let values: Vec<(i64, String)> =
+        sqlx::query_as("SELECT user_id, value FROM member_field_values WHERE field_id = ?")
+            .bind(&field.id)
+            .fetch_all(&mut *conn)
+            .await?;
+    let mut changed = 0;
+    for (user_id, stored) in values {
+        let kept = match field.field_type {
+            FieldType::Enum => field.options.contains(&stored).then(|| stored.clone()),
+            FieldType::Tags if !field.options.is_empty() => {
+                let tags: Vec<String> = serde_json::from_str(&stored).unwrap_or_default();
+                let tags: Vec<String> = tags
+                    .into_iter()
+                    .filter(|t| field.options.contains(t))
+                    .collect();
+                (!tags.is_empty()).then(|| Value::from(tags).to_string())
+            }
+            _ => Some(stored.clone()),
+        };
+        if kept.as_deref() == Some(stored.as_str()) {
+            continue;
+        }
+        match kept {
+            Some(value) => {
+                sqlx::query(
+                    "UPDATE member_field_values SET value = ? WHERE field_id = ? AND user_id = ?",
+                )
+                .bind(value)
+                .bind(&field.id)
+                .bind(user_id)
+                .execute(&mut *conn)
+                .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM member_field_values WHERE field_id = ? AND user_id = ?")
+                    .bind(&field.id)
+                    .bind(user_id)
+                    .execute(&mut *conn)
+                    .await?;
+            }
+        }
+        changed += 1;
+    }
+    Ok(changed)
+}
+
+async fn find_field(
+    db: &DbPool,
+    tribe: &str,
+    key: &str,
+) -> Result<MemberField, (StatusCode, &'static str)> {
+    let row: Option<FieldRow> = sqlx::query_as(
+        "SELECT id, key, label, field_type, options, sort_order, created_at FROM tribe_fields WHERE tribe = ? AND key = ?",
+    )
+    .bind(tribe)
+    .bind(key)
+    .fetch_optional(db)
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    row.map(MemberField::from)
+        .ok_or((StatusCode::NOT_FOUND, "Field not found"))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/roster/fields",
+    params(
+        ("tribe" = Option<String>, Query, description = "Tribe whose fields to list")
+    ),
+    responses(
+        (status = 200, description = "Custom member fields of the tribe, in display order", body = Vec<MemberField>),
+        (status = 403, description = "Forbidden: Missing roster.read")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn list_fields(
+    access: TribeAccess<perm::RosterRead>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match tribe_fields(&state.db, &access.tribe).await {
+        Ok(fields) => Json(fields).into_response(),
+        Err(e) => {
+            eprintln!("Failed to list member fields: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/roster/fields",
+    params(
+        ("tribe" = Option<String>, Query, description = "Tribe to define the field in")
+    ),
+    request_body = CreateFieldRequest,
+    responses(
+        (status = 201, description = "Field defined", body = MemberField),
+        (status = 400, description = "Invalid key, label or options, or too many fields"),
+        (status = 403, description = "Forbidden: Missing roster.write"),
+        (status = 409, description = "The tribe already has a field with this key")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn create_field(
+    access: TribeAccess<perm::RosterWrite>,
+    State(state): State<AppState>,
+    Json(payload): Json<CreateFieldRequest>,
+) -> impl IntoResponse {
+    if !valid_key(&payload.key) {
+        return (
+            StatusCode::BAD_REQUEST,
+            "Keys are up to 32 lowercase letters, digits and underscores, start with a letter, and can't be a built-in column name",
+        )
+            .into_response();
+    }
+    let label = match clean_label(&payload.label) {
+        Ok(l) => l,
+        Err(msg) => return (StatusCode::BAD_REQUEST, msg).into_response(),
+    };
+    let options = match clean_options(payload.field_type, &payload.options) {
+        Ok(o) => o,
+        Err(msg) => return (StatusCode::BAD_REQUEST, msg).into_response(),
+    };
+
+    let (count, next_order): (i64, i64) = match sqlx::query_as(
+        "SELECT COUNT(*), COALESCE(MAX(sort_order) + 1, 0) FROM tribe_fields WHERE tribe = ?",
+    )
+    .bind(&access.tribe)
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(r) => r,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+    if count >= MAX_FIELDS_PER_TRIBE {
+        return (StatusCode::BAD_REQUEST, "The tribe has too many fields").into_response();
+    }
+
+    let field = MemberField {
+        id: Uuid::new_v4().to_string(),
+        key: payload.key,
+        label,
+        field_type: payload.field_type,
+        options,
+        sort_order: next_order,
+        created_at: Utc::now(),
+    };
+    let result = sqlx::query(
+        "INSERT INTO tribe_fields (id, tribe, key, label, field_type, options, sort_order, created_by, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&field.id)
+    .bind(&access.tribe)
+    .bind(&field.key)
+    .bind(&field.label)
+    .bind(field.field_type)
+    .bind(Value::from(field.options.clone()).to_string())
+    .bind(field.sort_order)
+    .bind(access.user.id)
+    .bind(field.created_at)
+    .execute(&state.db)
+    .await;
+    match result {
+        Ok(_) => {}
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return (
+                StatusCode::CONFLICT,
+                "The tribe already has a field with this key",
+            )
+                .into_response()
+        }
+        Err(e) => {
+            eprintln!("Failed to create member field: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+        }
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::MemberFieldDefine,
+        access.user.id,
+        None,
+        &format!(
+            "Defined field '{}' ({:?}) in tribe {}",
+            field.key, field.field_type, access.tribe
+        ),
+    )
+    .await;
+
+    (StatusCode::CREATED, Json(field)).into_response()
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/roster/fields/{key}",
+    params(
+        ("key" = String, Path, description = "Field key"),
+        ("tribe" = Option<String>, Query, description = "Tribe the field belongs to")
+    ),
+    request_body = UpdateFieldRequest,
+    responses(
+        (status = 200, description = "Field updated", body = MemberField),
+        (status = 400, description = "Invalid label or options"),
+        (status = 403, description = "Forbidden: Missing roster.write"),
+        (status = 404, description = "Field not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn update_field(
+    access: TribeAccess<perm::RosterWrite>,
+    Path(key): Path<String>,
+    State(state): State<AppState>,
+    Json(payload): Json<UpdateFieldRequest>,
+) -> impl IntoResponse {
+    let mut field = match find_field(&state.db, &access.tribe, &key).await {
+        Ok(f) => f,
+        Err(e) => return e.into_response(),
+    };
+    if let Some(label) = &payload.label {
+        match clean_label(label) {
+            Ok(l) => field.label = l,
+            Err(msg) => return (StatusCode::BAD_REQUEST, msg).into_response(),
+        }
+    }
+    if let Some(options) = &payload.options {
+        match clean_options(field.field_type, options) {
+            Ok(o) => field.options = o,
+            Err(msg) => return (StatusCode::BAD_REQUEST, msg).into_response(),
+        }
+    }
+    if let Some(order) = payload.sort_order {
+        field.sort_order = order;
+    }
+
+    let result: Result<u64, sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+        sqlx::query("UPDATE tribe_fields SET label = ?, options = ?, sort_order = ? WHERE id = ?")
+            .bind(&field.label)
+            .bind(Value::from(field.options.clone()).to_string())
+            .bind(field.sort_order)
+            .bind(&field.id)
+            .execute(&mut *tx)
+            .await?;
+        let pruned = prune_values(&mut tx, &field).await?;
+        tx.commit().await?;
+        Ok(pruned)
+    }
+    .await;
+    let pruned = match result {
+        Ok(n) => n,
+        Err(e) => {
+            eprintln!("Failed to update member field: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+        }
+    };
+
+    let mut details = format!("Updated field '{}' in tribe {}", field.key, access.tribe);
+    if pruned > 0 {
+        details.push_str(&format!(
+            "; {} member value(s) no longer allowed were cleared",
+            pruned
+        ));
+    }
+    let _ = log_audit(
+        &state.db,
+        AuditAction::MemberFieldDefine,
+        access.user.id,
+        None,
+        &details,
+    )
+    .await;
+
+    Json(field).into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/roster/fields/{key}",
+    params(
+        ("key" = String, Path, description = "Field key"),
+        ("tribe" = Option<String>, Query, description = "Tribe the field belongs to")
+    ),
+    responses(
+        (status = 200, description = "Field and all its values deleted"),
+        (status = 403, description = "Forbidden: Missing roster.write, or step-up required"),
+        (status = 404, description = "Field not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn delete_field(
+    access: TribeAccess<perm::RosterWrite>,
+    Path(key): Path<String>,
+    State(state): State<AppState>,
+    _step_up: StepUp,
+) -> impl IntoResponse {
+    let field = match find_field(&state.db, &access.tribe, &key).await {
+        Ok(f) => f,
+        Err(e) => return e.into_response(),
+    };
+    let values: i64 =
+        match sqlx::query_scalar("SELECT COUNT(*) FROM member_field_values WHERE field_id = ?")
+            .bind(&field.id)
+            .fetch_one(&state.db)
+            .await
+        {
+            Ok(n) => n,
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+        };
+    // Values go with the field
+    if let Err(e) = sqlx::query("DELETE FROM tribe_fields WHERE id = ?")
+        .bind(&field.id)
+        .execute(&state.db)
+        .await
+    {
+        eprintln!("Failed to delete member field: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::MemberFieldDefine,
+        access.user.id,
+        None,
+        &format!(
+            "Deleted field '{}' and {} member value(s) in tribe {}",
+            field.key, values, access.tribe
+        ),
+    )
+    .await;
+
+    StatusCode::OK.into_response()
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/roster/{discord_id}/fields",
+    params(
+        ("discord_id" = String, Path, description = "Discord ID of the member"),
+        ("tribe" = Option<String>, Query, description = "Tribe whose fields to set")
+    ),
+    request_body = SetFieldValuesRequest,
+    responses(
+        (status = 200, description = "The member's field values after the update", body = Object),
+        (status = 400, description = "Unknown field or invalid value"),
+        (status = 403, description = "Forbidden: Missing roster.write, or member not in the tribe"),
+        (status = 404, description = "Member not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn set_member_fields(
+    access: TribeAccess<perm::RosterWrite>,
+    Path(discord_id): Path<String>,
+    State(state): State<AppState>,
+    Json(payload): Json<SetFieldValuesRequest>,
+) -> impl IntoResponse {
+    let member = match get_user_by_discord_id(&state.db, &discord_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return (StatusCode::NOT_FOUND, "Member not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+    match sqlx::query_scalar::<_, i64>(
+        "SELECT COUNT(*) FROM user_tribes WHERE user_id = ? AND tribe = ?",
+    )
+    .bind(member.id)
+    .bind(&access.tribe)
+    .fetch_one(&state.db)
+    .await
+    {
+        Ok(0) => {
+            return (
+                StatusCode::FORBIDDEN,
+                "Access denied: Member is not in the specified tribe",
+            )
+                .into_response()
+        }
+        Ok(_) => {}
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    }
+
+    let fields: HashMap<String, MemberField> = match tribe_fields(&state.db, &access.tribe).await {
+        Ok(fields) => fields.into_iter().map(|f| (f.key.clone(), f)).collect(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+
+    // Validate everything before writing anything
+    let mut changes: Vec<(&MemberField, Option<String>)> = Vec::new();
+    for (key, value) in &payload.values {
+        let Some(field) = fields.get(key) else {
+            return (StatusCode::BAD_REQUEST, "Unknown field").into_response();
+        };
+        let stored = match value {
+            None | Some(Value::Null) => None,
+            Some(v) => match field.field_type.normalize(&field.options, v) {
+                Ok(stored) => stored,
+                Err(msg) => return (StatusCode::BAD_REQUEST, msg).into_response(),
+            },
+        };
+        changes.push((field, stored));
+    }
+    changes.sort_by(|a, b| a.0.key.cmp(&b.0.key));
+
+    let result: Result<(), sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+        for (field, stored) in &changes {
+            match stored {
+                Some(value) => {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO member_field_values (field_id, user_id, value, updated_by, updated_at)
+                        VALUES (?, ?, ?, ?, ?)
+                        ON CONFLICT(field_id, user_id) DO UPDATE SET
+                            value = excluded.value, updated_by = excluded.updated_by, updated_at = excluded.updated_at
+                        "#,
+                    )
+                    .bind(&field.id)
+                    .bind(member.id)
+                    .bind(value)
+                    .bind(access.user.id)
+                    .bind(Utc::now())
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                None => {
+                    sqlx::query(
+                        "DELETE FROM member_field_values WHERE field_id = ? AND user_id = ?",
+                    )
+                    .bind(&field.id)
+                    .bind(member.id)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+        }
+        tx.commit().await
+    }
+    .await;
+    if let Err(e) = result {
+        eprintln!("Failed to set member fields: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+    }
+
+    if !changes.is_empty() {
+        let keys: Vec<&str> = changes.iter().map(|(f, _)| f.key.as_str()).collect();
+        let _ = log_audit(
+            &state.db,
+            AuditAction::MemberFieldSet,
+            access.user.id,
+            Some(member.id),
+            &format!(
+                "Updated fields {} of {} in tribe {}",
+                keys.join(", "),
+                member.username,
+                access.tribe
+            ),
+        )
+        .await;
+    }
+
+    match values_for(&state.db, &access.tribe, &[member.id]).await {
+        Ok(mut values) => Json(values.remove(&member.id).unwrap_or_default()).into_response(),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        export::{export_roster, ExportQuery},
+        roster::{fetch_members, RosterFilter, RosterQuery},
+        test_support::{access, add_members, insert_users, setup_db},
+    };
+    use axum::extract::Query;
+    use serde_json::json;
+
+    async fn setup() -> AppState {
+        let db = setup_db().await;
+        insert_users(
+            &db,
+            &[
+                (1, "111", "Chief"),
+                (2, "222", "Digger"),
+                (3, "333", "Gunner"),
+                (4, "444", "Hauler"),
+            ],
+        )
+        .await;
+        add_members(
+            &db,
+            &[
+                (1, "Fire", true),
+                (2, "Fire", false),
+                (3, "Fire", false),
+                (4, "Fire", false),
+            ],
+        )
+        .await;
+        AppState::new(db)
+    }
+
+    async fn define(
+        state: &AppState,
+        key: &str,
+        field_type: FieldType,
+        options: &[&str],
+    ) -> StatusCode {
+        create_field(
+            access(&state.db, 1, "Fire").await,
+            State(state.clone()),
+            Json(CreateFieldRequest {
+                key: key.to_string(),
+                label: key.to_uppercase(),
+                field_type,
+                options: options.iter().map(|o| o.to_string()).collect(),
+            }),
+        )
+        .await
+        .into_response()
+        .status()
+    }
+
+    async fn set(state: &AppState, discord_id: &str, values: Value) -> axum::response::Response {
+        set_member_fields(
+            access(&state.db, 1, "Fire").await,
+            Path(discord_id.to_string()),
+            State(state.clone()),
+            Json(serde_json::from_value(json!({ "values": values })).unwrap()),
+        )
+        .await
+        .into_response()
+    }
+
+    fn roster_query(sort: &str, fields: Option<&str>) -> RosterQuery {
+        RosterQuery {
+            tribe: Some("Fire".to_string()),
+            sort: Some(sort.to_string()),
+            order: None,
+            search: None,
+            fields: fields.map(str::to_string),
+            as_of: None,
+            limit: None,
+            cursor: None,
+            format: None,
+        }
+    }
+
+    async fn roster(state: &AppState, sort: &str, fields: Option<&str>) -> Vec<String> {
+        let filter = RosterFilter::new(&roster_query(sort, fields), "Fire".to_string());
+        filter.check_fields(&state.db).await.unwrap();
+        fetch_members(&state.db, &filter, None, None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.user.username)
+            .collect()
+    }
+
+    #[test]
+    fn test_normalize_values() {
+        let opts = vec!["miner".to_string(), "pvp".to_string()];
+        assert_eq!(
+            FieldType::Date.normalize(&[], &json!("2026-03-01")),
+            Ok(Some("2026-03-01".to_string()))
+        );
+        assert!(FieldType::Date
+            .normalize(&[], &json!("01/03/2026"))
+            .is_err());
+        assert_eq!(
+            FieldType::Number.normalize(&[], &json!(-2.5)),
+            Ok(Some("-2.5".to_string()))
+        );
+        assert!(FieldType::Number.normalize(&[], &json!("5")).is_err());
+        assert!(FieldType::Number.normalize(&[], &json!(1e13)).is_err());
+        assert!(FieldType::Enum.normalize(&opts, &json!("hauler")).is_err());
+        assert_eq!(
+            FieldType::Tags.normalize(&opts, &json!(["pvp", " miner", "pvp"])),
+            Ok(Some(r#"["pvp","miner"]"#.to_string()))
+        );
+        assert_eq!(FieldType::Tags.normalize(&[], &json!([])), Ok(None));
+        assert_eq!(FieldType::Text.normalize(&[], &json!("  ")), Ok(None));
+        assert_eq!(FieldType::Tags.to_cell(r#"["pvp","miner"]"#), "pvp; miner");
+
+        assert!(valid_key("timezone_2"));
+        assert!(!valid_key("Timezone"));
+        assert!(!valid_key("2tz"));
+        assert!(!valid_key("username"));
+    }
+
+    #[tokio::test]
+    async fn test_define_and_set_fields() {
+        let state = setup().await;
+        assert_eq!(
+            define(&state, "role", FieldType::Enum, &["miner", "hauler", "pvp"]).await,
+            StatusCode::CREATED
+        );
+        assert_eq!(
+            define(&state, "skills", FieldType::Tags, &[]).await,
+            StatusCode::CREATED
+        );
+        assert_eq!(
+            define(&state, "kills", FieldType::Number, &[]).await,
+            StatusCode::CREATED
+        );
+        assert_eq!(
+            define(&state, "joined", FieldType::Date, &[]).await,
+            StatusCode::CREATED
+        );
+        assert_eq!(
+            define(&state, "role", FieldType::Text, &[]).await,
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            define(&state, "note", FieldType::Enum, &[]).await,
+            StatusCode::BAD_REQUEST
+        );
+
+        let res = set(
+            &state,
+            "222",
+            json!({ "role": "miner", "skills": ["ore", "refining"], "kills": 3 }),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let values: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(
+            values,
+            json!({ "role": "miner", "skills": ["ore", "refining"], "kills": 3 })
+        );
+
+        // A bad value rejects the whole update
+        let res = set(&state, "333", json!({ "role": "pvp", "kills": "lots" })).await;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            set(&state, "333", json!({ "rank": "x" })).await.status(),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            set(
+                &state,
+                "333",
+                json!({ "role": "pvp", "kills": -12, "skills": ["Ore"] })
+            )
+            .await
+            .status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            set(
+                &state,
+                "444",
+                json!({ "kills": 120.5, "joined": "2026-01-02" })
+            )
+            .await
+            .status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            set(&state, "999", json!({})).await.status(),
+            StatusCode::NOT_FOUND
+        );
+
+        // Numbers sort numerically, members without a value first
+        assert_eq!(
+            roster(&state, "field:kills", None).await,
+            ["Chief", "Gunner", "Digger", "Hauler"]
+        );
+        assert_eq!(
+            roster(&state, "field:role", None).await,
+            ["Chief", "Hauler", "Digger", "Gunner"]
+        );
+        // Filters match exact values, any tag, and numbers by value
+        assert_eq!(
+            roster(&state, "username", Some("skills:ore")).await,
+            ["Digger", "Gunner"]
+        );
+        assert_eq!(
+            roster(&state, "username", Some("skills:ore,role:PVP")).await,
+            ["Gunner"]
+        );
+        assert_eq!(
+            roster(&state, "username", Some("kills:120.50")).await,
+            ["Hauler"]
+        );
+        let filter = RosterFilter::new(&roster_query("field:rank", None), "Fire".to_string());
+        assert!(filter.check_fields(&state.db).await.is_err());
+        let filter = RosterFilter::new(&roster_query("username", Some("role")), "Fire".to_string());
+        assert!(filter.check_fields(&state.db).await.is_err());
+
+        // Exports take field keys as columns
+        let res = export_roster(
+            access(&state.db, 1, "Fire").await,
+            Query(roster_query("field:kills", Some("skills:ore"))),
+            Query(ExportQuery {
+                columns: Some("username,role,skills,kills".to_string()),
+            }),
+            State(state.clone()),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(body.to_vec()).unwrap(),
+            "username,role,skills,kills\r\n\
+             Gunner,pvp,Ore,'-12\r\n\
+             Digger,miner,ore; refining,3\r\n"
+        );
+
+        // Narrowing options clears values that are no longer allowed
+        let res = update_field(
+            access(&state.db, 1, "Fire").await,
+            Path("role".to_string()),
+            State(state.clone()),
+            Json(UpdateFieldRequest {
+                label: Some("Main role".to_string()),
+                options: Some(vec!["miner".to_string(), "hauler".to_string()]),
+                sort_order: None,
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        let values = values_for(&state.db, "Fire", &[2, 3]).await.unwrap();
+        assert_eq!(values[&2]["role"], json!("miner"));
+        assert!(!values[&3].contains_key("role"));
+
+        // Leaving the tribe drops the member's values
+        sqlx::query("DELETE FROM user_tribes WHERE user_id = 2")
+            .execute(&state.db)
+            .await
+            .unwrap();
+        assert!(values_for(&state.db, "Fire", &[2])
+            .await
+            .unwrap()
+            .is_empty());
+
+        let res = delete_field(
+            access(&state.db, 1, "Fire").await,
+            Path("kills".to_string()),
+            State(state.clone()),
+            StepUp,
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        let values = values_for(&state.db, "Fire", &[4]).await.unwrap();
+        assert_eq!(values[&4].keys().collect::<Vec<_>>(), ["joined"]);
+
+        let actions: Vec<String> =
+            sqlx::query_scalar("SELECT DISTINCT action FROM audit_logs ORDER BY action")
+                .fetch_all(&state.db)
+                .await
+                .unwrap();
+        assert_eq!(
+            actions,
+            ["EXPORT_ROSTER", "MEMBER_FIELD_DEFINE", "MEMBER_FIELD_SET"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fields_follow_tribe_rename() {
+        let state = setup().await;
+        assert_eq!(
+            define(&state, "role", FieldType::Text, &[]).await,
+            StatusCode::CREATED
+        );
+        assert_eq!(
+            set(&state, "222", json!({ "role": "miner" }))
+                .await
+                .status(),
+            StatusCode::OK
+        );
+
+        let res = crate::admin::apply_update_tribe(
+            &state,
+            &crate::middleware::admin::RequireSuperAdmin {
+                discord_id: "111".to_string(),
+            },
+            "Fire".to_string(),
+            "Flame".to_string(),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let (tribe, value): (String, String) = sqlx::query_as(
+            "SELECT f.tribe, v.value FROM tribe_fields f JOIN member_field_values v ON v.field_id = f.id WHERE f.key = 'role'",
+        )
+        .fetch_one(&state.db)
+        .await
+        .unwrap();
+        assert_eq!((tribe.as_str(), value.as_str()), ("Flame", "miner"));
+    }
+}
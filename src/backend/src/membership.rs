@@ -0,0 +1,378 @@
+use crate::{
+    audit::{log_audit, AuditAction},
+    rbac::{perm, TribeAccess},
+    state::AppState,
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+/// Record who added a member on the interval the insert trigger just opened.
+pub async fn attribute_join<'e, E>(
+    executor: E,
+    user_id: i64,
+    tribe: &str,
+    actor_id: i64,
+) -> Result<(), sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+{
+    sqlx::query(
+        r#"
+        UPDATE tribe_membership_history SET joined_by = ?
+        WHERE id = (
+            SELECT id FROM tribe_membership_history
+            WHERE user_id = ? AND tribe = ? AND left_at IS NULL
+            ORDER BY id DESC LIMIT 1
+        )
+        "#,
+    )
+    .bind(actor_id)
+    .bind(user_id)
+    .bind(tribe)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Record who removed a member on the interval the delete trigger just closed.
+pub async fn attribute_leave<'e, E>(
+    executor: E,
+    user_id: i64,
+    tribe: &str,
+    actor_id: i64,
+) -> Result<(), sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+{
+    sqlx::query(
+        r#"
+        UPDATE tribe_membership_history SET left_by = ?
+        WHERE id = (
+            SELECT id FROM tribe_membership_history
+            WHERE user_id = ? AND tribe = ? AND left_at IS NOT NULL
+            ORDER BY id DESC LIMIT 1
+        )
+        "#,
+    )
+    .bind(actor_id)
+    .bind(user_id)
+    .bind(tribe)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct MembershipChangesQuery {
+    pub tribe: Option<String>,
+    /// Start of the window (exclusive)
+    pub from: DateTime<Utc>,
+    /// End of the window (inclusive); defaults to now
+    pub to: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, ToSchema, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct MembershipChange {
+    pub discord_id: String,
+    pub username: String,
+    pub source: String,
+    pub at: DateTime<Utc>,
+    /// The user who made the change; absent for automatic and self-service changes
+    pub actor_username: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MembershipChanges {
+    pub tribe: String,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub joined: Vec<MembershipChange>,
+    pub left: Vec<MembershipChange>,
+}
+
+async fn changes(
+    state: &AppState,
+    tribe: &str,
+    column: &str,
+    actor_column: &str,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+) -> Result<Vec<MembershipChange>, sqlx::Error> {
+    sqlx::query_as::<_, MembershipChange>(&format!(
+        r#"
+        SELECT u.discord_id, u.username, h.source, h.{column} AS at, a.username AS actor_username
+        FROM tribe_membership_history h
+        JOIN users u ON u.id = h.user_id
+        LEFT JOIN users a ON a.id = h.{actor_column}
+        WHERE h.tribe = ?
+          AND h.{column} > strftime('%Y-%m-%dT%H:%M:%fZ', ?)
+          AND h.{column} <= strftime('%Y-%m-%dT%H:%M:%fZ', ?)
+        ORDER BY h.{column}, u.username
+        "#
+    ))
+    .bind(tribe)
+    .bind(from)
+    .bind(to)
+    .fetch_all(&state.db)
+    .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/roster/changes",
+    params(MembershipChangesQuery),
+    responses(
+        (status = 200, description = "Members who joined or left the tribe in the window", body = MembershipChanges),
+        (status = 400, description = "Invalid window"),
+        (status = 403, description = "Forbidden: Missing roster.read in tribe")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn get_membership_changes(
+    access: TribeAccess<perm::RosterRead>,
+    Query(query): Query<MembershipChangesQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let to = query.to.unwrap_or_else(Utc::now);
+    if query.from >= to {
+        return (StatusCode::BAD_REQUEST, "'from' must be before 'to'").into_response();
+    }
+
+    let joined = changes(
+        &state,
+        &access.tribe,
+        "joined_at",
+        "joined_by",
+        query.from,
+        to,
+    )
+    .await;
+    let left = changes(&state, &access.tribe, "left_at", "left_by", query.from, to).await;
+    let (joined, left) = match (joined, left) {
+        (Ok(j), Ok(l)) => (j, l),
+        (Err(e), _) | (_, Err(e)) => {
+            eprintln!("Failed to fetch membership changes: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response();
+        }
+    };
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::ViewRoster,
+        access.user.id,
+        None,
+        &format!(
+            "Viewed membership changes for tribe {} from {} to {}",
+            access.tribe,
+            query.from.to_rfc3339(),
+            to.to_rfc3339()
+        ),
+    )
+    .await;
+
+    Json(MembershipChanges {
+        tribe: access.tribe,
+        from: query.from,
+        to,
+        joined,
+        left,
+    })
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::DbPool,
+        test_support::{access, add_members, insert_users, setup_db},
+    };
+    use chrono::Duration;
+
+    async fn setup() -> DbPool {
+        let pool = setup_db().await;
+        insert_users(
+            &pool,
+            &[
+                (1, "111", "Chief"),
+                (2, "222", "Veteran"),
+                (3, "333", "Recruit"),
+            ],
+        )
+        .await;
+        add_members(&pool, &[(1, "Fire", true)]).await;
+        pool
+    }
+
+    async fn rename_fire(db: &DbPool) {
+        let res = crate::admin::apply_update_tribe(
+            &AppState::new(db.clone()),
+            &crate::middleware::admin::RequireSuperAdmin {
+                discord_id: "111".to_string(),
+            },
+            "Fire".to_string(),
+            "Flame".to_string(),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_history_follows_membership_writes() {
+        let db = setup().await;
+
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe) VALUES (2, 'Fire')")
+            .execute(&db)
+            .await
+            .unwrap();
+        attribute_join(&db, 2, "Fire", 1).await.unwrap();
+        sqlx::query("DELETE FROM user_tribes WHERE user_id = 2")
+            .execute(&db)
+            .await
+            .unwrap();
+        attribute_leave(&db, 2, "Fire", 1).await.unwrap();
+        // Rejoining opens a second interval
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe, source) VALUES (2, 'Fire', 'CHAIN')")
+            .execute(&db)
+            .await
+            .unwrap();
+        // Renames carry the history over
+        rename_fire(&db).await;
+
+        type HistoryRow = (String, String, bool, Option<i64>, Option<i64>);
+        let rows: Vec<HistoryRow> = sqlx::query_as(
+            "SELECT tribe, source, left_at IS NOT NULL, joined_by, left_by FROM tribe_membership_history WHERE user_id = 2 ORDER BY id",
+        )
+        .fetch_all(&db)
+        .await
+        .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                (
+                    "Flame".to_string(),
+                    "MANUAL".to_string(),
+                    true,
+                    Some(1),
+                    Some(1)
+                ),
+                ("Flame".to_string(), "CHAIN".to_string(), false, None, None),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rename_carries_departed_members_history() {
+        let db = setup().await;
+
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe) VALUES (3, 'Fire')")
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM user_tribes WHERE user_id = 3")
+            .execute(&db)
+            .await
+            .unwrap();
+        rename_fire(&db).await;
+
+        let tribes: Vec<String> =
+            sqlx::query_scalar("SELECT tribe FROM tribe_membership_history WHERE user_id = 3")
+                .fetch_all(&db)
+                .await
+                .unwrap();
+        assert_eq!(tribes, vec!["Flame"]);
+    }
+
+    #[tokio::test]
+    async fn test_membership_changes() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+        let now = Utc::now();
+        let stamp = |days: i64| {
+            (now - Duration::days(days))
+                .format("%Y-%m-%dT%H:%M:%S%.3fZ")
+                .to_string()
+        };
+
+        // Veteran left five days ago, Recruit joined two days ago
+        sqlx::query("INSERT INTO tribe_membership_history (user_id, tribe, joined_at, left_at, left_by) VALUES (2, 'Fire', ?, ?, 1)")
+            .bind(stamp(60))
+            .bind(stamp(5))
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO tribe_membership_history (user_id, tribe, source, joined_at) VALUES (3, 'Fire', 'CHAIN', ?)")
+            .bind(stamp(2))
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let res = get_membership_changes(
+            access(&db, 1, "Fire").await,
+            Query(MembershipChangesQuery {
+                tribe: Some("Fire".to_string()),
+                from: now - Duration::days(7),
+                to: None,
+            }),
+            State(state.clone()),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        // Chief's membership was opened by the insert trigger during setup
+        assert_eq!(json["joined"].as_array().unwrap().len(), 2);
+        assert_eq!(json["joined"][0]["username"], "Recruit");
+        assert_eq!(json["joined"][1]["username"], "Chief");
+        assert_eq!(json["joined"][0]["source"], "CHAIN");
+        assert_eq!(json["left"].as_array().unwrap().len(), 1);
+        assert_eq!(json["left"][0]["username"], "Veteran");
+        assert_eq!(json["left"][0]["actorUsername"], "Chief");
+
+        // A window before both changes is empty
+        let res = get_membership_changes(
+            access(&db, 1, "Fire").await,
+            Query(MembershipChangesQuery {
+                tribe: Some("Fire".to_string()),
+                from: now - Duration::days(30),
+                to: Some(now - Duration::days(10)),
+            }),
+            State(state.clone()),
+        )
+        .await
+        .into_response();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert!(json["joined"].as_array().unwrap().is_empty());
+        assert!(json["left"].as_array().unwrap().is_empty());
+
+        let res = get_membership_changes(
+            access(&db, 1, "Fire").await,
+            Query(MembershipChangesQuery {
+                tribe: Some("Fire".to_string()),
+                from: now,
+                to: Some(now - Duration::days(1)),
+            }),
+            State(state),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+}
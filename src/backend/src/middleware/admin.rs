@@ -1,10 +1,11 @@
-use crate::auth::Claims;
+use crate::{
+    auth::decode_bearer, state::AppState, super_admins::is_super_admin,
+    totp::require_super_admin_step_up,
+};
 use axum::{
-    extract::FromRequestParts,
-    http::{header, request::Parts, StatusCode},
+    extract::{FromRef, FromRequestParts},
+    http::{request::Parts, StatusCode},
 };
-use jsonwebtoken::{decode, DecodingKey, Validation};
-use std::env;
 
 pub struct RequireSuperAdmin {
     pub discord_id: String,
@@ -12,43 +13,30 @@ pub struct RequireSuperAdmin {
 
 impl<S> FromRequestParts<S> for RequireSuperAdmin
 where
+    AppState: FromRef<S>,
     S: Send + Sync,
 {
     type Rejection = (StatusCode, &'static str);
 
-    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        // 1. Extract Bearer Token
-        let auth_header = parts
-            .headers
-            .get(header::AUTHORIZATION)
-            .and_then(|h| h.to_str().ok())
-            .ok_or((StatusCode::UNAUTHORIZED, "Missing Auth Header"))?;
-
-        let token = auth_header
-            .strip_prefix("Bearer ")
-            .ok_or((StatusCode::UNAUTHORIZED, "Invalid Auth Header"))?;
-
-        // 2. Decode Token
-        let secret = env::var("JWT_SECRET")
-            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "JWT Config Error"))?;
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        // 1. Decode Bearer Token
+        let claims = decode_bearer(&parts.headers)?;
 
-        let token_data = decode::<Claims>(
-            token,
-            &DecodingKey::from_secret(secret.as_bytes()),
-            &Validation::default(),
-        )
-        .map_err(|_| (StatusCode::UNAUTHORIZED, "Invalid Token"))?;
+        // 2. Strict Check against the super admin registry
+        let state = AppState::from_ref(state);
+        let allowed = is_super_admin(&state.db, &claims.discord_id)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
 
-        let discord_id = token_data.claims.discord_id;
+        if !allowed {
+            return Err((StatusCode::FORBIDDEN, "Not a Super Admin"));
+        }
 
-        // 3. Strict Check against Environment Variable
-        let super_admin_ids_str = env::var("SUPER_ADMIN_DISCORD_IDS").unwrap_or_default();
-        let super_admin_ids: Vec<&str> = super_admin_ids_str.split(',').map(|s| s.trim()).collect();
+        // 3. Recent TOTP step-up, when the super admin policy requires one
+        require_super_admin_step_up(&state.db, claims.step_up_at).await?;
 
-        if super_admin_ids.contains(&discord_id.as_str()) {
-            Ok(RequireSuperAdmin { discord_id })
-        } else {
-            Err((StatusCode::FORBIDDEN, "Not a Super Admin"))
-        }
+        Ok(RequireSuperAdmin {
+            discord_id: claims.discord_id,
+        })
     }
 }
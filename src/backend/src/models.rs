@@ -23,6 +23,29 @@ pub mod i64_as_string {
     }
 }
 
+pub mod option_i64_as_string {
+    use serde::{self, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(value: &Option<i64>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match value {
+            Some(v) => serializer.serialize_some(&v.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<String>::deserialize(deserializer)?
+            .map(|s| s.parse().map_err(serde::de::Error::custom))
+            .transpose()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone, ToSchema)]
 pub struct User {
     #[serde(with = "i64_as_string")]
@@ -64,9 +87,24 @@ pub struct LinkedWallet {
     pub deleted_at: Option<DateTime<Utc>>,
     #[serde(default = "default_network")]
     pub network: String,
+    #[serde(default)]
+    pub stale_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub character_name: Option<String>,
+    #[serde(default)]
+    pub is_primary: bool,
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub sort_order: i64,
     pub tribes: Vec<String>,
 }
 
+/// Order wallets the way the user arranged them, oldest link first on ties.
+pub fn sort_wallets(wallets: &mut [LinkedWallet]) {
+    wallets.sort_by_key(|w| (w.sort_order, w.verified_at));
+}
+
 fn default_network() -> String {
     "mainnet".to_string()
 }
@@ -80,5 +118,11 @@ pub struct FlatLinkedWallet {
     pub verified_at: DateTime<Utc>,
     pub deleted_at: Option<DateTime<Utc>>,
     pub network: String,
+    pub stale_at: Option<DateTime<Utc>>,
+    pub character_name: Option<String>,
+    pub character_name_refreshed_at: Option<DateTime<Utc>>,
+    pub is_primary: bool,
+    pub label: Option<String>,
+    pub sort_order: i64,
     pub tribe: Option<String>,
 }
@@ -1,8 +1,11 @@
+use crate::api_keys::{ApiKey, API_KEY_HEADER, SCOPE_MUMBLE_AUTH};
 use crate::auth::{self, InternalSecret};
+use crate::character::resolve_character_name;
+use crate::models::FlatLinkedWallet;
 use crate::state::AppState;
 use axum::{
-    extract::{Json, State},
-    http::StatusCode,
+    extract::{FromRef, FromRequestParts, Json, State},
+    http::{request::Parts, StatusCode},
     response::IntoResponse,
 };
 use bcrypt::{hash, verify, DEFAULT_COST};
@@ -33,6 +36,31 @@ pub struct VerifyLoginRequest {
 pub struct VerifyLoginResponse {
     pub user_id: i64,
     pub username: String,
+    /// Mumble groups from the user's rank and squads in the required tribe
+    pub groups: Vec<String>,
+}
+
+/// The Murmur authenticator: either the shared `INTERNAL_SECRET` or an API key with
+/// the `mumble:auth` scope.
+pub struct MumbleAuthenticator;
+
+impl<S> FromRequestParts<S> for MumbleAuthenticator
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        if parts.headers.contains_key(API_KEY_HEADER) {
+            ApiKey::from_request_parts(parts, state)
+                .await?
+                .require_scope(SCOPE_MUMBLE_AUTH)?;
+        } else {
+            InternalSecret::from_request_parts(parts, state).await?;
+        }
+        Ok(MumbleAuthenticator)
+    }
 }
 
 pub async fn create_account(
@@ -66,14 +94,29 @@ pub async fn create_account(
         }
     };
 
-    // ... (previous code)
-
-    // 2. Get username (based on rider name/wallet from tribe) - simplified: using database username for now or custom logic
-    // Requirement: "create a username based upon their rider name (the wallet that is in the Fire tribe)"
-    // We need to fetch the wallet/rider name associated with the tribe.
-
-    // Update: If no wallet_id is found (manual assignment), fall back to the user's website username.
+    // A membership backed by a stale wallet under an enforcing policy doesn't qualify
+    match crate::reverification::stale_enforced_tribes(&state.db, user_id).await {
+        Ok(stale) if stale.contains(&state.mumble_required_tribe) => {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(json!({"error": "Wallet re-verification required"})),
+            )
+                .into_response()
+        }
+        Ok(_) => {}
+        Err(_) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({"error": "Database error"})),
+            )
+                .into_response()
+        }
+    }
 
+    // 2. Get username based on the rider name: the on-chain character of the wallet
+    // that grants membership of the required tribe.
+    // If no wallet is linked (manual assignment) or it has no character, fall back to
+    // the user's website username.
     let rider_name_query = sqlx::query(
         "SELECT ut.wallet_id, u.username
          FROM user_tribes ut
@@ -89,7 +132,11 @@ pub async fn create_account(
         Ok(row) => {
             let wallet_id: Option<String> = row.get("wallet_id");
             let user_username: String = row.get("username");
-            resolve_mumble_username(wallet_id, user_username)
+            let character_name = match wallet_id {
+                Some(id) => rider_name(&state, &id).await,
+                None => None,
+            };
+            resolve_mumble_username(character_name, user_username)
         }
         Err(_) => {
             return (
@@ -174,12 +221,25 @@ pub async fn create_account(
         .into_response()
 }
 
+/// Looks up the character name of an active wallet, refreshing the cached value if needed.
+async fn rider_name(state: &AppState, wallet_id: &str) -> Option<String> {
+    let wallet = sqlx::query_as::<_, FlatLinkedWallet>(
+        "SELECT *, NULL as tribe FROM wallets WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(wallet_id)
+    .fetch_optional(&state.db)
+    .await
+    .ok()??;
+
+    resolve_character_name(&state.db, state.character_client.as_ref(), &wallet).await
+}
+
 /// Resolves the username to use for Mumble.
-/// Prioritizes the wallet_id (rider name/address) if present.
-/// Falls back to the user's username if wallet_id is None.
-fn resolve_mumble_username(wallet_id: Option<String>, username: String) -> String {
-    match wallet_id {
-        Some(w) if !w.trim().is_empty() => w,
+/// Prioritizes the character (rider) name if present.
+/// Falls back to the user's username if there is no character name.
+fn resolve_mumble_username(character_name: Option<String>, username: String) -> String {
+    match character_name {
+        Some(name) if !name.trim().is_empty() => name,
         _ => username,
     }
 }
@@ -191,7 +251,7 @@ fn sanitize_username(name: &str) -> String {
 
 pub async fn verify_login(
     State(state): State<AppState>,
-    InternalSecret(_secret): InternalSecret, // Ensures this is only called by trusted Authenticator
+    _authenticator: MumbleAuthenticator, // Ensures this is only called by trusted Authenticator
     Json(payload): Json<VerifyLoginRequest>,
 ) -> impl IntoResponse {
     let row = sqlx::query("SELECT user_id, password_hash FROM mumble_accounts WHERE username = ?")
@@ -219,11 +279,20 @@ pub async fn verify_login(
                 eprintln!("Failed to log mumble login audit: {}", e);
             }
 
+            let groups =
+                crate::ranks::mumble_groups(&state.db, user_id, &state.mumble_required_tribe)
+                    .await
+                    .unwrap_or_else(|e| {
+                        eprintln!("Failed to load mumble groups: {}", e);
+                        Vec::new()
+                    });
+
             return (
                 StatusCode::OK,
                 Json(VerifyLoginResponse {
                     user_id,
                     username: payload.username,
+                    groups,
                 }),
             )
                 .into_response();
@@ -290,26 +359,26 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_resolve_mumble_username_with_wallet() {
-        let wallet = Some("Friendly Rider".to_string());
+    fn test_resolve_mumble_username_with_character() {
+        let character = Some("Friendly Rider".to_string());
         let username = "VoidUser".to_string();
-        let result = resolve_mumble_username(wallet, username);
+        let result = resolve_mumble_username(character, username);
         assert_eq!(result, "Friendly Rider");
     }
 
     #[test]
     fn test_resolve_mumble_username_fallback() {
-        let wallet = None;
+        let character = None;
         let username = "VoidUser".to_string();
-        let result = resolve_mumble_username(wallet, username);
+        let result = resolve_mumble_username(character, username);
         assert_eq!(result, "VoidUser");
     }
 
     #[test]
-    fn test_resolve_mumble_username_empty_wallet() {
-        let wallet = Some("   ".to_string());
+    fn test_resolve_mumble_username_empty_character() {
+        let character = Some("   ".to_string());
         let username = "VoidUser".to_string();
-        let result = resolve_mumble_username(wallet, username);
+        let result = resolve_mumble_username(character, username);
         assert_eq!(result, "VoidUser");
     }
 
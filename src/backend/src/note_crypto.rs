@@ -0,0 +1,787 @@
+use crate::{
+    admin::get_admin_id,
+    attachments,
+    audit::{alert_admin_action, log_audit, AuditAction},
+    db::DbPool,
+    middleware::admin::RequireSuperAdmin,
+    state::AppState,
+    storage::BlobStore,
+};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use sqlx::SqliteConnection;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+const NONCE_BYTES: usize = 24;
+/// Index terms are every prefix of a word from this length up to the maximum
+const MIN_PREFIX_CHARS: usize = 2;
+const MAX_PREFIX_CHARS: usize = 20;
+const MAX_QUERY_TERMS: usize = 8;
+const SNIPPET_WORDS: usize = 16;
+/// Search key ID recorded for notes indexed without a search key
+const PLAIN_INDEX: &str = "plain";
+const RESEAL_BATCH_SIZE: i64 = 200;
+/// How often the background pass looks for rows on an old key or not yet indexed
+const RESEAL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+/// Set while a re-encryption pass runs so passes never overlap
+static RESEAL_RUNNING: AtomicBool = AtomicBool::new(false);
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Keys for sealing note content. Every key that may still be in use by a row is
+/// kept so it can be opened; new writes use the current key.
+pub struct NoteKeys {
+    current: String,
+    keys: HashMap<String, [u8; 32]>,
+    search_key: [u8; 32],
+}
+
+fn decode_key(name: &str, encoded: &str) -> Result<[u8; 32], String> {
+    STANDARD
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or_else(|| format!("{} must be 32 bytes of base64", name))
+}
+
+impl NoteKeys {
+    pub fn new(
+        current: &str,
+        keys: impl IntoIterator<Item = (String, [u8; 32])>,
+        search_key: [u8; 32],
+    ) -> Result<Self, String> {
+        let keys: HashMap<String, [u8; 32]> = keys.into_iter().collect();
+        if !keys.contains_key(current) {
+            return Err(format!("current note key '{}' is not configured", current));
+        }
+        Ok(Self {
+            current: current.to_string(),
+            keys,
+            search_key,
+        })
+    }
+
+    /// Load keys from `NOTES_ENCRYPTION_KEYS` (`id:base64,...`), the current key
+    /// from `NOTES_ENCRYPTION_KEY_ID` (default: the first listed) and the search
+    /// key from `NOTES_SEARCH_KEY`. `None` when encryption is not configured.
+    pub fn from_env() -> Result<Option<Self>, String> {
+        let Ok(list) = std::env::var("NOTES_ENCRYPTION_KEYS") else {
+            return Ok(None);
+        };
+        if list.trim().is_empty() {
+            return Ok(None);
+        }
+
+        let mut keys = Vec::new();
+        for entry in list.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            let (id, encoded) = entry
+                .split_once(':')
+                .ok_or("NOTES_ENCRYPTION_KEYS entries must look like id:base64key")?;
+            let id = id.trim();
+            if id.is_empty() {
+                return Err("note key IDs cannot be empty".to_string());
+            }
+            keys.push((
+                id.to_string(),
+                decode_key("NOTES_ENCRYPTION_KEYS", encoded)?,
+            ));
+        }
+        let current = match std::env::var("NOTES_ENCRYPTION_KEY_ID") {
+            Ok(id) if !id.trim().is_empty() => id.trim().to_string(),
+            _ => keys
+                .first()
+                .map(|(id, _)| id.clone())
+                .ok_or("NOTES_ENCRYPTION_KEYS lists no keys")?,
+        };
+        let search_key = std::env::var("NOTES_SEARCH_KEY")
+            .map_err(|_| "NOTES_SEARCH_KEY is required when notes are encrypted".to_string())
+            .and_then(|k| decode_key("NOTES_SEARCH_KEY", &k))?;
+
+        Self::new(&current, keys, search_key).map(Some)
+    }
+
+    pub fn current_key_id(&self) -> &str {
+        &self.current
+    }
+
+    fn search_token(&self, term: &str) -> String {
+        let mut mac = <HmacSha256 as Mac>::new_from_slice(&self.search_key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(term.as_bytes());
+        hex::encode(&mac.finalize().into_bytes()[..10])
+    }
+
+    /// Stable, non-secret name for the search key, so a changed key is noticed
+    fn search_key_id(&self) -> String {
+        format!("hmac-{}", &self.search_token("\0search-key-id")[..8])
+    }
+}
+
+/// Seal bytes under the current key as nonce || ciphertext, bound to `aad`.
+/// Unchanged, with no key ID, when encryption is not configured.
+pub fn seal_bytes(
+    keys: Option<&NoteKeys>,
+    aad: &str,
+    plaintext: &[u8],
+) -> (Vec<u8>, Option<String>) {
+    let Some(keys) = keys else {
+        return (plaintext.to_vec(), None);
+    };
+    let key = &keys.keys[&keys.current];
+    let nonce: [u8; NONCE_BYTES] = rand::random();
+    let ciphertext = XChaCha20Poly1305::new(key.into())
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext,
+                aad: aad.as_bytes(),
+            },
+        )
+        .expect("encryption of note content cannot fail");
+
+    let mut stored = nonce.to_vec();
+    stored.extend_from_slice(&ciphertext);
+    (stored, Some(keys.current.clone()))
+}
+
+/// Recover bytes stored by [`seal_bytes`]. `None` when the key is unknown or the
+/// data has been tampered with.
+pub fn open_bytes(
+    keys: Option<&NoteKeys>,
+    aad: &str,
+    key_id: Option<&str>,
+    stored: Vec<u8>,
+) -> Option<Vec<u8>> {
+    let Some(key_id) = key_id else {
+        return Some(stored);
+    };
+    let key = keys?.keys.get(key_id)?;
+    if stored.len() <= NONCE_BYTES {
+        return None;
+    }
+    let (nonce, ciphertext) = stored.split_at(NONCE_BYTES);
+    XChaCha20Poly1305::new(key.into())
+        .decrypt(
+            XNonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: aad.as_bytes(),
+            },
+        )
+        .ok()
+}
+
+/// Seal note content for storage. Returns the stored text and the key ID, or the
+/// plaintext and no key when encryption is not configured. The note ID is bound
+/// in so sealed content can't be moved to another note.
+pub fn seal(keys: Option<&NoteKeys>, note_id: &str, plaintext: &str) -> (String, Option<String>) {
+    match seal_bytes(keys, note_id, plaintext.as_bytes()) {
+        (sealed, Some(key_id)) => (STANDARD.encode(sealed), Some(key_id)),
+        (_, None) => (plaintext.to_string(), None),
+    }
+}
+
+/// Recover note content stored by [`seal`].
+pub fn open(
+    keys: Option<&NoteKeys>,
+    note_id: &str,
+    key_id: Option<&str>,
+    stored: &str,
+) -> Option<String> {
+    if key_id.is_none() {
+        return Some(stored.to_string());
+    }
+    let bytes = STANDARD.decode(stored).ok()?;
+    String::from_utf8(open_bytes(keys, note_id, key_id, bytes)?).ok()
+}
+
+/// Lowercased alphanumeric words of `text`.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+}
+
+fn truncate_chars(word: &str, max: usize) -> &str {
+    match word.char_indices().nth(max) {
+        Some((end, _)) => &word[..end],
+        None => word,
+    }
+}
+
+fn token(keys: Option<&NoteKeys>, term: &str) -> String {
+    match keys {
+        Some(keys) => keys.search_token(term),
+        None => term.to_string(),
+    }
+}
+
+/// Index text for a note: every word prefix, hashed with the search key when
+/// notes are encrypted so the index never holds readable words.
+pub fn index_terms(keys: Option<&NoteKeys>, plaintext: &str) -> String {
+    let mut terms = Vec::new();
+    for word in words(plaintext) {
+        let len = word.chars().count();
+        if len < MIN_PREFIX_CHARS {
+            terms.push(token(keys, &word));
+            continue;
+        }
+        for n in MIN_PREFIX_CHARS..=len.min(MAX_PREFIX_CHARS) {
+            terms.push(token(keys, truncate_chars(&word, n)));
+        }
+    }
+    terms.join(" ")
+}
+
+/// The search words in `input`, as matched against the index.
+fn query_words(input: &str) -> Vec<String> {
+    words(input)
+        .take(MAX_QUERY_TERMS)
+        .map(|w| truncate_chars(&w, MAX_PREFIX_CHARS).to_string())
+        .collect()
+}
+
+/// FTS5 query matching notes that contain a word starting with each word of
+/// `input`. `None` when there is nothing to search for.
+pub fn query_terms(keys: Option<&NoteKeys>, input: &str) -> Option<String> {
+    let terms: Vec<String> = query_words(input)
+        .iter()
+        .map(|w| format!("\"{}\"", token(keys, w)))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// A short excerpt of `text` around the first word matching `input`, with the
+/// matching words wrapped in `**`.
+pub fn snippet(text: &str, input: &str) -> String {
+    let wanted = query_words(input);
+    let matches = |raw: &str| words(raw).any(|w| wanted.iter().any(|q| w.starts_with(q.as_str())));
+
+    let raw: Vec<&str> = text.split_whitespace().collect();
+    let first = raw.iter().position(|w| matches(w)).unwrap_or(0);
+    let start = first.saturating_sub(SNIPPET_WORDS / 3);
+    let end = (start + SNIPPET_WORDS).min(raw.len());
+
+    let mut out: Vec<String> = raw[start..end]
+        .iter()
+        .map(|w| {
+            if matches(w) {
+                format!("**{}**", w)
+            } else {
+                w.to_string()
+            }
+        })
+        .collect();
+    if start > 0 {
+        out.insert(0, "…".to_string());
+    }
+    if end < raw.len() {
+        out.push("…".to_string());
+    }
+    out.join(" ")
+}
+
+/// Replace the note's search index entry. Call with the plaintext whenever the
+/// content changes.
+pub async fn index_note(
+    conn: &mut SqliteConnection,
+    keys: Option<&NoteKeys>,
+    note_id: &str,
+    plaintext: &str,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("DELETE FROM notes_fts WHERE rowid = (SELECT rowid FROM notes WHERE id = ?)")
+        .bind(note_id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("INSERT INTO notes_fts (rowid, terms) SELECT rowid, ? FROM notes WHERE id = ?")
+        .bind(index_terms(keys, plaintext))
+        .bind(note_id)
+        .execute(&mut *conn)
+        .await?;
+    sqlx::query("UPDATE notes SET search_key_id = ? WHERE id = ?")
+        .bind(search_key_id(keys))
+        .bind(note_id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+fn search_key_id(keys: Option<&NoteKeys>) -> String {
+    keys.map(NoteKeys::search_key_id)
+        .unwrap_or_else(|| PLAIN_INDEX.to_string())
+}
+
+#[derive(Debug, Default, Serialize, ToSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResealReport {
+    /// Notes re-encrypted or re-indexed
+    pub notes: u64,
+    /// Revisions re-encrypted
+    pub revisions: u64,
+    /// Attachment files re-encrypted
+    pub attachments: u64,
+    /// Rows that could not be opened with any configured key
+    pub failed: u64,
+}
+
+#[derive(sqlx::FromRow)]
+struct StoredNote {
+    rowid: i64,
+    id: String,
+    content: String,
+    key_id: Option<String>,
+    search_key_id: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct StoredRevision {
+    id: i64,
+    note_id: String,
+    content: String,
+    key_id: Option<String>,
+}
+
+/// Bring every note and revision onto the current key (sealing plaintext rows
+/// once keys are configured) and rebuild stale search index entries.
+pub async fn reseal_all(db: &DbPool, keys: Option<&NoteKeys>) -> Result<ResealReport, sqlx::Error> {
+    let current = keys.map(|k| k.current.clone());
+    let index_id = search_key_id(keys);
+    let mut report = ResealReport::default();
+
+    let mut after = 0_i64;
+    loop {
+        let batch: Vec<StoredNote> = sqlx::query_as(
+            r#"
+            SELECT rowid, id, content, key_id, search_key_id FROM notes
+            WHERE rowid > ? AND (key_id IS NOT ? OR search_key_id IS NOT ?)
+            ORDER BY rowid LIMIT ?
+            "#,
+        )
+        .bind(after)
+        .bind(&current)
+        .bind(&index_id)
+        .bind(RESEAL_BATCH_SIZE)
+        .fetch_all(db)
+        .await?;
+        let Some(last) = batch.last() else { break };
+        after = last.rowid;
+
+        for note in batch {
+            let Some(plaintext) = open(keys, &note.id, note.key_id.as_deref(), &note.content)
+            else {
+                eprintln!(
+                    "Note {} could not be opened with the configured keys",
+                    note.id
+                );
+                report.failed += 1;
+                continue;
+            };
+            let mut tx = db.begin().await?;
+            if note.key_id != current {
+                let (stored, key_id) = seal(keys, &note.id, &plaintext);
+                // Skip notes edited since they were read; the edit sealed them already
+                let updated = sqlx::query(
+                    "UPDATE notes SET content = ?, key_id = ? WHERE id = ? AND content = ?",
+                )
+                .bind(&stored)
+                .bind(&key_id)
+                .bind(&note.id)
+                .bind(&note.content)
+                .execute(&mut *tx)
+                .await?;
+                if updated.rows_affected() == 0 {
+                    continue;
+                }
+            }
+            if note.search_key_id.as_deref() != Some(index_id.as_str()) || note.key_id != current {
+                index_note(&mut tx, keys, &note.id, &plaintext).await?;
+            }
+            tx.commit().await?;
+            report.notes += 1;
+        }
+    }
+
+    let mut after = 0_i64;
+    loop {
+        let batch: Vec<StoredRevision> = sqlx::query_as(
+            "SELECT id, note_id, content, key_id FROM note_revisions WHERE id > ? AND key_id IS NOT ? ORDER BY id LIMIT ?",
+        )
+        .bind(after)
+        .bind(&current)
+        .bind(RESEAL_BATCH_SIZE)
+        .fetch_all(db)
+        .await?;
+        let Some(last) = batch.last() else { break };
+        after = last.id;
+
+        for revision in batch {
+            let Some(plaintext) = open(
+                keys,
+                &revision.note_id,
+                revision.key_id.as_deref(),
+                &revision.content,
+            ) else {
+                eprintln!(
+                    "Revision {} of note {} could not be opened with the configured keys",
+                    revision.id, revision.note_id
+                );
+                report.failed += 1;
+                continue;
+            };
+            let (stored, key_id) = seal(keys, &revision.note_id, &plaintext);
+            sqlx::query("UPDATE note_revisions SET content = ?, key_id = ? WHERE id = ?")
+                .bind(&stored)
+                .bind(&key_id)
+                .bind(revision.id)
+                .execute(db)
+                .await?;
+            report.revisions += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Run [`reseal_all`] and re-seal attachment files unless a pass is already
+/// running. `None` when skipped.
+async fn reseal_exclusive(
+    db: &DbPool,
+    store: &dyn BlobStore,
+    keys: Option<&NoteKeys>,
+) -> Option<Result<ResealReport, sqlx::Error>> {
+    if RESEAL_RUNNING.swap(true, Ordering::SeqCst) {
+        return None;
+    }
+    let result = async {
+        let mut report = reseal_all(db, keys).await?;
+        let (moved, failed) = attachments::reseal_attachments(db, store, keys).await?;
+        report.attachments = moved;
+        report.failed += failed;
+        Ok(report)
+    }
+    .await;
+    RESEAL_RUNNING.store(false, Ordering::SeqCst);
+    Some(result)
+}
+
+fn report_reseal(result: Option<Result<ResealReport, sqlx::Error>>) {
+    match result {
+        None
+        | Some(Ok(ResealReport {
+            notes: 0,
+            revisions: 0,
+            attachments: 0,
+            failed: 0,
+        })) => {}
+        Some(Ok(r)) => println!(
+            "Re-sealed {} note(s), {} revision(s) and {} attachment(s); {} could not be opened",
+            r.notes, r.revisions, r.attachments, r.failed
+        ),
+        Some(Err(e)) => eprintln!("Note re-encryption pass failed: {}", e),
+    }
+}
+
+pub fn spawn_note_resealer(db: DbPool, store: Arc<dyn BlobStore>, keys: Option<Arc<NoteKeys>>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(RESEAL_INTERVAL);
+        loop {
+            interval.tick().await;
+            report_reseal(reseal_exclusive(&db, store.as_ref(), keys.as_deref()).await);
+        }
+    });
+}
+
+#[derive(Serialize, ToSchema, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyUsage {
+    /// Absent for rows still stored as plaintext
+    pub key_id: Option<String>,
+    pub notes: i64,
+    pub revisions: i64,
+    pub attachments: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteEncryptionStatus {
+    pub enabled: bool,
+    pub current_key_id: Option<String>,
+    /// Rows per key; rotation is complete when only the current key remains
+    pub keys: Vec<KeyUsage>,
+    /// Notes whose search index entry is missing or built with another key
+    pub unindexed: i64,
+    pub running: bool,
+}
+
+async fn encryption_status(
+    db: &DbPool,
+    keys: Option<&NoteKeys>,
+) -> Result<NoteEncryptionStatus, sqlx::Error> {
+    let usage: Vec<KeyUsage> = sqlx::query_as(
+        r#"
+        SELECT key_id, SUM(notes) AS notes, SUM(revisions) AS revisions,
+            SUM(attachments) AS attachments
+        FROM (
+            SELECT key_id, 1 AS notes, 0 AS revisions, 0 AS attachments FROM notes
+            UNION ALL
+            SELECT key_id, 0, 1, 0 FROM note_revisions
+            UNION ALL
+            SELECT key_id, 0, 0, 1 FROM note_attachments
+        )
+        GROUP BY key_id ORDER BY key_id
+        "#,
+    )
+    .fetch_all(db)
+    .await?;
+    let unindexed: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM notes WHERE search_key_id IS NOT ?")
+            .bind(search_key_id(keys))
+            .fetch_one(db)
+            .await?;
+
+    Ok(NoteEncryptionStatus {
+        enabled: keys.is_some(),
+        current_key_id: keys.map(|k| k.current.clone()),
+        keys: usage,
+        unindexed,
+        running: RESEAL_RUNNING.load(Ordering::SeqCst),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/notes/encryption",
+    tag = "Admin",
+    responses(
+        (status = 200, description = "Note encryption keys in use and rows per key", body = NoteEncryptionStatus),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn get_encryption_status(
+    State(state): State<AppState>,
+    _admin: RequireSuperAdmin,
+) -> impl IntoResponse {
+    match encryption_status(&state.db, state.note_keys.as_deref()).await {
+        Ok(status) => Json(status).into_response(),
+        Err(e) => {
+            eprintln!("Failed to read note encryption status: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/notes/encryption/rotate",
+    tag = "Admin",
+    responses(
+        (status = 202, description = "Re-encryption started in the background", body = NoteEncryptionStatus),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+        (status = 409, description = "A re-encryption pass is already running"),
+    )
+)]
+pub async fn rotate_note_keys(
+    State(state): State<AppState>,
+    admin: RequireSuperAdmin,
+) -> impl IntoResponse {
+    if RESEAL_RUNNING.load(Ordering::SeqCst) {
+        return (
+            StatusCode::CONFLICT,
+            "A re-encryption pass is already running",
+        )
+            .into_response();
+    }
+    let status = match encryption_status(&state.db, state.note_keys.as_deref()).await {
+        Ok(s) => s,
+        Err(e) => {
+            eprintln!("Failed to read note encryption status: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let details = format!(
+        "Started note re-encryption to key {}",
+        status.current_key_id.as_deref().unwrap_or("(plaintext)")
+    );
+    let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+    let _ = log_audit(
+        &state.db,
+        AuditAction::NoteKeyRotation,
+        admin_id,
+        None,
+        &details,
+    )
+    .await;
+    alert_admin_action(
+        format!("SuperAdmin ({})", admin.discord_id),
+        AuditAction::NoteKeyRotation,
+        details,
+    );
+
+    let db = state.db.clone();
+    let store = state.attachment_store.clone();
+    let keys = state.note_keys.clone();
+    tokio::spawn(async move {
+        report_reseal(reseal_exclusive(&db, store.as_ref(), keys.as_deref()).await);
+    });
+
+    (StatusCode::ACCEPTED, Json(status)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    fn keys(current: &str) -> NoteKeys {
+        NoteKeys::new(
+            current,
+            [("k1".to_string(), [1u8; 32]), ("k2".to_string(), [2u8; 32])],
+            [9u8; 32],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_seal_roundtrip_and_binding() {
+        let k1 = keys("k1");
+        let (stored, key_id) = seal(Some(&k1), "note-1", "Spotted near the gate");
+        assert_eq!(key_id.as_deref(), Some("k1"));
+        assert!(!stored.contains("gate"));
+        assert_eq!(
+            open(Some(&k1), "note-1", Some("k1"), &stored).as_deref(),
+            Some("Spotted near the gate")
+        );
+        // Bound to the note, and unreadable without the key
+        assert_eq!(open(Some(&k1), "note-2", Some("k1"), &stored), None);
+        assert_eq!(open(None, "note-1", Some("k1"), &stored), None);
+        // Plaintext rows pass through
+        assert_eq!(
+            open(Some(&k1), "note-1", None, "plain").as_deref(),
+            Some("plain")
+        );
+        assert_eq!(seal(None, "note-1", "plain"), ("plain".to_string(), None));
+    }
+
+    #[test]
+    fn test_search_terms() {
+        let k1 = keys("k1");
+        assert_eq!(index_terms(None, "Storm, a X"), "st sto stor storm a x");
+        assert_eq!(
+            query_terms(None, "STO \"x").as_deref(),
+            Some("\"sto\" \"x\"")
+        );
+        // Keyed tokens hide the words but still line up with queries
+        let indexed = index_terms(Some(&k1), "Storm");
+        assert!(!indexed.contains("sto"));
+        let query = query_terms(Some(&k1), "stor").unwrap();
+        assert!(indexed.contains(query.trim_matches('"')));
+        assert_eq!(query_terms(None, " ,. "), None);
+
+        assert_eq!(
+            snippet("Seen with the storm fleet", "fle"),
+            "Seen with the storm **fleet**"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reseal_and_rotation() {
+        let db = SqlitePoolOptions::new()
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        sqlx::migrate!("./migrations").run(&db).await.unwrap();
+        sqlx::query("INSERT INTO users (id, discord_id, username, discriminator) VALUES (1, '111', 'Chief', '0000')")
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO notes (id, target_user_id, author_id, tribe, content) VALUES ('n1', 1, 1, 'Fire', 'Storm fleet sighted')")
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO note_revisions (note_id, revision, content, category, visibility, created_at) VALUES ('n1', 1, 'Storm sighted', 'GENERAL', 'TRIBE_ADMINS', CURRENT_TIMESTAMP)")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let stored = || async {
+            sqlx::query_as::<_, (String, Option<String>)>(
+                "SELECT content, key_id FROM notes WHERE id = 'n1'",
+            )
+            .fetch_one(&db)
+            .await
+            .unwrap()
+        };
+        let hits = |q: String| {
+            let db = db.clone();
+            async move {
+                sqlx::query_scalar::<_, i64>(
+                    "SELECT COUNT(*) FROM notes_fts WHERE notes_fts MATCH ?",
+                )
+                .bind(q)
+                .fetch_one(&db)
+                .await
+                .unwrap()
+            }
+        };
+
+        // Without keys the pass only builds the index
+        let report = reseal_all(&db, None).await.unwrap();
+        assert_eq!((report.notes, report.revisions), (1, 0));
+        assert_eq!(stored().await.1, None);
+        assert_eq!(hits(query_terms(None, "fle").unwrap()).await, 1);
+
+        // Configuring keys seals plaintext rows and rebuilds the index
+        let k1 = keys("k1");
+        let report = reseal_all(&db, Some(&k1)).await.unwrap();
+        assert_eq!((report.notes, report.revisions), (1, 1));
+        let (content, key_id) = stored().await;
+        assert_eq!(key_id.as_deref(), Some("k1"));
+        assert!(!content.contains("Storm"));
+        assert_eq!(hits(query_terms(None, "fle").unwrap()).await, 0);
+        assert_eq!(hits(query_terms(Some(&k1), "fle").unwrap()).await, 1);
+        assert_eq!(
+            reseal_all(&db, Some(&k1)).await.unwrap(),
+            ResealReport::default()
+        );
+
+        // Rotating moves every row to the new key
+        let k2 = keys("k2");
+        let report = reseal_all(&db, Some(&k2)).await.unwrap();
+        assert_eq!((report.notes, report.revisions), (1, 1));
+        let (content, key_id) = stored().await;
+        assert_eq!(key_id.as_deref(), Some("k2"));
+        assert_eq!(
+            open(Some(&k2), "n1", Some("k2"), &content).as_deref(),
+            Some("Storm fleet sighted")
+        );
+        let status = encryption_status(&db, Some(&k2)).await.unwrap();
+        assert_eq!(status.keys.len(), 1);
+        assert_eq!(status.keys[0].key_id.as_deref(), Some("k2"));
+        assert_eq!((status.keys[0].notes, status.keys[0].revisions), (1, 1));
+        assert_eq!(status.unindexed, 0);
+
+        // Rows on a key that was dropped are reported, not lost
+        let only_k1 = NoteKeys::new("k1", [("k1".to_string(), [1u8; 32])], [9u8; 32]).unwrap();
+        let report = reseal_all(&db, Some(&only_k1)).await.unwrap();
+        assert_eq!(report.failed, 2);
+        assert_eq!(stored().await.1.as_deref(), Some("k2"));
+    }
+}
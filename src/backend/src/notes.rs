@@ -1,11 +1,16 @@
 use crate::{
     audit::{log_audit, AuditAction},
     auth::AuthenticatedUser,
-    helpers::{get_user_by_discord_id, require_admin_in_tribe},
+    helpers::get_user_by_discord_id,
+    models::User,
+    note_crypto::{self, index_note},
+    rbac::{has_permission_in_tribe, perm, Permission, TribeAccess},
     state::AppState,
+    super_admins::is_super_admin,
+    totp::StepUp,
 };
 use axum::{
-    extract::{Json, Path, Query, State},
+    extract::{Json, Path, State},
     http::StatusCode,
     response::IntoResponse,
 };
@@ -14,6 +19,39 @@ use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
+/// Who may read a note, on top of `notes.read` in its tribe. Authors always
+/// see their own notes.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, sqlx::Type, ToSchema,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NoteVisibility {
+    /// Everyone with `notes.read` in the tribe
+    #[default]
+    TribeAdmins,
+    Author,
+    SuperAdmins,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, sqlx::Type, ToSchema,
+)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NoteCategory {
+    Recruitment,
+    Warning,
+    Commendation,
+    #[default]
+    General,
+}
+
+/// SQL condition limiting notes aliased `n` to those the caller may read.
+/// Bind the caller's user ID and whether they are a super admin.
+pub(crate) const VISIBLE_NOTES_SQL: &str =
+    "(n.visibility = 'TRIBE_ADMINS' OR n.author_id = ? OR (n.visibility = 'SUPER_ADMINS' AND ?))";
+
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct Note {
@@ -26,6 +64,31 @@ pub struct Note {
     pub content: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub pinned: bool,
+    pub visibility: NoteVisibility,
+    pub category: NoteCategory,
+    #[serde(skip)]
+    pub deleted_at: Option<DateTime<Utc>>,
+    /// Key that sealed `content` in storage; `None` once revealed or for plaintext rows
+    #[serde(skip)]
+    pub key_id: Option<String>,
+}
+
+impl NoteVisibility {
+    /// Whether `user_id` may read content saved under this visibility by `author_id`
+    fn allows(self, author_id: i64, user_id: i64, super_admin: bool) -> bool {
+        match self {
+            NoteVisibility::TribeAdmins => true,
+            NoteVisibility::Author => author_id == user_id,
+            NoteVisibility::SuperAdmins => author_id == user_id || super_admin,
+        }
+    }
+}
+
+impl Note {
+    fn visible_to(&self, user_id: i64, super_admin: bool) -> bool {
+        self.visibility.allows(self.author_id, user_id, super_admin)
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, sqlx::FromRow, ToSchema)]
@@ -40,8 +103,13 @@ pub struct NoteWithAuthor {
     pub content: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub pinned: bool,
+    pub visibility: NoteVisibility,
+    pub category: NoteCategory,
     pub author_username: String,
     pub author_discriminator: String,
+    #[serde(skip)]
+    pub key_id: Option<String>,
 }
 
 #[derive(Deserialize, IntoParams)]
@@ -52,11 +120,105 @@ pub struct NotesQuery {
 #[derive(Deserialize, ToSchema)]
 pub struct CreateNoteRequest {
     pub content: String,
+    #[serde(default)]
+    pub category: NoteCategory,
+    #[serde(default)]
+    pub visibility: NoteVisibility,
+    #[serde(default)]
+    pub pinned: bool,
 }
 
 #[derive(Deserialize, ToSchema)]
 pub struct EditNoteRequest {
     pub content: String,
+    /// Unchanged when absent
+    #[serde(default)]
+    pub category: Option<NoteCategory>,
+    /// Unchanged when absent
+    #[serde(default)]
+    pub visibility: Option<NoteVisibility>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct PinNoteRequest {
+    pub pinned: bool,
+}
+
+/// Start of a note for audit details. Encrypted notes are not copied into the
+/// audit log.
+fn excerpt(state: &AppState, content: &str) -> String {
+    if state.note_keys.is_some() {
+        "(content encrypted)".to_string()
+    } else if content.len() > 50 {
+        let end = (0..=50)
+            .rev()
+            .find(|&i| content.is_char_boundary(i))
+            .unwrap_or(0);
+        format!("{}...", &content[..end])
+    } else {
+        content.to_string()
+    }
+}
+
+/// Replace content loaded from storage with its plaintext.
+fn reveal(
+    state: &AppState,
+    note_id: &str,
+    key_id: &mut Option<String>,
+    content: &mut String,
+) -> Result<(), (StatusCode, &'static str)> {
+    match note_crypto::open(
+        state.note_keys.as_deref(),
+        note_id,
+        key_id.as_deref(),
+        content,
+    ) {
+        Some(plaintext) => {
+            *content = plaintext;
+            *key_id = None;
+            Ok(())
+        }
+        None => {
+            eprintln!("Failed to decrypt note {}", note_id);
+            Err((StatusCode::INTERNAL_SERVER_ERROR, "Failed to decrypt note"))
+        }
+    }
+}
+
+/// Load a live note the caller may see and act on with `permission` in its tribe.
+/// Hidden and deleted notes are reported as missing.
+pub(crate) async fn note_for_action(
+    state: &AppState,
+    note_id: &str,
+    user: &User,
+    permission: Permission,
+) -> Result<Note, (StatusCode, &'static str)> {
+    let note: Option<Note> =
+        sqlx::query_as("SELECT * FROM notes WHERE id = ? AND deleted_at IS NULL")
+            .bind(note_id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    let mut note = note.ok_or((StatusCode::NOT_FOUND, "Note not found"))?;
+
+    let super_admin = is_super_admin(&state.db, &user.discord_id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    if !note.visible_to(user.id, super_admin) {
+        return Err((StatusCode::NOT_FOUND, "Note not found"));
+    }
+
+    match has_permission_in_tribe(&state.db, user.id, &note.tribe, permission).await {
+        Ok(true) => {
+            reveal(state, &note.id, &mut note.key_id, &mut note.content)?;
+            Ok(note)
+        }
+        Ok(false) => Err((
+            StatusCode::FORBIDDEN,
+            "Access denied: You don't have permission in this tribe",
+        )),
+        Err(_) => Err((StatusCode::INTERNAL_SERVER_ERROR, "Database error")),
+    }
 }
 
 #[utoipa::path(
@@ -67,7 +229,7 @@ pub struct EditNoteRequest {
         NotesQuery
     ),
     responses(
-        (status = 200, description = "Get notes for a member", body = Vec<NoteWithAuthor>),
+        (status = 200, description = "Notes for a member visible to the caller, pinned first", body = Vec<NoteWithAuthor>),
         (status = 403, description = "Forbidden")
     ),
     security(
@@ -76,16 +238,14 @@ pub struct EditNoteRequest {
 )]
 pub async fn get_notes(
     Path(discord_id): Path<String>,
-    Query(query): Query<NotesQuery>,
     State(state): State<AppState>,
-    auth_user: AuthenticatedUser,
+    access: TribeAccess<perm::NotesRead>,
 ) -> impl IntoResponse {
-    // Verify admin in tribe
-    let (_current_user, tribe, _all_tribes) =
-        match require_admin_in_tribe(&state.db, auth_user.user_id, query.tribe.as_deref()).await {
-            Ok(result) => result,
-            Err(e) => return e.into_response(),
-        };
+    let TribeAccess {
+        user: current_user,
+        tribe,
+        ..
+    } = access;
 
     // Get target user
     let target_user = match get_user_by_discord_id(&state.db, &discord_id).await {
@@ -94,22 +254,37 @@ pub async fn get_notes(
         Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
     };
 
+    let super_admin = match is_super_admin(&state.db, &current_user.discord_id).await {
+        Ok(s) => s,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+
     // Fetch notes with author info
-    let notes = sqlx::query_as::<_, NoteWithAuthor>(
+    let notes = sqlx::query_as::<_, NoteWithAuthor>(&format!(
         r#"
         SELECT n.*, u.username as author_username, u.discriminator as author_discriminator
         FROM notes n
         JOIN users u ON n.author_id = u.id
-        WHERE n.target_user_id = ? AND n.tribe = ?
-        ORDER BY n.created_at DESC
-        "#,
-    )
+        WHERE n.target_user_id = ? AND n.tribe = ? AND n.deleted_at IS NULL
+          AND {VISIBLE_NOTES_SQL}
+        ORDER BY n.pinned DESC, n.created_at DESC
+        "#
+    ))
     .bind(target_user.id)
     .bind(&tribe)
+    .bind(current_user.id)
+    .bind(super_admin)
     .fetch_all(&state.db)
     .await
     .unwrap_or_default();
 
+    let mut notes = notes;
+    for note in &mut notes {
+        if let Err(e) = reveal(&state, &note.id, &mut note.key_id, &mut note.content) {
+            return e.into_response();
+        }
+    }
+
     Json(notes).into_response()
 }
 
@@ -131,17 +306,15 @@ pub async fn get_notes(
 )]
 pub async fn create_note(
     Path(discord_id): Path<String>,
-    Query(query): Query<NotesQuery>,
     State(state): State<AppState>,
-    auth_user: AuthenticatedUser,
+    access: TribeAccess<perm::NotesWrite>,
     Json(payload): Json<CreateNoteRequest>,
 ) -> impl IntoResponse {
-    // Verify admin in tribe
-    let (current_user, tribe, _all_tribes) =
-        match require_admin_in_tribe(&state.db, auth_user.user_id, query.tribe.as_deref()).await {
-            Ok(result) => result,
-            Err(e) => return e.into_response(),
-        };
+    let TribeAccess {
+        user: current_user,
+        tribe,
+        ..
+    } = access;
 
     // Get target user
     let target_user = match get_user_by_discord_id(&state.db, &discord_id).await {
@@ -165,21 +338,34 @@ pub async fn create_note(
     let note_id = Uuid::new_v4().to_string();
     let now = Utc::now();
 
-    // Insert note
-    let result = sqlx::query(
-        "INSERT INTO notes (id, target_user_id, author_id, tribe, content, created_at, updated_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
-    )
-    .bind(&note_id)
-    .bind(target_user.id)
-    .bind(current_user.id)
-    .bind(&tribe)
-    .bind(&payload.content)
-    .bind(now)
-    .bind(now)
-    .execute(&state.db)
+    // Insert the sealed note and its search entry
+    let keys = state.note_keys.as_deref();
+    let (stored, key_id) = note_crypto::seal(keys, &note_id, &payload.content);
+    let result: Result<(), sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+        sqlx::query(
+            "INSERT INTO notes (id, target_user_id, author_id, tribe, content, key_id, created_at, updated_at, pinned, visibility, category) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&note_id)
+        .bind(target_user.id)
+        .bind(current_user.id)
+        .bind(&tribe)
+        .bind(&stored)
+        .bind(&key_id)
+        .bind(now)
+        .bind(now)
+        .bind(payload.pinned)
+        .bind(payload.visibility)
+        .bind(payload.category)
+        .execute(&mut *tx)
+        .await?;
+        index_note(&mut tx, keys, &note_id, &payload.content).await?;
+        tx.commit().await
+    }
     .await;
 
-    if result.is_err() {
+    if let Err(e) = result {
+        eprintln!("Failed to create note: {}", e);
         return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to create note").into_response();
     }
 
@@ -193,11 +379,7 @@ pub async fn create_note(
             "Created note for {} in tribe {}: {}",
             target_user.username,
             tribe,
-            if payload.content.len() > 50 {
-                format!("{}...", &payload.content[..50])
-            } else {
-                payload.content.clone()
-            }
+            excerpt(&state, &payload.content)
         ),
     )
     .await;
@@ -210,6 +392,11 @@ pub async fn create_note(
         content: payload.content,
         created_at: now,
         updated_at: now,
+        pinned: payload.pinned,
+        visibility: payload.visibility,
+        category: payload.category,
+        deleted_at: None,
+        key_id: None,
     };
 
     (StatusCode::CREATED, Json(note)).into_response()
@@ -224,7 +411,7 @@ pub async fn create_note(
     request_body = EditNoteRequest,
     responses(
         (status = 200, description = "Note updated", body = Note),
-        (status = 403, description = "Forbidden: Not the author"),
+        (status = 403, description = "Forbidden: Not the author, or step-up required"),
         (status = 404, description = "Note not found")
     ),
     security(
@@ -235,18 +422,19 @@ pub async fn edit_note(
     Path(note_id): Path<String>,
     State(state): State<AppState>,
     auth_user: AuthenticatedUser,
+    _step_up: StepUp,
     Json(payload): Json<EditNoteRequest>,
 ) -> impl IntoResponse {
-    // Fetch the note
-    let note: Option<Note> = sqlx::query_as("SELECT * FROM notes WHERE id = ?")
-        .bind(&note_id)
-        .fetch_optional(&state.db)
-        .await
-        .unwrap_or(None);
+    let user = match crate::helpers::get_user_by_id(&state.db, auth_user.user_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return (StatusCode::NOT_FOUND, "User not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
 
-    let note = match note {
-        Some(n) => n,
-        None => return (StatusCode::NOT_FOUND, "Note not found").into_response(),
+    // Authors who have since lost note access in the tribe can't edit either
+    let note = match note_for_action(&state, &note_id, &user, Permission::NotesWrite).await {
+        Ok(n) => n,
+        Err(e) => return e.into_response(),
     };
 
     // Verify authorship
@@ -267,16 +455,45 @@ pub async fn edit_note(
     }
 
     let now = Utc::now();
+    let category = payload.category.unwrap_or(note.category);
+    let visibility = payload.visibility.unwrap_or(note.visibility);
 
-    // Update note
-    let result = sqlx::query("UPDATE notes SET content = ?, updated_at = ? WHERE id = ?")
-        .bind(&payload.content)
+    // Keep the version being replaced, still sealed, then update the note
+    let keys = state.note_keys.as_deref();
+    let (stored, key_id) = note_crypto::seal(keys, &note_id, &payload.content);
+    let result: Result<(), sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+        // Only authors edit, so the author wrote the version being replaced
+        sqlx::query(
+            r#"
+            INSERT INTO note_revisions (note_id, revision, content, key_id, category, visibility, editor_id, created_at)
+            SELECT n.id,
+                (SELECT COALESCE(MAX(r.revision), 0) + 1 FROM note_revisions r WHERE r.note_id = n.id),
+                n.content, n.key_id, n.category, n.visibility, n.author_id, n.updated_at
+            FROM notes n WHERE n.id = ?
+            "#,
+        )
+        .bind(&note_id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query(
+            "UPDATE notes SET content = ?, key_id = ?, category = ?, visibility = ?, updated_at = ? WHERE id = ?",
+        )
+        .bind(&stored)
+        .bind(&key_id)
+        .bind(category)
+        .bind(visibility)
         .bind(now)
         .bind(&note_id)
-        .execute(&state.db)
-        .await;
+        .execute(&mut *tx)
+        .await?;
+        index_note(&mut tx, keys, &note_id, &payload.content).await?;
+        tx.commit().await
+    }
+    .await;
 
-    if result.is_err() {
+    if let Err(e) = result {
+        eprintln!("Failed to update note: {}", e);
         return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update note").into_response();
     }
 
@@ -289,17 +506,15 @@ pub async fn edit_note(
         &format!(
             "Edited note in tribe {}: {}",
             note.tribe,
-            if payload.content.len() > 50 {
-                format!("{}...", &payload.content[..50])
-            } else {
-                payload.content.clone()
-            }
+            excerpt(&state, &payload.content)
         ),
     )
     .await;
 
     let updated_note = Note {
         content: payload.content,
+        category,
+        visibility,
         updated_at: now,
         ..note
     };
@@ -307,6 +522,335 @@ pub async fn edit_note(
     Json(updated_note).into_response()
 }
 
+#[derive(Debug, Serialize, ToSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffOp {
+    Equal,
+    Insert,
+    Delete,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    pub op: DiffOp,
+    pub text: String,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteRevision {
+    /// 1 for the original text; the highest number is the live version
+    pub revision: i64,
+    pub content: String,
+    pub category: NoteCategory,
+    pub visibility: NoteVisibility,
+    #[serde(with = "crate::models::option_i64_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub editor_id: Option<i64>,
+    pub editor_username: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub current: bool,
+    /// Line changes from the previous revision; absent for the first
+    pub diff: Option<Vec<DiffLine>>,
+}
+
+#[derive(sqlx::FromRow)]
+struct RevisionRow {
+    revision: i64,
+    content: String,
+    key_id: Option<String>,
+    category: NoteCategory,
+    visibility: NoteVisibility,
+    editor_id: Option<i64>,
+    editor_username: Option<String>,
+    created_at: DateTime<Utc>,
+}
+
+/// Line-by-line changes turning `old` into `new`.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    similar::TextDiff::from_lines(old, new)
+        .iter_all_changes()
+        .map(|change| DiffLine {
+            op: match change.tag() {
+                similar::ChangeTag::Equal => DiffOp::Equal,
+                similar::ChangeTag::Insert => DiffOp::Insert,
+                similar::ChangeTag::Delete => DiffOp::Delete,
+            },
+            text: change.value().trim_end_matches(['\r', '\n']).to_string(),
+        })
+        .collect()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/notes/{note_id}/revisions",
+    params(
+        ("note_id" = String, Path, description = "Note ID")
+    ),
+    responses(
+        (status = 200, description = "Every version of the note, oldest first, with diffs", body = Vec<NoteRevision>),
+        (status = 403, description = "Forbidden: Missing notes.read in the note's tribe"),
+        (status = 404, description = "Note not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn get_note_revisions(
+    Path(note_id): Path<String>,
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+) -> impl IntoResponse {
+    let user = match crate::helpers::get_user_by_id(&state.db, auth_user.user_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return (StatusCode::NOT_FOUND, "User not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+
+    // Same audience as the note itself
+    let note = match note_for_action(&state, &note_id, &user, Permission::NotesRead).await {
+        Ok(n) => n,
+        Err(e) => return e.into_response(),
+    };
+
+    let rows = match sqlx::query_as::<_, RevisionRow>(
+        r#"
+        SELECT r.revision, r.content, r.key_id, r.category, r.visibility, r.editor_id, u.username AS editor_username, r.created_at
+        FROM note_revisions r
+        LEFT JOIN users u ON u.id = r.editor_id
+        WHERE r.note_id = ?
+        ORDER BY r.revision
+        "#,
+    )
+    .bind(&note_id)
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(rows) => rows,
+        Err(e) => {
+            eprintln!("Failed to fetch note revisions: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+        }
+    };
+
+    let live_revision = rows.last().map(|r| r.revision).unwrap_or(0) + 1;
+
+    // Each revision keeps the audience it was saved under, so relaxing a
+    // note's visibility doesn't expose what was written while it was stricter
+    let super_admin = match is_super_admin(&state.db, &user.discord_id).await {
+        Ok(s) => s,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+    let rows: Vec<RevisionRow> = rows
+        .into_iter()
+        .filter(|r| r.visibility.allows(note.author_id, user.id, super_admin))
+        .collect();
+
+    let author_username: Option<String> =
+        sqlx::query_scalar("SELECT username FROM users WHERE id = ?")
+            .bind(note.author_id)
+            .fetch_optional(&state.db)
+            .await
+            .unwrap_or(None);
+    let live = RevisionRow {
+        revision: live_revision,
+        content: note.content,
+        key_id: None,
+        category: note.category,
+        visibility: note.visibility,
+        editor_id: Some(note.author_id),
+        editor_username: author_username,
+        created_at: note.updated_at,
+    };
+
+    let mut revisions: Vec<NoteRevision> = Vec::with_capacity(rows.len() + 1);
+    let mut previous: Option<String> = None;
+    let count = rows.len() + 1;
+    for (i, mut row) in rows.into_iter().chain(std::iter::once(live)).enumerate() {
+        if let Err(e) = reveal(&state, &note_id, &mut row.key_id, &mut row.content) {
+            return e.into_response();
+        }
+        let diff = previous.as_deref().map(|p| diff_lines(p, &row.content));
+        previous = Some(row.content.clone());
+        revisions.push(NoteRevision {
+            revision: row.revision,
+            content: row.content,
+            category: row.category,
+            visibility: row.visibility,
+            editor_id: row.editor_id,
+            editor_username: row.editor_username,
+            created_at: row.created_at,
+            current: i + 1 == count,
+            diff,
+        });
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::NoteViewRevisions,
+        user.id,
+        Some(note.target_user_id),
+        &format!("Viewed {} revision(s) of note {}", revisions.len(), note_id),
+    )
+    .await;
+
+    Json(revisions).into_response()
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/notes/{note_id}/pin",
+    params(
+        ("note_id" = String, Path, description = "Note ID")
+    ),
+    request_body = PinNoteRequest,
+    responses(
+        (status = 200, description = "Note pinned or unpinned", body = Note),
+        (status = 403, description = "Forbidden: Missing notes.write in the note's tribe, or step-up required"),
+        (status = 404, description = "Note not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn pin_note(
+    Path(note_id): Path<String>,
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    _step_up: StepUp,
+    Json(payload): Json<PinNoteRequest>,
+) -> impl IntoResponse {
+    let user = match crate::helpers::get_user_by_id(&state.db, auth_user.user_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return (StatusCode::NOT_FOUND, "User not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+
+    // Any note writer in the tribe may pin, not only the author
+    let note = match note_for_action(&state, &note_id, &user, Permission::NotesWrite).await {
+        Ok(n) => n,
+        Err(e) => return e.into_response(),
+    };
+
+    if let Err(e) = sqlx::query("UPDATE notes SET pinned = ? WHERE id = ?")
+        .bind(payload.pinned)
+        .bind(&note_id)
+        .execute(&state.db)
+        .await
+    {
+        eprintln!("Failed to pin note: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to update note").into_response();
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::NoteEdit,
+        user.id,
+        Some(note.target_user_id),
+        &format!(
+            "{} note in tribe {}: {}",
+            if payload.pinned { "Pinned" } else { "Unpinned" },
+            note.tribe,
+            excerpt(&state, &note.content)
+        ),
+    )
+    .await;
+
+    Json(Note {
+        pinned: payload.pinned,
+        ..note
+    })
+    .into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/notes/{note_id}",
+    params(
+        ("note_id" = String, Path, description = "Note ID")
+    ),
+    responses(
+        (status = 200, description = "Note deleted"),
+        (status = 403, description = "Forbidden: Not the author or a tribe admin, or step-up required"),
+        (status = 404, description = "Note not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn delete_note(
+    Path(note_id): Path<String>,
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    _step_up: StepUp,
+) -> impl IntoResponse {
+    let user = match crate::helpers::get_user_by_id(&state.db, auth_user.user_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return (StatusCode::NOT_FOUND, "User not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+
+    let note = match note_for_action(&state, &note_id, &user, Permission::NotesWrite).await {
+        Ok(n) => n,
+        Err(e) => return e.into_response(),
+    };
+
+    // Authors can remove their own notes; tribe admins can remove anyone's
+    if note.author_id != user.id {
+        match has_permission_in_tribe(&state.db, user.id, &note.tribe, Permission::AdminGrant).await
+        {
+            Ok(true) => {}
+            Ok(false) => {
+                return (
+                    StatusCode::FORBIDDEN,
+                    "Only the author or a tribe admin can delete this note",
+                )
+                    .into_response()
+            }
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+        }
+    }
+
+    // Soft delete: the note stays for the audit trail but is never served again
+    let result = sqlx::query(
+        "UPDATE notes SET deleted_at = ?, deleted_by = ? WHERE id = ? AND deleted_at IS NULL",
+    )
+    .bind(Utc::now())
+    .bind(user.id)
+    .bind(&note_id)
+    .execute(&state.db)
+    .await;
+
+    match result {
+        Ok(r) if r.rows_affected() == 0 => {
+            return (StatusCode::NOT_FOUND, "Note not found").into_response()
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Failed to delete note: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to delete note").into_response();
+        }
+    }
+    // The soft delete dropped the note's attachments; remove their files too
+    crate::attachments::purge_deleted_now(&state.db, state.attachment_store.as_ref()).await;
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::NoteDelete,
+        user.id,
+        Some(note.target_user_id),
+        &format!(
+            "Deleted note in tribe {}: {}",
+            note.tribe,
+            excerpt(&state, &note.content)
+        ),
+    )
+    .await;
+
+    StatusCode::OK.into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -367,6 +911,11 @@ mod tests {
             content: "Test note content".to_string(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            pinned: true,
+            visibility: NoteVisibility::Author,
+            category: NoteCategory::Warning,
+            deleted_at: None,
+            key_id: None,
         };
 
         let json = serde_json::to_string(&note).expect("Serialize failed");
@@ -375,6 +924,10 @@ mod tests {
         assert!(json.contains("\"authorId\":"));
         assert!(json.contains("\"tribe\":\"test_tribe\""));
         assert!(json.contains("\"content\":\"Test note content\""));
+        assert!(json.contains("\"pinned\":true"));
+        assert!(json.contains("\"visibility\":\"AUTHOR\""));
+        assert!(json.contains("\"category\":\"WARNING\""));
+        assert!(!json.contains("deletedAt"));
     }
 
     #[test]
@@ -387,8 +940,12 @@ mod tests {
             content: "Note with author".to_string(),
             created_at: Utc::now(),
             updated_at: Utc::now(),
+            pinned: false,
+            visibility: NoteVisibility::TribeAdmins,
+            category: NoteCategory::General,
             author_username: "AuthorUser".to_string(),
             author_discriminator: "1234".to_string(),
+            key_id: None,
         };
 
         let json = serde_json::to_string(&note).expect("Serialize failed");
@@ -401,6 +958,9 @@ mod tests {
         let json = r#"{"content":"This is a new note"}"#;
         let request: CreateNoteRequest = serde_json::from_str(json).expect("Deserialize failed");
         assert_eq!(request.content, "This is a new note");
+        assert_eq!(request.category, NoteCategory::General);
+        assert_eq!(request.visibility, NoteVisibility::TribeAdmins);
+        assert!(!request.pinned);
     }
 
     #[test]
@@ -531,4 +1091,446 @@ mod tests {
         assert_eq!(notes[0].author_username, "AdminUser");
         assert_eq!(notes[0].content, "Note with author join");
     }
+
+    async fn insert_note(
+        db: &crate::db::DbPool,
+        id: &str,
+        author_id: i64,
+        visibility: &str,
+        pinned: bool,
+        age_minutes: i64,
+    ) {
+        let at = Utc::now() - chrono::Duration::minutes(age_minutes);
+        sqlx::query(
+            "INSERT INTO notes (id, target_user_id, author_id, tribe, content, created_at, updated_at, pinned, visibility) VALUES (?, 1002, ?, 'test_tribe', ?, ?, ?, ?, ?)",
+        )
+        .bind(id)
+        .bind(author_id)
+        .bind(format!("Note {}", id))
+        .bind(at)
+        .bind(at)
+        .bind(pinned)
+        .bind(visibility)
+        .execute(db)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_note_visibility_pinning_and_deletion() {
+        let db = setup_db().await;
+        let state = AppState::new(db.clone());
+
+        // A recruiter: notes.read and notes.write, but not a tribe admin
+        sqlx::query("INSERT INTO users (id, discord_id, username, discriminator) VALUES (1003, 'recruiter-discord-id', 'Recruiter', '0003')")
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe) VALUES (1003, 'test_tribe')")
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO user_roles (id, user_id, role_id, tribe) VALUES ('r1', 1003, 'recruiter', 'test_tribe')")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        insert_note(&db, "general", 1001, "TRIBE_ADMINS", false, 1).await;
+        insert_note(&db, "private", 1001, "AUTHOR", false, 2).await;
+        insert_note(&db, "super", 1001, "SUPER_ADMINS", false, 3).await;
+        insert_note(&db, "pinned", 1003, "TRIBE_ADMINS", true, 60).await;
+
+        let list = |user_id: i64| {
+            let state = state.clone();
+            let db = db.clone();
+            async move {
+                let user = crate::helpers::get_user_by_id(&db, user_id)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                let access = TribeAccess::new(
+                    user,
+                    "test_tribe".to_string(),
+                    vec!["test_tribe".to_string()],
+                );
+                let res = get_notes(Path("target-discord-id".to_string()), State(state), access)
+                    .await
+                    .into_response();
+                let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                let notes: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+                notes
+                    .iter()
+                    .map(|n| n["id"].as_str().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        // Pinned first; the recruiter can't see author-only or super-admin notes
+        assert_eq!(list(1003).await, vec!["pinned", "general"]);
+        assert_eq!(
+            list(1001).await,
+            vec!["pinned", "general", "private", "super"]
+        );
+
+        let delete = |note_id: &str, user_id: i64| {
+            delete_note(
+                Path(note_id.to_string()),
+                State(state.clone()),
+                AuthenticatedUser { user_id },
+                StepUp,
+            )
+        };
+
+        // Hidden notes look missing; other authors' notes need a tribe admin
+        assert_eq!(
+            delete("private", 1003).await.into_response().status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            delete("general", 1003).await.into_response().status(),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            delete("pinned", 1001).await.into_response().status(),
+            StatusCode::OK
+        );
+        assert_eq!(
+            delete("pinned", 1001).await.into_response().status(),
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(list(1003).await, vec!["general"]);
+
+        let res = pin_note(
+            Path("general".to_string()),
+            State(state.clone()),
+            AuthenticatedUser { user_id: 1003 },
+            StepUp,
+            Json(PinNoteRequest { pinned: true }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let actions: Vec<String> = sqlx::query_scalar(
+            "SELECT action FROM audit_logs WHERE action IN ('NOTE_DELETE', 'NOTE_EDIT') ORDER BY created_at",
+        )
+        .fetch_all(&db)
+        .await
+        .unwrap();
+        assert_eq!(actions, vec!["NOTE_DELETE", "NOTE_EDIT"]);
+    }
+
+    #[tokio::test]
+    async fn test_note_revisions() {
+        let db = setup_db().await;
+        let state = AppState::new(db.clone());
+        insert_note(&db, "history", 1001, "TRIBE_ADMINS", false, 10).await;
+
+        let edit = |content: &str, category: Option<NoteCategory>| {
+            edit_note(
+                Path("history".to_string()),
+                State(state.clone()),
+                AuthenticatedUser { user_id: 1001 },
+                StepUp,
+                Json(EditNoteRequest {
+                    content: content.to_string(),
+                    category,
+                    visibility: None,
+                }),
+            )
+        };
+        let res = edit("Note history\nsecond line", None)
+            .await
+            .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = edit("Note HISTORY\nsecond line", Some(NoteCategory::Warning))
+            .await
+            .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let revisions = |user_id: i64| {
+            get_note_revisions(
+                Path("history".to_string()),
+                State(state.clone()),
+                AuthenticatedUser { user_id },
+            )
+        };
+        let res = revisions(1001).await.into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let list = json.as_array().unwrap();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list[0]["revision"], 1);
+        assert_eq!(list[0]["content"], "Note history");
+        assert_eq!(list[0]["editorUsername"], "AdminUser");
+        assert!(list[0]["diff"].is_null());
+        assert_eq!(list[1]["category"], "GENERAL");
+        assert!(!list[1]["current"].as_bool().unwrap());
+        assert_eq!(list[2]["category"], "WARNING");
+        assert!(list[2]["current"].as_bool().unwrap());
+
+        let ops = |diff: &serde_json::Value| {
+            diff.as_array()
+                .unwrap()
+                .iter()
+                .map(|l| {
+                    format!(
+                        "{} {}",
+                        l["op"].as_str().unwrap(),
+                        l["text"].as_str().unwrap()
+                    )
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            ops(&list[1]["diff"]),
+            vec![
+                "delete Note history",
+                "insert Note history",
+                "insert second line"
+            ]
+        );
+        assert_eq!(
+            ops(&list[2]["diff"]),
+            vec![
+                "delete Note history",
+                "insert Note HISTORY",
+                "equal second line"
+            ]
+        );
+
+        // Same audience as the note: the target isn't in the tribe
+        assert_eq!(
+            revisions(1002).await.into_response().status(),
+            StatusCode::FORBIDDEN
+        );
+    }
+
+    #[tokio::test]
+    async fn test_note_revisions_keep_saved_visibility() {
+        let db = setup_db().await;
+        let state = AppState::new(db.clone());
+        sqlx::query("INSERT INTO users (id, discord_id, username, discriminator) VALUES (1003, 'officer-discord-id', 'Officer', '0003')")
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO user_tribes (user_id, tribe, is_admin) VALUES (1003, 'test_tribe', 1)",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        insert_note(&db, "drafted", 1001, "AUTHOR", false, 10).await;
+
+        let res = edit_note(
+            Path("drafted".to_string()),
+            State(state.clone()),
+            AuthenticatedUser { user_id: 1001 },
+            StepUp,
+            Json(EditNoteRequest {
+                content: "Shared with officers".to_string(),
+                category: None,
+                visibility: Some(NoteVisibility::TribeAdmins),
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let revisions = |user_id: i64| {
+            let state = state.clone();
+            async move {
+                let res = get_note_revisions(
+                    Path("drafted".to_string()),
+                    State(state),
+                    AuthenticatedUser { user_id },
+                )
+                .await
+                .into_response();
+                assert_eq!(res.status(), StatusCode::OK);
+                let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+            }
+        };
+
+        // The author-only draft stays with the author after the note is shared
+        let json = revisions(1003).await;
+        let list = json.as_array().unwrap();
+        assert_eq!(list.len(), 1);
+        assert_eq!(list[0]["revision"], 2);
+        assert_eq!(list[0]["content"], "Shared with officers");
+        assert!(list[0]["diff"].is_null());
+        assert_eq!(revisions(1001).await.as_array().unwrap().len(), 2);
+
+        let viewers: Vec<i64> = sqlx::query_scalar(
+            "SELECT actor_id FROM audit_logs WHERE action = 'NOTE_VIEW_REVISIONS' AND target_id = 1002 ORDER BY created_at",
+        )
+        .fetch_all(&db)
+        .await
+        .unwrap();
+        assert_eq!(viewers, vec![1003, 1001]);
+    }
+
+    #[tokio::test]
+    async fn test_notes_encrypted_at_rest() {
+        let db = setup_db().await;
+        let mut state = AppState::new(db.clone());
+        state.note_keys = Some(std::sync::Arc::new(
+            note_crypto::NoteKeys::new("k1", [("k1".to_string(), [7u8; 32])], [8u8; 32]).unwrap(),
+        ));
+        let admin = crate::helpers::get_user_by_id(&db, 1001)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let res = create_note(
+            Path("target-discord-id".to_string()),
+            State(state.clone()),
+            TribeAccess::new(
+                admin.clone(),
+                "test_tribe".to_string(),
+                vec!["test_tribe".to_string()],
+            ),
+            Json(CreateNoteRequest {
+                content: "Seen trading with pirates".to_string(),
+                category: NoteCategory::Warning,
+                visibility: NoteVisibility::TribeAdmins,
+                pinned: false,
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let note_id = json["id"].as_str().unwrap().to_string();
+        assert_eq!(json["content"], "Seen trading with pirates");
+
+        let res = edit_note(
+            Path(note_id.clone()),
+            State(state.clone()),
+            AuthenticatedUser { user_id: 1001 },
+            StepUp,
+            Json(EditNoteRequest {
+                content: "Seen trading with pirates twice".to_string(),
+                category: None,
+                visibility: None,
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // Nothing readable in the note, its revisions or the audit log
+        let stored: Vec<(String, Option<String>)> = sqlx::query_as(
+            "SELECT content, key_id FROM notes UNION ALL SELECT content, key_id FROM note_revisions",
+        )
+        .fetch_all(&db)
+        .await
+        .unwrap();
+        assert_eq!(stored.len(), 2);
+        for (content, key_id) in stored {
+            assert!(!content.contains("pirates"));
+            assert_eq!(key_id.as_deref(), Some("k1"));
+        }
+        let details: Vec<String> = sqlx::query_scalar("SELECT details FROM audit_logs")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert!(details.iter().all(|d| !d.contains("pirates")));
+
+        let res = get_notes(
+            Path("target-discord-id".to_string()),
+            State(state.clone()),
+            TribeAccess::new(
+                admin,
+                "test_tribe".to_string(),
+                vec!["test_tribe".to_string()],
+            ),
+        )
+        .await
+        .into_response();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json[0]["content"], "Seen trading with pirates twice");
+
+        let res = get_note_revisions(
+            Path(note_id),
+            State(state),
+            AuthenticatedUser { user_id: 1001 },
+        )
+        .await
+        .into_response();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json[0]["content"], "Seen trading with pirates");
+        assert_eq!(json[1]["content"], "Seen trading with pirates twice");
+    }
+
+    #[tokio::test]
+    async fn test_pin_needs_step_up() {
+        use axum::extract::FromRequestParts;
+        use jsonwebtoken::{encode, EncodingKey, Header};
+
+        let db = setup_db().await;
+        let state = AppState::new(db.clone());
+        insert_note(&db, "pin-me", 1001, "TRIBE_ADMINS", false, 10).await;
+        sqlx::query("UPDATE step_up_policies SET required = TRUE, max_age_minutes = 10 WHERE role = 'admin'")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        std::env::set_var("JWT_SECRET", "test-jwt-secret");
+        let now = Utc::now().timestamp() as usize;
+        let step_up = |step_up_at: Option<usize>| {
+            let token = encode(
+                &Header::default(),
+                &crate::auth::Claims {
+                    id: "1001".to_string(),
+                    discord_id: "admin-discord-id".to_string(),
+                    username: "AdminUser".to_string(),
+                    exp: now + 3600,
+                    step_up_at,
+                },
+                &EncodingKey::from_secret(b"test-jwt-secret"),
+            )
+            .unwrap();
+            let (mut parts, _) = axum::http::Request::builder()
+                .header("Authorization", format!("Bearer {}", token))
+                .body(())
+                .unwrap()
+                .into_parts();
+            let state = state.clone();
+            async move { StepUp::from_request_parts(&mut parts, &state).await }
+        };
+
+        // A tribe admin under a required policy must step up before pinning
+        let err = step_up(None).await.err().unwrap();
+        assert_eq!(err.0, StatusCode::FORBIDDEN);
+
+        let guard = step_up(Some(now)).await.unwrap();
+        let res = pin_note(
+            Path("pin-me".to_string()),
+            State(state.clone()),
+            AuthenticatedUser { user_id: 1001 },
+            guard,
+            Json(PinNoteRequest { pinned: true }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+    }
 }
@@ -0,0 +1,504 @@
+use crate::{
+    audit::{log_audit, AuditAction},
+    helpers::csv_response,
+    rbac::{perm, TribeAccess},
+    state::AppState,
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use utoipa::{IntoParams, ToSchema};
+
+const DEFAULT_RECENT_DAYS: i64 = 30;
+const MAX_RECENT_DAYS: i64 = 365;
+
+#[derive(Debug, Serialize, ToSchema, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum Severity {
+    Low,
+    Medium,
+    High,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Low => "LOW",
+            Severity::Medium => "MEDIUM",
+            Severity::High => "HIGH",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum FindingKind {
+    /// The member also belongs to other tribes
+    MultiTribe,
+    /// One of the member's wallets was linked to another account before
+    PreviousOwner,
+    /// One of the member's wallets was unlinked and linked again recently
+    RecentRelink,
+}
+
+impl FindingKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FindingKind::MultiTribe => "MULTI_TRIBE",
+            FindingKind::PreviousOwner => "PREVIOUS_OWNER",
+            FindingKind::RecentRelink => "RECENT_RELINK",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OverlapFinding {
+    pub discord_id: String,
+    pub username: String,
+    pub kind: FindingKind,
+    pub severity: Severity,
+    pub wallet_address: Option<String>,
+    /// How many other tribes are involved: the member's own, or the previous wallet owners'
+    pub other_tribe_count: usize,
+    /// Names of those tribes, only shown to global admins
+    pub other_tribes: Vec<String>,
+    pub detail: String,
+    pub observed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct OverlapQuery {
+    pub tribe: Option<String>,
+    /// Window for re-link findings in days (default 30)
+    pub recent_days: Option<i64>,
+    /// `json` (default) or `csv`
+    pub format: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct MemberRow {
+    id: i64,
+    discord_id: String,
+    username: String,
+}
+
+#[derive(sqlx::FromRow)]
+struct LinkEvent {
+    actor_id: i64,
+    details: String,
+    created_at: DateTime<Utc>,
+}
+
+/// Address named in a `LINK_WALLET` audit entry, and whether it was a re-link.
+fn parse_link_details(details: &str) -> Option<(&str, bool)> {
+    if let Some(address) = details.strip_prefix("Re-linked wallet ") {
+        Some((address, true))
+    } else {
+        details
+            .strip_prefix("Linked wallet ")
+            .map(|address| (address, false))
+    }
+}
+
+/// Build the findings for the members of `tribe`. Unless `global_view` is set, other
+/// tribes are only counted: their names and who administers them stay hidden.
+async fn analyse(
+    state: &AppState,
+    tribe: &str,
+    recent_days: i64,
+    global_view: bool,
+) -> Result<Vec<OverlapFinding>, sqlx::Error> {
+    let members = sqlx::query_as::<_, MemberRow>(
+        "SELECT u.id, u.discord_id, u.username FROM users u JOIN user_tribes ut ON ut.user_id = u.id WHERE ut.tribe = ?",
+    )
+    .bind(tribe)
+    .fetch_all(&state.db)
+    .await?;
+
+    // Every membership outside this tribe, keyed by user
+    let other_memberships: Vec<(i64, String, bool)> = sqlx::query_as(
+        "SELECT user_id, tribe, COALESCE(is_admin, FALSE) FROM user_tribes WHERE tribe != ?",
+    )
+    .bind(tribe)
+    .fetch_all(&state.db)
+    .await?;
+    let mut elsewhere: HashMap<i64, Vec<(String, bool)>> = HashMap::new();
+    for (user_id, other, is_admin) in other_memberships {
+        elsewhere
+            .entry(user_id)
+            .or_default()
+            .push((other, is_admin));
+    }
+
+    let wallets: Vec<(i64, String)> = sqlx::query_as(
+        "SELECT w.user_id, w.address FROM wallets w JOIN user_tribes ut ON ut.user_id = w.user_id AND ut.tribe = ? WHERE w.deleted_at IS NULL",
+    )
+    .bind(tribe)
+    .fetch_all(&state.db)
+    .await?;
+
+    // Wallet ownership history comes from the link audit trail, since a re-link
+    // moves the wallet row to its new owner
+    let link_events = sqlx::query_as::<_, LinkEvent>(
+        "SELECT actor_id, details, created_at FROM audit_logs WHERE action = ? ORDER BY created_at",
+    )
+    .bind(AuditAction::LinkWallet.as_str())
+    .fetch_all(&state.db)
+    .await?;
+    let mut links_by_address: HashMap<&str, Vec<(&LinkEvent, bool)>> = HashMap::new();
+    for event in &link_events {
+        if let Some((address, relinked)) = parse_link_details(&event.details) {
+            links_by_address
+                .entry(address)
+                .or_default()
+                .push((event, relinked));
+        }
+    }
+
+    let members_by_id: HashMap<i64, &MemberRow> = members.iter().map(|m| (m.id, m)).collect();
+    let recent_cutoff = Utc::now() - Duration::days(recent_days);
+    let mut findings = Vec::new();
+
+    for member in &members {
+        if let Some(others) = elsewhere.get(&member.id) {
+            let admin_elsewhere = global_view && others.iter().any(|(_, is_admin)| *is_admin);
+            findings.push(OverlapFinding {
+                discord_id: member.discord_id.clone(),
+                username: member.username.clone(),
+                kind: FindingKind::MultiTribe,
+                severity: if admin_elsewhere {
+                    Severity::High
+                } else {
+                    Severity::Medium
+                },
+                wallet_address: None,
+                other_tribe_count: others.len(),
+                other_tribes: if global_view {
+                    others.iter().map(|(t, _)| t.clone()).collect()
+                } else {
+                    Vec::new()
+                },
+                detail: if admin_elsewhere {
+                    "Member is an admin of another tribe".to_string()
+                } else {
+                    format!("Member also belongs to {} other tribe(s)", others.len())
+                },
+                observed_at: None,
+            });
+        }
+    }
+
+    for (user_id, address) in &wallets {
+        let Some(member) = members_by_id.get(user_id) else {
+            continue;
+        };
+        let events = links_by_address
+            .get(address.as_str())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        // Earlier owners: anyone else who linked this address
+        let previous: BTreeSet<i64> = events
+            .iter()
+            .map(|(e, _)| e.actor_id)
+            .filter(|actor| actor != user_id)
+            .collect();
+        if !previous.is_empty() {
+            // Previous owners are never named, only their tribes
+            let previous_tribes: BTreeSet<String> = previous
+                .iter()
+                .flat_map(|id| elsewhere.get(id).into_iter().flatten())
+                .map(|(t, _)| t.clone())
+                .collect();
+            let last_seen = events
+                .iter()
+                .filter(|(e, _)| e.actor_id != *user_id)
+                .map(|(e, _)| e.created_at)
+                .max();
+            findings.push(OverlapFinding {
+                discord_id: member.discord_id.clone(),
+                username: member.username.clone(),
+                kind: FindingKind::PreviousOwner,
+                severity: if previous_tribes.is_empty() {
+                    Severity::Medium
+                } else {
+                    Severity::High
+                },
+                wallet_address: Some(address.clone()),
+                detail: format!(
+                    "Wallet was previously linked to {} other account(s)",
+                    previous.len()
+                ),
+                other_tribe_count: previous_tribes.len(),
+                other_tribes: if global_view {
+                    previous_tribes.into_iter().collect()
+                } else {
+                    Vec::new()
+                },
+                observed_at: last_seen,
+            });
+        }
+
+        let recent_relink = events
+            .iter()
+            .filter(|(e, relinked)| {
+                *relinked && e.actor_id == *user_id && e.created_at >= recent_cutoff
+            })
+            .map(|(e, _)| e.created_at)
+            .max();
+        if let Some(at) = recent_relink {
+            findings.push(OverlapFinding {
+                discord_id: member.discord_id.clone(),
+                username: member.username.clone(),
+                kind: FindingKind::RecentRelink,
+                severity: Severity::Low,
+                wallet_address: Some(address.clone()),
+                other_tribe_count: 0,
+                other_tribes: Vec::new(),
+                detail: format!("Wallet re-linked within the last {} days", recent_days),
+                observed_at: Some(at),
+            });
+        }
+    }
+
+    findings.sort_by(|a, b| {
+        b.severity
+            .cmp(&a.severity)
+            .then_with(|| a.username.cmp(&b.username))
+    });
+    Ok(findings)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/roster/overlap",
+    params(OverlapQuery),
+    responses(
+        (status = 200, description = "Overlap and spy-risk findings for the tribe, most severe first", body = Vec<OverlapFinding>),
+        (status = 400, description = "Invalid format"),
+        (status = 403, description = "Forbidden: No audit access in this tribe")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn get_overlap_report(
+    access: TribeAccess<perm::AuditRead>,
+    Query(query): Query<OverlapQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let csv = match query.format.as_deref() {
+        None | Some("json") => false,
+        Some("csv") => true,
+        Some(_) => return (StatusCode::BAD_REQUEST, "Invalid format").into_response(),
+    };
+    let recent_days = query
+        .recent_days
+        .unwrap_or(DEFAULT_RECENT_DAYS)
+        .clamp(1, MAX_RECENT_DAYS);
+
+    let findings = match analyse(&state, &access.tribe, recent_days, access.user.is_admin).await {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("Failed to build overlap report: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response();
+        }
+    };
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::ViewOverlapReport,
+        access.user.id,
+        None,
+        &format!(
+            "Viewed overlap report for tribe {} ({} findings{})",
+            access.tribe,
+            findings.len(),
+            if csv { ", exported as CSV" } else { "" }
+        ),
+    )
+    .await;
+
+    if !csv {
+        return Json(findings).into_response();
+    }
+
+    let rows: Vec<Vec<String>> = findings
+        .iter()
+        .map(|f| {
+            vec![
+                f.severity.as_str().to_string(),
+                f.kind.as_str().to_string(),
+                f.discord_id.clone(),
+                f.username.clone(),
+                f.wallet_address.clone().unwrap_or_default(),
+                f.other_tribe_count.to_string(),
+                f.other_tribes.join("; "),
+                f.detail.clone(),
+                f.observed_at.map(|t| t.to_rfc3339()).unwrap_or_default(),
+            ]
+        })
+        .collect();
+    csv_response(
+        &format!("overlap-{}.csv", access.tribe),
+        &[
+            "severity",
+            "kind",
+            "discord_id",
+            "username",
+            "wallet_address",
+            "other_tribe_count",
+            "other_tribes",
+            "detail",
+            "observed_at",
+        ],
+        &rows,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        audit::log_audit,
+        db::DbPool,
+        test_support::{access, add_members, insert_users, setup_db},
+    };
+
+    async fn setup() -> DbPool {
+        let pool = setup_db().await;
+        insert_users(
+            &pool,
+            &[
+                (1, "100", "admin"),
+                (2, "200", "double"),
+                (3, "300", "newcomer"),
+                (4, "400", "spymaster"),
+            ],
+        )
+        .await;
+        add_members(
+            &pool,
+            &[
+                (1, "Fire", true),
+                (2, "Fire", false),
+                (2, "Water", true),
+                (3, "Fire", false),
+                (4, "Water", false),
+            ],
+        )
+        .await;
+
+        // The newcomer's wallet used to belong to a Water member
+        sqlx::query("INSERT INTO wallets (id, user_id, address, verified_at) VALUES ('w3', 3, '0xaaa', CURRENT_TIMESTAMP)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        log_audit(
+            &pool,
+            AuditAction::LinkWallet,
+            4,
+            None,
+            "Linked wallet 0xaaa",
+        )
+        .await
+        .unwrap();
+        log_audit(
+            &pool,
+            AuditAction::LinkWallet,
+            3,
+            None,
+            "Re-linked wallet 0xaaa",
+        )
+        .await
+        .unwrap();
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_overlap_findings() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+
+        let findings = analyse(&state, "Fire", 30, true).await.unwrap();
+        let summary: Vec<(&str, FindingKind, Severity)> = findings
+            .iter()
+            .map(|f| (f.username.as_str(), f.kind, f.severity))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("double", FindingKind::MultiTribe, Severity::High),
+                ("newcomer", FindingKind::PreviousOwner, Severity::High),
+                ("newcomer", FindingKind::RecentRelink, Severity::Low),
+            ]
+        );
+        assert_eq!(findings[1].other_tribes, vec!["Water"]);
+    }
+
+    #[tokio::test]
+    async fn test_overlap_hides_other_tribes_from_tribe_admins() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+
+        let findings = analyse(&state, "Fire", 30, false).await.unwrap();
+        let summary: Vec<(&str, FindingKind, Severity, usize)> = findings
+            .iter()
+            .map(|f| (f.username.as_str(), f.kind, f.severity, f.other_tribe_count))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("newcomer", FindingKind::PreviousOwner, Severity::High, 1),
+                ("double", FindingKind::MultiTribe, Severity::Medium, 1),
+                ("newcomer", FindingKind::RecentRelink, Severity::Low, 0),
+            ]
+        );
+        assert!(findings.iter().all(|f| f.other_tribes.is_empty()));
+        assert!(findings.iter().all(|f| !f.detail.contains("admin")));
+    }
+
+    #[tokio::test]
+    async fn test_overlap_csv_export() {
+        let db = setup().await;
+        let res = get_overlap_report(
+            access(&db, 1, "Fire").await,
+            Query(OverlapQuery {
+                tribe: None,
+                recent_days: None,
+                format: Some("csv".to_string()),
+            }),
+            State(AppState::new(db.clone())),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            res.headers()[axum::http::header::CONTENT_TYPE],
+            "text/csv; charset=utf-8"
+        );
+
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let text = String::from_utf8(body.to_vec()).unwrap();
+        assert!(text.starts_with("severity,kind,discord_id"));
+        assert_eq!(text.lines().count(), 4);
+
+        let logged: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM audit_logs WHERE action = 'VIEW_OVERLAP_REPORT'",
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(logged, 1);
+    }
+}
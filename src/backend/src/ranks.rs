@@ -0,0 +1,1258 @@
+use crate::{
+    audit::{log_audit, AuditAction},
+    db::DbPool,
+    helpers::{get_user_by_discord_id, ID_BATCH_SIZE},
+    rbac::{has_permission_in_tribe, perm, Permission, TribeAccess, ADMIN_ROLE_ID},
+    state::AppState,
+    totp::StepUp,
+};
+use axum::{
+    extract::{Json, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+const MAX_RANKS: usize = 20;
+const MAX_KEY_CHARS: usize = 32;
+const MAX_NAME_CHARS: usize = 64;
+const MAX_SQUADS_PER_TRIBE: i64 = 50;
+
+#[derive(Debug, Clone, Serialize, ToSchema, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TribeRank {
+    pub key: String,
+    pub name: String,
+    /// 0 is the lowest rank
+    pub position: i64,
+    /// Role granted in the tribe to holders of this rank and every rank above
+    pub role_id: Option<String>,
+    pub mumble_group: Option<String>,
+    pub member_count: i64,
+    #[serde(skip)]
+    pub id: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RankDefinition {
+    /// Lowercase letters, digits and underscores, starting with a letter
+    pub key: String,
+    pub name: String,
+    pub role_id: Option<String>,
+    /// Mumble group for holders of this rank and every rank above
+    pub mumble_group: Option<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SetRanksRequest {
+    /// The whole ladder, lowest rank first. Ranks left out are deleted and
+    /// their holders become unranked.
+    pub ranks: Vec<RankDefinition>,
+}
+
+#[derive(Deserialize, ToSchema, Default)]
+#[serde(default)]
+pub struct RankChangeRequest {
+    /// Rank to move to; one step when absent
+    pub to: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RankChange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Squad {
+    pub id: String,
+    pub name: String,
+    pub mumble_group: Option<String>,
+    /// Discord IDs of the squad's members
+    pub members: Vec<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateSquadRequest {
+    pub name: String,
+    pub mumble_group: Option<String>,
+}
+
+#[derive(sqlx::FromRow)]
+struct SquadRow {
+    id: String,
+    name: String,
+    mumble_group: Option<String>,
+}
+
+fn valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    key.len() <= MAX_KEY_CHARS
+        && chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn clean_name(name: &str) -> Option<String> {
+    let name = name.trim();
+    (!name.is_empty() && name.chars().count() <= MAX_NAME_CHARS).then(|| name.to_string())
+}
+
+/// Mumble group names: letters, digits, `-` and `_`. Blank means no group.
+fn clean_group(group: Option<&str>) -> Result<Option<String>, &'static str> {
+    match group.map(str::trim).filter(|g| !g.is_empty()) {
+        None => Ok(None),
+        Some(g)
+            if g.len() <= MAX_NAME_CHARS
+                && g.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') =>
+        {
+            Ok(Some(g.to_string()))
+        }
+        Some(_) => Err("Mumble groups are letters, digits, - and _"),
+    }
+}
+
+pub(crate) async fn tribe_ranks(db: &DbPool, tribe: &str) -> Result<Vec<TribeRank>, sqlx::Error> {
+    sqlx::query_as(
+        r#"
+        SELECT r.id, r.key, r.name, r.position, r.role_id, r.mumble_group,
+               (SELECT COUNT(*) FROM user_tribes ut WHERE ut.rank_id = r.id) AS member_count
+        FROM tribe_ranks r
+        WHERE r.tribe = ?
+        ORDER BY r.position
+        "#,
+    )
+    .bind(tribe)
+    .fetch_all(db)
+    .await
+}
+
+/// A member's rank position in a tribe; `None` when unranked
+async fn rank_position(db: &DbPool, user_id: i64, tribe: &str) -> Result<Option<i64>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT r.position FROM user_tribes ut JOIN tribe_ranks r ON r.id = ut.rank_id WHERE ut.user_id = ? AND ut.tribe = ?",
+    )
+    .bind(user_id)
+    .bind(tribe)
+    .fetch_optional(db)
+    .await
+}
+
+/// Rank key and squad names of the given members in a tribe
+pub(crate) async fn ranks_and_squads_for(
+    db: &DbPool,
+    tribe: &str,
+    user_ids: &[i64],
+) -> Result<HashMap<i64, (Option<String>, Vec<String>)>, sqlx::Error> {
+    let mut result: HashMap<i64, (Option<String>, Vec<String>)> = HashMap::new();
+    for chunk in user_ids.chunks(ID_BATCH_SIZE) {
+        let placeholders = chunk.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+
+        let sql = format!(
+            "SELECT ut.user_id, r.key FROM user_tribes ut JOIN tribe_ranks r ON r.id = ut.rank_id WHERE ut.tribe = ? AND ut.user_id IN ({})",
+            placeholders
+        );
+        let mut q = sqlx::query_as::<_, (i64, String)>(&sql).bind(tribe);
+        for id in chunk {
+            q = q.bind(*id);
+        }
+        for (user_id, key) in q.fetch_all(db).await? {
+            result.entry(user_id).or_default().0 = Some(key);
+        }
+
+        let sql = format!(
+            "SELECT m.user_id, s.name FROM squad_members m JOIN tribe_squads s ON s.id = m.squad_id WHERE s.tribe = ? AND m.user_id IN ({}) ORDER BY s.name",
+            placeholders
+        );
+        let mut q = sqlx::query_as::<_, (i64, String)>(&sql).bind(tribe);
+        for id in chunk {
+            q = q.bind(*id);
+        }
+        for (user_id, name) in q.fetch_all(db).await? {
+            result.entry(user_id).or_default().1.push(name);
+        }
+    }
+    Ok(result)
+}
+
+/// Mumble groups for a member of a tribe: those of their rank and every rank
+/// below it, and those of their squads.
+pub async fn mumble_groups(
+    db: &DbPool,
+    user_id: i64,
+    tribe: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT r.mumble_group FROM user_tribes ut
+        JOIN tribe_ranks cur ON cur.id = ut.rank_id
+        JOIN tribe_ranks r ON r.tribe = cur.tribe AND r.position <= cur.position
+        WHERE ut.user_id = ? AND ut.tribe = ? AND r.mumble_group IS NOT NULL
+        UNION
+        SELECT s.mumble_group FROM squad_members m
+        JOIN tribe_squads s ON s.id = m.squad_id
+        WHERE m.user_id = ? AND s.tribe = ? AND s.mumble_group IS NOT NULL
+        ORDER BY 1
+        "#,
+    )
+    .bind(user_id)
+    .bind(tribe)
+    .bind(user_id)
+    .bind(tribe)
+    .fetch_all(db)
+    .await
+}
+
+/// Load a member of the caller's tribe by Discord ID
+async fn tribe_member(
+    db: &DbPool,
+    tribe: &str,
+    discord_id: &str,
+) -> Result<crate::models::User, (StatusCode, &'static str)> {
+    let member = get_user_by_discord_id(db, discord_id)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Member not found"))?;
+    let in_tribe: Option<i64> =
+        sqlx::query_scalar("SELECT 1 FROM user_tribes WHERE user_id = ? AND tribe = ?")
+            .bind(member.id)
+            .bind(tribe)
+            .fetch_optional(db)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?;
+    if in_tribe.is_none() {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "Access denied: Member is not in the specified tribe",
+        ));
+    }
+    Ok(member)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/roster/ranks",
+    params(
+        ("tribe" = Option<String>, Query, description = "Tribe whose ladder to list")
+    ),
+    responses(
+        (status = 200, description = "The tribe's rank ladder, lowest first", body = Vec<TribeRank>),
+        (status = 403, description = "Forbidden: Missing roster.read")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn list_ranks(
+    access: TribeAccess<perm::RosterRead>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match tribe_ranks(&state.db, &access.tribe).await {
+        Ok(ranks) => Json(ranks).into_response(),
+        Err(e) => {
+            eprintln!("Failed to list ranks: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/roster/ranks",
+    params(
+        ("tribe" = Option<String>, Query, description = "Tribe whose ladder to replace")
+    ),
+    request_body = SetRanksRequest,
+    responses(
+        (status = 200, description = "The new ladder, lowest first", body = Vec<TribeRank>),
+        (status = 400, description = "Invalid or duplicate key, name, role or group"),
+        (status = 403, description = "Forbidden: Missing admin.grant, or step-up required")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn set_ranks(
+    access: TribeAccess<perm::AdminGrant>,
+    State(state): State<AppState>,
+    _step_up: StepUp,
+    Json(payload): Json<SetRanksRequest>,
+) -> impl IntoResponse {
+    if payload.ranks.len() > MAX_RANKS {
+        return (StatusCode::BAD_REQUEST, "Too many ranks").into_response();
+    }
+    let mut ladder: Vec<(String, String, Option<String>, Option<String>)> = Vec::new();
+    for rank in &payload.ranks {
+        if !valid_key(&rank.key) {
+            return (
+                StatusCode::BAD_REQUEST,
+                "Rank keys are up to 32 lowercase letters, digits and underscores, starting with a letter",
+            )
+                .into_response();
+        }
+        if ladder.iter().any(|(key, ..)| *key == rank.key) {
+            return (StatusCode::BAD_REQUEST, "Duplicate rank key").into_response();
+        }
+        let Some(name) = clean_name(&rank.name) else {
+            return (
+                StatusCode::BAD_REQUEST,
+                "Rank names must be 1 to 64 characters",
+            )
+                .into_response();
+        };
+        let group = match clean_group(rank.mumble_group.as_deref()) {
+            Ok(g) => g,
+            Err(msg) => return (StatusCode::BAD_REQUEST, msg).into_response(),
+        };
+        if let Some(role_id) = &rank.role_id {
+            if role_id == ADMIN_ROLE_ID {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    "The admin role is managed through admin grants",
+                )
+                    .into_response();
+            }
+            match sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM roles WHERE id = ?")
+                .bind(role_id)
+                .fetch_one(&state.db)
+                .await
+            {
+                Ok(0) => return (StatusCode::BAD_REQUEST, "Unknown role").into_response(),
+                Ok(_) => {}
+                Err(_) => {
+                    return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response()
+                }
+            }
+        }
+        ladder.push((rank.key.clone(), name, rank.role_id.clone(), group));
+    }
+
+    let existing = match tribe_ranks(&state.db, &access.tribe).await {
+        Ok(r) => r,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+    let removed: Vec<&TribeRank> = existing
+        .iter()
+        .filter(|r| !ladder.iter().any(|(key, ..)| *key == r.key))
+        .collect();
+    let unranked: i64 = removed.iter().map(|r| r.member_count).sum();
+
+    let result: Result<(), sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+        for rank in &removed {
+            // Holders become unranked through the foreign key
+            sqlx::query("DELETE FROM tribe_ranks WHERE id = ?")
+                .bind(&rank.id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        for (position, (key, name, role_id, group)) in ladder.iter().enumerate() {
+            sqlx::query(
+                r#"
+                INSERT INTO tribe_ranks (id, tribe, key, name, position, role_id, mumble_group)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(tribe, key) DO UPDATE SET
+                    name = excluded.name, position = excluded.position,
+                    role_id = excluded.role_id, mumble_group = excluded.mumble_group
+                "#,
+            )
+            .bind(Uuid::new_v4().to_string())
+            .bind(&access.tribe)
+            .bind(key)
+            .bind(name)
+            .bind(position as i64)
+            .bind(role_id)
+            .bind(group)
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+    .await;
+    if let Err(e) = result {
+        eprintln!("Failed to update rank ladder: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+    }
+
+    let keys: Vec<&str> = ladder.iter().map(|(key, ..)| key.as_str()).collect();
+    let mut details = format!(
+        "Set rank ladder of tribe {} to {}",
+        access.tribe,
+        if keys.is_empty() {
+            "(none)".to_string()
+        } else {
+            keys.join(" < ")
+        }
+    );
+    if !removed.is_empty() {
+        details.push_str(&format!(
+            "; removed {} rank(s), unranking {} member(s)",
+            removed.len(),
+            unranked
+        ));
+    }
+    let _ = log_audit(
+        &state.db,
+        AuditAction::RankLadderUpdate,
+        access.user.id,
+        None,
+        &details,
+    )
+    .await;
+
+    match tribe_ranks(&state.db, &access.tribe).await {
+        Ok(ranks) => Json(ranks).into_response(),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    }
+}
+
+/// Move a member up (`promote`) or down the ladder, one step or to `to`.
+/// Callers without `admin.grant` may only move members below their own rank,
+/// and only to ranks below it.
+async fn change_rank(
+    state: &AppState,
+    access: TribeAccess<perm::RanksManage>,
+    discord_id: &str,
+    promote: bool,
+    to: Option<&str>,
+) -> Result<RankChange, (StatusCode, &'static str)> {
+    let db_error = |_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error");
+    let member = tribe_member(&state.db, &access.tribe, discord_id).await?;
+    let ladder = tribe_ranks(&state.db, &access.tribe)
+        .await
+        .map_err(db_error)?;
+    if ladder.is_empty() {
+        return Err((StatusCode::BAD_REQUEST, "The tribe has no ranks"));
+    }
+    let current = rank_position(&state.db, member.id, &access.tribe)
+        .await
+        .map_err(db_error)?;
+
+    let target: Option<&TribeRank> = match (to, promote, current) {
+        (Some(key), _, _) => Some(
+            ladder
+                .iter()
+                .find(|r| r.key == key)
+                .ok_or((StatusCode::BAD_REQUEST, "Unknown rank"))?,
+        ),
+        (None, true, None) => ladder.first(),
+        (None, true, Some(p)) => Some(ladder.iter().find(|r| r.position > p).ok_or((
+            StatusCode::BAD_REQUEST,
+            "Member already holds the highest rank",
+        ))?),
+        (None, false, None) => return Err((StatusCode::BAD_REQUEST, "Member has no rank")),
+        // Demoting from the lowest rank leaves the member unranked
+        (None, false, Some(p)) => ladder.iter().rev().find(|r| r.position < p),
+    };
+    let new_position = target.map(|r| r.position);
+    let moves_up = new_position.unwrap_or(-1) > current.unwrap_or(-1);
+    if new_position == current || moves_up != promote {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            if promote {
+                "Promotion must move the member up"
+            } else {
+                "Demotion must move the member down"
+            },
+        ));
+    }
+
+    let tribe_admin = has_permission_in_tribe(
+        &state.db,
+        access.user.id,
+        &access.tribe,
+        Permission::AdminGrant,
+    )
+    .await
+    .map_err(db_error)?;
+    if !tribe_admin {
+        let own = rank_position(&state.db, access.user.id, &access.tribe)
+            .await
+            .map_err(db_error)?
+            .unwrap_or(-1);
+        if current.unwrap_or(-1) >= own || new_position.unwrap_or(-1) >= own {
+            return Err((
+                StatusCode::FORBIDDEN,
+                "You can only move members below your own rank",
+            ));
+        }
+    }
+
+    sqlx::query("UPDATE user_tribes SET rank_id = ? WHERE user_id = ? AND tribe = ?")
+        .bind(target.map(|r| &r.id))
+        .bind(member.id)
+        .bind(&access.tribe)
+        .execute(&state.db)
+        .await
+        .map_err(db_error)?;
+
+    let from = current.and_then(|p| ladder.iter().find(|r| r.position == p));
+    let label = |r: Option<&TribeRank>| r.map_or("unranked".to_string(), |r| r.key.clone());
+    let _ = log_audit(
+        &state.db,
+        if promote {
+            AuditAction::RankPromote
+        } else {
+            AuditAction::RankDemote
+        },
+        access.user.id,
+        Some(member.id),
+        &format!(
+            "{} {} from {} to {} in tribe {}",
+            if promote { "Promoted" } else { "Demoted" },
+            member.username,
+            label(from),
+            label(target),
+            access.tribe
+        ),
+    )
+    .await;
+
+    Ok(RankChange {
+        from: from.map(|r| r.key.clone()),
+        to: target.map(|r| r.key.clone()),
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/roster/{discord_id}/promote",
+    params(
+        ("discord_id" = String, Path, description = "Discord ID of the member"),
+        ("tribe" = Option<String>, Query, description = "Tribe to promote in")
+    ),
+    request_body = RankChangeRequest,
+    responses(
+        (status = 200, description = "Member promoted", body = RankChange),
+        (status = 400, description = "No higher rank, unknown rank, or not a promotion"),
+        (status = 403, description = "Forbidden: Missing ranks.manage, or the member or rank is not below yours"),
+        (status = 404, description = "Member not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn promote_member(
+    access: TribeAccess<perm::RanksManage>,
+    Path(discord_id): Path<String>,
+    State(state): State<AppState>,
+    Json(payload): Json<RankChangeRequest>,
+) -> impl IntoResponse {
+    match change_rank(&state, access, &discord_id, true, payload.to.as_deref()).await {
+        Ok(change) => Json(change).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/roster/{discord_id}/demote",
+    params(
+        ("discord_id" = String, Path, description = "Discord ID of the member"),
+        ("tribe" = Option<String>, Query, description = "Tribe to demote in")
+    ),
+    request_body = RankChangeRequest,
+    responses(
+        (status = 200, description = "Member demoted; demoting from the lowest rank leaves them unranked", body = RankChange),
+        (status = 400, description = "Member has no rank, unknown rank, or not a demotion"),
+        (status = 403, description = "Forbidden: Missing ranks.manage, or the member is not below your rank"),
+        (status = 404, description = "Member not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn demote_member(
+    access: TribeAccess<perm::RanksManage>,
+    Path(discord_id): Path<String>,
+    State(state): State<AppState>,
+    Json(payload): Json<RankChangeRequest>,
+) -> impl IntoResponse {
+    match change_rank(&state, access, &discord_id, false, payload.to.as_deref()).await {
+        Ok(change) => Json(change).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn tribe_squads(db: &DbPool, tribe: &str) -> Result<Vec<Squad>, sqlx::Error> {
+    let squads: Vec<SquadRow> = sqlx::query_as(
+        "SELECT id, name, mumble_group FROM tribe_squads WHERE tribe = ? ORDER BY name",
+    )
+    .bind(tribe)
+    .fetch_all(db)
+    .await?;
+    let members: Vec<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT m.squad_id, u.discord_id FROM squad_members m
+        JOIN tribe_squads s ON s.id = m.squad_id
+        JOIN users u ON u.id = m.user_id
+        WHERE s.tribe = ?
+        ORDER BY u.username
+        "#,
+    )
+    .bind(tribe)
+    .fetch_all(db)
+    .await?;
+
+    Ok(squads
+        .into_iter()
+        .map(|s| Squad {
+            members: members
+                .iter()
+                .filter(|(squad_id, _)| *squad_id == s.id)
+                .map(|(_, discord_id)| discord_id.clone())
+                .collect(),
+            id: s.id,
+            name: s.name,
+            mumble_group: s.mumble_group,
+        })
+        .collect())
+}
+
+async fn find_squad(
+    db: &DbPool,
+    tribe: &str,
+    squad_id: &str,
+) -> Result<SquadRow, (StatusCode, &'static str)> {
+    sqlx::query_as("SELECT id, name, mumble_group FROM tribe_squads WHERE id = ? AND tribe = ?")
+        .bind(squad_id)
+        .bind(tribe)
+        .fetch_optional(db)
+        .await
+        .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+        .ok_or((StatusCode::NOT_FOUND, "Squad not found"))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/roster/squads",
+    params(
+        ("tribe" = Option<String>, Query, description = "Tribe whose squads to list")
+    ),
+    responses(
+        (status = 200, description = "The tribe's squads and their members", body = Vec<Squad>),
+        (status = 403, description = "Forbidden: Missing roster.read")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn list_squads(
+    access: TribeAccess<perm::RosterRead>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match tribe_squads(&state.db, &access.tribe).await {
+        Ok(squads) => Json(squads).into_response(),
+        Err(e) => {
+            eprintln!("Failed to list squads: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/roster/squads",
+    params(
+        ("tribe" = Option<String>, Query, description = "Tribe to create the squad in")
+    ),
+    request_body = CreateSquadRequest,
+    responses(
+        (status = 201, description = "Squad created", body = Squad),
+        (status = 400, description = "Invalid name or group, or too many squads"),
+        (status = 403, description = "Forbidden: Missing ranks.manage"),
+        (status = 409, description = "The tribe already has a squad with this name")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn create_squad(
+    access: TribeAccess<perm::RanksManage>,
+    State(state): State<AppState>,
+    Json(payload): Json<CreateSquadRequest>,
+) -> impl IntoResponse {
+    let Some(name) = clean_name(&payload.name) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            "Squad names must be 1 to 64 characters",
+        )
+            .into_response();
+    };
+    let mumble_group = match clean_group(payload.mumble_group.as_deref()) {
+        Ok(g) => g,
+        Err(msg) => return (StatusCode::BAD_REQUEST, msg).into_response(),
+    };
+    match sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM tribe_squads WHERE tribe = ?")
+        .bind(&access.tribe)
+        .fetch_one(&state.db)
+        .await
+    {
+        Ok(n) if n >= MAX_SQUADS_PER_TRIBE => {
+            return (StatusCode::BAD_REQUEST, "The tribe has too many squads").into_response()
+        }
+        Ok(_) => {}
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    }
+
+    let squad = Squad {
+        id: Uuid::new_v4().to_string(),
+        name,
+        mumble_group,
+        members: Vec::new(),
+    };
+    let result = sqlx::query(
+        "INSERT INTO tribe_squads (id, tribe, name, mumble_group, created_at) VALUES (?, ?, ?, ?, ?)",
+    )
+    .bind(&squad.id)
+    .bind(&access.tribe)
+    .bind(&squad.name)
+    .bind(&squad.mumble_group)
+    .bind(Utc::now())
+    .execute(&state.db)
+    .await;
+    match result {
+        Ok(_) => {}
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return (
+                StatusCode::CONFLICT,
+                "The tribe already has a squad with this name",
+            )
+                .into_response()
+        }
+        Err(e) => {
+            eprintln!("Failed to create squad: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+        }
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::SquadUpdate,
+        access.user.id,
+        None,
+        &format!("Created squad {} in tribe {}", squad.name, access.tribe),
+    )
+    .await;
+
+    (StatusCode::CREATED, Json(squad)).into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/roster/squads/{squad_id}",
+    params(
+        ("squad_id" = String, Path, description = "Squad ID"),
+        ("tribe" = Option<String>, Query, description = "Tribe the squad belongs to")
+    ),
+    responses(
+        (status = 200, description = "Squad deleted"),
+        (status = 403, description = "Forbidden: Missing ranks.manage"),
+        (status = 404, description = "Squad not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn delete_squad(
+    access: TribeAccess<perm::RanksManage>,
+    Path(squad_id): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let squad = match find_squad(&state.db, &access.tribe, &squad_id).await {
+        Ok(s) => s,
+        Err(e) => return e.into_response(),
+    };
+    if let Err(e) = sqlx::query("DELETE FROM tribe_squads WHERE id = ?")
+        .bind(&squad.id)
+        .execute(&state.db)
+        .await
+    {
+        eprintln!("Failed to delete squad: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::SquadUpdate,
+        access.user.id,
+        None,
+        &format!("Deleted squad {} in tribe {}", squad.name, access.tribe),
+    )
+    .await;
+
+    StatusCode::OK.into_response()
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/roster/squads/{squad_id}/members/{discord_id}",
+    params(
+        ("squad_id" = String, Path, description = "Squad ID"),
+        ("discord_id" = String, Path, description = "Discord ID of the member"),
+        ("tribe" = Option<String>, Query, description = "Tribe the squad belongs to")
+    ),
+    responses(
+        (status = 200, description = "Member is in the squad"),
+        (status = 403, description = "Forbidden: Missing ranks.manage, or member not in the tribe"),
+        (status = 404, description = "Squad or member not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn add_squad_member(
+    access: TribeAccess<perm::RanksManage>,
+    Path((squad_id, discord_id)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let squad = match find_squad(&state.db, &access.tribe, &squad_id).await {
+        Ok(s) => s,
+        Err(e) => return e.into_response(),
+    };
+    let member = match tribe_member(&state.db, &access.tribe, &discord_id).await {
+        Ok(m) => m,
+        Err(e) => return e.into_response(),
+    };
+
+    let result = sqlx::query(
+        "INSERT OR IGNORE INTO squad_members (squad_id, user_id, added_by, added_at) VALUES (?, ?, ?, ?)",
+    )
+    .bind(&squad.id)
+    .bind(member.id)
+    .bind(access.user.id)
+    .bind(Utc::now())
+    .execute(&state.db)
+    .await;
+    match result {
+        Ok(r) if r.rows_affected() == 0 => return StatusCode::OK.into_response(),
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Failed to add squad member: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+        }
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::SquadUpdate,
+        access.user.id,
+        Some(member.id),
+        &format!(
+            "Added {} to squad {} in tribe {}",
+            member.username, squad.name, access.tribe
+        ),
+    )
+    .await;
+
+    StatusCode::OK.into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/roster/squads/{squad_id}/members/{discord_id}",
+    params(
+        ("squad_id" = String, Path, description = "Squad ID"),
+        ("discord_id" = String, Path, description = "Discord ID of the member"),
+        ("tribe" = Option<String>, Query, description = "Tribe the squad belongs to")
+    ),
+    responses(
+        (status = 200, description = "Member removed from the squad"),
+        (status = 403, description = "Forbidden: Missing ranks.manage"),
+        (status = 404, description = "Squad, member or squad membership not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn remove_squad_member(
+    access: TribeAccess<perm::RanksManage>,
+    Path((squad_id, discord_id)): Path<(String, String)>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let squad = match find_squad(&state.db, &access.tribe, &squad_id).await {
+        Ok(s) => s,
+        Err(e) => return e.into_response(),
+    };
+    let member = match get_user_by_discord_id(&state.db, &discord_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return (StatusCode::NOT_FOUND, "Member not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+
+    match sqlx::query("DELETE FROM squad_members WHERE squad_id = ? AND user_id = ?")
+        .bind(&squad.id)
+        .bind(member.id)
+        .execute(&state.db)
+        .await
+    {
+        Ok(r) if r.rows_affected() == 0 => {
+            return (StatusCode::NOT_FOUND, "Member is not in the squad").into_response()
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Failed to remove squad member: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+        }
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::SquadUpdate,
+        access.user.id,
+        Some(member.id),
+        &format!(
+            "Removed {} from squad {} in tribe {}",
+            member.username, squad.name, access.tribe
+        ),
+    )
+    .await;
+
+    StatusCode::OK.into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rbac::permission_grants,
+        test_support::{access, add_members, insert_users, setup_db},
+    };
+
+    async fn setup() -> AppState {
+        let db = setup_db().await;
+        insert_users(
+            &db,
+            &[
+                (1, "111", "Chief"),
+                (2, "222", "Officer"),
+                (3, "333", "Recruit"),
+                (4, "444", "Veteran"),
+            ],
+        )
+        .await;
+        add_members(
+            &db,
+            &[
+                (1, "Fire", true),
+                (2, "Fire", false),
+                (3, "Fire", false),
+                (4, "Fire", false),
+            ],
+        )
+        .await;
+        // Officers may manage ranks through a custom role carried by their rank
+        sqlx::query("INSERT INTO roles (id, name) VALUES ('officer', 'Officer')")
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO role_permissions (role_id, permission) VALUES ('officer', 'ranks.manage')",
+        )
+        .execute(&db)
+        .await
+        .unwrap();
+        AppState::new(db)
+    }
+
+    fn rank(key: &str, role_id: Option<&str>, group: Option<&str>) -> RankDefinition {
+        RankDefinition {
+            key: key.to_string(),
+            name: key.to_uppercase(),
+            role_id: role_id.map(str::to_string),
+            mumble_group: group.map(str::to_string),
+        }
+    }
+
+    async fn ladder(state: &AppState, ranks: Vec<RankDefinition>) -> StatusCode {
+        set_ranks(
+            access(&state.db, 1, "Fire").await,
+            State(state.clone()),
+            StepUp,
+            Json(SetRanksRequest { ranks }),
+        )
+        .await
+        .into_response()
+        .status()
+    }
+
+    async fn promote(
+        state: &AppState,
+        by: i64,
+        discord_id: &str,
+        to: Option<&str>,
+    ) -> axum::response::Response {
+        promote_member(
+            access(&state.db, by, "Fire").await,
+            Path(discord_id.to_string()),
+            State(state.clone()),
+            Json(RankChangeRequest {
+                to: to.map(str::to_string),
+            }),
+        )
+        .await
+        .into_response()
+    }
+
+    async fn demote(state: &AppState, by: i64, discord_id: &str) -> StatusCode {
+        demote_member(
+            access(&state.db, by, "Fire").await,
+            Path(discord_id.to_string()),
+            State(state.clone()),
+            Json(RankChangeRequest::default()),
+        )
+        .await
+        .into_response()
+        .status()
+    }
+
+    async fn rank_of(state: &AppState, user_id: i64) -> Option<String> {
+        ranks_and_squads_for(&state.db, "Fire", &[user_id])
+            .await
+            .unwrap()
+            .remove(&user_id)
+            .and_then(|(rank, _)| rank)
+    }
+
+    #[tokio::test]
+    async fn test_rank_ladder_and_promotions() {
+        let state = setup().await;
+        assert_eq!(
+            ladder(
+                &state,
+                vec![rank("recruit", None, None), rank("recruit", None, None)]
+            )
+            .await,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            ladder(&state, vec![rank("member", Some(ADMIN_ROLE_ID), None)]).await,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            ladder(
+                &state,
+                vec![
+                    rank("recruit", None, Some("recruits")),
+                    rank("member", Some("viewer"), Some("members")),
+                    rank("officer", Some("officer"), Some("officers")),
+                    rank("director", None, None),
+                ]
+            )
+            .await,
+            StatusCode::OK
+        );
+
+        // The chief (tribe admin) can place anyone anywhere
+        let res = promote(&state, 1, "222", Some("officer")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let change: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(change, serde_json::json!({ "from": null, "to": "officer" }));
+
+        // The officer's rank grants ranks.manage through its role, and the
+        // member rank's roster.read below it
+        for permission in [Permission::RanksManage, Permission::RosterRead] {
+            let grants = permission_grants(&state.db, 2, permission).await.unwrap();
+            assert!(grants.allows("Fire"));
+        }
+
+        // One step at a time from unranked
+        assert_eq!(
+            promote(&state, 2, "333", None).await.status(),
+            StatusCode::OK
+        );
+        assert_eq!(rank_of(&state, 3).await.as_deref(), Some("recruit"));
+        assert_eq!(
+            promote(&state, 2, "333", None).await.status(),
+            StatusCode::OK
+        );
+        assert_eq!(rank_of(&state, 3).await.as_deref(), Some("member"));
+        // Officers can't promote to their own rank, or touch their peers and superiors
+        assert_eq!(
+            promote(&state, 2, "333", Some("officer")).await.status(),
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(demote(&state, 2, "222").await, StatusCode::FORBIDDEN);
+        // Promotions only go up
+        assert_eq!(
+            promote(&state, 1, "333", Some("recruit")).await.status(),
+            StatusCode::BAD_REQUEST
+        );
+
+        assert_eq!(
+            mumble_groups(&state.db, 3, "Fire").await.unwrap(),
+            ["members", "recruits"]
+        );
+        assert_eq!(demote(&state, 2, "333").await, StatusCode::OK);
+        assert_eq!(demote(&state, 2, "333").await, StatusCode::OK);
+        assert_eq!(rank_of(&state, 3).await, None);
+        assert_eq!(demote(&state, 2, "333").await, StatusCode::BAD_REQUEST);
+
+        // Dropping a rank from the ladder unranks its holders
+        assert_eq!(
+            ladder(
+                &state,
+                vec![rank("member", None, None), rank("director", None, None)]
+            )
+            .await,
+            StatusCode::OK
+        );
+        assert_eq!(rank_of(&state, 2).await, None);
+        assert!(!permission_grants(&state.db, 2, Permission::RanksManage)
+            .await
+            .unwrap()
+            .allows("Fire"));
+
+        let actions: Vec<String> =
+            sqlx::query_scalar("SELECT DISTINCT action FROM audit_logs ORDER BY action")
+                .fetch_all(&state.db)
+                .await
+                .unwrap();
+        assert_eq!(
+            actions,
+            ["RANK_DEMOTE", "RANK_LADDER_UPDATE", "RANK_PROMOTE"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_squads() {
+        let state = setup().await;
+        let res = create_squad(
+            access(&state.db, 1, "Fire").await,
+            State(state.clone()),
+            Json(CreateSquadRequest {
+                name: "Alpha Wing".to_string(),
+                mumble_group: Some("alpha".to_string()),
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let squad: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let squad_id = squad["id"].as_str().unwrap().to_string();
+
+        for discord_id in ["333", "444"] {
+            let res = add_squad_member(
+                access(&state.db, 1, "Fire").await,
+                Path((squad_id.clone(), discord_id.to_string())),
+                State(state.clone()),
+            )
+            .await
+            .into_response();
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+
+        let squads = tribe_squads(&state.db, "Fire").await.unwrap();
+        assert_eq!(squads[0].members, ["333", "444"]);
+        assert_eq!(
+            mumble_groups(&state.db, 4, "Fire").await.unwrap(),
+            ["alpha"]
+        );
+        let members = ranks_and_squads_for(&state.db, "Fire", &[3, 4])
+            .await
+            .unwrap();
+        assert_eq!(members[&3].1, ["Alpha Wing"]);
+
+        // Leaving the tribe leaves its squads
+        sqlx::query("DELETE FROM user_tribes WHERE user_id = 4")
+            .execute(&state.db)
+            .await
+            .unwrap();
+        let squads = tribe_squads(&state.db, "Fire").await.unwrap();
+        assert_eq!(squads[0].members, ["333"]);
+
+        let res = remove_squad_member(
+            access(&state.db, 1, "Fire").await,
+            Path((squad_id.clone(), "333".to_string())),
+            State(state.clone()),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = delete_squad(
+            access(&state.db, 1, "Fire").await,
+            Path(squad_id.clone()),
+            State(state.clone()),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(tribe_squads(&state.db, "Fire").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ranks_and_squads_follow_tribe_rename() {
+        let state = setup().await;
+        assert_eq!(
+            ladder(
+                &state,
+                vec![
+                    rank("recruit", None, None),
+                    rank("officer", Some("officer"), None)
+                ]
+            )
+            .await,
+            StatusCode::OK
+        );
+        let res = promote(&state, 1, "222", Some("officer")).await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = create_squad(
+            access(&state.db, 1, "Fire").await,
+            State(state.clone()),
+            Json(CreateSquadRequest {
+                name: "Alpha Wing".to_string(),
+                mumble_group: None,
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let squad: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let res = add_squad_member(
+            access(&state.db, 1, "Fire").await,
+            Path((squad["id"].as_str().unwrap().to_string(), "333".to_string())),
+            State(state.clone()),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = crate::admin::apply_update_tribe(
+            &state,
+            &crate::middleware::admin::RequireSuperAdmin {
+                discord_id: "111".to_string(),
+            },
+            "Fire".to_string(),
+            "Flame".to_string(),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        assert_eq!(tribe_ranks(&state.db, "Flame").await.unwrap().len(), 2);
+        let members = ranks_and_squads_for(&state.db, "Flame", &[2, 3])
+            .await
+            .unwrap();
+        assert!(members[&2].0.is_some());
+        assert_eq!(members[&3].1, ["Alpha Wing"]);
+        // The rank's role still applies under the new name
+        assert!(permission_grants(&state.db, 2, Permission::RanksManage)
+            .await
+            .unwrap()
+            .allows("Flame"));
+    }
+}
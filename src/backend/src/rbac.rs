@@ -0,0 +1,977 @@
+use crate::{
+    admin::get_admin_id,
+    audit::{alert_admin_action, log_audit, AuditAction},
+    auth::AuthenticatedUser,
+    db::DbPool,
+    helpers::require_permission_in_tribe,
+    middleware::admin::RequireSuperAdmin,
+    models::User,
+    state::AppState,
+};
+use axum::{
+    extract::{FromRef, FromRequestParts, Path, Query, State},
+    http::{request::Parts, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, marker::PhantomData};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// The built-in admin role mirrors the legacy `is_admin` flags and is only
+/// granted through grant-admin and the super admin user editor.
+pub const ADMIN_ROLE_ID: &str = "admin";
+
+const MAX_ROLE_NAME_LENGTH: usize = 50;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum Permission {
+    /// List the tribe roster and view member wallets
+    #[serde(rename = "roster.read")]
+    RosterRead,
+    /// Define custom member fields and set their values
+    #[serde(rename = "roster.write")]
+    RosterWrite,
+    /// View the audit history of tribe members
+    #[serde(rename = "audit.read")]
+    AuditRead,
+    /// Read notes on tribe members
+    #[serde(rename = "notes.read")]
+    NotesRead,
+    /// Write and edit notes on tribe members
+    #[serde(rename = "notes.write")]
+    NotesWrite,
+    /// Grant tribe admin to other members
+    #[serde(rename = "admin.grant")]
+    AdminGrant,
+    /// Promote and demote members below your own rank, and manage squads
+    #[serde(rename = "ranks.manage")]
+    RanksManage,
+    /// Add and remove the tribe's sanctions entries and review their hits
+    #[serde(rename = "sanctions.manage")]
+    SanctionsManage,
+}
+
+impl Permission {
+    pub const ALL: [Permission; 8] = [
+        Permission::RosterRead,
+        Permission::RosterWrite,
+        Permission::AuditRead,
+        Permission::NotesRead,
+        Permission::NotesWrite,
+        Permission::AdminGrant,
+        Permission::RanksManage,
+        Permission::SanctionsManage,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::RosterRead => "roster.read",
+            Permission::RosterWrite => "roster.write",
+            Permission::AuditRead => "audit.read",
+            Permission::NotesRead => "notes.read",
+            Permission::NotesWrite => "notes.write",
+            Permission::AdminGrant => "admin.grant",
+            Permission::RanksManage => "ranks.manage",
+            Permission::SanctionsManage => "sanctions.manage",
+        }
+    }
+}
+
+/// Where a user holds a permission: everywhere (global role) or in specific tribes.
+#[derive(Debug, Default)]
+pub struct Grants {
+    pub global: bool,
+    pub tribes: Vec<String>,
+}
+
+impl Grants {
+    pub fn allows(&self, tribe: &str) -> bool {
+        self.global || self.tribes.iter().any(|t| t == tribe)
+    }
+}
+
+/// Resolve where a user holds a permission through any of their roles, or the
+/// roles carried by their rank and the ranks below it. A tribe admin grant past its
+/// `admin_expires_at` no longer counts, even before the expiry sweeper clears it.
+pub async fn permission_grants(
+    db: &DbPool,
+    user_id: i64,
+    permission: Permission,
+) -> Result<Grants, sqlx::Error> {
+    let scopes: Vec<(Option<String>,)> = sqlx::query_as(
+        r#"
+        SELECT ur.tribe
+        FROM user_roles ur
+        JOIN role_permissions rp ON rp.role_id = ur.role_id
+        WHERE ur.user_id = ? AND rp.permission = ?
+          AND NOT (ur.role_id = ? AND EXISTS (
+              SELECT 1 FROM user_tribes ut
+              WHERE ut.user_id = ur.user_id AND ut.tribe = ur.tribe AND ut.admin_expires_at <= ?))
+        UNION
+        SELECT ut.tribe
+        FROM user_tribes ut
+        JOIN tribe_ranks cur ON cur.id = ut.rank_id
+        JOIN tribe_ranks r ON r.tribe = cur.tribe AND r.position <= cur.position
+        JOIN role_permissions rp ON rp.role_id = r.role_id
+        WHERE ut.user_id = ? AND rp.permission = ?
+        "#,
+    )
+    .bind(user_id)
+    .bind(permission.as_str())
+    .bind(ADMIN_ROLE_ID)
+    .bind(Utc::now())
+    .bind(user_id)
+    .bind(permission.as_str())
+    .fetch_all(db)
+    .await?;
+
+    let mut grants = Grants::default();
+    for (tribe,) in scopes {
+        match tribe {
+            Some(t) => grants.tribes.push(t),
+            None => grants.global = true,
+        }
+    }
+    Ok(grants)
+}
+
+pub async fn has_permission_in_tribe(
+    db: &DbPool,
+    user_id: i64,
+    tribe: &str,
+    permission: Permission,
+) -> Result<bool, sqlx::Error> {
+    Ok(permission_grants(db, user_id, permission)
+        .await?
+        .allows(tribe))
+}
+
+/// Permissions held in each of the given tribes, for the client to decide what to show.
+pub async fn permissions_by_tribe(
+    db: &DbPool,
+    user_id: i64,
+    tribes: &[String],
+) -> Result<BTreeMap<String, Vec<&'static str>>, sqlx::Error> {
+    let mut result: BTreeMap<String, Vec<&'static str>> =
+        tribes.iter().map(|t| (t.clone(), Vec::new())).collect();
+
+    for permission in Permission::ALL {
+        let grants = permission_grants(db, user_id, permission).await?;
+        for (tribe, permissions) in result.iter_mut() {
+            if grants.allows(tribe) {
+                permissions.push(permission.as_str());
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Type-level permission for the [`TribeAccess`] extractor.
+pub trait RequiredPermission {
+    const PERMISSION: Permission;
+}
+
+/// Marker types naming each permission, e.g. `TribeAccess<perm::NotesWrite>`.
+pub mod perm {
+    use super::{Permission, RequiredPermission};
+
+    pub struct RosterRead;
+    pub struct RosterWrite;
+    pub struct AuditRead;
+    pub struct NotesRead;
+    pub struct NotesWrite;
+    pub struct AdminGrant;
+    pub struct RanksManage;
+    pub struct SanctionsManage;
+
+    impl RequiredPermission for RosterRead {
+        const PERMISSION: Permission = Permission::RosterRead;
+    }
+    impl RequiredPermission for RosterWrite {
+        const PERMISSION: Permission = Permission::RosterWrite;
+    }
+    impl RequiredPermission for AuditRead {
+        const PERMISSION: Permission = Permission::AuditRead;
+    }
+    impl RequiredPermission for NotesRead {
+        const PERMISSION: Permission = Permission::NotesRead;
+    }
+    impl RequiredPermission for NotesWrite {
+        const PERMISSION: Permission = Permission::NotesWrite;
+    }
+    impl RequiredPermission for AdminGrant {
+        const PERMISSION: Permission = Permission::AdminGrant;
+    }
+    impl RequiredPermission for RanksManage {
+        const PERMISSION: Permission = Permission::RanksManage;
+    }
+    impl RequiredPermission for SanctionsManage {
+        const PERMISSION: Permission = Permission::SanctionsManage;
+    }
+}
+
+#[derive(Deserialize)]
+struct TribeParam {
+    tribe: Option<String>,
+}
+
+/// A logged-in user holding permission `P` in the tribe named by the `tribe`
+/// query parameter (or their only eligible tribe when it is omitted).
+pub struct TribeAccess<P> {
+    pub user: User,
+    pub tribe: String,
+    pub all_tribes: Vec<String>,
+    _permission: PhantomData<P>,
+}
+
+impl<P> TribeAccess<P> {
+    pub fn new(user: User, tribe: String, all_tribes: Vec<String>) -> Self {
+        Self {
+            user,
+            tribe,
+            all_tribes,
+            _permission: PhantomData,
+        }
+    }
+}
+
+impl<S, P> FromRequestParts<S> for TribeAccess<P>
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+    P: RequiredPermission,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let auth_user = AuthenticatedUser::from_request_parts(parts, state).await?;
+        let Query(param) = Query::<TribeParam>::try_from_uri(&parts.uri)
+            .map_err(|_| (StatusCode::BAD_REQUEST, "Invalid query string"))?;
+
+        let state = AppState::from_ref(state);
+        let (user, tribe, all_tribes) = require_permission_in_tribe(
+            &state.db,
+            auth_user.user_id,
+            param.tribe.as_deref(),
+            P::PERMISSION,
+        )
+        .await?;
+
+        Ok(Self::new(user, tribe, all_tribes))
+    }
+}
+
+// --- Role management (super admin) ---
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleResponse {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_builtin: bool,
+    pub permissions: Vec<Permission>,
+}
+
+#[derive(sqlx::FromRow)]
+struct RoleRow {
+    id: String,
+    name: String,
+    description: Option<String>,
+    is_builtin: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateRoleRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub permissions: Vec<Permission>,
+}
+
+#[derive(Serialize, ToSchema, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleAssignment {
+    pub id: String,
+    pub role_id: String,
+    pub role_name: String,
+    /// `None` for global assignments
+    pub tribe: Option<String>,
+    #[serde(with = "crate::models::option_i64_as_string")]
+    #[schema(value_type = Option<String>)]
+    pub granted_by: Option<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct AssignRoleRequest {
+    pub role_id: String,
+    /// Omit for a global assignment
+    pub tribe: Option<String>,
+}
+
+async fn fetch_roles(db: &DbPool) -> Result<Vec<RoleResponse>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, RoleRow>(
+        "SELECT id, name, description, is_builtin FROM roles ORDER BY is_builtin DESC, name",
+    )
+    .fetch_all(db)
+    .await?;
+
+    let permissions: Vec<(String, String)> =
+        sqlx::query_as("SELECT role_id, permission FROM role_permissions")
+            .fetch_all(db)
+            .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| RoleResponse {
+            permissions: Permission::ALL
+                .into_iter()
+                .filter(|p| {
+                    permissions
+                        .iter()
+                        .any(|(role_id, perm)| *role_id == row.id && perm == p.as_str())
+                })
+                .collect(),
+            id: row.id,
+            name: row.name,
+            description: row.description,
+            is_builtin: row.is_builtin,
+        })
+        .collect())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/roles",
+    tag = "Admin",
+    responses(
+        (status = 200, description = "All roles with their permissions", body = Vec<RoleResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn list_roles(
+    State(state): State<AppState>,
+    _admin: RequireSuperAdmin,
+) -> impl IntoResponse {
+    match fetch_roles(&state.db).await {
+        Ok(roles) => Json(roles).into_response(),
+        Err(e) => {
+            eprintln!("Failed to list roles: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/roles",
+    tag = "Admin",
+    request_body = CreateRoleRequest,
+    responses(
+        (status = 201, description = "Role created", body = RoleResponse),
+        (status = 400, description = "Invalid name or no permissions"),
+        (status = 409, description = "Role name already exists"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn create_role(
+    State(state): State<AppState>,
+    admin: RequireSuperAdmin,
+    Json(payload): Json<CreateRoleRequest>,
+) -> impl IntoResponse {
+    let name = payload.name.trim().to_string();
+    if name.is_empty() || name.len() > MAX_ROLE_NAME_LENGTH {
+        return (StatusCode::BAD_REQUEST, "Role name must be 1-50 characters").into_response();
+    }
+    if payload.permissions.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            "A role needs at least one permission",
+        )
+            .into_response();
+    }
+
+    let mut permissions = payload.permissions;
+    permissions.sort_by_key(|p| p.as_str());
+    permissions.dedup();
+
+    let mut tx = match state.db.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            eprintln!("Failed to start transaction for create_role: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let id = Uuid::new_v4().to_string();
+    let result = sqlx::query(
+        "INSERT INTO roles (id, name, description, is_builtin) VALUES (?, ?, ?, FALSE)",
+    )
+    .bind(&id)
+    .bind(&name)
+    .bind(&payload.description)
+    .execute(&mut *tx)
+    .await;
+    match result {
+        Ok(_) => {}
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            let _ = tx.rollback().await;
+            return (StatusCode::CONFLICT, "Role name already exists").into_response();
+        }
+        Err(e) => {
+            eprintln!("Failed to create role: {}", e);
+            let _ = tx.rollback().await;
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+        }
+    }
+
+    for permission in &permissions {
+        if let Err(e) =
+            sqlx::query("INSERT INTO role_permissions (role_id, permission) VALUES (?, ?)")
+                .bind(&id)
+                .bind(permission.as_str())
+                .execute(&mut *tx)
+                .await
+        {
+            eprintln!("Failed to add permission to role: {}", e);
+            let _ = tx.rollback().await;
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+
+    if let Err(e) = tx.commit().await {
+        eprintln!("Transaction commit failed for create_role: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    let details = format!(
+        "Created role '{}' ({}) with permissions {}",
+        name,
+        id,
+        permissions
+            .iter()
+            .map(|p| p.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+    let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+    let _ = log_audit(&state.db, AuditAction::RoleCreate, admin_id, None, &details).await;
+    alert_admin_action(
+        format!("SuperAdmin ({})", admin.discord_id),
+        AuditAction::RoleCreate,
+        details,
+    );
+
+    (
+        StatusCode::CREATED,
+        Json(RoleResponse {
+            id,
+            name,
+            description: payload.description,
+            is_builtin: false,
+            permissions,
+        }),
+    )
+        .into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/roles/{id}",
+    tag = "Admin",
+    params(
+        ("id" = String, Path, description = "Role ID")
+    ),
+    responses(
+        (status = 200, description = "Role and its assignments deleted"),
+        (status = 400, description = "Built-in roles cannot be deleted"),
+        (status = 404, description = "Role not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn delete_role(
+    State(state): State<AppState>,
+    admin: RequireSuperAdmin,
+    Path(id): Path<String>,
+) -> impl IntoResponse {
+    let role: Option<RoleRow> =
+        match sqlx::query_as("SELECT id, name, description, is_builtin FROM roles WHERE id = ?")
+            .bind(&id)
+            .fetch_optional(&state.db)
+            .await
+        {
+            Ok(r) => r,
+            Err(e) => {
+                eprintln!("Failed to fetch role: {}", e);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        };
+
+    let Some(role) = role else {
+        return (StatusCode::NOT_FOUND, "Role not found").into_response();
+    };
+    if role.is_builtin {
+        return (StatusCode::BAD_REQUEST, "Built-in roles cannot be deleted").into_response();
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM roles WHERE id = ?")
+        .bind(&id)
+        .execute(&state.db)
+        .await
+    {
+        eprintln!("Failed to delete role: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    let details = format!("Deleted role '{}' ({})", role.name, role.id);
+    let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+    let _ = log_audit(&state.db, AuditAction::RoleDelete, admin_id, None, &details).await;
+    alert_admin_action(
+        format!("SuperAdmin ({})", admin.discord_id),
+        AuditAction::RoleDelete,
+        details,
+    );
+
+    StatusCode::OK.into_response()
+}
+
+async fn fetch_assignments(db: &DbPool, user_id: i64) -> Result<Vec<RoleAssignment>, sqlx::Error> {
+    sqlx::query_as::<_, RoleAssignment>(
+        r#"
+        SELECT ur.id, ur.role_id, r.name as role_name, ur.tribe, ur.granted_by, ur.created_at
+        FROM user_roles ur
+        JOIN roles r ON r.id = ur.role_id
+        WHERE ur.user_id = ?
+        ORDER BY ur.tribe IS NOT NULL, ur.tribe, r.name
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/users/{id}/roles",
+    tag = "Admin",
+    params(
+        ("id" = i64, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Role assignments of the user", body = Vec<RoleAssignment>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn list_user_roles(
+    State(state): State<AppState>,
+    _admin: RequireSuperAdmin,
+    Path(user_id): Path<i64>,
+) -> impl IntoResponse {
+    match fetch_assignments(&state.db, user_id).await {
+        Ok(assignments) => Json(assignments).into_response(),
+        Err(e) => {
+            eprintln!("Failed to list user roles: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{id}/roles",
+    tag = "Admin",
+    params(
+        ("id" = i64, Path, description = "User ID")
+    ),
+    request_body = AssignRoleRequest,
+    responses(
+        (status = 201, description = "Role assigned"),
+        (status = 400, description = "The admin role is managed through admin grants"),
+        (status = 404, description = "User, role or tribe not found"),
+        (status = 409, description = "Role already assigned"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn assign_role(
+    State(state): State<AppState>,
+    admin: RequireSuperAdmin,
+    Path(user_id): Path<i64>,
+    Json(payload): Json<AssignRoleRequest>,
+) -> impl IntoResponse {
+    if payload.role_id == ADMIN_ROLE_ID {
+        return (
+            StatusCode::BAD_REQUEST,
+            "The admin role is managed through admin grants",
+        )
+            .into_response();
+    }
+
+    let user = match crate::helpers::get_user_by_id(&state.db, user_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return (StatusCode::NOT_FOUND, "User not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+
+    let role_name: Option<String> = sqlx::query_scalar("SELECT name FROM roles WHERE id = ?")
+        .bind(&payload.role_id)
+        .fetch_optional(&state.db)
+        .await
+        .unwrap_or(None);
+    let Some(role_name) = role_name else {
+        return (StatusCode::NOT_FOUND, "Role not found").into_response();
+    };
+
+    if let Some(tribe) = &payload.tribe {
+        let exists: Option<(String,)> = sqlx::query_as("SELECT name FROM tribes WHERE name = ?")
+            .bind(tribe)
+            .fetch_optional(&state.db)
+            .await
+            .unwrap_or(None);
+        if exists.is_none() {
+            return (StatusCode::NOT_FOUND, "Tribe not found").into_response();
+        }
+    }
+
+    let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+    let result = sqlx::query(
+        "INSERT INTO user_roles (id, user_id, role_id, tribe, granted_by, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(Uuid::new_v4().to_string())
+    .bind(user_id)
+    .bind(&payload.role_id)
+    .bind(&payload.tribe)
+    .bind(admin_id)
+    .bind(Utc::now())
+    .execute(&state.db)
+    .await;
+
+    match result {
+        Ok(_) => {}
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return (StatusCode::CONFLICT, "Role already assigned").into_response()
+        }
+        Err(e) => {
+            eprintln!("Failed to assign role: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+        }
+    }
+
+    let details = format!(
+        "Assigned role '{}' to {} in {}",
+        role_name,
+        user.username,
+        payload.tribe.as_deref().unwrap_or("all tribes")
+    );
+    let _ = log_audit(
+        &state.db,
+        AuditAction::RoleAssign,
+        admin_id,
+        Some(user_id),
+        &details,
+    )
+    .await;
+    alert_admin_action(
+        format!("SuperAdmin ({})", admin.discord_id),
+        AuditAction::RoleAssign,
+        details,
+    );
+
+    StatusCode::CREATED.into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/users/{id}/roles/{assignment_id}",
+    tag = "Admin",
+    params(
+        ("id" = i64, Path, description = "User ID"),
+        ("assignment_id" = String, Path, description = "Role assignment ID")
+    ),
+    responses(
+        (status = 200, description = "Role unassigned"),
+        (status = 400, description = "The admin role is managed through admin grants"),
+        (status = 404, description = "Assignment not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn unassign_role(
+    State(state): State<AppState>,
+    admin: RequireSuperAdmin,
+    Path((user_id, assignment_id)): Path<(i64, String)>,
+) -> impl IntoResponse {
+    let assignments = match fetch_assignments(&state.db, user_id).await {
+        Ok(a) => a,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+    let Some(assignment) = assignments.into_iter().find(|a| a.id == assignment_id) else {
+        return (StatusCode::NOT_FOUND, "Assignment not found").into_response();
+    };
+    if assignment.role_id == ADMIN_ROLE_ID {
+        return (
+            StatusCode::BAD_REQUEST,
+            "The admin role is managed through admin grants",
+        )
+            .into_response();
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM user_roles WHERE id = ?")
+        .bind(&assignment_id)
+        .execute(&state.db)
+        .await
+    {
+        eprintln!("Failed to unassign role: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    let details = format!(
+        "Removed role '{}' from user {} in {}",
+        assignment.role_name,
+        user_id,
+        assignment.tribe.as_deref().unwrap_or("all tribes")
+    );
+    let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+    let _ = log_audit(
+        &state.db,
+        AuditAction::RoleUnassign,
+        admin_id,
+        Some(user_id),
+        &details,
+    )
+    .await;
+    alert_admin_action(
+        format!("SuperAdmin ({})", admin.discord_id),
+        AuditAction::RoleUnassign,
+        details,
+    );
+
+    StatusCode::OK.into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        helpers::require_admin_in_tribe,
+        test_support::{add_members, insert_users, setup_db},
+    };
+
+    async fn setup() -> DbPool {
+        let pool = setup_db().await;
+        insert_users(
+            &pool,
+            &[(1, "super-1", "SuperUser"), (2, "member-2", "Recruiter")],
+        )
+        .await;
+        sqlx::query("INSERT INTO tribes (name) VALUES ('Fire')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        add_members(&pool, &[(2, "Fire", false)]).await;
+        pool
+    }
+
+    async fn role_count(db: &DbPool, user_id: i64, tribe: Option<&str>) -> i64 {
+        sqlx::query_scalar(
+            "SELECT COUNT(*) FROM user_roles WHERE user_id = ? AND role_id = 'admin' AND tribe IS ?",
+        )
+        .bind(user_id)
+        .bind(tribe)
+        .fetch_one(db)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_admin_flags_mirror_admin_role() {
+        let db = setup().await;
+
+        sqlx::query("UPDATE user_tribes SET is_admin = TRUE WHERE user_id = 2")
+            .execute(&db)
+            .await
+            .unwrap();
+        assert_eq!(role_count(&db, 2, Some("Fire")).await, 1);
+        assert!(require_admin_in_tribe(&db, 2, Some("Fire")).await.is_ok());
+
+        sqlx::query("UPDATE user_tribes SET is_admin = FALSE WHERE user_id = 2")
+            .execute(&db)
+            .await
+            .unwrap();
+        assert_eq!(role_count(&db, 2, Some("Fire")).await, 0);
+        assert!(require_admin_in_tribe(&db, 2, Some("Fire")).await.is_err());
+
+        sqlx::query("UPDATE users SET is_admin = TRUE WHERE id = 2")
+            .execute(&db)
+            .await
+            .unwrap();
+        assert_eq!(role_count(&db, 2, None).await, 1);
+
+        sqlx::query("UPDATE users SET is_admin = FALSE WHERE id = 2")
+            .execute(&db)
+            .await
+            .unwrap();
+        assert_eq!(role_count(&db, 2, None).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_recruiter_permissions() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+
+        let res = assign_role(
+            State(state.clone()),
+            RequireSuperAdmin {
+                discord_id: "super-1".to_string(),
+            },
+            Path(2),
+            Json(AssignRoleRequest {
+                role_id: "recruiter".to_string(),
+                tribe: Some("Fire".to_string()),
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::CREATED);
+
+        for permission in [
+            Permission::RosterRead,
+            Permission::NotesRead,
+            Permission::NotesWrite,
+        ] {
+            let (_, tribe, _) = require_permission_in_tribe(&db, 2, None, permission)
+                .await
+                .unwrap();
+            assert_eq!(tribe, "Fire");
+        }
+        for permission in [Permission::AuditRead, Permission::AdminGrant] {
+            let err = require_permission_in_tribe(&db, 2, Some("Fire"), permission)
+                .await
+                .unwrap_err();
+            assert_eq!(err.0, StatusCode::FORBIDDEN);
+        }
+
+        let by_tribe = permissions_by_tribe(&db, 2, &["Fire".to_string()])
+            .await
+            .unwrap();
+        assert_eq!(
+            by_tribe["Fire"],
+            vec!["roster.read", "notes.read", "notes.write"]
+        );
+
+        // Leaving the tribe drops the role
+        sqlx::query("DELETE FROM user_tribes WHERE user_id = 2")
+            .execute(&db)
+            .await
+            .unwrap();
+        let remaining: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM user_roles WHERE user_id = 2")
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(remaining, 0);
+    }
+
+    #[tokio::test]
+    async fn test_admin_role_not_assignable() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+
+        let res = assign_role(
+            State(state),
+            RequireSuperAdmin {
+                discord_id: "super-1".to_string(),
+            },
+            Path(2),
+            Json(AssignRoleRequest {
+                role_id: ADMIN_ROLE_ID.to_string(),
+                tribe: Some("Fire".to_string()),
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(role_count(&db, 2, Some("Fire")).await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_custom_role_lifecycle() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+        let admin = || RequireSuperAdmin {
+            discord_id: "super-1".to_string(),
+        };
+
+        let res = create_role(
+            State(state.clone()),
+            admin(),
+            Json(CreateRoleRequest {
+                name: "Auditor".to_string(),
+                description: None,
+                permissions: vec![Permission::AuditRead, Permission::AuditRead],
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::CREATED);
+
+        let roles = fetch_roles(&db).await.unwrap();
+        let auditor = roles.iter().find(|r| r.name == "Auditor").unwrap();
+        assert_eq!(auditor.permissions, vec![Permission::AuditRead]);
+        assert!(!auditor.is_builtin);
+
+        // Global assignment applies in every tribe the user belongs to
+        let res = assign_role(
+            State(state.clone()),
+            admin(),
+            Path(2),
+            Json(AssignRoleRequest {
+                role_id: auditor.id.clone(),
+                tribe: None,
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::CREATED);
+        assert!(
+            has_permission_in_tribe(&db, 2, "Fire", Permission::AuditRead)
+                .await
+                .unwrap()
+        );
+
+        let res = assign_role(
+            State(state.clone()),
+            admin(),
+            Path(2),
+            Json(AssignRoleRequest {
+                role_id: auditor.id.clone(),
+                tribe: None,
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+
+        let res = delete_role(State(state.clone()), admin(), Path("viewer".to_string()))
+            .await
+            .into_response();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let res = delete_role(State(state), admin(), Path(auditor.id.clone()))
+            .await
+            .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(
+            !has_permission_in_tribe(&db, 2, "Fire", Permission::AuditRead)
+                .await
+                .unwrap()
+        );
+    }
+}
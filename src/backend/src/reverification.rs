@@ -0,0 +1,682 @@
+use crate::{
+    admin::get_admin_id,
+    audit::{alert_admin_action, log_audit, AuditAction},
+    auth::AuthenticatedUser,
+    db::DbPool,
+    middleware::admin::RequireSuperAdmin,
+    models::FlatLinkedWallet,
+    state::AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// How often the background sweep looks for wallets past their deadline
+const SWEEP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60 * 60);
+
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, Clone, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReverificationPolicy {
+    pub id: String,
+    /// Tribe the policy applies to, or every tribe when absent
+    pub tribe: Option<String>,
+    /// Only applies to wallets backing a tribe-admin membership
+    pub admin_only: bool,
+    pub interval_days: i64,
+    /// Stale wallets no longer count towards tribe membership checks
+    pub enforce: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request fields are snake_case like the other request bodies; the created
+/// policy comes back camelCase
+#[derive(Deserialize, ToSchema)]
+pub struct CreatePolicyRequest {
+    pub tribe: Option<String>,
+    #[serde(default)]
+    pub admin_only: bool,
+    pub interval_days: i64,
+    #[serde(default)]
+    pub enforce: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletReverificationStatus {
+    pub wallet_id: String,
+    pub address: String,
+    pub verified_at: DateTime<Utc>,
+    pub due_at: Option<DateTime<Utc>>,
+    pub stale_at: Option<DateTime<Utc>>,
+}
+
+// A tribe membership backed by a wallet, as seen by the policy matcher
+#[derive(sqlx::FromRow)]
+struct WalletMembership {
+    wallet_id: String,
+    tribe: String,
+    is_admin: bool,
+}
+
+fn policy_applies(policy: &ReverificationPolicy, memberships: &[(&str, bool)]) -> bool {
+    if policy.tribe.is_none() && !policy.admin_only {
+        return true;
+    }
+
+    memberships.iter().any(|(tribe, is_admin)| {
+        policy.tribe.as_deref().is_none_or(|t| t == *tribe) && (!policy.admin_only || *is_admin)
+    })
+}
+
+/// Earliest re-verification deadline for a wallet, given the (tribe, is_admin) memberships it
+/// backs. Returns None when no policy applies to the wallet.
+pub fn reverify_deadline(
+    verified_at: DateTime<Utc>,
+    memberships: &[(&str, bool)],
+    policies: &[ReverificationPolicy],
+) -> Option<DateTime<Utc>> {
+    policies
+        .iter()
+        .filter(|p| policy_applies(p, memberships))
+        .map(|p| verified_at + Duration::days(p.interval_days))
+        .min()
+}
+
+async fn fetch_policies(db: &DbPool) -> Result<Vec<ReverificationPolicy>, sqlx::Error> {
+    sqlx::query_as::<_, ReverificationPolicy>(
+        "SELECT * FROM wallet_reverification_policies ORDER BY created_at ASC",
+    )
+    .fetch_all(db)
+    .await
+}
+
+fn group_memberships(memberships: &[WalletMembership]) -> HashMap<&str, Vec<(&str, bool)>> {
+    let mut by_wallet: HashMap<&str, Vec<(&str, bool)>> = HashMap::new();
+    for m in memberships {
+        by_wallet
+            .entry(m.wallet_id.as_str())
+            .or_default()
+            .push((m.tribe.as_str(), m.is_admin));
+    }
+    by_wallet
+}
+
+/// Mark every active wallet whose re-verification deadline has passed as stale.
+/// Returns the number of wallets newly marked.
+pub async fn mark_stale_wallets(db: &DbPool) -> Result<u64, sqlx::Error> {
+    let policies = fetch_policies(db).await?;
+    if policies.is_empty() {
+        return Ok(0);
+    }
+
+    let wallets = sqlx::query_as::<_, FlatLinkedWallet>(
+        "SELECT *, NULL as tribe FROM wallets WHERE deleted_at IS NULL AND stale_at IS NULL",
+    )
+    .fetch_all(db)
+    .await?;
+
+    let memberships = sqlx::query_as::<_, WalletMembership>(
+        "SELECT wallet_id, tribe, is_admin FROM user_tribes WHERE wallet_id IS NOT NULL",
+    )
+    .fetch_all(db)
+    .await?;
+    let by_wallet = group_memberships(&memberships);
+
+    let now = Utc::now();
+    let mut marked = 0;
+
+    for wallet in wallets {
+        let wallet_memberships = by_wallet
+            .get(wallet.id.as_str())
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        let deadline = match reverify_deadline(wallet.verified_at, wallet_memberships, &policies) {
+            Some(d) if d <= now => d,
+            _ => continue,
+        };
+
+        let result =
+            sqlx::query("UPDATE wallets SET stale_at = ? WHERE id = ? AND stale_at IS NULL")
+                .bind(now)
+                .bind(&wallet.id)
+                .execute(db)
+                .await?;
+
+        if result.rows_affected() == 0 {
+            continue;
+        }
+        marked += 1;
+
+        // No human actor: the owner is recorded as both actor and target
+        let _ = log_audit(
+            db,
+            AuditAction::WalletStale,
+            wallet.user_id,
+            Some(wallet.user_id),
+            &format!(
+                "Wallet {} marked stale: re-verification was due {}",
+                wallet.address,
+                deadline.to_rfc3339()
+            ),
+        )
+        .await;
+    }
+
+    Ok(marked)
+}
+
+/// Run `mark_stale_wallets` on startup and then hourly.
+pub fn spawn_stale_wallet_sweeper(db: DbPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(SWEEP_INTERVAL);
+        loop {
+            interval.tick().await;
+            match mark_stale_wallets(&db).await {
+                Ok(0) => {}
+                Ok(n) => println!("Marked {} wallet(s) stale pending re-verification", n),
+                Err(e) => eprintln!("Stale wallet sweep failed: {}", e),
+            }
+        }
+    });
+}
+
+/// Tribes in which the user's membership is backed by a stale wallet whose deadline under
+/// an enforcing policy for that membership has passed. `stale_at` may come from a stricter
+/// notify-only policy, so the enforcing policy's own interval is checked here. These
+/// memberships are treated as invalid until the wallet is re-signed.
+pub async fn stale_enforced_tribes(db: &DbPool, user_id: i64) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT DISTINCT ut.tribe
+        FROM user_tribes ut
+        JOIN wallets w ON w.id = ut.wallet_id
+        WHERE ut.user_id = ? AND w.stale_at IS NOT NULL AND w.deleted_at IS NULL
+          AND EXISTS (
+            SELECT 1 FROM wallet_reverification_policies p
+            WHERE p.enforce = TRUE
+              AND (p.tribe IS NULL OR p.tribe = ut.tribe)
+              AND (p.admin_only = FALSE OR ut.is_admin = TRUE)
+              AND julianday(w.verified_at) + p.interval_days <= julianday('now')
+          )
+        "#,
+    )
+    .bind(user_id)
+    .fetch_all(db)
+    .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/wallets/reverification",
+    responses(
+        (status = 200, description = "Re-verification deadlines for the current user's wallets", body = Vec<WalletReverificationStatus>)
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn get_my_reverification_status(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let policies = match fetch_policies(&state.db).await {
+        Ok(p) => p,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+
+    let wallets = sqlx::query_as::<_, FlatLinkedWallet>(
+        "SELECT *, NULL as tribe FROM wallets WHERE user_id = ? AND deleted_at IS NULL ORDER BY verified_at ASC",
+    )
+    .bind(auth_user.user_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+
+    let memberships = sqlx::query_as::<_, WalletMembership>(
+        "SELECT wallet_id, tribe, is_admin FROM user_tribes WHERE user_id = ? AND wallet_id IS NOT NULL",
+    )
+    .bind(auth_user.user_id)
+    .fetch_all(&state.db)
+    .await
+    .unwrap_or_default();
+    let by_wallet = group_memberships(&memberships);
+
+    let statuses: Vec<WalletReverificationStatus> = wallets
+        .into_iter()
+        .map(|w| {
+            let wallet_memberships = by_wallet
+                .get(w.id.as_str())
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            WalletReverificationStatus {
+                due_at: reverify_deadline(w.verified_at, wallet_memberships, &policies),
+                wallet_id: w.id,
+                address: w.address,
+                verified_at: w.verified_at,
+                stale_at: w.stale_at,
+            }
+        })
+        .collect();
+
+    Json(statuses).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/reverification-policies",
+    tag = "Admin",
+    responses(
+        (status = 200, description = "List wallet re-verification policies", body = Vec<ReverificationPolicy>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn list_policies(
+    State(state): State<AppState>,
+    _admin: RequireSuperAdmin,
+) -> impl IntoResponse {
+    match fetch_policies(&state.db).await {
+        Ok(policies) => Json(policies).into_response(),
+        Err(e) => {
+            eprintln!("Failed to list reverification policies: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/reverification-policies",
+    tag = "Admin",
+    request_body = CreatePolicyRequest,
+    responses(
+        (status = 201, description = "Policy created", body = ReverificationPolicy),
+        (status = 400, description = "Invalid interval or unknown tribe"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn create_policy(
+    State(state): State<AppState>,
+    admin: RequireSuperAdmin,
+    Json(payload): Json<CreatePolicyRequest>,
+) -> impl IntoResponse {
+    if !(1..=3650).contains(&payload.interval_days) {
+        return (
+            StatusCode::BAD_REQUEST,
+            "interval_days must be between 1 and 3650",
+        )
+            .into_response();
+    }
+
+    if let Some(tribe) = &payload.tribe {
+        let tribe_exists: bool =
+            sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM tribes WHERE name = ?)")
+                .bind(tribe)
+                .fetch_one(&state.db)
+                .await
+                .unwrap_or(false);
+
+        if !tribe_exists {
+            return (StatusCode::BAD_REQUEST, "Tribe does not exist").into_response();
+        }
+    }
+
+    let policy = ReverificationPolicy {
+        id: Uuid::new_v4().to_string(),
+        tribe: payload.tribe,
+        admin_only: payload.admin_only,
+        interval_days: payload.interval_days,
+        enforce: payload.enforce,
+        created_at: Utc::now(),
+    };
+
+    let insert_res = sqlx::query(
+        "INSERT INTO wallet_reverification_policies (id, tribe, admin_only, interval_days, enforce, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&policy.id)
+    .bind(&policy.tribe)
+    .bind(policy.admin_only)
+    .bind(policy.interval_days)
+    .bind(policy.enforce)
+    .bind(policy.created_at)
+    .execute(&state.db)
+    .await;
+
+    if let Err(e) = insert_res {
+        eprintln!("Failed to insert reverification policy: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    let details = format!(
+        "Created re-verification policy {}: every {} days, tribe {}, admin_only {}, enforce {}",
+        policy.id,
+        policy.interval_days,
+        policy.tribe.as_deref().unwrap_or("*"),
+        policy.admin_only,
+        policy.enforce
+    );
+
+    let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+    let _ = log_audit(
+        &state.db,
+        AuditAction::SuperAdminUpdateReverifyPolicy,
+        admin_id,
+        None,
+        &details,
+    )
+    .await;
+
+    alert_admin_action(
+        format!("SuperAdmin ({})", admin.discord_id),
+        AuditAction::SuperAdminUpdateReverifyPolicy,
+        details,
+    );
+
+    (StatusCode::CREATED, Json(policy)).into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/reverification-policies/{id}",
+    tag = "Admin",
+    params(
+        ("id" = String, Path, description = "Policy ID")
+    ),
+    responses(
+        (status = 200, description = "Policy deleted"),
+        (status = 404, description = "Policy not found"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn delete_policy(
+    State(state): State<AppState>,
+    admin: RequireSuperAdmin,
+    Path(policy_id): Path<String>,
+) -> impl IntoResponse {
+    let result = sqlx::query("DELETE FROM wallet_reverification_policies WHERE id = ?")
+        .bind(&policy_id)
+        .execute(&state.db)
+        .await;
+
+    match result {
+        Ok(r) if r.rows_affected() > 0 => {}
+        Ok(_) => return (StatusCode::NOT_FOUND, "Policy not found").into_response(),
+        Err(e) => {
+            eprintln!("Failed to delete reverification policy: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+
+    let details = format!("Deleted re-verification policy {}", policy_id);
+
+    let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+    let _ = log_audit(
+        &state.db,
+        AuditAction::SuperAdminUpdateReverifyPolicy,
+        admin_id,
+        None,
+        &details,
+    )
+    .await;
+
+    alert_admin_action(
+        format!("SuperAdmin ({})", admin.discord_id),
+        AuditAction::SuperAdminUpdateReverifyPolicy,
+        details,
+    );
+
+    StatusCode::OK.into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{insert_users, setup_db};
+
+    fn policy(tribe: Option<&str>, admin_only: bool, interval_days: i64) -> ReverificationPolicy {
+        ReverificationPolicy {
+            id: Uuid::new_v4().to_string(),
+            tribe: tribe.map(str::to_string),
+            admin_only,
+            interval_days,
+            enforce: false,
+            created_at: Utc::now(),
+        }
+    }
+
+    async fn setup() -> DbPool {
+        let pool = setup_db().await;
+        insert_users(&pool, &[(1001, "test-discord-id", "TestUser")]).await;
+        pool
+    }
+
+    async fn insert_policy(
+        db: &DbPool,
+        tribe: Option<&str>,
+        admin_only: bool,
+        interval_days: i64,
+        enforce: bool,
+    ) {
+        sqlx::query(
+            "INSERT INTO wallet_reverification_policies (id, tribe, admin_only, interval_days, enforce) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(tribe)
+        .bind(admin_only)
+        .bind(interval_days)
+        .bind(enforce)
+        .execute(db)
+        .await
+        .unwrap();
+    }
+
+    async fn insert_wallet(db: &DbPool, id: &str, address: &str, verified_days_ago: i64) {
+        sqlx::query("INSERT INTO wallets (id, user_id, address, verified_at) VALUES (?, ?, ?, ?)")
+            .bind(id)
+            .bind(1001_i64)
+            .bind(address)
+            .bind(Utc::now() - Duration::days(verified_days_ago))
+            .execute(db)
+            .await
+            .unwrap();
+    }
+
+    #[test]
+    fn test_deadline_without_policies() {
+        assert!(reverify_deadline(Utc::now(), &[], &[]).is_none());
+    }
+
+    #[test]
+    fn test_global_policy_applies_to_unaffiliated_wallet() {
+        let verified_at = Utc::now();
+        let deadline = reverify_deadline(verified_at, &[], &[policy(None, false, 30)]);
+        assert_eq!(deadline, Some(verified_at + Duration::days(30)));
+    }
+
+    #[test]
+    fn test_tribe_policy_only_applies_to_members() {
+        let policies = [policy(Some("Fire"), false, 30)];
+        let verified_at = Utc::now();
+
+        assert!(reverify_deadline(verified_at, &[("Water", false)], &policies).is_none());
+        assert!(reverify_deadline(verified_at, &[("Fire", false)], &policies).is_some());
+    }
+
+    #[test]
+    fn test_admin_only_policy_requires_admin_membership() {
+        let policies = [policy(None, true, 90)];
+        let verified_at = Utc::now();
+
+        assert!(reverify_deadline(verified_at, &[], &policies).is_none());
+        assert!(reverify_deadline(verified_at, &[("Fire", false)], &policies).is_none());
+        assert!(reverify_deadline(verified_at, &[("Fire", true)], &policies).is_some());
+    }
+
+    #[test]
+    fn test_strictest_policy_wins() {
+        let policies = [policy(None, false, 365), policy(Some("Fire"), true, 90)];
+        let verified_at = Utc::now();
+
+        let deadline = reverify_deadline(verified_at, &[("Fire", true)], &policies);
+        assert_eq!(deadline, Some(verified_at + Duration::days(90)));
+    }
+
+    #[tokio::test]
+    async fn test_mark_stale_wallets() {
+        let db = setup().await;
+        insert_policy(&db, None, false, 90, false).await;
+        insert_wallet(&db, "old-wallet", "0xold", 120).await;
+        insert_wallet(&db, "fresh-wallet", "0xfresh", 10).await;
+
+        let marked = mark_stale_wallets(&db).await.unwrap();
+        assert_eq!(marked, 1);
+
+        let stale: Vec<String> =
+            sqlx::query_scalar("SELECT id FROM wallets WHERE stale_at IS NOT NULL")
+                .fetch_all(&db)
+                .await
+                .unwrap();
+        assert_eq!(stale, vec!["old-wallet"]);
+
+        let audits: (i64,) =
+            sqlx::query_as("SELECT COUNT(*) FROM audit_logs WHERE action = 'WALLET_STALE'")
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(audits.0, 1);
+
+        // A second sweep does not re-mark or re-audit
+        assert_eq!(mark_stale_wallets(&db).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_mark_stale_wallets_without_policies() {
+        let db = setup().await;
+        insert_wallet(&db, "old-wallet", "0xold", 1000).await;
+
+        assert_eq!(mark_stale_wallets(&db).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_stale_enforced_tribes() {
+        let db = setup().await;
+        insert_wallet(&db, "stale-wallet", "0xstale", 120).await;
+        sqlx::query("UPDATE wallets SET stale_at = ? WHERE id = 'stale-wallet'")
+            .bind(Utc::now())
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe, wallet_id, is_admin) VALUES (?, 'Fire', 'stale-wallet', TRUE)")
+            .bind(1001_i64)
+            .execute(&db)
+            .await
+            .unwrap();
+
+        // Non-enforcing policy: membership stays valid
+        insert_policy(&db, Some("Fire"), true, 90, false).await;
+        assert!(stale_enforced_tribes(&db, 1001).await.unwrap().is_empty());
+
+        // Enforcing policy: membership is suspended
+        insert_policy(&db, Some("Fire"), true, 90, true).await;
+        assert_eq!(
+            stale_enforced_tribes(&db, 1001).await.unwrap(),
+            vec!["Fire"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_notify_only_deadline_does_not_enforce() {
+        let db = setup().await;
+        insert_wallet(&db, "wallet", "0xwallet", 60).await;
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe, wallet_id) VALUES (?, 'Fire', 'wallet'), (?, 'Water', 'wallet')")
+            .bind(1001_i64)
+            .bind(1001_i64)
+            .execute(&db)
+            .await
+            .unwrap();
+        insert_policy(&db, Some("Fire"), false, 30, false).await;
+        insert_policy(&db, None, false, 365, true).await;
+
+        // The 30-day reminder marks the wallet stale...
+        assert_eq!(mark_stale_wallets(&db).await.unwrap(), 1);
+        // ...but the enforcing policy allows a year, so no membership is suspended
+        assert!(stale_enforced_tribes(&db, 1001).await.unwrap().is_empty());
+
+        sqlx::query("UPDATE wallets SET verified_at = ? WHERE id = 'wallet'")
+            .bind(Utc::now() - Duration::days(400))
+            .execute(&db)
+            .await
+            .unwrap();
+        assert_eq!(
+            stale_enforced_tribes(&db, 1001).await.unwrap(),
+            vec!["Fire", "Water"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_policies_follow_tribe_rename() {
+        let db = setup().await;
+        sqlx::query("INSERT INTO tribes (name) VALUES ('Fire')")
+            .execute(&db)
+            .await
+            .unwrap();
+        insert_policy(&db, Some("Fire"), false, 30, true).await;
+
+        let res = crate::admin::apply_update_tribe(
+            &AppState::new(db.clone()),
+            &RequireSuperAdmin {
+                discord_id: "test-discord-id".to_string(),
+            },
+            "Fire".to_string(),
+            "Flame".to_string(),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            fetch_policies(&db).await.unwrap()[0].tribe.as_deref(),
+            Some("Flame")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_policy_returns_created_policy() {
+        let db = setup().await;
+        let res = create_policy(
+            State(AppState::new(db.clone())),
+            RequireSuperAdmin {
+                discord_id: "test-discord-id".to_string(),
+            },
+            Json(CreatePolicyRequest {
+                tribe: None,
+                admin_only: true,
+                interval_days: 30,
+                enforce: false,
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::CREATED);
+
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let created: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(created["intervalDays"], 30);
+        assert_eq!(created["adminOnly"], true);
+        assert_eq!(
+            fetch_policies(&db).await.unwrap()[0].id,
+            created["id"].as_str().unwrap()
+        );
+    }
+}
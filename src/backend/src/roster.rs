@@ -1,18 +1,30 @@
 use crate::{
+    api_keys::{roster_read_scope, Caller},
     audit::{log_audit, AuditAction},
     auth::AuthenticatedUser,
-    helpers::{get_user_by_discord_id, require_admin_in_tribe},
-    models::{LinkedWallet, User},
+    db::DbPool,
+    helpers::{get_user_by_discord_id, get_user_by_id, require_permission_in_tribe, ID_BATCH_SIZE},
+    member_fields::{self, FieldValues, FIELD_FILTER_SQL, FIELD_SORT_KEY_SQL},
+    membership::{attribute_join, attribute_leave},
+    models::{sort_wallets, LinkedWallet, User},
+    ranks,
+    rbac::{has_permission_in_tribe, perm, Permission, TribeAccess},
     state::AppState,
+    totp::StepUp,
+    vouches::{self, Vouch},
 };
 use axum::{
+    body::Body,
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sqlx::{query::QueryAs, sqlite::SqliteArguments, Sqlite};
+use tokio_stream::wrappers::ReceiverStream;
 use utoipa::{IntoParams, ToSchema};
 
 #[derive(Serialize, ToSchema, sqlx::FromRow)]
@@ -43,9 +55,20 @@ pub struct PaginatedAudits {
 pub struct RosterMember {
     pub discord_id: String,
     pub username: String,
+    /// Character name of the member's primary wallet, falling back to the username
+    pub display_name: String,
     pub avatar: Option<String>,
     pub last_login_at: Option<DateTime<Utc>>,
     pub wallets: Vec<LinkedWallet>,
+    /// Values of the tribe's custom member fields, by key
+    #[schema(value_type = Object)]
+    pub fields: FieldValues,
+    /// Key of the member's rank in the tribe
+    pub rank: Option<String>,
+    /// Names of the tribe's squads the member is in
+    pub squads: Vec<String>,
+    /// Vouches for the member in the tribe, for holders of `admin.grant`
+    pub vouches: Option<Vec<Vouch>>,
     pub audits: Option<PaginatedAudits>,
 }
 
@@ -59,14 +82,86 @@ pub struct MemberQuery {
 #[derive(Deserialize, IntoParams, Clone)]
 pub struct RosterQuery {
     pub tribe: Option<String>,
-    pub sort: Option<String>,  // "username", "wallet_count", "last_login"
+    pub sort: Option<String>, // "username", "wallet_count", "last_login", "rank", "field:<key>"
     pub order: Option<String>, // "asc", "desc"
     pub search: Option<String>,
+    /// Comma-separated `key:value` custom field filters; all must match
+    pub fields: Option<String>,
+    /// Return the membership as it stood at this moment; wallets are always current
+    pub as_of: Option<DateTime<Utc>>,
+    /// Page size (default 100, at most 500); returns a `RosterPage`
+    pub limit: Option<i64>,
+    /// `nextCursor` from the previous page; returns a `RosterPage`
+    pub cursor: Option<String>,
+    /// `json` (default) or `ndjson` to stream one member per line
+    pub format: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RosterPage {
+    pub items: Vec<RosterMember>,
+    /// Members matching the filters across every page
+    pub total: i64,
+    /// Pass as `cursor` to fetch the next page; absent on the last page
+    pub next_cursor: Option<String>,
 }
 
 #[derive(Deserialize, ToSchema)]
 pub struct GrantAdminRequest {
-    pub wallet_id: String,
+    /// Defaults to the member's primary wallet
+    #[serde(default)]
+    pub wallet_id: Option<String>,
+    /// When the admin grant lapses; permanent when absent
+    #[serde(default)]
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct OverviewQuery {
+    /// Comma-separated tribe names; every tribe when absent
+    pub tribes: Option<String>,
+}
+
+#[derive(Serialize, ToSchema, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TribeSummary {
+    pub tribe: String,
+    pub member_count: i64,
+}
+
+#[derive(Serialize, ToSchema, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OverviewMember {
+    pub discord_id: String,
+    pub username: String,
+    pub avatar: Option<String>,
+    pub last_login_at: Option<DateTime<Utc>>,
+    pub wallet_count: i64,
+    /// Every tribe the user belongs to, not only the selected ones
+    pub tribes: Vec<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RosterOverview {
+    pub tribes: Vec<TribeSummary>,
+    /// Members of at least one selected tribe
+    pub members: Vec<OverviewMember>,
+    /// Discord IDs of members who belong to more than one tribe
+    pub multi_tribe_members: Vec<String>,
+    /// Users who belong to no tribe at all
+    pub unaffiliated: Vec<OverviewMember>,
+}
+
+#[derive(sqlx::FromRow)]
+struct OverviewUserRow {
+    id: i64,
+    discord_id: String,
+    username: String,
+    avatar: Option<String>,
+    last_login_at: Option<DateTime<Utc>>,
+    wallet_count: i64,
 }
 
 #[utoipa::path(
@@ -87,17 +182,17 @@ pub struct GrantAdminRequest {
     )
 )]
 pub async fn get_roster_member(
-    auth_user: AuthenticatedUser,
+    access: TribeAccess<perm::RosterRead>,
     Path(discord_id): Path<String>,
     Query(query): Query<MemberQuery>,
     State(state): State<AppState>,
 ) -> impl IntoResponse {
-    // 1. Verify admin in tribe
-    let (current_user, tribe, _all_tribes) =
-        match require_admin_in_tribe(&state.db, auth_user.user_id, query.tribe.as_deref()).await {
-            Ok(result) => result,
-            Err((status, msg)) => return (status, msg).into_response(),
-        };
+    // 1. Roster access in the tribe is checked by the extractor
+    let TribeAccess {
+        user: current_user,
+        tribe,
+        ..
+    } = access;
 
     // 2. Fetch Target Member
     let target_member = match get_user_by_discord_id(&state.db, &discord_id).await {
@@ -142,6 +237,11 @@ pub async fn get_roster_member(
                 verified_at: flat.verified_at,
                 deleted_at: flat.deleted_at,
                 network: flat.network.clone(),
+                stale_at: flat.stale_at,
+                character_name: flat.character_name.clone(),
+                is_primary: flat.is_primary,
+                label: flat.label.clone(),
+                sort_order: flat.sort_order,
                 tribes: Vec::new(),
             });
         if let Some(t) = flat.tribe {
@@ -150,20 +250,108 @@ pub async fn get_roster_member(
             }
         }
     }
-    let wallets: Vec<LinkedWallet> = wallet_map.into_values().collect();
+    let mut wallets: Vec<LinkedWallet> = wallet_map.into_values().collect();
+    sort_wallets(&mut wallets);
 
-    // 5. Fetch Audit Logs with Actor Info (paginated)
+    // 5. Fetch Audit Logs with Actor Info (paginated), for roles allowed to read them
     // Include both: actions targeting this user AND self-actions (login, wallet link/unlink)
-    let page = query.audit_page.unwrap_or(1).max(1);
-    let per_page = query.audit_per_page.unwrap_or(10).clamp(1, 100);
+    let can_read_audits =
+        match has_permission_in_tribe(&state.db, current_user.id, &tribe, Permission::AuditRead)
+            .await
+        {
+            Ok(allowed) => allowed,
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+        };
+
+    let audits = if can_read_audits {
+        Some(
+            member_audits(
+                &state,
+                target_member.id,
+                query.audit_page,
+                query.audit_per_page,
+            )
+            .await,
+        )
+    } else {
+        None
+    };
+
+    // 6. Audit Log (Write) - Only log if viewing someone else (not self)
+    if current_user.id != target_member.id {
+        let _ = log_audit(
+            &state.db,
+            AuditAction::ViewMember,
+            current_user.id,
+            Some(target_member.id),
+            &format!(
+                "Viewed member {} ({})",
+                target_member.username, target_member.discord_id
+            ),
+        )
+        .await;
+    }
+
+    let fields = match member_fields::values_for(&state.db, &tribe, &[target_member.id]).await {
+        Ok(mut values) => values.remove(&target_member.id).unwrap_or_default(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+    let (rank, squads) =
+        match ranks::ranks_and_squads_for(&state.db, &tribe, &[target_member.id]).await {
+            Ok(mut ranks) => ranks.remove(&target_member.id).unwrap_or_default(),
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+        };
+
+    let can_see_vouches =
+        match has_permission_in_tribe(&state.db, current_user.id, &tribe, Permission::AdminGrant)
+            .await
+        {
+            Ok(allowed) => allowed,
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+        };
+    let vouches = if can_see_vouches {
+        match vouches::tribe_vouches(&state.db, &tribe, Some(target_member.id)).await {
+            Ok(v) => Some(v),
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+        }
+    } else {
+        None
+    };
+
+    // 7. Return RosterMember
+    Json(RosterMember {
+        discord_id: target_member.discord_id,
+        display_name: display_name(&target_member.username, &wallets),
+        username: target_member.username,
+        avatar: target_member.avatar,
+        last_login_at: target_member.last_login_at,
+        wallets,
+        fields,
+        rank,
+        squads,
+        vouches,
+        audits,
+    })
+    .into_response()
+}
+
+/// One page of a member's audit history: actions targeting them and their own self-actions.
+async fn member_audits(
+    state: &AppState,
+    member_id: i64,
+    page: Option<i64>,
+    per_page: Option<i64>,
+) -> PaginatedAudits {
+    let page = page.unwrap_or(1).max(1);
+    let per_page = per_page.unwrap_or(10).clamp(1, 100);
     let offset = (page - 1) * per_page;
 
     // Get total count
     let total: (i64,) = sqlx::query_as(
         "SELECT COUNT(*) FROM audit_logs WHERE target_id = ? OR (actor_id = ? AND target_id IS NULL)"
     )
-    .bind(target_member.id)
-    .bind(target_member.id)
+    .bind(member_id)
+    .bind(member_id)
     .fetch_one(&state.db)
     .await
     .unwrap_or((0,));
@@ -178,8 +366,8 @@ pub async fn get_roster_member(
         LIMIT ? OFFSET ?
         "#
     )
-    .bind(target_member.id)
-    .bind(target_member.id)
+    .bind(member_id)
+    .bind(member_id)
     .bind(per_page)
     .bind(offset)
     .fetch_all(&state.db)
@@ -188,148 +376,284 @@ pub async fn get_roster_member(
 
     let total_pages = (total.0 as f64 / per_page as f64).ceil() as i64;
 
-    // 6. Audit Log (Write) - Only log if viewing someone else (not self)
-    if current_user.id != target_member.id {
-        let _ = log_audit(
-            &state.db,
-            AuditAction::ViewMember,
-            current_user.id,
-            Some(target_member.id),
-            &format!(
-                "Viewed member {} ({})",
-                target_member.username, target_member.discord_id
-            ),
-        )
-        .await;
+    PaginatedAudits {
+        items: audits,
+        total: total.0,
+        page,
+        per_page,
+        total_pages,
     }
+}
 
-    // 7. Return RosterMember
-    Json(RosterMember {
-        discord_id: target_member.discord_id,
-        username: target_member.username,
-        avatar: target_member.avatar,
-        last_login_at: target_member.last_login_at,
-        wallets,
-        audits: Some(PaginatedAudits {
-            items: audits,
-            total: total.0,
-            page,
-            per_page,
-            total_pages,
-        }),
-    })
-    .into_response()
+/// Page size for paged roster requests that give no `limit`
+const DEFAULT_PAGE_SIZE: i64 = 100;
+/// Largest page a client may request; also the batch size for NDJSON streaming
+const MAX_PAGE_SIZE: i64 = 500;
+
+const WALLET_COUNT_SQL: &str =
+    "(SELECT COUNT(*) FROM wallets w WHERE w.user_id = u.id AND w.deleted_at IS NULL)";
+
+/// Current rank position in the tribe (bound first), zero-padded; unranked sorts lowest
+const RANK_SORT_KEY_SQL: &str = "COALESCE((SELECT printf('%05d', r.position) FROM user_tribes rut \
+     JOIN tribe_ranks r ON r.id = rut.rank_id WHERE rut.user_id = u.id AND rut.tribe = ?), '')";
+
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RosterSort {
+    Username,
+    LastLogin,
+    WalletCount,
+    Rank,
+    /// A custom member field, by key
+    Field(String),
 }
 
-#[utoipa::path(
-    get,
-    path = "/api/roster",
-    params(RosterQuery),
-    responses(
-        (status = 200, description = "Get tribe roster", body = Vec<RosterMember>),
-        (status = 403, description = "Forbidden: Not an admin"),
-        (status = 404, description = "User not found")
-    ),
-    security(
-        ("jwt" = [])
-    )
-)]
-pub async fn get_roster(
-    auth_user: AuthenticatedUser,
-    Query(query): Query<RosterQuery>,
-    State(state): State<AppState>,
-) -> impl IntoResponse {
-    // 1. Verify admin in tribe
-    let (current_user, tribe, _all_tribes) =
-        match require_admin_in_tribe(&state.db, auth_user.user_id, query.tribe.as_deref()).await {
-            Ok(result) => result,
-            Err((status, msg)) => {
-                // Special case: if no tribe, return empty roster instead of error
-                if msg == "Access denied: You are not in any tribe" {
-                    return Json(Vec::<RosterMember>::new()).into_response();
-                }
-                return (status, msg).into_response();
+impl RosterSort {
+    fn parse(sort: Option<&str>) -> Self {
+        match sort {
+            Some("last_login") => RosterSort::LastLogin,
+            Some("wallet_count") => RosterSort::WalletCount,
+            Some("rank") => RosterSort::Rank,
+            Some(s) if s.starts_with("field:") => {
+                RosterSort::Field(s["field:".len()..].to_string())
             }
-        };
+            _ => RosterSort::Username,
+        }
+    }
 
-    // 2. Build Query - get all users in the specified tribe from user_tribes table
-    let mut sql =
-        "SELECT u.* FROM users u INNER JOIN user_tribes ut ON u.id = ut.user_id WHERE ut.tribe = ?"
-            .to_string();
+    /// Text expression members are ordered by. Wallet counts are zero-padded so
+    /// that every key compares as text, which keeps the keyset cursor uniform.
+    fn key_sql(&self) -> String {
+        match self {
+            RosterSort::Username => "u.username".to_string(),
+            RosterSort::LastLogin => "COALESCE(u.last_login_at, '')".to_string(),
+            RosterSort::WalletCount => format!("printf('%010d', {})", WALLET_COUNT_SQL),
+            RosterSort::Rank => RANK_SORT_KEY_SQL.to_string(),
+            RosterSort::Field(_) => FIELD_SORT_KEY_SQL.to_string(),
+        }
+    }
+}
+
+/// Position after the last member of a page, handed to clients as an opaque string
+#[derive(Serialize, Deserialize)]
+pub(crate) struct RosterCursor {
+    sort: RosterSort,
+    desc: bool,
+    key: String,
+    id: i64,
+}
 
-    if let Some(search) = &query.search {
-        if !search.is_empty() {
-            sql.push_str(" AND (username LIKE ? OR discord_id LIKE ?)");
+impl RosterCursor {
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    fn decode(cursor: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+}
+
+#[derive(sqlx::FromRow)]
+pub(crate) struct RosterRow {
+    #[sqlx(flatten)]
+    pub(crate) user: User,
+    sort_key: String,
+}
+
+#[derive(Clone)]
+pub(crate) struct RosterFilter {
+    tribe: String,
+    /// LIKE pattern for username or Discord ID
+    search: Option<String>,
+    /// Custom field (key, value) pairs members must match
+    fields: Vec<(String, String)>,
+    as_of: Option<DateTime<Utc>>,
+    sort: RosterSort,
+    desc: bool,
+}
+
+impl RosterFilter {
+    pub(crate) fn new(query: &RosterQuery, tribe: String) -> Self {
+        RosterFilter {
+            tribe,
+            search: query
+                .search
+                .as_deref()
+                .filter(|s| !s.is_empty())
+                .map(|s| format!("%{}%", s)),
+            fields: query
+                .fields
+                .as_deref()
+                .unwrap_or_default()
+                .split(',')
+                .filter(|f| !f.trim().is_empty())
+                .map(|f| match f.split_once(':') {
+                    Some((key, value)) => (key.trim().to_string(), value.trim().to_string()),
+                    None => (f.trim().to_string(), String::new()),
+                })
+                .collect(),
+            as_of: query.as_of,
+            sort: RosterSort::parse(query.sort.as_deref()),
+            desc: query.order.as_deref() == Some("desc"),
         }
     }
 
-    // Sorting (basic implementation)
-    // Note: complex sorting often safer to do in code if not strictly pagination,
-    // but SQL order by is fine. We need to be careful with SQL injection on column names though.
-    // Bind parameters cannot be used for column names or ASC/DESC.
-    match query.sort.as_deref() {
-        Some("username") => {
-            sql.push_str(" ORDER BY username");
+    /// Reject sorts and filters naming fields the tribe doesn't have
+    pub(crate) async fn check_fields(&self, db: &DbPool) -> Result<(), (StatusCode, &'static str)> {
+        if self.fields.is_empty() && !matches!(self.sort, RosterSort::Field(_)) {
+            return Ok(());
         }
-        Some("last_login") => {
-            sql.push_str(" ORDER BY last_login_at");
+        if self.fields.iter().any(|(_, value)| value.is_empty()) {
+            return Err((StatusCode::BAD_REQUEST, "Field filters are key:value"));
         }
-        _ => {
-            // default sort
-            sql.push_str(" ORDER BY username");
+        let keys: Vec<String> = member_fields::tribe_fields(db, &self.tribe)
+            .await
+            .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "Database error"))?
+            .into_iter()
+            .map(|f| f.key)
+            .collect();
+        let sort_key = match &self.sort {
+            RosterSort::Field(key) => Some(key),
+            _ => None,
+        };
+        if sort_key
+            .into_iter()
+            .chain(self.fields.iter().map(|(key, _)| key))
+            .any(|key| !keys.contains(key))
+        {
+            return Err((StatusCode::BAD_REQUEST, "Unknown field"));
         }
+        Ok(())
     }
 
-    if let Some("desc") = query.order.as_deref() {
-        sql.push_str(" DESC");
-    } else {
-        sql.push_str(" ASC");
+    /// Members of the tribe, from current memberships or the history for
+    /// point-in-time queries, with their sort key
+    fn members_sql(&self) -> String {
+        let mut sql = if self.as_of.is_some() {
+            format!(
+                "SELECT DISTINCT u.*, {} AS sort_key FROM users u \
+                 INNER JOIN tribe_membership_history h ON u.id = h.user_id \
+                 WHERE h.tribe = ? AND h.joined_at <= strftime('%Y-%m-%dT%H:%M:%fZ', ?) \
+                 AND (h.left_at IS NULL OR h.left_at > strftime('%Y-%m-%dT%H:%M:%fZ', ?))",
+                self.sort.key_sql()
+            )
+        } else {
+            format!(
+                "SELECT u.*, {} AS sort_key FROM users u \
+                 INNER JOIN user_tribes ut ON u.id = ut.user_id WHERE ut.tribe = ?",
+                self.sort.key_sql()
+            )
+        };
+        if self.search.is_some() {
+            sql.push_str(" AND (u.username LIKE ? OR u.discord_id LIKE ?)");
+        }
+        for _ in &self.fields {
+            sql.push_str(" AND ");
+            sql.push_str(FIELD_FILTER_SQL);
+        }
+        sql
     }
 
-    // 5. Execute Query
-    let mut q = sqlx::query_as::<_, User>(&sql).bind(&tribe);
-
-    if let Some(search) = &query.search {
-        if !search.is_empty() {
-            let pattern = format!("%{}%", search);
-            q = q.bind(pattern.clone()).bind(pattern);
+    fn bind<'q, O>(
+        &'q self,
+        mut q: QueryAs<'q, Sqlite, O, SqliteArguments<'q>>,
+    ) -> QueryAs<'q, Sqlite, O, SqliteArguments<'q>> {
+        // The sort key comes first in the statement
+        match &self.sort {
+            RosterSort::Rank => q = q.bind(&self.tribe),
+            RosterSort::Field(key) => q = q.bind(&self.tribe).bind(key),
+            _ => {}
+        }
+        q = q.bind(&self.tribe);
+        if let Some(as_of) = self.as_of {
+            q = q.bind(as_of).bind(as_of);
+        }
+        if let Some(pattern) = &self.search {
+            q = q.bind(pattern).bind(pattern);
         }
+        for (key, value) in &self.fields {
+            q = q
+                .bind(&self.tribe)
+                .bind(key)
+                .bind(value)
+                .bind(value)
+                .bind(value);
+        }
+        q
     }
 
-    let members = match q.fetch_all(&state.db).await {
-        Ok(m) => m,
-        Err(e) => {
-            eprintln!("Database error fetching roster members: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Internal server error".to_string(),
-            )
-                .into_response();
+    fn cursor_after(&self, row: &RosterRow) -> RosterCursor {
+        RosterCursor {
+            sort: self.sort.clone(),
+            desc: self.desc,
+            key: row.sort_key.clone(),
+            id: row.user.id,
         }
+    }
+}
+
+async fn count_members(db: &DbPool, filter: &RosterFilter) -> Result<i64, sqlx::Error> {
+    let sql = format!("SELECT COUNT(*) FROM ({})", filter.members_sql());
+    let (total,): (i64,) = filter.bind(sqlx::query_as(&sql)).fetch_one(db).await?;
+    Ok(total)
+}
+
+/// Fetch members in sort order, optionally starting after a cursor. Ties on the
+/// sort key are broken by user ID so that pages never overlap.
+pub(crate) async fn fetch_members(
+    db: &DbPool,
+    filter: &RosterFilter,
+    after: Option<&RosterCursor>,
+    limit: Option<i64>,
+) -> Result<Vec<RosterRow>, sqlx::Error> {
+    let (cmp, dir) = if filter.desc {
+        ("<", "DESC")
+    } else {
+        (">", "ASC")
     };
+    let mut sql = format!("SELECT * FROM ({}) r", filter.members_sql());
+    if after.is_some() {
+        sql.push_str(&format!(
+            " WHERE (r.sort_key {cmp} ? OR (r.sort_key = ? AND r.id {cmp} ?))"
+        ));
+    }
+    sql.push_str(&format!(" ORDER BY r.sort_key {dir}, r.id {dir}"));
+    if limit.is_some() {
+        sql.push_str(" LIMIT ?");
+    }
 
-    // 4. Batch fetch all wallets for these members (fixes N+1 query)
-    let member_ids: Vec<i64> = members.iter().map(|m| m.id).collect();
+    let mut q = filter.bind(sqlx::query_as::<_, RosterRow>(&sql));
+    if let Some(cursor) = after {
+        q = q.bind(&cursor.key).bind(&cursor.key).bind(cursor.id);
+    }
+    if let Some(limit) = limit {
+        q = q.bind(limit);
+    }
+    q.fetch_all(db).await
+}
 
-    let all_wallets: Vec<crate::models::FlatLinkedWallet> = if !member_ids.is_empty() {
-        // Build parameterized IN clause
-        let placeholders: String = member_ids
-            .iter()
-            .map(|_| "?")
-            .collect::<Vec<_>>()
-            .join(", ");
+/// Attach wallets and the tribe's custom field values to members, keeping their order
+pub(crate) async fn build_members(
+    db: &DbPool,
+    tribe: &str,
+    members: Vec<User>,
+) -> Vec<RosterMember> {
+    // Batch fetch wallets in chunks to stay under SQLite's parameter limit
+    let member_ids: Vec<i64> = members.iter().map(|m| m.id).collect();
+    let mut all_wallets: Vec<crate::models::FlatLinkedWallet> = Vec::new();
+    for chunk in member_ids.chunks(ID_BATCH_SIZE) {
+        let placeholders: String = chunk.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
         let sql = format!("SELECT w.*, ut.tribe FROM wallets w LEFT JOIN user_tribes ut ON w.id = ut.wallet_id WHERE w.user_id IN ({})", placeholders);
 
         let mut query_builder = sqlx::query_as::<_, crate::models::FlatLinkedWallet>(&sql);
-        for id in &member_ids {
+        for id in chunk {
             query_builder = query_builder.bind(*id);
         }
-        query_builder.fetch_all(&state.db).await.unwrap_or_default()
-    } else {
-        Vec::new()
-    };
+        all_wallets.extend(query_builder.fetch_all(db).await.unwrap_or_default());
+    }
 
-    // 5. Group wallets by user_id, and internally group tribes by wallet ID
+    // Group wallets by user_id, and internally group tribes by wallet ID
     use std::collections::HashMap;
     let mut wallets_by_user: HashMap<i64, Vec<LinkedWallet>> = HashMap::new();
 
@@ -345,6 +669,11 @@ pub async fn get_roster(
                 verified_at: flat.verified_at,
                 deleted_at: flat.deleted_at,
                 network: flat.network.clone(),
+                stale_at: flat.stale_at,
+                character_name: flat.character_name.clone(),
+                is_primary: flat.is_primary,
+                label: flat.label.clone(),
+                sort_order: flat.sort_order,
                 tribes: Vec::new(),
             });
         if let Some(t) = flat.tribe {
@@ -354,6 +683,13 @@ pub async fn get_roster(
         }
     }
 
+    let mut fields = member_fields::values_for(db, tribe, &member_ids)
+        .await
+        .unwrap_or_default();
+    let mut ranks = ranks::ranks_and_squads_for(db, tribe, &member_ids)
+        .await
+        .unwrap_or_default();
+
     // Now group the unique wallets by user_id
     for (_, wallet) in wallet_map {
         wallets_by_user
@@ -362,43 +698,368 @@ pub async fn get_roster(
             .push(wallet);
     }
 
-    // 6. Final Roster Response
-    let mut roster: Vec<RosterMember> = members
+    members
         .into_iter()
         .map(|m| {
-            let user_wallets = wallets_by_user.get(&m.id).cloned().unwrap_or_default();
+            let mut user_wallets = wallets_by_user.get(&m.id).cloned().unwrap_or_default();
+            sort_wallets(&mut user_wallets);
+            let (rank, squads) = ranks.remove(&m.id).unwrap_or_default();
             RosterMember {
                 discord_id: m.discord_id,
+                display_name: display_name(&m.username, &user_wallets),
                 username: m.username,
                 avatar: m.avatar,
                 last_login_at: m.last_login_at,
                 wallets: user_wallets,
+                fields: fields.remove(&m.id).unwrap_or_default(),
+                rank,
+                squads,
+                vouches: None,
                 audits: None,
             }
         })
-        .collect();
+        .collect()
+}
 
-    // 7. Post-processing sort if by wallet count
-    if let Some("wallet_count") = query.sort.as_deref() {
-        roster.sort_by(|a, b| a.wallets.len().cmp(&b.wallets.len()));
-        if let Some("desc") = query.order.as_deref() {
-            roster.reverse();
+/// Stream every matching member as one JSON object per line, a page at a time
+fn stream_roster(db: DbPool, filter: RosterFilter) -> Response {
+    let (tx, rx) = tokio::sync::mpsc::channel::<Result<String, std::io::Error>>(16);
+    tokio::spawn(async move {
+        let mut after: Option<RosterCursor> = None;
+        loop {
+            let rows = match fetch_members(&db, &filter, after.as_ref(), Some(MAX_PAGE_SIZE)).await
+            {
+                Ok(rows) => rows,
+                Err(e) => {
+                    eprintln!("Database error streaming roster: {}", e);
+                    // Abort the body so the client sees a truncated response
+                    let _ = tx.send(Err(std::io::Error::other(e))).await;
+                    return;
+                }
+            };
+            let last_page = (rows.len() as i64) < MAX_PAGE_SIZE;
+            after = rows.last().map(|r| filter.cursor_after(r));
+
+            let users = rows.into_iter().map(|r| r.user).collect();
+            for member in build_members(&db, &filter.tribe, users).await {
+                let mut line = serde_json::to_string(&member).unwrap_or_default();
+                line.push('\n');
+                if tx.send(Ok(line)).await.is_err() {
+                    // Client went away
+                    return;
+                }
+            }
+            if last_page {
+                return;
+            }
         }
+    });
+
+    (
+        [(header::CONTENT_TYPE, "application/x-ndjson")],
+        Body::from_stream(ReceiverStream::new(rx)),
+    )
+        .into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/roster",
+    params(RosterQuery),
+    responses(
+        (status = 200, description = "Get tribe roster. Returns a `RosterPage` when `limit` or `cursor` is given, and NDJSON when `format=ndjson`", body = Vec<RosterMember>),
+        (status = 400, description = "Invalid format or cursor"),
+        (status = 403, description = "Forbidden: Not an admin"),
+        (status = 404, description = "User not found")
+    ),
+    security(
+        ("jwt" = []),
+        ("api_key" = [])
+    )
+)]
+pub async fn get_roster(
+    caller: Caller,
+    Query(query): Query<RosterQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let stream = match query.format.as_deref() {
+        None | Some("json") => false,
+        Some("ndjson") => true,
+        Some(_) => return (StatusCode::BAD_REQUEST, "Invalid format").into_response(),
+    };
+    let paged = query.limit.is_some() || query.cursor.is_some();
+    if stream && paged {
+        return (
+            StatusCode::BAD_REQUEST,
+            "Streaming returns every member; omit limit and cursor",
+        )
+            .into_response();
     }
 
-    // 8. Audit Log (Write) - Only log if viewing someone else (not self)
-    if should_log_view(&state, current_user.id, &tribe).await {
-        let _ = log_audit(
-            &state.db,
-            AuditAction::ViewRoster,
-            current_user.id,
-            None,
-            &format!("Viewed roster for tribe {}", tribe),
+    // 1. Verify roster access in tribe, or an API key scoped to the tribe.
+    // Key reads are audited against the user who issued the key.
+    let (actor_id, tribe, via_key) = match caller {
+        Caller::User(auth_user) => {
+            match require_permission_in_tribe(
+                &state.db,
+                auth_user.user_id,
+                query.tribe.as_deref(),
+                Permission::RosterRead,
+            )
+            .await
+            {
+                Ok((current_user, tribe, _all_tribes)) => (current_user.id, tribe, None),
+                Err((status, msg)) => {
+                    // Special case: if no tribe, return empty roster instead of error
+                    if msg == "Access denied: You are not in any tribe" {
+                        if paged {
+                            return Json(RosterPage {
+                                items: Vec::new(),
+                                total: 0,
+                                next_cursor: None,
+                            })
+                            .into_response();
+                        }
+                        return Json(Vec::<RosterMember>::new()).into_response();
+                    }
+                    return (status, msg).into_response();
+                }
+            }
+        }
+        Caller::ApiKey(key) => {
+            let Some(tribe) = query.tribe.clone() else {
+                return (StatusCode::BAD_REQUEST, "Please specify a tribe").into_response();
+            };
+            if let Err(e) = key.require_scope(&roster_read_scope(&tribe)) {
+                return e.into_response();
+            }
+            (key.created_by, tribe, Some(key.name))
+        }
+    };
+
+    // 2. Build the filter; sorting and paging happen in SQL
+    let filter = RosterFilter::new(&query, tribe.clone());
+    if let Err(e) = filter.check_fields(&state.db).await {
+        return e.into_response();
+    }
+
+    let after = match query.cursor.as_deref() {
+        None => None,
+        Some(raw) => match RosterCursor::decode(raw) {
+            // A cursor only makes sense for the ordering it was issued for
+            Some(c) if c.sort == filter.sort && c.desc == filter.desc => Some(c),
+            _ => return (StatusCode::BAD_REQUEST, "Invalid cursor").into_response(),
+        },
+    };
+
+    // 3. Audit Log (Write) - Only log if viewing someone else (not self)
+    if should_log_view(&state, actor_id, &tribe).await {
+        let mut details = match &via_key {
+            Some(name) => format!("Viewed roster for tribe {} via API key '{}'", tribe, name),
+            None => format!("Viewed roster for tribe {}", tribe),
+        };
+        if let Some(as_of) = query.as_of {
+            details.push_str(&format!(" as of {}", as_of.to_rfc3339()));
+        }
+        if stream {
+            details.push_str(" (streamed)");
+        }
+        let _ = log_audit(&state.db, AuditAction::ViewRoster, actor_id, None, &details).await;
+    }
+
+    if stream {
+        return stream_roster(state.db.clone(), filter);
+    }
+
+    // 4. Execute Query
+    let db_error = |e: sqlx::Error| {
+        eprintln!("Database error fetching roster members: {}", e);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Internal server error".to_string(),
         )
-        .await;
+            .into_response()
+    };
+
+    if !paged {
+        let rows = match fetch_members(&state.db, &filter, None, None).await {
+            Ok(rows) => rows,
+            Err(e) => return db_error(e),
+        };
+        let users = rows.into_iter().map(|r| r.user).collect();
+        return Json(build_members(&state.db, &tribe, users).await).into_response();
     }
 
-    Json(roster).into_response()
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let total = match count_members(&state.db, &filter).await {
+        Ok(total) => total,
+        Err(e) => return db_error(e),
+    };
+    // One extra row tells us whether another page follows
+    let mut rows = match fetch_members(&state.db, &filter, after.as_ref(), Some(limit + 1)).await {
+        Ok(rows) => rows,
+        Err(e) => return db_error(e),
+    };
+    let next_cursor = if rows.len() as i64 > limit {
+        rows.truncate(limit as usize);
+        rows.last().map(|r| filter.cursor_after(r).encode())
+    } else {
+        None
+    };
+
+    let users = rows.into_iter().map(|r| r.user).collect();
+    Json(RosterPage {
+        items: build_members(&state.db, &tribe, users).await,
+        total,
+        next_cursor,
+    })
+    .into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/roster/overview",
+    params(OverviewQuery),
+    responses(
+        (status = 200, description = "Aggregate roster across tribes", body = RosterOverview),
+        (status = 403, description = "Forbidden: Not a global admin")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn get_roster_overview(
+    auth_user: AuthenticatedUser,
+    Query(query): Query<OverviewQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let user = match get_user_by_id(&state.db, auth_user.user_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return (StatusCode::UNAUTHORIZED, "User not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+    if !user.is_admin {
+        return (
+            StatusCode::FORBIDDEN,
+            "Access denied: Global admin required",
+        )
+            .into_response();
+    }
+
+    let selected: Option<Vec<String>> = query.tribes.as_deref().map(|t| {
+        t.split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect()
+    });
+    let is_selected = |tribe: &str| {
+        selected
+            .as_ref()
+            .is_none_or(|set| set.iter().any(|t| t == tribe))
+    };
+
+    let users = sqlx::query_as::<_, OverviewUserRow>(
+        r#"
+        SELECT u.id, u.discord_id, u.username, u.avatar, u.last_login_at,
+               (SELECT COUNT(*) FROM wallets w WHERE w.user_id = u.id AND w.deleted_at IS NULL) AS wallet_count
+        FROM users u
+        ORDER BY u.username
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await;
+    let memberships: Result<Vec<(i64, String)>, _> =
+        sqlx::query_as("SELECT user_id, tribe FROM user_tribes ORDER BY tribe")
+            .fetch_all(&state.db)
+            .await;
+    let tribe_names: Result<Vec<String>, _> =
+        sqlx::query_scalar("SELECT name FROM tribes ORDER BY name")
+            .fetch_all(&state.db)
+            .await;
+
+    let (users, memberships, tribe_names) = match (users, memberships, tribe_names) {
+        (Ok(u), Ok(m), Ok(t)) => (u, m, t),
+        (Err(e), _, _) | (_, Err(e), _) | (_, _, Err(e)) => {
+            eprintln!("Database error building roster overview: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response();
+        }
+    };
+
+    use std::collections::{BTreeMap, HashMap};
+    let mut tribes_by_user: HashMap<i64, Vec<String>> = HashMap::new();
+    let mut counts: BTreeMap<String, i64> = tribe_names.into_iter().map(|t| (t, 0)).collect();
+    for (user_id, tribe) in memberships {
+        *counts.entry(tribe.clone()).or_default() += 1;
+        tribes_by_user.entry(user_id).or_default().push(tribe);
+    }
+
+    let mut members = Vec::new();
+    let mut unaffiliated = Vec::new();
+    for row in users {
+        let tribes = tribes_by_user.remove(&row.id).unwrap_or_default();
+        let member = OverviewMember {
+            discord_id: row.discord_id,
+            username: row.username,
+            avatar: row.avatar,
+            last_login_at: row.last_login_at,
+            wallet_count: row.wallet_count,
+            tribes,
+        };
+        if member.tribes.is_empty() {
+            unaffiliated.push(member);
+        } else if member.tribes.iter().any(|t| is_selected(t)) {
+            members.push(member);
+        }
+    }
+
+    let multi_tribe_members = members
+        .iter()
+        .filter(|m| m.tribes.len() > 1)
+        .map(|m| m.discord_id.clone())
+        .collect();
+    let tribes = counts
+        .into_iter()
+        .filter(|(tribe, _)| is_selected(tribe))
+        .map(|(tribe, member_count)| TribeSummary {
+            tribe,
+            member_count,
+        })
+        .collect();
+
+    let scope = match &selected {
+        Some(set) => set.join(", "),
+        None => "all tribes".to_string(),
+    };
+    let _ = log_audit(
+        &state.db,
+        AuditAction::ViewRoster,
+        user.id,
+        None,
+        &format!("Viewed cross-tribe roster overview ({})", scope),
+    )
+    .await;
+
+    Json(RosterOverview {
+        tribes,
+        members,
+        multi_tribe_members,
+        unaffiliated,
+    })
+    .into_response()
+}
+
+/// The name a member is shown under: the character on their primary wallet
+/// (mainnet first), or their username when no primary wallet has a character.
+pub fn display_name(username: &str, wallets: &[LinkedWallet]) -> String {
+    wallets
+        .iter()
+        .filter(|w| w.is_primary && w.deleted_at.is_none())
+        .min_by_key(|w| (w.network != "mainnet", w.sort_order))
+        .and_then(|w| w.character_name.clone())
+        .unwrap_or_else(|| username.to_string())
 }
 
 /// Check if we should log a view roster action (debounce)
@@ -427,6 +1088,82 @@ async fn should_log_view(state: &AppState, user_id: i64, tribe: &str) -> bool {
     true
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/roster/{discord_id}",
+    params(
+        ("discord_id" = String, Path, description = "Discord ID of the member"),
+        ("tribe" = Option<String>, Query, description = "Tribe to remove the member from")
+    ),
+    responses(
+        (status = 200, description = "Member kicked"),
+        (status = 400, description = "Kicking yourself; leave the tribe instead"),
+        (status = 403, description = "Forbidden: Missing admin.grant, step-up required, or the member is a tribe admin"),
+        (status = 404, description = "Member not found, or not in the tribe")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn kick_member(
+    access: TribeAccess<perm::AdminGrant>,
+    _step_up: StepUp,
+    Path(discord_id): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let TribeAccess {
+        user: current_user,
+        tribe,
+        ..
+    } = access;
+
+    let target_user = match get_user_by_discord_id(&state.db, &discord_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return (StatusCode::NOT_FOUND, "Member not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+    if target_user.id == current_user.id {
+        return (
+            StatusCode::BAD_REQUEST,
+            "Leave the tribe instead of kicking yourself",
+        )
+            .into_response();
+    }
+    match has_permission_in_tribe(&state.db, target_user.id, &tribe, Permission::AdminGrant).await {
+        Ok(true) => return (StatusCode::FORBIDDEN, "Tribe admins can't be kicked").into_response(),
+        Ok(false) => {}
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    }
+
+    match sqlx::query("DELETE FROM user_tribes WHERE user_id = ? AND tribe = ?")
+        .bind(target_user.id)
+        .bind(&tribe)
+        .execute(&state.db)
+        .await
+    {
+        Ok(r) if r.rows_affected() == 0 => {
+            return (StatusCode::NOT_FOUND, "Member is not in the tribe").into_response()
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Failed to kick member: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+        }
+    }
+    let _ = attribute_leave(&state.db, target_user.id, &tribe, current_user.id).await;
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::MemberKick,
+        current_user.id,
+        Some(target_user.id),
+        &format!("Kicked {} from tribe {}", target_user.username, tribe),
+    )
+    .await;
+
+    StatusCode::OK.into_response()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -438,9 +1175,46 @@ mod tests {
             sort: Some("username".to_string()),
             order: Some("asc".to_string()),
             search: None,
+            fields: None,
+            as_of: None,
+            limit: None,
+            cursor: None,
+            format: None,
         };
         assert_eq!(q.sort.unwrap(), "username");
     }
+
+    fn wallet(network: &str, is_primary: bool, character: Option<&str>) -> LinkedWallet {
+        LinkedWallet {
+            id: format!("{}-{}", network, is_primary),
+            user_id: 1,
+            address: "0x1".to_string(),
+            verified_at: Utc::now(),
+            deleted_at: None,
+            network: network.to_string(),
+            stale_at: None,
+            character_name: character.map(str::to_string),
+            is_primary,
+            label: None,
+            sort_order: 0,
+            tribes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_display_name_uses_primary_character() {
+        let wallets = vec![
+            wallet("mainnet", false, Some("Alt Rider")),
+            wallet("testnet", true, Some("Test Rider")),
+            wallet("mainnet", true, Some("Main Rider")),
+        ];
+        assert_eq!(display_name("VoidUser", &wallets), "Main Rider");
+        assert_eq!(
+            display_name("VoidUser", &[wallet("mainnet", true, None)]),
+            "VoidUser"
+        );
+        assert_eq!(display_name("VoidUser", &[]), "VoidUser");
+    }
 }
 
 #[cfg(test)]
@@ -543,9 +1317,14 @@ mod integration_tests {
             sort: None,
             order: None,
             search: None,
+            fields: None,
+            as_of: None,
+            limit: None,
+            cursor: None,
+            format: None,
         };
 
-        let response = get_roster(auth_user, Query(query), State(state.clone()))
+        let response = get_roster(auth_user.into(), Query(query), State(state.clone()))
             .await
             .into_response();
         assert_eq!(response.status(), StatusCode::OK);
@@ -585,9 +1364,14 @@ mod integration_tests {
             sort: None,
             order: None,
             search: None,
+            fields: None,
+            as_of: None,
+            limit: None,
+            cursor: None,
+            format: None,
         };
 
-        let response = get_roster(auth_user, Query(query), State(state.clone()))
+        let response = get_roster(auth_user.into(), Query(query), State(state.clone()))
             .await
             .into_response();
         assert_eq!(response.status(), StatusCode::FORBIDDEN);
@@ -628,9 +1412,14 @@ mod integration_tests {
             sort: None,
             order: None,
             search: None,
+            fields: None,
+            as_of: None,
+            limit: None,
+            cursor: None,
+            format: None,
         };
         let _ = get_roster(
-            auth_user.clone(),
+            auth_user.clone().into(),
             Query(query.clone()),
             State(state.clone()),
         )
@@ -645,7 +1434,7 @@ mod integration_tests {
 
         // 3. Second View (Immediate) - Should NOT Log
         let _ = get_roster(
-            auth_user.clone(),
+            auth_user.clone().into(),
             Query(query.clone()),
             State(state.clone()),
         )
@@ -672,8 +1461,18 @@ mod integration_tests {
             sort: None,
             order: None,
             search: None,
+            fields: None,
+            as_of: None,
+            limit: None,
+            cursor: None,
+            format: None,
         };
-        let _ = get_roster(auth_user.clone(), Query(query_water), State(state.clone())).await;
+        let _ = get_roster(
+            auth_user.clone().into(),
+            Query(query_water),
+            State(state.clone()),
+        )
+        .await;
 
         let count: (i64,) =
             sqlx::query_as("SELECT COUNT(*) FROM audit_logs WHERE action = 'VIEW_ROSTER'")
@@ -682,6 +1481,329 @@ mod integration_tests {
                 .unwrap();
         assert_eq!(count.0, 2);
     }
+
+    #[tokio::test]
+    async fn test_roster_as_of() {
+        let db = setup_db().await;
+        let state = AppState::new(db.clone());
+
+        for (id, discord_id, username, is_admin) in
+            [(1_i64, "100", "chief", true), (2, "200", "deserter", false)]
+        {
+            sqlx::query("INSERT INTO users (id, discord_id, username, discriminator, is_admin) VALUES (?, ?, ?, '0000', ?)")
+                .bind(id)
+                .bind(discord_id)
+                .bind(username)
+                .bind(is_admin)
+                .execute(&db)
+                .await
+                .unwrap();
+        }
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe) VALUES (1, 'Fire')")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        // The deserter was a member from ten to five days ago
+        let now = Utc::now();
+        sqlx::query("INSERT INTO tribe_membership_history (user_id, tribe, joined_at, left_at) VALUES (2, 'Fire', ?, ?)")
+            .bind((now - chrono::Duration::days(10)).format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string())
+            .bind((now - chrono::Duration::days(5)).format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string())
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let roster_at = |as_of: Option<DateTime<Utc>>| {
+            let state = state.clone();
+            async move {
+                let query = RosterQuery {
+                    tribe: Some("Fire".to_string()),
+                    sort: None,
+                    order: None,
+                    search: None,
+                    fields: None,
+                    as_of,
+                    limit: None,
+                    cursor: None,
+                    format: None,
+                };
+                let res = get_roster(
+                    AuthenticatedUser { user_id: 1 }.into(),
+                    Query(query),
+                    State(state),
+                )
+                .await
+                .into_response();
+                assert_eq!(res.status(), StatusCode::OK);
+                let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+                json.as_array()
+                    .unwrap()
+                    .iter()
+                    .map(|m| m["username"].as_str().unwrap().to_string())
+                    .collect::<Vec<_>>()
+            }
+        };
+
+        assert_eq!(roster_at(None).await, vec!["chief"]);
+        assert_eq!(
+            roster_at(Some(now - chrono::Duration::days(7))).await,
+            vec!["deserter"]
+        );
+        assert_eq!(
+            roster_at(Some(now - chrono::Duration::days(1))).await,
+            Vec::<String>::new()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_roster_pagination_and_streaming() {
+        let db = setup_db().await;
+        let state = AppState::new(db.clone());
+
+        // Wallet counts: chief 1, alpha 3, bravo 0, charlie 3
+        for (id, discord_id, username, is_admin, wallets) in [
+            (1_i64, "100", "chief", true, 1),
+            (2, "200", "alpha", false, 3),
+            (3, "300", "bravo", false, 0),
+            (4, "400", "charlie", false, 3),
+        ] {
+            sqlx::query("INSERT INTO users (id, discord_id, username, discriminator, is_admin) VALUES (?, ?, ?, '0000', ?)")
+                .bind(id)
+                .bind(discord_id)
+                .bind(username)
+                .bind(is_admin)
+                .execute(&db)
+                .await
+                .unwrap();
+            sqlx::query("INSERT INTO user_tribes (user_id, tribe) VALUES (?, 'Fire')")
+                .bind(id)
+                .execute(&db)
+                .await
+                .unwrap();
+            for n in 0..wallets {
+                sqlx::query("INSERT INTO wallets (id, user_id, address, verified_at) VALUES (?, ?, ?, CURRENT_TIMESTAMP)")
+                    .bind(format!("w{}-{}", id, n))
+                    .bind(id)
+                    .bind(format!("0x{}{}", id, n))
+                    .execute(&db)
+                    .await
+                    .unwrap();
+            }
+        }
+
+        let fetch = |cursor: Option<String>, format: Option<&str>| {
+            let state = state.clone();
+            let query = RosterQuery {
+                tribe: Some("Fire".to_string()),
+                sort: Some("wallet_count".to_string()),
+                order: Some("desc".to_string()),
+                search: None,
+                fields: None,
+                as_of: None,
+                limit: if format.is_some() { None } else { Some(2) },
+                cursor,
+                format: format.map(str::to_string),
+            };
+            async move {
+                let res = get_roster(
+                    AuthenticatedUser { user_id: 1 }.into(),
+                    Query(query),
+                    State(state),
+                )
+                .await
+                .into_response();
+                assert_eq!(res.status(), StatusCode::OK);
+                axum::body::to_bytes(res.into_body(), usize::MAX)
+                    .await
+                    .unwrap()
+            }
+        };
+        let names = |page: &serde_json::Value| {
+            page["items"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|m| m["username"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        // Ties on wallet count fall back to user ID, in the same direction
+        let first: serde_json::Value = serde_json::from_slice(&fetch(None, None).await).unwrap();
+        assert_eq!(first["total"], 4);
+        assert_eq!(names(&first), vec!["charlie", "alpha"]);
+        let cursor = first["nextCursor"].as_str().unwrap().to_string();
+
+        let second: serde_json::Value =
+            serde_json::from_slice(&fetch(Some(cursor), None).await).unwrap();
+        assert_eq!(names(&second), vec!["chief", "bravo"]);
+        assert!(second["nextCursor"].is_null());
+
+        let res = get_roster(
+            AuthenticatedUser { user_id: 1 }.into(),
+            Query(RosterQuery {
+                tribe: Some("Fire".to_string()),
+                sort: None,
+                order: None,
+                search: None,
+                fields: None,
+                as_of: None,
+                limit: None,
+                cursor: Some("not-a-cursor".to_string()),
+                format: None,
+            }),
+            State(state.clone()),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+        let body = fetch(None, Some("ndjson")).await;
+        let streamed: Vec<String> = String::from_utf8(body.to_vec())
+            .unwrap()
+            .lines()
+            .map(|line| {
+                let member: serde_json::Value = serde_json::from_str(line).unwrap();
+                member["username"].as_str().unwrap().to_string()
+            })
+            .collect();
+        assert_eq!(streamed, vec!["charlie", "alpha", "chief", "bravo"]);
+    }
+
+    #[tokio::test]
+    async fn test_roster_overview() {
+        let db = setup_db().await;
+        let state = AppState::new(db.clone());
+
+        for (id, discord_id, username, is_admin) in [
+            (1_i64, "100", "global", true),
+            (2, "200", "both", false),
+            (3, "300", "water", false),
+            (4, "400", "loner", false),
+        ] {
+            sqlx::query("INSERT INTO users (id, discord_id, username, discriminator, is_admin) VALUES (?, ?, ?, '0000', ?)")
+                .bind(id)
+                .bind(discord_id)
+                .bind(username)
+                .bind(is_admin)
+                .execute(&db)
+                .await
+                .unwrap();
+        }
+        for (user_id, tribe) in [(1_i64, "Fire"), (2, "Fire"), (2, "Water"), (3, "Water")] {
+            sqlx::query("INSERT INTO user_tribes (user_id, tribe) VALUES (?, ?)")
+                .bind(user_id)
+                .bind(tribe)
+                .execute(&db)
+                .await
+                .unwrap();
+        }
+
+        let overview = |user_id: i64, tribes: Option<&str>| {
+            get_roster_overview(
+                AuthenticatedUser { user_id },
+                Query(OverviewQuery {
+                    tribes: tribes.map(str::to_string),
+                }),
+                State(state.clone()),
+            )
+        };
+
+        let res = overview(2, None).await.into_response();
+        assert_eq!(res.status(), StatusCode::FORBIDDEN);
+
+        let res = overview(1, None).await.into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["members"].as_array().unwrap().len(), 3);
+        assert_eq!(json["multiTribeMembers"], serde_json::json!(["200"]));
+        assert_eq!(json["unaffiliated"][0]["username"], "loner");
+
+        // Narrowed to Water: members keep their full tribe lists
+        let res = overview(1, Some("Water")).await.into_response();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let names: Vec<&str> = json["members"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["username"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["both", "water"]);
+        assert_eq!(
+            json["members"][0]["tribes"],
+            serde_json::json!(["Fire", "Water"])
+        );
+        assert_eq!(
+            json["tribes"],
+            serde_json::json!([{"tribe": "Water", "memberCount": 2}])
+        );
+    }
+    #[tokio::test]
+    async fn test_kick_member() {
+        use crate::test_support::{access, add_members, insert_users};
+
+        let db = setup_db().await;
+        let state = AppState::new(db.clone());
+        insert_users(
+            &db,
+            &[
+                (1, "111", "Chief"),
+                (2, "222", "Officer"),
+                (3, "333", "Scout"),
+            ],
+        )
+        .await;
+        add_members(
+            &db,
+            &[(1, "Fire", true), (2, "Fire", true), (3, "Fire", false)],
+        )
+        .await;
+
+        let kick = |discord_id: &str| {
+            let state = state.clone();
+            let discord_id = discord_id.to_string();
+            async move {
+                kick_member(
+                    access(&state.db, 1, "Fire").await,
+                    StepUp,
+                    Path(discord_id),
+                    State(state.clone()),
+                )
+                .await
+                .into_response()
+                .status()
+            }
+        };
+        assert_eq!(kick("111").await, StatusCode::BAD_REQUEST);
+        assert_eq!(kick("222").await, StatusCode::FORBIDDEN);
+        assert_eq!(kick("999").await, StatusCode::NOT_FOUND);
+        assert_eq!(kick("333").await, StatusCode::OK);
+        assert_eq!(kick("333").await, StatusCode::NOT_FOUND);
+
+        // The removal is attributed to the admin who made it
+        let left_by: Option<i64> = sqlx::query_scalar(
+            "SELECT left_by FROM tribe_membership_history WHERE user_id = 3 AND tribe = 'Fire'",
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(left_by, Some(1));
+        let kicks: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM audit_logs WHERE action = 'MEMBER_KICK' AND actor_id = 1 AND target_id = 3",
+        )
+        .fetch_one(&db)
+        .await
+        .unwrap();
+        assert_eq!(kicks, 1);
+    }
 }
 
 #[utoipa::path(
@@ -694,6 +1816,7 @@ mod integration_tests {
     request_body = GrantAdminRequest,
     responses(
         (status = 200, description = "Admin granted successfully"),
+        (status = 400, description = "Expiry must be in the future, or a non-member lacks the tribe's required vouches"),
         (status = 403, description = "Forbidden"),
         (status = 404, description = "User or wallet not found")
     ),
@@ -703,17 +1826,20 @@ mod integration_tests {
 )]
 pub async fn grant_admin(
     Path(discord_id): Path<String>,
-    Query(query): Query<MemberQuery>,
     State(state): State<AppState>,
-    auth_user: AuthenticatedUser,
+    access: TribeAccess<perm::AdminGrant>,
+    _step_up: StepUp,
     Json(payload): Json<GrantAdminRequest>,
 ) -> impl IntoResponse {
-    // Verify admin in tribe
-    let (current_user, tribe, _all_tribes) =
-        match require_admin_in_tribe(&state.db, auth_user.user_id, query.tribe.as_deref()).await {
-            Ok(result) => result,
-            Err(e) => return e.into_response(),
-        };
+    let TribeAccess {
+        user: current_user,
+        tribe,
+        ..
+    } = access;
+
+    if let Err(msg) = crate::expiry::validate_expiry(payload.expires_at) {
+        return (StatusCode::BAD_REQUEST, msg).into_response();
+    }
 
     // Get target user
     let target_user = match get_user_by_discord_id(&state.db, &discord_id).await {
@@ -722,11 +1848,22 @@ pub async fn grant_admin(
         Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
     };
 
+    let wallet_id = match payload.wallet_id {
+        Some(id) => id,
+        None => match crate::wallet::primary_wallet_id(&state.db, target_user.id).await {
+            Ok(Some(id)) => id,
+            Ok(None) => {
+                return (StatusCode::NOT_FOUND, "User has no primary wallet").into_response()
+            }
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+        },
+    };
+
     // Verify wallet exists, belongs to target user, and is active
     let wallet: Option<crate::models::FlatLinkedWallet> = sqlx::query_as(
         "SELECT *, NULL as tribe FROM wallets WHERE id = ? AND user_id = ? AND deleted_at IS NULL",
     )
-    .bind(&payload.wallet_id)
+    .bind(&wallet_id)
     .bind(target_user.id)
     .fetch_optional(&state.db)
     .await
@@ -752,9 +1889,10 @@ pub async fn grant_admin(
     if existing.is_some() {
         // Update existing entry
         let result = sqlx::query(
-            "UPDATE user_tribes SET wallet_id = ?, is_admin = TRUE, source = 'MANUAL' WHERE user_id = ? AND tribe = ?",
+            "UPDATE user_tribes SET wallet_id = ?, is_admin = TRUE, admin_expires_at = ?, source = 'MANUAL' WHERE user_id = ? AND tribe = ?",
         )
-        .bind(&payload.wallet_id)
+        .bind(&wallet_id)
+        .bind(payload.expires_at)
         .bind(target_user.id)
         .bind(&tribe)
         .execute(&state.db)
@@ -764,30 +1902,54 @@ pub async fn grant_admin(
             return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to grant admin").into_response();
         }
     } else {
+        // Granting admin to a non-member admits them, so the tribe's vouch policy applies
+        match crate::vouches::vouch_standing(&state.db, &tribe, target_user.id).await {
+            Ok((vouches, required)) if vouches < required => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    "User does not have enough vouches from tribe members",
+                )
+                    .into_response();
+            }
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("Failed to check vouches in grant_admin: {}", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to grant admin")
+                    .into_response();
+            }
+        }
+
         // Insert new entry
         let result = sqlx::query(
-            "INSERT INTO user_tribes (user_id, tribe, wallet_id, is_admin, source) VALUES (?, ?, ?, TRUE, 'MANUAL')",
+            "INSERT INTO user_tribes (user_id, tribe, wallet_id, is_admin, admin_expires_at, source) VALUES (?, ?, ?, TRUE, ?, 'MANUAL')",
         )
         .bind(target_user.id)
         .bind(&tribe)
-        .bind(&payload.wallet_id)
+        .bind(&wallet_id)
+        .bind(payload.expires_at)
         .execute(&state.db)
         .await;
 
         if result.is_err() {
             return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to grant admin").into_response();
         }
+        let _ = attribute_join(&state.db, target_user.id, &tribe, current_user.id).await;
+        crate::sanctions::raise_alerts(&state.db).await;
     }
 
     // Audit log
+    let until = payload
+        .expires_at
+        .map(|at| format!(" until {}", at.to_rfc3339()))
+        .unwrap_or_default();
     let _ = log_audit(
         &state.db,
         AuditAction::AdminGrant,
         current_user.id,
         Some(target_user.id),
         &format!(
-            "Granted admin to {} in tribe {} via wallet {}",
-            target_user.username, tribe, payload.wallet_id
+            "Granted admin to {} in tribe {} via wallet {}{}",
+            target_user.username, tribe, wallet_id, until
         ),
     )
     .await;
@@ -0,0 +1,962 @@
+use crate::{
+    audit::{log_audit, AuditAction},
+    db::DbPool,
+    rbac::{perm, TribeAccess},
+    state::AppState,
+    totp::StepUp,
+};
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// How often the background job raises alerts for hits recorded outside a request,
+/// such as tribe joins written by the chain sync
+const ALERT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+/// Hits alerted per pass
+const ALERT_BATCH_SIZE: i64 = 100;
+const MAX_REASON_CHARS: usize = 500;
+const MAX_ALLIES: usize = 20;
+const DEFAULT_HITS_LIMIT: i64 = 100;
+const MAX_HITS_LIMIT: i64 = 500;
+
+/// Active entries, binding the tribe they must be visible to twice
+const VISIBLE_SQL: &str = "(s.expires_at IS NULL OR julianday(s.expires_at) > julianday('now')) \
+     AND (s.tribe = ? OR (s.shared AND EXISTS \
+     (SELECT 1 FROM sanction_allies a WHERE a.tribe = s.tribe AND a.ally = ?)))";
+
+const ACTIVE_SQL: &str = "(s.expires_at IS NULL OR julianday(s.expires_at) > julianday('now'))";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+#[sqlx(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum HitContext {
+    Login,
+    WalletLink,
+    TribeJoin,
+}
+
+impl HitContext {
+    fn describe(&self) -> &'static str {
+        match self {
+            HitContext::Login => "logged in",
+            HitContext::WalletLink => "linked a wallet",
+            HitContext::TribeJoin => "joined a tribe",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Sanction {
+    pub id: String,
+    /// Tribe that owns the entry
+    pub tribe: String,
+    pub discord_id: Option<String>,
+    pub wallet_address: Option<String>,
+    pub reason: String,
+    /// Visible to the owning tribe's allies
+    pub shared: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub created_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateSanctionRequest {
+    /// Exactly one of `discord_id` and `wallet_address`
+    pub discord_id: Option<String>,
+    pub wallet_address: Option<String>,
+    pub reason: String,
+    /// Permanent when absent
+    pub expires_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub shared: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct SetAlliesRequest {
+    /// Tribes this tribe's shared entries are visible to
+    pub allies: Vec<String>,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct HitsQuery {
+    pub tribe: Option<String>,
+    /// Most recent hits to return (default 100, at most 500)
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SanctionHit {
+    pub id: String,
+    pub context: HitContext,
+    /// The tribe joined, for `TRIBE_JOIN` hits
+    pub joined_tribe: Option<String>,
+    /// Matched user; absent once their account is deleted
+    pub discord_id: Option<String>,
+    pub username: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub sanction_id: String,
+    pub sanction_tribe: String,
+    pub sanction_discord_id: Option<String>,
+    pub sanction_wallet_address: Option<String>,
+    pub reason: String,
+}
+
+/// Active entries visible to a tribe: its own and those its allies share with it
+pub async fn visible_sanctions(db: &DbPool, tribe: &str) -> Result<Vec<Sanction>, sqlx::Error> {
+    let sql = format!(
+        r#"
+        SELECT s.id, s.tribe, s.discord_id, s.wallet_address, s.reason, s.shared,
+               s.expires_at, u.discord_id AS created_by, s.created_at
+        FROM sanctions s LEFT JOIN users u ON u.id = s.created_by
+        WHERE {}
+        ORDER BY s.created_at DESC, s.id
+        "#,
+        VISIBLE_SQL
+    );
+    sqlx::query_as(&sql)
+        .bind(tribe)
+        .bind(tribe)
+        .fetch_all(db)
+        .await
+}
+
+/// Record a hit for every active entry matching the user's Discord ID or one of
+/// their active wallets, then raise the alerts. Screening never blocks the caller;
+/// failures are logged.
+pub async fn screen_login(db: &DbPool, user_id: i64) {
+    let sql = format!(
+        r#"
+        INSERT INTO sanction_hits (id, sanction_id, user_id, context, created_at)
+        SELECT lower(hex(randomblob(16))), s.id, u.id, ?, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+        FROM sanctions s JOIN users u ON u.id = ?
+        WHERE {}
+          AND (s.discord_id = u.discord_id
+               OR s.wallet_address IN (SELECT address FROM wallets WHERE user_id = u.id AND deleted_at IS NULL))
+        "#,
+        ACTIVE_SQL
+    );
+    record_hits(db, sqlx::query(&sql).bind(HitContext::Login).bind(user_id)).await;
+}
+
+/// Screen a newly linked wallet address
+pub async fn screen_wallet(db: &DbPool, user_id: i64, address: &str) {
+    let sql = format!(
+        r#"
+        INSERT INTO sanction_hits (id, sanction_id, user_id, context, created_at)
+        SELECT lower(hex(randomblob(16))), s.id, ?, ?, strftime('%Y-%m-%dT%H:%M:%fZ', 'now')
+        FROM sanctions s
+        WHERE {} AND s.wallet_address = ?
+        "#,
+        ACTIVE_SQL
+    );
+    record_hits(
+        db,
+        sqlx::query(&sql)
+            .bind(user_id)
+            .bind(HitContext::WalletLink)
+            .bind(address.to_lowercase()),
+    )
+    .await;
+}
+
+async fn record_hits<'q>(
+    db: &DbPool,
+    query: sqlx::query::Query<'q, sqlx::Sqlite, sqlx::sqlite::SqliteArguments<'q>>,
+) {
+    match query.execute(db).await {
+        Ok(r) if r.rows_affected() == 0 => {}
+        Ok(_) => raise_alerts(db).await,
+        Err(e) => eprintln!("Failed to screen against sanctions: {}", e),
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct PendingHit {
+    id: String,
+    sanction_id: String,
+    user_id: Option<i64>,
+    username: Option<String>,
+    discord_id: Option<String>,
+    context: HitContext,
+    joined_tribe: Option<String>,
+    sanction_tribe: String,
+    reason: String,
+}
+
+/// Audit and alert hits that haven't been alerted yet. Each hit is claimed first,
+/// so concurrent callers never alert it twice.
+pub async fn dispatch_alerts(db: &DbPool) -> Result<u64, sqlx::Error> {
+    let pending: Vec<PendingHit> = sqlx::query_as(
+        r#"
+        SELECT h.id, h.sanction_id, h.user_id, u.username, u.discord_id, h.context, h.tribe AS joined_tribe,
+               s.tribe AS sanction_tribe, s.reason
+        FROM sanction_hits h
+        JOIN sanctions s ON s.id = h.sanction_id
+        LEFT JOIN users u ON u.id = h.user_id
+        WHERE h.alerted_at IS NULL
+        ORDER BY h.created_at
+        LIMIT ?
+        "#,
+    )
+    .bind(ALERT_BATCH_SIZE)
+    .fetch_all(db)
+    .await?;
+
+    let mut alerted = 0;
+    for hit in pending {
+        let claimed = sqlx::query(
+            "UPDATE sanction_hits SET alerted_at = strftime('%Y-%m-%dT%H:%M:%fZ', 'now') WHERE id = ? AND alerted_at IS NULL",
+        )
+        .bind(&hit.id)
+        .execute(db)
+        .await?;
+        if claimed.rows_affected() == 0 {
+            continue;
+        }
+        alerted += 1;
+
+        // Member histories are read in every tribe the member belongs to, so
+        // the audit row names only the entry; its owner and reason stay with
+        // the tribes that can see the sanction
+        if let Some(user_id) = hit.user_id {
+            let _ = log_audit(
+                db,
+                AuditAction::SanctionHit,
+                user_id,
+                Some(user_id),
+                &format!(
+                    "Matched sanction {} when they {}",
+                    hit.sanction_id,
+                    hit.context.describe()
+                ),
+            )
+            .await;
+        }
+        let details = format!(
+            "{} ({}) {}{} and matches a sanction by tribe {}: {}",
+            hit.username.as_deref().unwrap_or("Deleted user"),
+            hit.discord_id.as_deref().unwrap_or("-"),
+            hit.context.describe(),
+            hit.joined_tribe
+                .as_deref()
+                .map(|t| format!(" ({})", t))
+                .unwrap_or_default(),
+            hit.sanction_tribe,
+            hit.reason
+        );
+        alert_sanction_hit(details);
+    }
+    Ok(alerted)
+}
+
+/// Raise pending alerts straight away after a write that may have fired the
+/// tribe join trigger, logging failures
+pub async fn raise_alerts(db: &DbPool) {
+    if let Err(e) = dispatch_alerts(db).await {
+        eprintln!("Failed to raise sanction alerts: {}", e);
+    }
+}
+
+/// Send a sanction hit to `SANCTIONS_WEBHOOK` (fire and forget)
+fn alert_sanction_hit(details: String) {
+    tokio::spawn(async move {
+        let webhook_url = match std::env::var("SANCTIONS_WEBHOOK") {
+            Ok(url) if !url.is_empty() => url,
+            _ => return,
+        };
+
+        let payload = serde_json::json!({
+            "content": format!("🚨 **Sanctions Match**\n{}", details)
+        });
+        if let Err(e) = reqwest::Client::new()
+            .post(&webhook_url)
+            .json(&payload)
+            .send()
+            .await
+        {
+            eprintln!("Failed to send sanctions webhook: {}", e);
+        }
+    });
+}
+
+/// Raise alerts for hits recorded by the tribe join trigger on startup and then
+/// every minute.
+pub fn spawn_sanction_alerter(db: DbPool) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(ALERT_INTERVAL);
+        loop {
+            interval.tick().await;
+            match dispatch_alerts(&db).await {
+                Ok(0) => {}
+                Ok(n) => println!("Raised {} sanction alert(s)", n),
+                Err(e) => eprintln!("Sanction alert sweep failed: {}", e),
+            }
+        }
+    });
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/roster/sanctions",
+    params(
+        ("tribe" = Option<String>, Query, description = "Tribe whose list to show")
+    ),
+    responses(
+        (status = 200, description = "Active entries of the tribe and those shared with it by allies", body = Vec<Sanction>),
+        (status = 403, description = "Forbidden: Missing roster.read")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn list_sanctions(
+    access: TribeAccess<perm::RosterRead>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match visible_sanctions(&state.db, &access.tribe).await {
+        Ok(sanctions) => Json(sanctions).into_response(),
+        Err(e) => {
+            eprintln!("Failed to list sanctions: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/roster/sanctions",
+    params(
+        ("tribe" = Option<String>, Query, description = "Tribe adding the entry")
+    ),
+    request_body = CreateSanctionRequest,
+    responses(
+        (status = 201, description = "Entry added", body = Sanction),
+        (status = 400, description = "Not exactly one target, invalid target or reason, or expiry not in the future"),
+        (status = 403, description = "Forbidden: Missing sanctions.manage"),
+        (status = 409, description = "The tribe already has an active entry for this target")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn create_sanction(
+    access: TribeAccess<perm::SanctionsManage>,
+    State(state): State<AppState>,
+    Json(payload): Json<CreateSanctionRequest>,
+) -> impl IntoResponse {
+    let discord_id = payload
+        .discord_id
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty());
+    let wallet_address = payload
+        .wallet_address
+        .as_deref()
+        .map(|w| w.trim().to_lowercase())
+        .filter(|w| !w.is_empty());
+    match (discord_id, &wallet_address) {
+        (Some(d), None) if d.len() <= 20 && d.chars().all(|c| c.is_ascii_digit()) => {}
+        (None, Some(w)) if w.len() <= 128 && w.chars().all(|c| c.is_ascii_alphanumeric()) => {}
+        (Some(_), None) | (None, Some(_)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                "Invalid Discord ID or wallet address",
+            )
+                .into_response()
+        }
+        _ => {
+            return (
+                StatusCode::BAD_REQUEST,
+                "Give exactly one of discord_id and wallet_address",
+            )
+                .into_response()
+        }
+    }
+    let reason = payload.reason.trim();
+    if reason.is_empty() || reason.chars().count() > MAX_REASON_CHARS {
+        return (
+            StatusCode::BAD_REQUEST,
+            "Reason must be 1 to 500 characters",
+        )
+            .into_response();
+    }
+    if let Err(msg) = crate::expiry::validate_expiry(payload.expires_at) {
+        return (StatusCode::BAD_REQUEST, msg).into_response();
+    }
+
+    let existing_sql = format!(
+        "SELECT 1 FROM sanctions s WHERE s.tribe = ? AND {} AND (s.discord_id = ? OR s.wallet_address = ?)",
+        ACTIVE_SQL
+    );
+    match sqlx::query_scalar::<_, i64>(&existing_sql)
+        .bind(&access.tribe)
+        .bind(discord_id)
+        .bind(&wallet_address)
+        .fetch_optional(&state.db)
+        .await
+    {
+        Ok(Some(_)) => {
+            return (
+                StatusCode::CONFLICT,
+                "The tribe already has an active entry for this target",
+            )
+                .into_response()
+        }
+        Ok(None) => {}
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    }
+
+    let sanction = Sanction {
+        id: Uuid::new_v4().to_string(),
+        tribe: access.tribe.clone(),
+        discord_id: discord_id.map(str::to_string),
+        wallet_address,
+        reason: reason.to_string(),
+        shared: payload.shared,
+        expires_at: payload.expires_at,
+        created_by: Some(access.user.discord_id.clone()),
+        created_at: Utc::now(),
+    };
+    if let Err(e) = sqlx::query(
+        "INSERT INTO sanctions (id, tribe, discord_id, wallet_address, reason, shared, expires_at, created_by, created_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&sanction.id)
+    .bind(&sanction.tribe)
+    .bind(&sanction.discord_id)
+    .bind(&sanction.wallet_address)
+    .bind(&sanction.reason)
+    .bind(sanction.shared)
+    .bind(sanction.expires_at)
+    .bind(access.user.id)
+    .bind(sanction.created_at)
+    .execute(&state.db)
+    .await
+    {
+        eprintln!("Failed to add sanction: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+    }
+
+    // The actor's history is visible in their other tribes: leave out the
+    // target, reason and owning tribe
+    let until = sanction
+        .expires_at
+        .map(|at| format!(" until {}", at.to_rfc3339()))
+        .unwrap_or_default();
+    let _ = log_audit(
+        &state.db,
+        AuditAction::SanctionAdd,
+        access.user.id,
+        None,
+        &format!(
+            "Added sanction {}{}{}",
+            sanction.id,
+            if sanction.shared { " (shared)" } else { "" },
+            until
+        ),
+    )
+    .await;
+
+    (StatusCode::CREATED, Json(sanction)).into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/roster/sanctions/{id}",
+    params(
+        ("id" = String, Path, description = "Sanction ID"),
+        ("tribe" = Option<String>, Query, description = "Tribe that owns the entry")
+    ),
+    responses(
+        (status = 200, description = "Entry removed, with its hits"),
+        (status = 403, description = "Forbidden: Missing sanctions.manage"),
+        (status = 404, description = "The tribe has no entry with this ID")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn delete_sanction(
+    access: TribeAccess<perm::SanctionsManage>,
+    Path(id): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let found: Option<i64> =
+        match sqlx::query_scalar("SELECT 1 FROM sanctions WHERE id = ? AND tribe = ?")
+            .bind(&id)
+            .bind(&access.tribe)
+            .fetch_optional(&state.db)
+            .await
+        {
+            Ok(t) => t,
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+        };
+    if found.is_none() {
+        return (StatusCode::NOT_FOUND, "Sanction not found").into_response();
+    }
+
+    if let Err(e) = sqlx::query("DELETE FROM sanctions WHERE id = ?")
+        .bind(&id)
+        .execute(&state.db)
+        .await
+    {
+        eprintln!("Failed to remove sanction: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::SanctionRemove,
+        access.user.id,
+        None,
+        &format!("Removed sanction {}", id),
+    )
+    .await;
+
+    StatusCode::OK.into_response()
+}
+
+async fn tribe_allies(db: &DbPool, tribe: &str) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT ally FROM sanction_allies WHERE tribe = ? ORDER BY ally")
+        .bind(tribe)
+        .fetch_all(db)
+        .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/roster/sanctions/allies",
+    params(
+        ("tribe" = Option<String>, Query, description = "Tribe whose allies to list")
+    ),
+    responses(
+        (status = 200, description = "Tribes that see this tribe's shared entries", body = Vec<String>),
+        (status = 403, description = "Forbidden: Missing sanctions.manage")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn list_allies(
+    access: TribeAccess<perm::SanctionsManage>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    match tribe_allies(&state.db, &access.tribe).await {
+        Ok(allies) => Json(allies).into_response(),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/roster/sanctions/allies",
+    params(
+        ("tribe" = Option<String>, Query, description = "Tribe whose allies to set")
+    ),
+    request_body = SetAlliesRequest,
+    responses(
+        (status = 200, description = "The new allies", body = Vec<String>),
+        (status = 400, description = "Unknown tribe, the tribe itself, or too many allies"),
+        (status = 403, description = "Forbidden: Missing admin.grant, or step-up required")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn set_allies(
+    access: TribeAccess<perm::AdminGrant>,
+    State(state): State<AppState>,
+    _step_up: StepUp,
+    Json(payload): Json<SetAlliesRequest>,
+) -> impl IntoResponse {
+    let mut allies: Vec<String> = payload
+        .allies
+        .iter()
+        .map(|a| a.trim().to_string())
+        .collect();
+    allies.sort();
+    allies.dedup();
+    if allies.len() > MAX_ALLIES {
+        return (StatusCode::BAD_REQUEST, "Too many allies").into_response();
+    }
+    if allies.contains(&access.tribe) {
+        return (StatusCode::BAD_REQUEST, "A tribe can't ally with itself").into_response();
+    }
+    for ally in &allies {
+        match sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM tribes WHERE name = ?")
+            .bind(ally)
+            .fetch_one(&state.db)
+            .await
+        {
+            Ok(0) => return (StatusCode::BAD_REQUEST, "Unknown tribe").into_response(),
+            Ok(_) => {}
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+        }
+    }
+
+    let result: Result<(), sqlx::Error> = async {
+        let mut tx = state.db.begin().await?;
+        sqlx::query("DELETE FROM sanction_allies WHERE tribe = ?")
+            .bind(&access.tribe)
+            .execute(&mut *tx)
+            .await?;
+        for ally in &allies {
+            sqlx::query("INSERT INTO sanction_allies (tribe, ally) VALUES (?, ?)")
+                .bind(&access.tribe)
+                .bind(ally)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await
+    }
+    .await;
+    if let Err(e) = result {
+        eprintln!("Failed to set sanction allies: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::SanctionAlliesUpdate,
+        access.user.id,
+        None,
+        &format!(
+            "Set sanctions allies of tribe {} to {}",
+            access.tribe,
+            if allies.is_empty() {
+                "(none)".to_string()
+            } else {
+                allies.join(", ")
+            }
+        ),
+    )
+    .await;
+
+    Json(allies).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/roster/sanctions/hits",
+    params(HitsQuery),
+    responses(
+        (status = 200, description = "Recent matches against entries visible to the tribe, newest first", body = Vec<SanctionHit>),
+        (status = 403, description = "Forbidden: Missing sanctions.manage")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn list_hits(
+    access: TribeAccess<perm::SanctionsManage>,
+    Query(query): Query<HitsQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_HITS_LIMIT)
+        .clamp(1, MAX_HITS_LIMIT);
+    // Hits stay listed after their entry expires, so only the sharing rule applies
+    let sql = r#"
+        SELECT h.id, h.context, h.tribe AS joined_tribe, u.discord_id, u.username,
+               h.created_at, s.id AS sanction_id, s.tribe AS sanction_tribe,
+               s.discord_id AS sanction_discord_id, s.wallet_address AS sanction_wallet_address,
+               s.reason
+        FROM sanction_hits h
+        JOIN sanctions s ON s.id = h.sanction_id
+        LEFT JOIN users u ON u.id = h.user_id
+        WHERE s.tribe = ? OR (s.shared AND EXISTS
+            (SELECT 1 FROM sanction_allies a WHERE a.tribe = s.tribe AND a.ally = ?))
+        ORDER BY h.created_at DESC, h.id
+        LIMIT ?
+    "#;
+    match sqlx::query_as::<_, SanctionHit>(sql)
+        .bind(&access.tribe)
+        .bind(&access.tribe)
+        .bind(limit)
+        .fetch_all(&state.db)
+        .await
+    {
+        Ok(hits) => Json(hits).into_response(),
+        Err(e) => {
+            eprintln!("Failed to list sanction hits: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{access, add_members, insert_users, setup_db};
+
+    async fn setup() -> AppState {
+        let db = setup_db().await;
+        insert_users(
+            &db,
+            &[
+                (1, "111", "FireChief"),
+                (2, "222", "WaterChief"),
+                (3, "333", "Griefer"),
+            ],
+        )
+        .await;
+        sqlx::query("INSERT INTO tribes (name) VALUES ('Fire'), ('Water'), ('Earth')")
+            .execute(&db)
+            .await
+            .unwrap();
+        add_members(&db, &[(1, "Fire", true), (2, "Water", true)]).await;
+        sqlx::query("INSERT INTO wallets (id, user_id, address, verified_at) VALUES ('w3', 3, '0xbad', CURRENT_TIMESTAMP)")
+            .execute(&db)
+            .await
+            .unwrap();
+        AppState::new(db)
+    }
+
+    async fn add(
+        state: &AppState,
+        discord_id: Option<&str>,
+        wallet_address: Option<&str>,
+        shared: bool,
+    ) -> StatusCode {
+        create_sanction(
+            access(&state.db, 1, "Fire").await,
+            State(state.clone()),
+            Json(CreateSanctionRequest {
+                discord_id: discord_id.map(str::to_string),
+                wallet_address: wallet_address.map(str::to_string),
+                reason: "Podded our haulers".to_string(),
+                expires_at: None,
+                shared,
+            }),
+        )
+        .await
+        .into_response()
+        .status()
+    }
+
+    async fn hits(state: &AppState, user_id: i64, tribe: &str) -> Vec<serde_json::Value> {
+        let res = list_hits(
+            access(&state.db, user_id, tribe).await,
+            Query(HitsQuery {
+                tribe: None,
+                limit: None,
+            }),
+            State(state.clone()),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sanction_entries_and_sharing() {
+        let state = setup().await;
+        assert_eq!(
+            add(&state, Some("333"), Some("0xbad"), false).await,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            add(&state, None, None, false).await,
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            add(&state, Some("333"), None, false).await,
+            StatusCode::CREATED
+        );
+        assert_eq!(
+            add(&state, Some("333"), None, true).await,
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            add(&state, None, Some("0xBAD"), true).await,
+            StatusCode::CREATED
+        );
+
+        // Water sees only the shared entry, and only once it's an ally
+        assert!(visible_sanctions(&state.db, "Water")
+            .await
+            .unwrap()
+            .is_empty());
+        let res = set_allies(
+            access(&state.db, 1, "Fire").await,
+            State(state.clone()),
+            StepUp,
+            Json(SetAlliesRequest {
+                allies: vec!["Water".to_string()],
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        let water = visible_sanctions(&state.db, "Water").await.unwrap();
+        assert_eq!(water.len(), 1);
+        assert_eq!(water[0].wallet_address.as_deref(), Some("0xbad"));
+        assert_eq!(visible_sanctions(&state.db, "Fire").await.unwrap().len(), 2);
+        assert!(visible_sanctions(&state.db, "Earth")
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Water can't remove Fire's entry
+        let res = delete_sanction(
+            access(&state.db, 2, "Water").await,
+            Path(water[0].id.clone()),
+            State(state.clone()),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        // Expired entries stop matching
+        sqlx::query(
+            "UPDATE sanctions SET expires_at = '2020-01-01T00:00:00Z' WHERE discord_id = '333'",
+        )
+        .execute(&state.db)
+        .await
+        .unwrap();
+        assert_eq!(visible_sanctions(&state.db, "Fire").await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_screening_records_and_alerts_hits() {
+        let state = setup().await;
+        assert_eq!(
+            add(&state, Some("333"), None, false).await,
+            StatusCode::CREATED
+        );
+        assert_eq!(
+            add(&state, None, Some("0xbad"), true).await,
+            StatusCode::CREATED
+        );
+        sqlx::query("INSERT INTO sanction_allies (tribe, ally) VALUES ('Fire', 'Water')")
+            .execute(&state.db)
+            .await
+            .unwrap();
+
+        // Login matches both the Discord ID and the linked wallet
+        screen_login(&state.db, 3).await;
+        let fire = hits(&state, 1, "Fire").await;
+        assert_eq!(fire.len(), 2);
+        assert!(fire.iter().all(|h| h["context"] == "LOGIN"));
+        assert_eq!(hits(&state, 2, "Water").await.len(), 1);
+
+        screen_wallet(&state.db, 3, "0xBAD").await;
+        screen_wallet(&state.db, 3, "0xgood").await;
+        assert_eq!(hits(&state, 1, "Fire").await.len(), 3);
+
+        // Joining Water only matches what Water can see
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe) VALUES (3, 'Water')")
+            .execute(&state.db)
+            .await
+            .unwrap();
+        let water = hits(&state, 2, "Water").await;
+        let joins: Vec<_> = water
+            .iter()
+            .filter(|h| h["context"] == "TRIBE_JOIN")
+            .collect();
+        assert_eq!(joins.len(), 1);
+        assert_eq!(joins[0]["joinedTribe"], "Water");
+        assert_eq!(joins[0]["sanctionWalletAddress"], "0xbad");
+
+        // Screening alerts straight away; the trigger's hit waits for the sweep
+        assert_eq!(dispatch_alerts(&state.db).await.unwrap(), 1);
+        assert_eq!(dispatch_alerts(&state.db).await.unwrap(), 0);
+        let audited: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM audit_logs WHERE action = 'SANCTION_HIT' AND target_id = 3",
+        )
+        .fetch_one(&state.db)
+        .await
+        .unwrap();
+        assert_eq!(audited, 4);
+    }
+
+    #[tokio::test]
+    async fn test_unallied_tribes_cant_read_sanctions_from_audits() {
+        use crate::roster::{get_roster_member, MemberQuery};
+
+        let state = setup().await;
+        insert_users(&state.db, &[(4, "444", "EarthChief")]).await;
+        // The Fire chief and the griefer are both in unallied Earth
+        add_members(&state.db, &[(4, "Earth", true), (1, "Earth", false)]).await;
+        assert_eq!(
+            add(&state, Some("333"), None, false).await,
+            StatusCode::CREATED
+        );
+        add_members(&state.db, &[(3, "Earth", false)]).await;
+        screen_login(&state.db, 3).await;
+        dispatch_alerts(&state.db).await.unwrap();
+        assert!(hits(&state, 4, "Earth").await.is_empty());
+
+        for discord_id in ["333", "111"] {
+            let res = get_roster_member(
+                access(&state.db, 4, "Earth").await,
+                Path(discord_id.to_string()),
+                Query(MemberQuery {
+                    tribe: None,
+                    audit_page: None,
+                    audit_per_page: Some(100),
+                }),
+                State(state.clone()),
+            )
+            .await
+            .into_response();
+            assert_eq!(res.status(), StatusCode::OK);
+            let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let member: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            let items = member["audits"]["items"].as_array().unwrap();
+            assert!(items
+                .iter()
+                .any(|a| a["action"].as_str().unwrap().starts_with("SANCTION_")));
+            for audit in items {
+                let details = audit["details"].as_str().unwrap();
+                assert!(!details.contains("Podded"), "{}", details);
+                assert!(!details.contains("Fire"), "{}", details);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sanctions_follow_tribe_rename() {
+        let state = setup().await;
+        assert_eq!(
+            add(&state, None, Some("0xbad"), true).await,
+            StatusCode::CREATED
+        );
+        sqlx::query("INSERT INTO sanction_allies (tribe, ally) VALUES ('Fire', 'Water')")
+            .execute(&state.db)
+            .await
+            .unwrap();
+        add_members(&state.db, &[(3, "Water", false)]).await;
+
+        let admin = crate::middleware::admin::RequireSuperAdmin {
+            discord_id: "111".to_string(),
+        };
+        let rename = |from: &str, to: &str| {
+            crate::admin::apply_update_tribe(&state, &admin, from.to_string(), to.to_string())
+        };
+        assert_eq!(rename("Water", "Sea").await.status(), StatusCode::OK);
+        assert_eq!(rename("Fire", "Flame").await.status(), StatusCode::OK);
+
+        // The entry, the alliance and the join hit all carry the new names
+        assert_eq!(tribe_allies(&state.db, "Flame").await.unwrap(), ["Sea"]);
+        let sea = hits(&state, 2, "Sea").await;
+        assert_eq!(sea.len(), 1);
+        assert_eq!(sea[0]["joinedTribe"], "Sea");
+        assert_eq!(hits(&state, 1, "Flame").await.len(), 1);
+    }
+}
@@ -0,0 +1,417 @@
+use crate::{
+    audit::{log_audit, AuditAction},
+    note_crypto,
+    notes::{NoteCategory, VISIBLE_NOTES_SQL},
+    rbac::{has_permission_in_tribe, perm, Permission, TribeAccess},
+    state::AppState,
+    super_admins::is_super_admin,
+};
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+const DEFAULT_SEARCH_LIMIT: i64 = 20;
+const MAX_SEARCH_LIMIT: i64 = 100;
+const MAX_QUERY_TERMS: usize = 8;
+
+#[derive(Deserialize, IntoParams)]
+pub struct SearchQuery {
+    pub tribe: Option<String>,
+    /// Words to look for; each matches as a prefix and all must match
+    pub q: String,
+    /// Maximum hits per kind (default 20, max 100)
+    pub limit: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct MemberHit {
+    pub discord_id: String,
+    pub username: String,
+    /// Best matching field with the matched words wrapped in `**`
+    pub snippet: String,
+    /// Lower is a better match
+    pub rank: f64,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteHit {
+    pub note_id: String,
+    pub target_discord_id: String,
+    pub target_username: String,
+    pub category: NoteCategory,
+    /// Excerpt around the match with the matched words wrapped in `**`
+    pub snippet: String,
+    /// Lower is a better match
+    pub rank: f64,
+}
+
+#[derive(sqlx::FromRow)]
+struct NoteHitRow {
+    note_id: String,
+    target_discord_id: String,
+    target_username: String,
+    category: NoteCategory,
+    content: String,
+    key_id: Option<String>,
+    rank: f64,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResults {
+    pub tribe: String,
+    pub members: Vec<MemberHit>,
+    /// Empty unless the caller holds notes.read in the tribe
+    pub notes: Vec<NoteHit>,
+}
+
+/// Turn free text into an FTS5 query: every word quoted (so operators and
+/// column filters in user input are inert) and matched as a prefix.
+pub fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|t| t.replace('"', ""))
+        .filter(|t| !t.is_empty())
+        .take(MAX_QUERY_TERMS)
+        .map(|t| format!("\"{}\"*", t))
+        .collect();
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Live notes in the tribe matching `note_query` that the caller may read, best first.
+async fn search_notes(
+    state: &AppState,
+    access: &TribeAccess<perm::RosterRead>,
+    note_query: &str,
+    limit: i64,
+) -> Result<Vec<NoteHitRow>, sqlx::Error> {
+    let super_admin = is_super_admin(&state.db, &access.user.discord_id)
+        .await
+        .unwrap_or(false);
+    sqlx::query_as::<_, NoteHitRow>(&format!(
+        r#"
+        SELECT n.id AS note_id, u.discord_id AS target_discord_id, u.username AS target_username,
+            n.category, n.content, n.key_id, bm25(notes_fts) AS rank
+        FROM notes_fts
+        JOIN notes n ON n.rowid = notes_fts.rowid
+        JOIN users u ON u.id = n.target_user_id
+        WHERE notes_fts MATCH ? AND n.tribe = ? AND n.deleted_at IS NULL
+          AND {VISIBLE_NOTES_SQL}
+        ORDER BY rank, n.created_at DESC
+        LIMIT ?
+        "#
+    ))
+    .bind(note_query)
+    .bind(&access.tribe)
+    .bind(access.user.id)
+    .bind(super_admin)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/roster/search",
+    params(SearchQuery),
+    responses(
+        (status = 200, description = "Ranked member and note hits in the tribe", body = SearchResults),
+        (status = 400, description = "Empty query"),
+        (status = 403, description = "Forbidden: Missing roster.read in tribe")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn search_tribe(
+    access: TribeAccess<perm::RosterRead>,
+    Query(query): Query<SearchQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let Some(fts) = fts_query(&query.q) else {
+        return (StatusCode::BAD_REQUEST, "Search query cannot be empty").into_response();
+    };
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_SEARCH_LIMIT)
+        .clamp(1, MAX_SEARCH_LIMIT);
+
+    let members = match sqlx::query_as::<_, MemberHit>(
+        r#"
+        SELECT u.discord_id, u.username,
+            snippet(members_fts, -1, '**', '**', '…', 12) AS snippet,
+            bm25(members_fts) AS rank
+        FROM members_fts
+        JOIN users u ON u.id = members_fts.rowid
+        JOIN user_tribes ut ON ut.user_id = u.id AND ut.tribe = ?
+        WHERE members_fts MATCH ?
+        ORDER BY rank, u.username
+        LIMIT ?
+        "#,
+    )
+    .bind(&access.tribe)
+    .bind(&fts)
+    .bind(limit)
+    .fetch_all(&state.db)
+    .await
+    {
+        Ok(hits) => hits,
+        Err(e) => {
+            eprintln!("Failed to search members: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response();
+        }
+    };
+
+    let can_read_notes = match has_permission_in_tribe(
+        &state.db,
+        access.user.id,
+        &access.tribe,
+        Permission::NotesRead,
+    )
+    .await
+    {
+        Ok(allowed) => allowed,
+        Err(e) => {
+            eprintln!("Failed to check notes permission for search: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error").into_response();
+        }
+    };
+
+    // The note index only holds (possibly keyed) word hashes, so snippets are
+    // cut from the decrypted content
+    let keys = state.note_keys.as_deref();
+    let note_query = note_crypto::query_terms(keys, &query.q);
+    let rows = match note_query.filter(|_| can_read_notes) {
+        Some(note_query) => match search_notes(&state, &access, &note_query, limit).await {
+            Ok(rows) => rows,
+            Err(e) => {
+                eprintln!("Failed to search notes: {}", e);
+                return (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
+                    .into_response();
+            }
+        },
+        None => Vec::new(),
+    };
+
+    let mut notes = Vec::with_capacity(rows.len());
+    for row in rows {
+        let Some(content) =
+            note_crypto::open(keys, &row.note_id, row.key_id.as_deref(), &row.content)
+        else {
+            eprintln!("Failed to decrypt note {}", row.note_id);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to decrypt note").into_response();
+        };
+        notes.push(NoteHit {
+            snippet: note_crypto::snippet(&content, &query.q),
+            note_id: row.note_id,
+            target_discord_id: row.target_discord_id,
+            target_username: row.target_username,
+            category: row.category,
+            rank: row.rank,
+        });
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::Search,
+        access.user.id,
+        None,
+        &format!(
+            "Searched tribe {} ({} members, {} notes)",
+            access.tribe,
+            members.len(),
+            notes.len()
+        ),
+    )
+    .await;
+
+    Json(SearchResults {
+        tribe: access.tribe,
+        members,
+        notes,
+    })
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::DbPool,
+        test_support::{access, add_members, insert_users, setup_db},
+    };
+    use chrono::Utc;
+
+    async fn setup() -> DbPool {
+        let pool = setup_db().await;
+        insert_users(
+            &pool,
+            &[
+                (1, "111", "Chief"),
+                (2, "222", "Scout"),
+                (3, "333", "Outsider"),
+                (4, "444", "Recruiter"),
+            ],
+        )
+        .await;
+        add_members(
+            &pool,
+            &[
+                (1, "Fire", true),
+                (2, "Fire", false),
+                (4, "Fire", false),
+                (3, "Water", false),
+            ],
+        )
+        .await;
+        sqlx::query("INSERT INTO user_roles (id, user_id, role_id, tribe) VALUES ('r1', 4, 'recruiter', 'Fire')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO wallets (id, user_id, address, verified_at, character_name) VALUES ('w1', 2, '0xabc123', ?, 'Stormrider'), ('w2', 3, '0xabc999', ?, 'Stormcaller')")
+            .bind(Utc::now())
+            .bind(Utc::now())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let now = Utc::now();
+        for (id, author, content, visibility) in [
+            (
+                "n1",
+                1,
+                "Scouted enemy storm fleet near the gate",
+                "TRIBE_ADMINS",
+            ),
+            ("n2", 1, "Storm is a possible spy", "AUTHOR"),
+            ("n3", 1, "Storm fleet again", "TRIBE_ADMINS"),
+        ] {
+            sqlx::query("INSERT INTO notes (id, target_user_id, author_id, tribe, content, created_at, updated_at, visibility) VALUES (?, 2, ?, 'Fire', ?, ?, ?, ?)")
+                .bind(id)
+                .bind(author)
+                .bind(content)
+                .bind(now)
+                .bind(now)
+                .bind(visibility)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        // Notes written straight to the table are indexed by the background pass
+        note_crypto::reseal_all(&pool, None).await.unwrap();
+
+        pool
+    }
+
+    async fn search(db: &DbPool, user_id: i64, q: &str) -> serde_json::Value {
+        let res = search_tribe(
+            access(db, user_id, "Fire").await,
+            Query(SearchQuery {
+                tribe: Some("Fire".to_string()),
+                q: q.to_string(),
+                limit: None,
+            }),
+            State(AppState::new(db.clone())),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn ids(hits: &serde_json::Value, field: &str) -> Vec<String> {
+        hits.as_array()
+            .unwrap()
+            .iter()
+            .map(|h| h[field].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_fts_query() {
+        assert_eq!(fts_query("  "), None);
+        assert_eq!(
+            fts_query("storm 0xabc").as_deref(),
+            Some("\"storm\"* \"0xabc\"*")
+        );
+        // Quotes and operators in user input can't escape the terms
+        assert_eq!(
+            fts_query("\"a\" OR username:b").as_deref(),
+            Some("\"a\"* \"OR\"* \"username:b\"*")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_members_and_notes() {
+        let db = setup().await;
+
+        // Character names and address prefixes; the Water member is out of scope
+        let json = search(&db, 1, "storm").await;
+        assert_eq!(ids(&json["members"], "username"), vec!["Scout"]);
+        assert!(json["members"][0]["snippet"]
+            .as_str()
+            .unwrap()
+            .contains("**Stormrider**"));
+        assert_eq!(json["notes"].as_array().unwrap().len(), 3);
+        let json = search(&db, 1, "0xabc").await;
+        assert_eq!(ids(&json["members"], "username"), vec!["Scout"]);
+
+        // All terms must match
+        let json = search(&db, 1, "storm fleet gate").await;
+        assert_eq!(ids(&json["notes"], "noteId"), vec!["n1"]);
+
+        // Edits and deletions are reflected in the index
+        sqlx::query("UPDATE notes SET content = 'Cleared' WHERE id = 'n3'")
+            .execute(&db)
+            .await
+            .unwrap();
+        let mut conn = db.acquire().await.unwrap();
+        note_crypto::index_note(&mut conn, None, "n3", "Cleared")
+            .await
+            .unwrap();
+        drop(conn);
+        sqlx::query("UPDATE notes SET deleted_at = ? WHERE id = 'n1'")
+            .bind(Utc::now())
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE users SET username = 'Pathfinder' WHERE id = 2")
+            .execute(&db)
+            .await
+            .unwrap();
+        let json = search(&db, 1, "storm").await;
+        assert_eq!(ids(&json["notes"], "noteId"), vec!["n2"]);
+        assert_eq!(ids(&json["members"], "username"), vec!["Pathfinder"]);
+
+        // Author-only notes stay hidden from other note readers
+        let json = search(&db, 4, "storm").await;
+        assert!(json["notes"].as_array().unwrap().is_empty());
+        assert_eq!(json["members"].as_array().unwrap().len(), 1);
+
+        // Without notes.read only members are searched
+        let json = search(&db, 2, "storm").await;
+        assert!(json["notes"].as_array().unwrap().is_empty());
+
+        sqlx::query("UPDATE wallets SET deleted_at = ? WHERE id = 'w1'")
+            .bind(Utc::now())
+            .execute(&db)
+            .await
+            .unwrap();
+        let json = search(&db, 1, "0xabc").await;
+        assert!(json["members"].as_array().unwrap().is_empty());
+    }
+}
@@ -1,4 +1,7 @@
+use crate::character::{character_client_from_env, CharacterClient};
 use crate::db::DbPool;
+use crate::note_crypto::NoteKeys;
+use crate::storage::{blob_store_from_env, BlobStore};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 
@@ -24,6 +27,11 @@ pub struct AppState {
     pub identity_hash_pepper: String,
     pub oauth_states: OAuthStates,
     pub auth_codes: AuthCodes,
+    pub character_client: Arc<dyn CharacterClient>,
+    /// Keys for note content at rest; notes are stored as plaintext when absent
+    pub note_keys: Option<Arc<NoteKeys>>,
+    /// Where note attachment files are kept
+    pub attachment_store: Arc<dyn BlobStore>,
 }
 
 impl AppState {
@@ -32,6 +40,9 @@ impl AppState {
             std::env::var("MUMBLE_REQUIRED_TRIBE").unwrap_or_else(|_| "Fire".to_string());
         let identity_hash_pepper = std::env::var("IDENTITY_HASH_PEPPER")
             .expect("IDENTITY_HASH_PEPPER must be set for security and deterministic hashing");
+        let note_keys = NoteKeys::from_env()
+            .unwrap_or_else(|e| panic!("Invalid notes encryption configuration: {}", e))
+            .map(Arc::new);
         Self {
             db,
             wallet_nonces: Arc::new(Mutex::new(HashMap::new())),
@@ -40,6 +51,9 @@ impl AppState {
             identity_hash_pepper,
             oauth_states: Arc::new(Mutex::new(HashMap::new())),
             auth_codes: Arc::new(Mutex::new(HashMap::new())),
+            character_client: character_client_from_env(),
+            note_keys,
+            attachment_store: blob_store_from_env(),
         }
     }
 }
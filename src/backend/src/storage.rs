@@ -0,0 +1,107 @@
+use std::{env, future::Future, path::PathBuf, pin::Pin, sync::Arc};
+
+pub type StorageFuture<'a, T> = Pin<Box<dyn Future<Output = anyhow::Result<T>> + Send + 'a>>;
+
+/// Where attachment bytes live. Keys are generated by the backend and only ever
+/// contain ASCII letters, digits and dashes. A backend for S3-compatible object
+/// storage implements the same three operations.
+pub trait BlobStore: Send + Sync {
+    fn put<'a>(&'a self, key: &'a str, bytes: Vec<u8>) -> StorageFuture<'a, ()>;
+    /// `Ok(None)` when nothing is stored under the key
+    fn get<'a>(&'a self, key: &'a str) -> StorageFuture<'a, Option<Vec<u8>>>;
+    /// Deleting a missing key succeeds
+    fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()>;
+}
+
+/// Stores each blob as a file under a root directory, fanned out by the first
+/// two characters of the key.
+pub struct FsBlobStore {
+    root: PathBuf,
+}
+
+impl FsBlobStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path(&self, key: &str) -> anyhow::Result<PathBuf> {
+        if key.len() < 3 || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            anyhow::bail!("invalid storage key");
+        }
+        Ok(self.root.join(&key[..2]).join(key))
+    }
+}
+
+impl BlobStore for FsBlobStore {
+    fn put<'a>(&'a self, key: &'a str, bytes: Vec<u8>) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            let path = self.path(key)?;
+            if let Some(dir) = path.parent() {
+                tokio::fs::create_dir_all(dir).await?;
+            }
+            // Write then rename so a reader never sees a partial file
+            let tmp = path.with_extension("tmp");
+            tokio::fs::write(&tmp, bytes).await?;
+            tokio::fs::rename(&tmp, &path).await?;
+            Ok(())
+        })
+    }
+
+    fn get<'a>(&'a self, key: &'a str) -> StorageFuture<'a, Option<Vec<u8>>> {
+        Box::pin(async move {
+            match tokio::fs::read(self.path(key)?).await {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+
+    fn delete<'a>(&'a self, key: &'a str) -> StorageFuture<'a, ()> {
+        Box::pin(async move {
+            match tokio::fs::remove_file(self.path(key)?).await {
+                Ok(()) => Ok(()),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+                Err(e) => Err(e.into()),
+            }
+        })
+    }
+}
+
+/// Build the attachment store from the environment: files under
+/// `ATTACHMENT_STORAGE_DIR` (default `data/attachments`).
+pub fn blob_store_from_env() -> Arc<dyn BlobStore> {
+    let root = env::var("ATTACHMENT_STORAGE_DIR")
+        .ok()
+        .filter(|dir| !dir.trim().is_empty())
+        .unwrap_or_else(|| "data/attachments".to_string());
+    Arc::new(FsBlobStore::new(root))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_fs_blob_store() {
+        let root = env::temp_dir().join(format!("void-eid-blobs-{}", uuid::Uuid::new_v4()));
+        let store = FsBlobStore::new(&root);
+
+        store.put("abc-123", b"evidence".to_vec()).await.unwrap();
+        assert!(root.join("ab").join("abc-123").exists());
+        assert_eq!(
+            store.get("abc-123").await.unwrap().as_deref(),
+            Some(&b"evidence"[..])
+        );
+
+        store.delete("abc-123").await.unwrap();
+        assert_eq!(store.get("abc-123").await.unwrap(), None);
+        store.delete("abc-123").await.unwrap();
+
+        // Keys can't reach outside the root
+        assert!(store.get("../etc/passwd").await.is_err());
+        assert!(store.put("a/b", Vec::new()).await.is_err());
+
+        let _ = std::fs::remove_dir_all(root);
+    }
+}
@@ -0,0 +1,442 @@
+use crate::{
+    admin::get_admin_id,
+    approvals::reject_operations_of,
+    audit::{alert_admin_action, log_audit, AuditAction},
+    db::DbPool,
+    middleware::admin::RequireSuperAdmin,
+    state::AppState,
+    totp::{super_admin_step_up_required, totp_enrolled},
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SuperAdminResponse {
+    pub discord_id: String,
+    /// `None` until the super admin has logged in
+    pub username: Option<String>,
+    /// Discord ID of the nominating super admin; `None` for admins seeded from the environment
+    pub nominated_by: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct NominateSuperAdminRequest {
+    pub discord_id: String,
+}
+
+/// Fewest super admins allowed to remain, from `SUPER_ADMIN_MIN_COUNT` (default and floor 1).
+pub fn min_super_admins() -> i64 {
+    std::env::var("SUPER_ADMIN_MIN_COUNT")
+        .ok()
+        .and_then(|v| v.parse::<i64>().ok())
+        .unwrap_or(1)
+        .max(1)
+}
+
+/// Seed the registry from `SUPER_ADMIN_DISCORD_IDS` while it is still empty.
+/// Once populated, the registry is managed through the API and the variable is ignored.
+pub async fn seed_from_env(db: &DbPool) -> Result<(), sqlx::Error> {
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM super_admins")
+        .fetch_one(db)
+        .await?;
+    if count > 0 {
+        return Ok(());
+    }
+
+    let ids = std::env::var("SUPER_ADMIN_DISCORD_IDS").unwrap_or_default();
+    for id in ids.split(',').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        sqlx::query("INSERT OR IGNORE INTO super_admins (discord_id, created_at) VALUES (?, ?)")
+            .bind(id)
+            .bind(Utc::now())
+            .execute(db)
+            .await?;
+    }
+    Ok(())
+}
+
+pub async fn is_super_admin(db: &DbPool, discord_id: &str) -> Result<bool, sqlx::Error> {
+    let found: Option<String> =
+        sqlx::query_scalar("SELECT discord_id FROM super_admins WHERE discord_id = ?")
+            .bind(discord_id)
+            .fetch_optional(db)
+            .await?;
+    Ok(found.is_some())
+}
+
+pub async fn super_admin_ids(db: &DbPool) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT discord_id FROM super_admins")
+        .fetch_all(db)
+        .await
+}
+
+async fn user_id_for(db: &DbPool, discord_id: &str) -> Option<i64> {
+    sqlx::query_scalar("SELECT id FROM users WHERE discord_id = ?")
+        .bind(discord_id)
+        .fetch_optional(db)
+        .await
+        .unwrap_or(None)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/super-admins",
+    tag = "Admin",
+    responses(
+        (status = 200, description = "All super admins", body = Vec<SuperAdminResponse>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn list_super_admins(
+    State(state): State<AppState>,
+    _admin: RequireSuperAdmin,
+) -> impl IntoResponse {
+    let result = sqlx::query_as::<_, SuperAdminResponse>(
+        r#"
+        SELECT s.discord_id, u.username, s.nominated_by, s.created_at
+        FROM super_admins s
+        LEFT JOIN users u ON u.discord_id = s.discord_id
+        ORDER BY s.created_at
+        "#,
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    match result {
+        Ok(admins) => Json(admins).into_response(),
+        Err(e) => {
+            eprintln!("Failed to list super admins: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/admin/super-admins",
+    tag = "Admin",
+    request_body = NominateSuperAdminRequest,
+    responses(
+        (status = 201, description = "Super admin nominated"),
+        (status = 400, description = "Invalid Discord ID"),
+        (status = 409, description = "Already a super admin, or the nominee hasn't enabled TOTP while super admin step-up is required"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn nominate_super_admin(
+    State(state): State<AppState>,
+    admin: RequireSuperAdmin,
+    Json(payload): Json<NominateSuperAdminRequest>,
+) -> impl IntoResponse {
+    let discord_id = payload.discord_id.trim().to_string();
+    if discord_id.is_empty() || !discord_id.chars().all(|c| c.is_ascii_digit()) {
+        return (StatusCode::BAD_REQUEST, "Invalid Discord ID").into_response();
+    }
+
+    // While step-up is required, a super admin without TOTP couldn't use any admin endpoint
+    let enrolment = match super_admin_step_up_required(&state.db).await {
+        Ok(true) => totp_enrolled(&state.db, &discord_id).await.map(Some),
+        Ok(false) => Ok(None),
+        Err(e) => Err(e),
+    };
+    match enrolment {
+        Ok(Some(false)) => {
+            return (
+                StatusCode::CONFLICT,
+                "The nominee must enable TOTP while super admin step-up is required",
+            )
+                .into_response()
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Failed to check nominee TOTP enrolment: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+
+    let result = sqlx::query(
+        "INSERT INTO super_admins (discord_id, nominated_by, created_at) VALUES (?, ?, ?)",
+    )
+    .bind(&discord_id)
+    .bind(&admin.discord_id)
+    .bind(Utc::now())
+    .execute(&state.db)
+    .await;
+
+    if let Err(e) = result {
+        eprintln!("Failed to nominate super admin: {}", e);
+        return (StatusCode::CONFLICT, "Already a super admin").into_response();
+    }
+
+    let details = format!("Nominated {} as super admin", discord_id);
+    let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+    let target_id = user_id_for(&state.db, &discord_id).await;
+    let _ = log_audit(
+        &state.db,
+        AuditAction::SuperAdminNominate,
+        admin_id,
+        target_id,
+        &details,
+    )
+    .await;
+    alert_admin_action(
+        format!("SuperAdmin ({})", admin.discord_id),
+        AuditAction::SuperAdminNominate,
+        details,
+    );
+
+    StatusCode::CREATED.into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/super-admins/{discord_id}",
+    tag = "Admin",
+    params(
+        ("discord_id" = String, Path, description = "Discord ID of the super admin")
+    ),
+    responses(
+        (status = 200, description = "Super admin removed"),
+        (status = 404, description = "Not a super admin"),
+        (status = 409, description = "Removal would leave too few super admins"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn remove_super_admin(
+    State(state): State<AppState>,
+    admin: RequireSuperAdmin,
+    Path(discord_id): Path<String>,
+) -> impl IntoResponse {
+    let mut tx = match state.db.begin().await {
+        Ok(tx) => tx,
+        Err(e) => {
+            eprintln!("Failed to start transaction for remove_super_admin: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    // Count and delete in one transaction so concurrent removals can't both pass the check
+    let count: i64 = match sqlx::query_scalar("SELECT COUNT(*) FROM super_admins")
+        .fetch_one(&mut *tx)
+        .await
+    {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Failed to count super admins: {}", e);
+            let _ = tx.rollback().await;
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let deleted = match sqlx::query("DELETE FROM super_admins WHERE discord_id = ?")
+        .bind(&discord_id)
+        .execute(&mut *tx)
+        .await
+    {
+        Ok(r) => r.rows_affected(),
+        Err(e) => {
+            eprintln!("Failed to remove super admin: {}", e);
+            let _ = tx.rollback().await;
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    if deleted == 0 {
+        let _ = tx.rollback().await;
+        return (StatusCode::NOT_FOUND, "Not a super admin").into_response();
+    }
+
+    if count - 1 < min_super_admins() {
+        let _ = tx.rollback().await;
+        return (
+            StatusCode::CONFLICT,
+            "Removal would leave too few super admins",
+        )
+            .into_response();
+    }
+
+    if let Err(e) = tx.commit().await {
+        eprintln!("Transaction commit failed for remove_super_admin: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+    // Operations they requested would otherwise run as them once approved
+    if let Err(e) = reject_operations_of(&state.db, &discord_id, &admin.discord_id, admin_id).await
+    {
+        eprintln!(
+            "Failed to reject pending operations of {}: {}",
+            discord_id, e
+        );
+    }
+
+    let details = format!("Removed {} as super admin", discord_id);
+    let target_id = user_id_for(&state.db, &discord_id).await;
+    let _ = log_audit(
+        &state.db,
+        AuditAction::SuperAdminRemove,
+        admin_id,
+        target_id,
+        &details,
+    )
+    .await;
+    alert_admin_action(
+        format!("SuperAdmin ({})", admin.discord_id),
+        AuditAction::SuperAdminRemove,
+        details,
+    );
+
+    StatusCode::OK.into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{insert_users, setup_db};
+
+    async fn setup() -> DbPool {
+        let pool = setup_db().await;
+        insert_users(&pool, &[(1, "111", "First"), (2, "222", "Second")]).await;
+        sqlx::query("INSERT INTO super_admins (discord_id) VALUES ('111')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    fn as_admin(discord_id: &str) -> RequireSuperAdmin {
+        RequireSuperAdmin {
+            discord_id: discord_id.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_seed_only_when_empty() {
+        let db = setup().await;
+        // Already populated: seeding is a no-op whatever the environment says
+        seed_from_env(&db).await.unwrap();
+        assert_eq!(super_admin_ids(&db).await.unwrap(), vec!["111"]);
+
+        sqlx::query("DELETE FROM super_admins")
+            .execute(&db)
+            .await
+            .unwrap();
+        std::env::set_var("SUPER_ADMIN_DISCORD_IDS", " 333, ,444");
+        seed_from_env(&db).await.unwrap();
+        let mut ids = super_admin_ids(&db).await.unwrap();
+        ids.sort();
+        assert_eq!(ids, vec!["333", "444"]);
+    }
+
+    #[tokio::test]
+    async fn test_nominate_and_remove() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+
+        let res = nominate_super_admin(
+            State(state.clone()),
+            as_admin("111"),
+            Json(NominateSuperAdminRequest {
+                discord_id: "222".to_string(),
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::CREATED);
+        assert!(is_super_admin(&db, "222").await.unwrap());
+
+        let res = nominate_super_admin(
+            State(state.clone()),
+            as_admin("111"),
+            Json(NominateSuperAdminRequest {
+                discord_id: "222".to_string(),
+            }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+
+        sqlx::query("INSERT INTO pending_operations (id, action, payload, reason, requested_by, requested_at, expires_at) VALUES ('op1', 'delete_wallet', '{\"wallet_id\":\"w1\"}', 'cleanup', '111', ?, ?)")
+            .bind(Utc::now())
+            .bind(Utc::now() + chrono::Duration::hours(24))
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let res = remove_super_admin(State(state.clone()), as_admin("222"), Path("111".into()))
+            .await
+            .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(!is_super_admin(&db, "111").await.unwrap());
+
+        // Their pending operations are rejected rather than left to run as them
+        let status: String =
+            sqlx::query_scalar("SELECT status FROM pending_operations WHERE id = 'op1'")
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(status, "REJECTED");
+
+        let actions: Vec<String> = sqlx::query_scalar(
+            "SELECT action FROM audit_logs WHERE target_id IS NOT NULL ORDER BY created_at",
+        )
+        .fetch_all(&db)
+        .await
+        .unwrap();
+        assert_eq!(actions, vec!["SUPER_ADMIN_NOMINATE", "SUPER_ADMIN_REMOVE"]);
+    }
+
+    #[tokio::test]
+    async fn test_nominee_needs_totp_while_step_up_required() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+        sqlx::query("UPDATE step_up_policies SET required = TRUE WHERE role = 'super_admin'")
+            .execute(&db)
+            .await
+            .unwrap();
+        let nominate = || async {
+            nominate_super_admin(
+                State(state.clone()),
+                as_admin("111"),
+                Json(NominateSuperAdminRequest {
+                    discord_id: "222".to_string(),
+                }),
+            )
+            .await
+            .into_response()
+            .status()
+        };
+
+        assert_eq!(nominate().await, StatusCode::CONFLICT);
+        assert!(!is_super_admin(&db, "222").await.unwrap());
+
+        sqlx::query("INSERT INTO user_totp (user_id, secret_ciphertext, confirmed_at) VALUES (2, 'x', CURRENT_TIMESTAMP)")
+            .execute(&db)
+            .await
+            .unwrap();
+        assert_eq!(nominate().await, StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn test_last_super_admin_cannot_be_removed() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+
+        let res = remove_super_admin(State(state), as_admin("111"), Path("111".into()))
+            .await
+            .into_response();
+        assert_eq!(res.status(), StatusCode::CONFLICT);
+        assert!(is_super_admin(&db, "111").await.unwrap());
+    }
+}
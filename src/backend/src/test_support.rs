@@ -0,0 +1,53 @@
+//! Fixtures shared by the handler tests.
+
+use crate::{db::DbPool, helpers::get_user_by_id, rbac::TribeAccess};
+use sqlx::sqlite::SqlitePoolOptions;
+
+/// A fresh in-memory database with every migration applied.
+pub async fn setup_db() -> DbPool {
+    let pool = SqlitePoolOptions::new()
+        .connect("sqlite::memory:")
+        .await
+        .expect("Failed to create memory pool");
+
+    sqlx::migrate!("./migrations")
+        .run(&pool)
+        .await
+        .expect("Migrations failed");
+
+    pool
+}
+
+/// Insert plain users as `(id, discord_id, username)`.
+pub async fn insert_users(db: &DbPool, users: &[(i64, &str, &str)]) {
+    for (id, discord_id, username) in users {
+        sqlx::query(
+            "INSERT INTO users (id, discord_id, username, discriminator) VALUES (?, ?, ?, '0000')",
+        )
+        .bind(id)
+        .bind(discord_id)
+        .bind(username)
+        .execute(db)
+        .await
+        .unwrap();
+    }
+}
+
+/// Add tribe memberships as `(user_id, tribe, is_admin)`.
+pub async fn add_members(db: &DbPool, members: &[(i64, &str, bool)]) {
+    for (user_id, tribe, is_admin) in members {
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe, is_admin) VALUES (?, ?, ?)")
+            .bind(user_id)
+            .bind(tribe)
+            .bind(is_admin)
+            .execute(db)
+            .await
+            .unwrap();
+    }
+}
+
+/// The access a handler would see for `user_id` acting in `tribe`.
+pub async fn access<P>(db: &DbPool, user_id: i64, tribe: &str) -> TribeAccess<P> {
+    let user = get_user_by_id(db, user_id).await.unwrap().unwrap();
+    TribeAccess::new(user, tribe.to_string(), vec![tribe.to_string()])
+}
@@ -0,0 +1,1166 @@
+use crate::{
+    admin::get_admin_id,
+    audit::{alert_admin_action, log_audit, AuditAction},
+    auth::{decode_bearer, AuthenticatedUser, Claims},
+    db::DbPool,
+    helpers::ApiResult,
+    middleware::admin::RequireSuperAdmin,
+    models::i64_as_string,
+    state::AppState,
+};
+use axum::{
+    extract::{FromRef, FromRequestParts, Path, State},
+    http::{request::Parts, HeaderMap, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use base64::{engine::general_purpose::STANDARD, Engine as _};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    XChaCha20Poly1305, XNonce,
+};
+use chrono::{DateTime, Utc};
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use jsonwebtoken::{encode, EncodingKey, Header};
+use rand::{distr::Alphanumeric, RngExt};
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use utoipa::ToSchema;
+
+/// Policy row that applies to super admins on `/api/admin/*`
+pub const SUPER_ADMIN_POLICY: &str = "super_admin";
+
+const DIGITS: u32 = 6;
+const PERIOD_SECONDS: i64 = 30;
+/// Steps either side of now that are still accepted, to allow for clock drift
+const DRIFT_STEPS: i64 = 1;
+const SECRET_BYTES: usize = 20;
+const NONCE_BYTES: usize = 24;
+const RECOVERY_CODE_COUNT: usize = 10;
+const MAX_POLICY_AGE_MINUTES: i64 = 1440;
+
+type HmacSha1 = Hmac<Sha1>;
+
+/// RFC 4226 HOTP value for `counter`.
+fn hotp(secret: &[u8], counter: u64) -> u32 {
+    let mut mac =
+        <HmacSha1 as Mac>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(&counter.to_be_bytes());
+    let hash = mac.finalize().into_bytes();
+
+    let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        hash[offset] & 0x7f,
+        hash[offset + 1],
+        hash[offset + 2],
+        hash[offset + 3],
+    ]);
+    binary % 10u32.pow(DIGITS)
+}
+
+/// RFC 6238 time step for a Unix timestamp.
+fn time_step(unix_seconds: i64) -> i64 {
+    unix_seconds.div_euclid(PERIOD_SECONDS)
+}
+
+/// Check `code` against the steps around `now`, returning the matched step.
+/// Steps at or before `last_used_step` are rejected so a code can't be replayed.
+fn verify_code(secret: &[u8], code: &str, now: i64, last_used_step: Option<i64>) -> Option<i64> {
+    let code = code.trim();
+    if code.len() != DIGITS as usize || !code.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let current = time_step(now);
+    (current - DRIFT_STEPS..=current + DRIFT_STEPS)
+        .filter(|step| *step >= 0 && last_used_step.is_none_or(|last| *step > last))
+        .find(|step| {
+            let expected = format!(
+                "{:0width$}",
+                hotp(secret, *step as u64),
+                width = DIGITS as usize
+            );
+            bool::from(expected.as_bytes().ct_eq(code.as_bytes()))
+        })
+}
+
+fn encryption_key() -> ApiResult<[u8; 32]> {
+    let not_configured = (StatusCode::SERVICE_UNAVAILABLE, "TOTP is not configured");
+    let encoded = std::env::var("TOTP_ENCRYPTION_KEY").map_err(|_| not_configured)?;
+    STANDARD
+        .decode(encoded.trim())
+        .ok()
+        .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+        .ok_or(not_configured)
+}
+
+/// Encrypt a TOTP secret with XChaCha20-Poly1305, bound to the owning user.
+/// Stored as base64(nonce || ciphertext).
+fn encrypt_secret(key: &[u8; 32], user_id: i64, secret: &[u8]) -> String {
+    let cipher = XChaCha20Poly1305::new(key.into());
+    let nonce: [u8; NONCE_BYTES] = rand::random();
+    let aad = user_id.to_string();
+    let ciphertext = cipher
+        .encrypt(
+            XNonce::from_slice(&nonce),
+            Payload {
+                msg: secret,
+                aad: aad.as_bytes(),
+            },
+        )
+        .expect("encryption of a short secret cannot fail");
+
+    let mut stored = nonce.to_vec();
+    stored.extend_from_slice(&ciphertext);
+    STANDARD.encode(stored)
+}
+
+fn decrypt_secret(key: &[u8; 32], user_id: i64, stored: &str) -> Option<Vec<u8>> {
+    let bytes = STANDARD.decode(stored).ok()?;
+    if bytes.len() <= NONCE_BYTES {
+        return None;
+    }
+    let (nonce, ciphertext) = bytes.split_at(NONCE_BYTES);
+    let aad = user_id.to_string();
+    XChaCha20Poly1305::new(key.into())
+        .decrypt(
+            XNonce::from_slice(nonce),
+            Payload {
+                msg: ciphertext,
+                aad: aad.as_bytes(),
+            },
+        )
+        .ok()
+}
+
+fn hash_recovery_code(code: &str) -> String {
+    let normalised: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    hex::encode(Sha256::digest(normalised.as_bytes()))
+}
+
+fn generate_recovery_code() -> String {
+    let raw: String = rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(10)
+        .map(|b| char::from(b).to_ascii_lowercase())
+        .collect();
+    format!("{}-{}", &raw[..5], &raw[5..])
+}
+
+/// Replace a user's recovery codes, returning the new codes in clear (shown once).
+async fn replace_recovery_codes(db: &DbPool, user_id: i64) -> Result<Vec<String>, sqlx::Error> {
+    let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+        .map(|_| generate_recovery_code())
+        .collect();
+
+    let mut tx = db.begin().await?;
+    sqlx::query("DELETE FROM totp_recovery_codes WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    for code in &codes {
+        sqlx::query("INSERT INTO totp_recovery_codes (user_id, code_hash) VALUES (?, ?)")
+            .bind(user_id)
+            .bind(hash_recovery_code(code))
+            .execute(&mut *tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    Ok(codes)
+}
+
+#[derive(sqlx::FromRow)]
+struct TotpRow {
+    secret_ciphertext: String,
+    confirmed_at: Option<DateTime<Utc>>,
+    last_used_step: Option<i64>,
+}
+
+async fn fetch_totp(db: &DbPool, user_id: i64) -> ApiResult<Option<TotpRow>> {
+    sqlx::query_as::<_, TotpRow>(
+        "SELECT secret_ciphertext, confirmed_at, last_used_step FROM user_totp WHERE user_id = ?",
+    )
+    .bind(user_id)
+    .fetch_optional(db)
+    .await
+    .map_err(|e| {
+        eprintln!("Failed to fetch TOTP enrolment: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, "Database error")
+    })
+}
+
+/// Check a TOTP code against the user's secret and record the step it used.
+/// `confirmed` selects whether an active or a pending enrolment is checked.
+async fn consume_totp_code(
+    db: &DbPool,
+    user_id: i64,
+    code: &str,
+    confirmed: bool,
+) -> ApiResult<()> {
+    let row = fetch_totp(db, user_id)
+        .await?
+        .filter(|r| r.confirmed_at.is_some() == confirmed)
+        .ok_or((StatusCode::BAD_REQUEST, "TOTP is not enabled"))?;
+
+    let key = encryption_key()?;
+    let secret = decrypt_secret(&key, user_id, &row.secret_ciphertext).ok_or((
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "TOTP secret could not be decrypted",
+    ))?;
+
+    let step = verify_code(&secret, code, Utc::now().timestamp(), row.last_used_step)
+        .ok_or((StatusCode::UNAUTHORIZED, "Invalid code"))?;
+
+    // Conditional update so two concurrent requests can't both spend the same step
+    let updated = sqlx::query(
+        "UPDATE user_totp SET last_used_step = ? WHERE user_id = ? AND (last_used_step IS NULL OR last_used_step < ?)",
+    )
+    .bind(step)
+    .bind(user_id)
+    .bind(step)
+    .execute(db)
+    .await
+    .map_err(|e| {
+        eprintln!("Failed to record TOTP step: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, "Database error")
+    })?;
+
+    if updated.rows_affected() == 0 {
+        return Err((StatusCode::UNAUTHORIZED, "Invalid code"));
+    }
+    Ok(())
+}
+
+async fn consume_recovery_code(db: &DbPool, user_id: i64, code: &str) -> ApiResult<()> {
+    let updated = sqlx::query(
+        "UPDATE totp_recovery_codes SET used_at = ? WHERE user_id = ? AND code_hash = ? AND used_at IS NULL",
+    )
+    .bind(Utc::now())
+    .bind(user_id)
+    .bind(hash_recovery_code(code))
+    .execute(db)
+    .await
+    .map_err(|e| {
+        eprintln!("Failed to consume recovery code: {}", e);
+        (StatusCode::INTERNAL_SERVER_ERROR, "Database error")
+    })?;
+
+    if updated.rows_affected() == 0 {
+        return Err((StatusCode::UNAUTHORIZED, "Invalid code"));
+    }
+    Ok(())
+}
+
+/// Verify a second factor for a user with active TOTP: a code, or failing that a recovery code.
+async fn verify_second_factor(db: &DbPool, user_id: i64, payload: &StepUpRequest) -> ApiResult<()> {
+    match (&payload.code, &payload.recovery_code) {
+        (Some(code), _) => consume_totp_code(db, user_id, code, true).await,
+        (None, Some(recovery)) => {
+            // A recovery code only stands in for an active enrolment
+            fetch_totp(db, user_id)
+                .await?
+                .filter(|r| r.confirmed_at.is_some())
+                .ok_or((StatusCode::BAD_REQUEST, "TOTP is not enabled"))?;
+            consume_recovery_code(db, user_id, recovery).await
+        }
+        (None, None) => Err((
+            StatusCode::BAD_REQUEST,
+            "A code or recovery code is required",
+        )),
+    }
+}
+
+// --- Step-up policy ---
+
+/// Check a step-up claim is within `window_minutes`; `None` means no step-up is required.
+pub fn check_step_up(
+    step_up_at: Option<usize>,
+    window_minutes: Option<i64>,
+    now: i64,
+) -> ApiResult<()> {
+    let Some(window) = window_minutes else {
+        return Ok(());
+    };
+    match step_up_at {
+        Some(at) if now - (at as i64) <= window * 60 => Ok(()),
+        _ => Err((StatusCode::FORBIDDEN, "Step-up authentication required")),
+    }
+}
+
+fn policy_error(e: sqlx::Error) -> (StatusCode, &'static str) {
+    eprintln!("Failed to read step-up policy: {}", e);
+    (StatusCode::INTERNAL_SERVER_ERROR, "Database error")
+}
+
+/// Enforce the super admin step-up policy for `/api/admin/*`.
+pub async fn require_super_admin_step_up(db: &DbPool, step_up_at: Option<usize>) -> ApiResult<()> {
+    let window: Option<i64> = sqlx::query_scalar(
+        "SELECT max_age_minutes FROM step_up_policies WHERE role = ? AND required = TRUE",
+    )
+    .bind(SUPER_ADMIN_POLICY)
+    .fetch_optional(db)
+    .await
+    .map_err(policy_error)?;
+
+    check_step_up(step_up_at, window, Utc::now().timestamp())
+}
+
+/// Whether the super admin policy currently requires step-up
+pub async fn super_admin_step_up_required(db: &DbPool) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM step_up_policies WHERE role = ? AND required = TRUE)",
+    )
+    .bind(SUPER_ADMIN_POLICY)
+    .fetch_one(db)
+    .await
+}
+
+/// Whether the user with this Discord ID has confirmed TOTP and so can step up
+pub async fn totp_enrolled(db: &DbPool, discord_id: &str) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM user_totp t JOIN users u ON u.id = t.user_id WHERE u.discord_id = ? AND t.confirmed_at IS NOT NULL)",
+    )
+    .bind(discord_id)
+    .fetch_one(db)
+    .await
+}
+
+/// Super admins who couldn't step up because they haven't confirmed TOTP
+async fn unenrolled_super_admins(db: &DbPool) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"
+        SELECT sa.discord_id FROM super_admins sa
+        WHERE NOT EXISTS (
+            SELECT 1 FROM user_totp t JOIN users u ON u.id = t.user_id
+            WHERE u.discord_id = sa.discord_id AND t.confirmed_at IS NOT NULL)
+        ORDER BY sa.discord_id
+        "#,
+    )
+    .fetch_all(db)
+    .await
+}
+
+/// Enforce the strictest step-up policy among the roles a user holds in any tribe,
+/// directly or through their rank, plus the super admin policy if they are one.
+pub async fn require_user_step_up(db: &DbPool, claims: &Claims) -> ApiResult<()> {
+    let user_id = claims.id.parse::<i64>().unwrap_or_default();
+    let window: Option<i64> = sqlx::query_scalar(
+        r#"
+        SELECT MIN(max_age_minutes) FROM step_up_policies
+        WHERE required = TRUE
+          AND (role IN (
+                   SELECT role_id FROM user_roles WHERE user_id = ?
+                   UNION
+                   SELECT r.role_id
+                   FROM user_tribes ut
+                   JOIN tribe_ranks cur ON cur.id = ut.rank_id
+                   JOIN tribe_ranks r ON r.tribe = cur.tribe AND r.position <= cur.position
+                   WHERE ut.user_id = ? AND r.role_id IS NOT NULL)
+               OR (role = ? AND EXISTS (SELECT 1 FROM super_admins WHERE discord_id = ?)))
+        "#,
+    )
+    .bind(user_id)
+    .bind(user_id)
+    .bind(SUPER_ADMIN_POLICY)
+    .bind(&claims.discord_id)
+    .fetch_one(db)
+    .await
+    .map_err(policy_error)?;
+
+    check_step_up(claims.step_up_at, window, Utc::now().timestamp())
+}
+
+/// Guard for sensitive non-admin routes (grant-admin, note edits): requires a recent
+/// step-up when any of the caller's roles has a step-up policy.
+pub struct StepUp;
+
+impl<S> FromRequestParts<S> for StepUp
+where
+    AppState: FromRef<S>,
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, &'static str);
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let claims = decode_bearer(&parts.headers)?;
+        let state = AppState::from_ref(state);
+        require_user_step_up(&state.db, &claims).await?;
+        Ok(StepUp)
+    }
+}
+
+// --- Request / response types ---
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpStatus {
+    pub enabled: bool,
+    /// An enrolment has been started but not yet confirmed
+    pub pending: bool,
+    pub recovery_codes_remaining: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpEnrollment {
+    /// Base32 secret for manual entry
+    pub secret: String,
+    /// `otpauth://` URI for QR codes
+    pub otpauth_uri: String,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryCodesResponse {
+    /// Shown once; only hashes are stored
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TotpCodeRequest {
+    pub code: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct StepUpRequest {
+    pub code: Option<String>,
+    pub recovery_code: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct StepUpResponse {
+    pub token: String,
+}
+
+#[derive(Serialize, ToSchema, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct StepUpPolicy {
+    /// Role ID, or `super_admin` for the super admin endpoints
+    pub role: String,
+    pub required: bool,
+    pub max_age_minutes: i64,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateStepUpPolicyRequest {
+    pub required: bool,
+    pub max_age_minutes: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TotpResetResponse {
+    #[serde(with = "i64_as_string")]
+    #[schema(value_type = String)]
+    pub user_id: i64,
+}
+
+// --- User endpoints ---
+
+#[utoipa::path(
+    get,
+    path = "/api/me/totp",
+    responses(
+        (status = 200, description = "TOTP enrolment status", body = TotpStatus)
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn get_totp_status(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let row = match fetch_totp(&state.db, auth_user.user_id).await {
+        Ok(r) => r,
+        Err(e) => return e.into_response(),
+    };
+
+    let remaining: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM totp_recovery_codes WHERE user_id = ? AND used_at IS NULL",
+    )
+    .bind(auth_user.user_id)
+    .fetch_one(&state.db)
+    .await
+    .unwrap_or(0);
+
+    Json(TotpStatus {
+        enabled: row.as_ref().is_some_and(|r| r.confirmed_at.is_some()),
+        pending: row.as_ref().is_some_and(|r| r.confirmed_at.is_none()),
+        recovery_codes_remaining: remaining,
+    })
+    .into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/me/totp/enroll",
+    responses(
+        (status = 200, description = "New TOTP secret to confirm", body = TotpEnrollment),
+        (status = 409, description = "TOTP is already enabled"),
+        (status = 503, description = "TOTP is not configured")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn enroll_totp(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let key = match encryption_key() {
+        Ok(k) => k,
+        Err(e) => return e.into_response(),
+    };
+
+    match fetch_totp(&state.db, auth_user.user_id).await {
+        Ok(Some(r)) if r.confirmed_at.is_some() => {
+            return (StatusCode::CONFLICT, "TOTP is already enabled").into_response()
+        }
+        Ok(_) => {}
+        Err(e) => return e.into_response(),
+    }
+
+    let username: String = match sqlx::query_scalar("SELECT username FROM users WHERE id = ?")
+        .bind(auth_user.user_id)
+        .fetch_optional(&state.db)
+        .await
+    {
+        Ok(Some(u)) => u,
+        Ok(None) => return (StatusCode::NOT_FOUND, "User not found").into_response(),
+        Err(e) => {
+            eprintln!("Failed to fetch user for TOTP enrolment: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let secret: [u8; SECRET_BYTES] = rand::random();
+    let ciphertext = encrypt_secret(&key, auth_user.user_id, &secret);
+
+    // Starting again replaces any unconfirmed secret
+    let result = sqlx::query(
+        "INSERT OR REPLACE INTO user_totp (user_id, secret_ciphertext, confirmed_at, last_used_step, created_at) VALUES (?, ?, NULL, NULL, ?)",
+    )
+    .bind(auth_user.user_id)
+    .bind(&ciphertext)
+    .bind(Utc::now())
+    .execute(&state.db)
+    .await;
+
+    if let Err(e) = result {
+        eprintln!("Failed to store TOTP secret: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    let encoded = BASE32_NOPAD.encode(&secret);
+    let issuer = std::env::var("TOTP_ISSUER").unwrap_or_else(|_| "VoID eID".to_string());
+    let otpauth_uri = format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        urlencoding::encode(&issuer),
+        urlencoding::encode(&username),
+        encoded,
+        urlencoding::encode(&issuer),
+        DIGITS,
+        PERIOD_SECONDS
+    );
+
+    Json(TotpEnrollment {
+        secret: encoded,
+        otpauth_uri,
+    })
+    .into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/me/totp/confirm",
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 200, description = "TOTP enabled", body = RecoveryCodesResponse),
+        (status = 400, description = "No pending enrolment"),
+        (status = 401, description = "Invalid code")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn confirm_totp(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<TotpCodeRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = consume_totp_code(&state.db, auth_user.user_id, &payload.code, false).await {
+        return e.into_response();
+    }
+
+    if let Err(e) = sqlx::query("UPDATE user_totp SET confirmed_at = ? WHERE user_id = ?")
+        .bind(Utc::now())
+        .bind(auth_user.user_id)
+        .execute(&state.db)
+        .await
+    {
+        eprintln!("Failed to confirm TOTP: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    let recovery_codes = match replace_recovery_codes(&state.db, auth_user.user_id).await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Failed to create recovery codes: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::TotpEnroll,
+        auth_user.user_id,
+        None,
+        "Enabled TOTP",
+    )
+    .await;
+
+    Json(RecoveryCodesResponse { recovery_codes }).into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/me/totp",
+    request_body = StepUpRequest,
+    responses(
+        (status = 200, description = "TOTP disabled"),
+        (status = 400, description = "TOTP is not enabled"),
+        (status = 401, description = "Invalid code")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn disable_totp(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<StepUpRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = verify_second_factor(&state.db, auth_user.user_id, &payload).await {
+        return e.into_response();
+    }
+
+    if let Err(e) = clear_totp(&state.db, auth_user.user_id).await {
+        eprintln!("Failed to disable TOTP: {}", e);
+        return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::TotpDisable,
+        auth_user.user_id,
+        None,
+        "Disabled TOTP",
+    )
+    .await;
+
+    StatusCode::OK.into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/me/totp/recovery-codes",
+    request_body = TotpCodeRequest,
+    responses(
+        (status = 200, description = "New recovery codes", body = RecoveryCodesResponse),
+        (status = 400, description = "TOTP is not enabled"),
+        (status = 401, description = "Invalid code")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn regenerate_recovery_codes(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<TotpCodeRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = consume_totp_code(&state.db, auth_user.user_id, &payload.code, true).await {
+        return e.into_response();
+    }
+
+    let recovery_codes = match replace_recovery_codes(&state.db, auth_user.user_id).await {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("Failed to regenerate recovery codes: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::TotpRecoveryCodesRegenerate,
+        auth_user.user_id,
+        None,
+        "Regenerated TOTP recovery codes",
+    )
+    .await;
+
+    Json(RecoveryCodesResponse { recovery_codes }).into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/step-up",
+    request_body = StepUpRequest,
+    responses(
+        (status = 200, description = "Token carrying a fresh step-up claim", body = StepUpResponse),
+        (status = 400, description = "TOTP is not enabled"),
+        (status = 401, description = "Invalid code")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn step_up(
+    headers: HeaderMap,
+    State(state): State<AppState>,
+    Json(payload): Json<StepUpRequest>,
+) -> impl IntoResponse {
+    let claims = match decode_bearer(&headers) {
+        Ok(c) => c,
+        Err(e) => return e.into_response(),
+    };
+    let user_id = match claims.id.parse::<i64>() {
+        Ok(id) => id,
+        Err(_) => return (StatusCode::UNAUTHORIZED, "Invalid User ID in Token").into_response(),
+    };
+
+    if let Err(e) = verify_second_factor(&state.db, user_id, &payload).await {
+        return e.into_response();
+    }
+
+    let method = if payload.code.is_some() {
+        "TOTP code"
+    } else {
+        "recovery code"
+    };
+    let _ = log_audit(
+        &state.db,
+        AuditAction::StepUp,
+        user_id,
+        None,
+        &format!("Stepped up with a {}", method),
+    )
+    .await;
+
+    // The step-up token keeps the original expiry; it never extends the session
+    let jwt_secret = std::env::var("JWT_SECRET").expect("JWT_SECRET missing");
+    let claims = Claims {
+        step_up_at: Some(Utc::now().timestamp() as usize),
+        ..claims
+    };
+
+    match encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(jwt_secret.as_bytes()),
+    ) {
+        Ok(token) => Json(StepUpResponse { token }).into_response(),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, "Token generation failed").into_response(),
+    }
+}
+
+async fn clear_totp(db: &DbPool, user_id: i64) -> Result<u64, sqlx::Error> {
+    let mut tx = db.begin().await?;
+    let removed = sqlx::query("DELETE FROM user_totp WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+    sqlx::query("DELETE FROM totp_recovery_codes WHERE user_id = ?")
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(removed)
+}
+
+// --- Super admin endpoints ---
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/step-up-policies",
+    tag = "Admin",
+    responses(
+        (status = 200, description = "Step-up policy per role", body = Vec<StepUpPolicy>),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn list_step_up_policies(
+    State(state): State<AppState>,
+    _admin: RequireSuperAdmin,
+) -> impl IntoResponse {
+    let result = sqlx::query_as::<_, StepUpPolicy>(
+        "SELECT role, required, max_age_minutes, updated_at FROM step_up_policies ORDER BY role",
+    )
+    .fetch_all(&state.db)
+    .await;
+
+    match result {
+        Ok(policies) => Json(policies).into_response(),
+        Err(e) => {
+            eprintln!("Failed to list step-up policies: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR.into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/admin/step-up-policies/{role}",
+    tag = "Admin",
+    params(
+        ("role" = String, Path, description = "Role ID, or super_admin")
+    ),
+    request_body = UpdateStepUpPolicyRequest,
+    responses(
+        (status = 200, description = "Policy updated", body = StepUpPolicy),
+        (status = 400, description = "Invalid max age"),
+        (status = 404, description = "Role not found"),
+        (status = 409, description = "Requiring super admin step-up while a super admin hasn't enabled TOTP"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn update_step_up_policy(
+    State(state): State<AppState>,
+    admin: RequireSuperAdmin,
+    Path(role): Path<String>,
+    Json(payload): Json<UpdateStepUpPolicyRequest>,
+) -> impl IntoResponse {
+    let max_age = payload.max_age_minutes.unwrap_or(15);
+    if !(1..=MAX_POLICY_AGE_MINUTES).contains(&max_age) {
+        return (
+            StatusCode::BAD_REQUEST,
+            "Max age must be between 1 and 1440 minutes",
+        )
+            .into_response();
+    }
+
+    // Requiring step-up of a super admin without TOTP would lock them out of every
+    // admin endpoint, this one included
+    if role == SUPER_ADMIN_POLICY && payload.required {
+        match unenrolled_super_admins(&state.db).await {
+            Ok(ids) if ids.is_empty() => {}
+            Ok(ids) => {
+                eprintln!(
+                    "Refusing to require super admin step-up; not enrolled in TOTP: {}",
+                    ids.join(", ")
+                );
+                return (
+                    StatusCode::CONFLICT,
+                    "Every super admin must enable TOTP before step-up can be required",
+                )
+                    .into_response();
+            }
+            Err(e) => {
+                eprintln!("Failed to check super admin TOTP enrolment: {}", e);
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            }
+        }
+    }
+
+    if role != SUPER_ADMIN_POLICY {
+        let exists: Option<String> = sqlx::query_scalar("SELECT id FROM roles WHERE id = ?")
+            .bind(&role)
+            .fetch_optional(&state.db)
+            .await
+            .unwrap_or(None);
+        if exists.is_none() {
+            return (StatusCode::NOT_FOUND, "Role not found").into_response();
+        }
+    }
+
+    let result = sqlx::query_as::<_, StepUpPolicy>(
+        r#"
+        INSERT INTO step_up_policies (role, required, max_age_minutes, updated_at) VALUES (?, ?, ?, ?)
+        ON CONFLICT(role) DO UPDATE SET
+            required = excluded.required,
+            max_age_minutes = excluded.max_age_minutes,
+            updated_at = excluded.updated_at
+        RETURNING role, required, max_age_minutes, updated_at
+        "#,
+    )
+    .bind(&role)
+    .bind(payload.required)
+    .bind(max_age)
+    .bind(Utc::now())
+    .fetch_one(&state.db)
+    .await;
+
+    let policy = match result {
+        Ok(p) => p,
+        Err(e) => {
+            eprintln!("Failed to update step-up policy: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let details = format!(
+        "Set step-up policy for '{}': required={}, max age {} minutes",
+        role, policy.required, policy.max_age_minutes
+    );
+    let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+    let _ = log_audit(
+        &state.db,
+        AuditAction::StepUpPolicyUpdate,
+        admin_id,
+        None,
+        &details,
+    )
+    .await;
+    alert_admin_action(
+        format!("SuperAdmin ({})", admin.discord_id),
+        AuditAction::StepUpPolicyUpdate,
+        details,
+    );
+
+    Json(policy).into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/admin/users/{id}/totp",
+    tag = "Admin",
+    params(
+        ("id" = String, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "TOTP removed; the user can enrol again", body = TotpResetResponse),
+        (status = 404, description = "User has no TOTP enrolment"),
+        (status = 401, description = "Unauthorized"),
+        (status = 403, description = "User is not super admin"),
+    )
+)]
+pub async fn reset_user_totp(
+    State(state): State<AppState>,
+    admin: RequireSuperAdmin,
+    Path(user_id): Path<String>,
+) -> impl IntoResponse {
+    let Ok(user_id) = user_id.parse::<i64>() else {
+        return (StatusCode::BAD_REQUEST, "Invalid user ID").into_response();
+    };
+
+    match clear_totp(&state.db, user_id).await {
+        Ok(0) => return (StatusCode::NOT_FOUND, "User has no TOTP enrolment").into_response(),
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Failed to reset TOTP: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+
+    let details = format!("Reset TOTP for user {}", user_id);
+    let admin_id = get_admin_id(&state.db, &admin.discord_id).await;
+    let _ = log_audit(
+        &state.db,
+        AuditAction::TotpReset,
+        admin_id,
+        Some(user_id),
+        &details,
+    )
+    .await;
+    alert_admin_action(
+        format!("SuperAdmin ({})", admin.discord_id),
+        AuditAction::TotpReset,
+        details,
+    );
+
+    Json(TotpResetResponse { user_id }).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{add_members, insert_users, setup_db};
+
+    async fn setup() -> DbPool {
+        let pool = setup_db().await;
+        insert_users(&pool, &[(1, "111", "Admin")]).await;
+        add_members(&pool, &[(1, "Fire", true)]).await;
+        pool
+    }
+
+    fn claims(step_up_at: Option<usize>) -> Claims {
+        Claims {
+            id: "1".to_string(),
+            discord_id: "111".to_string(),
+            username: "Admin".to_string(),
+            exp: 0,
+            step_up_at,
+        }
+    }
+
+    #[test]
+    fn test_rfc6238_vectors() {
+        // RFC 6238 appendix B, SHA-1, truncated to six digits
+        let secret = b"12345678901234567890";
+        assert_eq!(hotp(secret, time_step(59) as u64), 287082);
+        assert_eq!(hotp(secret, time_step(1111111109) as u64), 81804);
+        assert_eq!(hotp(secret, time_step(1234567890) as u64), 5924);
+        assert_eq!(hotp(secret, time_step(2000000000) as u64), 279037);
+    }
+
+    #[test]
+    fn test_verify_code_window_and_replay() {
+        let secret = b"12345678901234567890";
+        let now = 1111111109;
+        let step = time_step(now);
+
+        assert_eq!(verify_code(secret, "081804", now, None), Some(step));
+        // One step of drift either way is tolerated
+        assert_eq!(verify_code(secret, "081804", now + 30, None), Some(step));
+        assert_eq!(verify_code(secret, "081804", now + 90, None), None);
+        // A step already used can't be replayed
+        assert_eq!(verify_code(secret, "081804", now, Some(step)), None);
+        assert_eq!(verify_code(secret, "81804", now, None), None);
+    }
+
+    #[test]
+    fn test_secret_encryption_roundtrip() {
+        let key = [7u8; 32];
+        let stored = encrypt_secret(&key, 42, b"secret");
+        assert_eq!(decrypt_secret(&key, 42, &stored).unwrap(), b"secret");
+        // Bound to the user and the key
+        assert!(decrypt_secret(&key, 43, &stored).is_none());
+        assert!(decrypt_secret(&[8u8; 32], 42, &stored).is_none());
+    }
+
+    #[test]
+    fn test_recovery_code_hash_normalises() {
+        assert_eq!(
+            hash_recovery_code("abcde-12345"),
+            hash_recovery_code(" ABCDE12345 ")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_step_up_policy_enforcement() {
+        let db = setup().await;
+        let now = Utc::now().timestamp() as usize;
+
+        // Nothing required by default
+        assert!(require_user_step_up(&db, &claims(None)).await.is_ok());
+        assert!(require_super_admin_step_up(&db, None).await.is_ok());
+
+        sqlx::query("UPDATE step_up_policies SET required = TRUE, max_age_minutes = 10 WHERE role = 'admin'")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let err = require_user_step_up(&db, &claims(None)).await.unwrap_err();
+        assert_eq!(err.0, StatusCode::FORBIDDEN);
+        assert!(require_user_step_up(&db, &claims(Some(now - 60)))
+            .await
+            .is_ok());
+        assert!(require_user_step_up(&db, &claims(Some(now - 11 * 60)))
+            .await
+            .is_err());
+        // The tribe admin policy doesn't reach the super admin endpoints
+        assert!(require_super_admin_step_up(&db, None).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_step_up_policy_covers_rank_roles() {
+        let db = setup().await;
+        insert_users(&db, &[(2, "222", "Recruiter")]).await;
+        // The recruiter role comes only from the rank below the one member 2 holds
+        sqlx::query("INSERT INTO tribe_ranks (id, tribe, key, name, position, role_id) VALUES ('r0', 'Fire', 'scout', 'Scout', 0, 'recruiter'), ('r1', 'Fire', 'officer', 'Officer', 1, NULL)")
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe, rank_id) VALUES (2, 'Fire', 'r1')")
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO step_up_policies (role, required, max_age_minutes) VALUES ('recruiter', TRUE, 10)")
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let ranked = |step_up_at: Option<usize>| Claims {
+            id: "2".to_string(),
+            discord_id: "222".to_string(),
+            ..claims(step_up_at)
+        };
+        let err = require_user_step_up(&db, &ranked(None)).await.unwrap_err();
+        assert_eq!(err.0, StatusCode::FORBIDDEN);
+        let now = Utc::now().timestamp() as usize;
+        assert!(require_user_step_up(&db, &ranked(Some(now - 60)))
+            .await
+            .is_ok());
+        // Member 1's admin role has no required policy
+        assert!(require_user_step_up(&db, &claims(None)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_super_admin_policy_needs_enrolled_super_admins() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+        sqlx::query("INSERT INTO super_admins (discord_id) VALUES ('111')")
+            .execute(&db)
+            .await
+            .unwrap();
+        let require = || async {
+            update_step_up_policy(
+                State(state.clone()),
+                RequireSuperAdmin {
+                    discord_id: "111".to_string(),
+                },
+                Path(SUPER_ADMIN_POLICY.to_string()),
+                Json(UpdateStepUpPolicyRequest {
+                    required: true,
+                    max_age_minutes: None,
+                }),
+            )
+            .await
+            .into_response()
+            .status()
+        };
+
+        assert_eq!(require().await, StatusCode::CONFLICT);
+        assert!(!super_admin_step_up_required(&db).await.unwrap());
+
+        sqlx::query("INSERT INTO user_totp (user_id, secret_ciphertext, confirmed_at) VALUES (1, 'x', CURRENT_TIMESTAMP)")
+            .execute(&db)
+            .await
+            .unwrap();
+        assert_eq!(require().await, StatusCode::OK);
+        assert!(super_admin_step_up_required(&db).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_recovery_code_is_single_use() {
+        let db = setup().await;
+        sqlx::query("INSERT INTO user_totp (user_id, secret_ciphertext, confirmed_at) VALUES (1, 'x', CURRENT_TIMESTAMP)")
+            .execute(&db)
+            .await
+            .unwrap();
+        let codes = replace_recovery_codes(&db, 1).await.unwrap();
+        assert_eq!(codes.len(), RECOVERY_CODE_COUNT);
+
+        let request = StepUpRequest {
+            code: None,
+            recovery_code: Some(codes[0].clone()),
+        };
+        assert!(verify_second_factor(&db, 1, &request).await.is_ok());
+        let err = verify_second_factor(&db, 1, &request).await.unwrap_err();
+        assert_eq!(err.0, StatusCode::UNAUTHORIZED);
+    }
+}
@@ -0,0 +1,333 @@
+use crate::{
+    audit::{log_audit, AuditAction},
+    auth::AuthenticatedUser,
+    state::AppState,
+};
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MembershipWallet {
+    pub id: String,
+    pub address: String,
+    pub network: String,
+    pub label: Option<String>,
+}
+
+#[derive(Serialize, ToSchema, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct TribeAdmin {
+    pub discord_id: String,
+    pub username: String,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct MyTribe {
+    pub tribe: String,
+    pub joined_at: Option<DateTime<Utc>>,
+    /// How the membership was created, e.g. `MANUAL` or `CHAIN`
+    pub source: String,
+    pub is_admin: bool,
+    /// When the membership lapses; permanent when absent
+    pub expires_at: Option<DateTime<Utc>>,
+    /// The wallet the membership is attributed to, if it is still linked
+    pub wallet: Option<MembershipWallet>,
+    pub admins: Vec<TribeAdmin>,
+    pub member_count: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct MembershipRow {
+    tribe: String,
+    created_at: Option<DateTime<Utc>>,
+    source: String,
+    is_admin: bool,
+    expires_at: Option<DateTime<Utc>>,
+    wallet_id: Option<String>,
+    address: Option<String>,
+    network: Option<String>,
+    label: Option<String>,
+    member_count: i64,
+}
+
+#[derive(sqlx::FromRow)]
+struct AdminRow {
+    tribe: String,
+    discord_id: String,
+    username: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/me/tribes",
+    responses(
+        (status = 200, description = "Tribes the current user belongs to", body = Vec<MyTribe>)
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn list_my_tribes(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let memberships = sqlx::query_as::<_, MembershipRow>(
+        r#"
+        SELECT ut.tribe, ut.created_at, ut.source,
+               (COALESCE(ut.is_admin, FALSE) AND (ut.admin_expires_at IS NULL OR ut.admin_expires_at > ?)) AS is_admin,
+               ut.expires_at,
+               w.id AS wallet_id, w.address, w.network, w.label,
+               (SELECT COUNT(*) FROM user_tribes m WHERE m.tribe = ut.tribe) AS member_count
+        FROM user_tribes ut
+        LEFT JOIN wallets w ON w.id = ut.wallet_id AND w.deleted_at IS NULL
+        WHERE ut.user_id = ?
+        ORDER BY ut.tribe
+        "#,
+    )
+    .bind(Utc::now())
+    .bind(auth_user.user_id)
+    .fetch_all(&state.db)
+    .await;
+
+    let memberships = match memberships {
+        Ok(m) => m,
+        Err(e) => {
+            eprintln!("Failed to list tribes for user: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    // Grants past their expiry are left out even before the sweeper clears them
+    let admins = sqlx::query_as::<_, AdminRow>(
+        r#"
+        SELECT a.tribe, u.discord_id, u.username
+        FROM user_tribes a
+        JOIN users u ON u.id = a.user_id
+        WHERE a.is_admin = TRUE
+          AND (a.admin_expires_at IS NULL OR a.admin_expires_at > ?)
+          AND a.tribe IN (SELECT tribe FROM user_tribes WHERE user_id = ?)
+        ORDER BY u.username
+        "#,
+    )
+    .bind(Utc::now())
+    .bind(auth_user.user_id)
+    .fetch_all(&state.db)
+    .await;
+
+    let mut admins_by_tribe: HashMap<String, Vec<TribeAdmin>> = HashMap::new();
+    match admins {
+        Ok(rows) => {
+            for row in rows {
+                admins_by_tribe
+                    .entry(row.tribe)
+                    .or_default()
+                    .push(TribeAdmin {
+                        discord_id: row.discord_id,
+                        username: row.username,
+                    });
+            }
+        }
+        Err(e) => {
+            eprintln!("Failed to list tribe admins: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+
+    let tribes: Vec<MyTribe> = memberships
+        .into_iter()
+        .map(|m| {
+            let wallet = match (m.wallet_id, m.address, m.network) {
+                (Some(id), Some(address), Some(network)) => Some(MembershipWallet {
+                    id,
+                    address,
+                    network,
+                    label: m.label,
+                }),
+                _ => None,
+            };
+            MyTribe {
+                admins: admins_by_tribe.remove(&m.tribe).unwrap_or_default(),
+                tribe: m.tribe,
+                joined_at: m.created_at,
+                source: m.source,
+                is_admin: m.is_admin,
+                expires_at: m.expires_at,
+                wallet,
+                member_count: m.member_count,
+            }
+        })
+        .collect();
+
+    Json(tribes).into_response()
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/me/tribes/{tribe}",
+    params(
+        ("tribe" = String, Path, description = "Tribe name")
+    ),
+    responses(
+        (status = 200, description = "Left the tribe"),
+        (status = 404, description = "Not a member of this tribe")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn leave_tribe(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Path(tribe): Path<String>,
+) -> impl IntoResponse {
+    let result = sqlx::query("DELETE FROM user_tribes WHERE user_id = ? AND tribe = ?")
+        .bind(auth_user.user_id)
+        .bind(&tribe)
+        .execute(&state.db)
+        .await;
+
+    match result {
+        Ok(r) if r.rows_affected() == 0 => {
+            return (StatusCode::NOT_FOUND, "Not a member of this tribe").into_response()
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Failed to leave tribe: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    }
+    // Leaving is self-service, so the history records no actor
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::TribeLeave,
+        auth_user.user_id,
+        Some(auth_user.user_id),
+        &format!("Left tribe {}", tribe),
+    )
+    .await;
+
+    StatusCode::OK.into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        db::DbPool,
+        test_support::{add_members, insert_users, setup_db},
+    };
+
+    async fn setup() -> DbPool {
+        let pool = setup_db().await;
+        insert_users(&pool, &[(1, "111", "Member"), (2, "222", "Chief")]).await;
+        sqlx::query("INSERT INTO wallets (id, user_id, address, verified_at) VALUES ('w1', 1, '0xabc', CURRENT_TIMESTAMP)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe, wallet_id, source) VALUES (1, 'Fire', 'w1', 'CHAIN')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        add_members(&pool, &[(2, "Fire", true)]).await;
+        pool
+    }
+
+    #[tokio::test]
+    async fn test_list_my_tribes() {
+        let db = setup().await;
+        let res = list_my_tribes(AuthenticatedUser { user_id: 1 }, State(AppState::new(db)))
+            .await
+            .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let fire = &json[0];
+        assert_eq!(fire["tribe"], "Fire");
+        assert_eq!(fire["source"], "CHAIN");
+        assert_eq!(fire["isAdmin"], false);
+        assert_eq!(fire["wallet"]["address"], "0xabc");
+        assert_eq!(fire["memberCount"], 2);
+        assert_eq!(fire["admins"][0]["username"], "Chief");
+    }
+
+    #[tokio::test]
+    async fn test_expired_admin_grant_not_listed() {
+        let db = setup().await;
+        insert_users(&db, &[(3, "333", "Lapsed")]).await;
+        sqlx::query("INSERT INTO user_tribes (user_id, tribe, is_admin, admin_expires_at) VALUES (3, 'Fire', TRUE, ?)")
+            .bind(Utc::now() - chrono::Duration::hours(1))
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let list = |user_id: i64| {
+            let db = db.clone();
+            async move {
+                let res = list_my_tribes(AuthenticatedUser { user_id }, State(AppState::new(db)))
+                    .await
+                    .into_response();
+                let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+                    .await
+                    .unwrap();
+                serde_json::from_slice::<serde_json::Value>(&body).unwrap()
+            }
+        };
+
+        // The sweeper hasn't run yet, but the lapsed grant is no longer shown
+        let json = list(1).await;
+        assert_eq!(json[0]["admins"].as_array().unwrap().len(), 1);
+        assert_eq!(json[0]["admins"][0]["username"], "Chief");
+        assert_eq!(list(3).await[0]["isAdmin"], false);
+    }
+
+    #[tokio::test]
+    async fn test_leave_tribe() {
+        let db = setup().await;
+        let state = AppState::new(db.clone());
+
+        let res = leave_tribe(
+            AuthenticatedUser { user_id: 1 },
+            State(state.clone()),
+            Path("Fire".to_string()),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = leave_tribe(
+            AuthenticatedUser { user_id: 1 },
+            State(state),
+            Path("Fire".to_string()),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        let action: String = sqlx::query_scalar("SELECT action FROM audit_logs WHERE actor_id = 1")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(action, "TRIBE_LEAVE");
+
+        let left_by: Option<i64> =
+            sqlx::query_scalar("SELECT left_by FROM tribe_membership_history WHERE user_id = 1")
+                .fetch_one(&db)
+                .await
+                .unwrap();
+        assert_eq!(left_by, None);
+    }
+}
@@ -0,0 +1,662 @@
+use crate::{
+    audit::{log_audit, AuditAction},
+    auth::AuthenticatedUser,
+    db::DbPool,
+    helpers::{get_user_by_discord_id, STALE_WALLET_MESSAGE},
+    rbac::{perm, TribeAccess},
+    reverification::stale_enforced_tribes,
+    state::AppState,
+    totp::StepUp,
+};
+use axum::{
+    extract::{Json, Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+const MAX_JUSTIFICATION_CHARS: usize = 500;
+const MAX_MIN_VOUCHES: i64 = 10;
+
+/// Vouches with both parties and whether the voucher was later kicked: a
+/// membership closed by someone else after vouching, even if they rejoined.
+const VOUCH_SELECT_SQL: &str = r#"
+    SELECT v.id, v.tribe,
+           vr.discord_id AS voucher_discord_id, vr.username AS voucher_username,
+           ve.discord_id AS vouchee_discord_id, ve.username AS vouchee_username,
+           v.justification, v.created_at,
+           EXISTS (
+               SELECT 1 FROM tribe_membership_history h
+               WHERE h.user_id = v.voucher_id AND h.tribe = v.tribe
+                 AND h.left_by IS NOT NULL AND h.left_by <> v.voucher_id
+                 AND h.left_at >= strftime('%Y-%m-%dT%H:%M:%fZ', v.created_at)
+           ) AS voucher_kicked
+    FROM vouches v
+    JOIN users vr ON vr.id = v.voucher_id
+    JOIN users ve ON ve.id = v.vouchee_id
+"#;
+
+#[derive(Debug, Serialize, ToSchema, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct Vouch {
+    pub id: String,
+    pub tribe: String,
+    pub voucher_discord_id: String,
+    pub voucher_username: String,
+    pub vouchee_discord_id: String,
+    pub vouchee_username: String,
+    pub justification: String,
+    pub created_at: DateTime<Utc>,
+    /// The voucher has since been kicked from the tribe
+    pub voucher_kicked: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct CreateVouchRequest {
+    /// Tribe to vouch in; you must be a member
+    pub tribe: String,
+    /// Discord ID of the user vouched for
+    pub discord_id: String,
+    pub justification: String,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct VouchQuery {
+    pub tribe: Option<String>,
+    /// Only vouches for this user
+    pub discord_id: Option<String>,
+}
+
+#[derive(Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TribeVouches {
+    /// Vouches from current members a user needs to be added to the tribe
+    pub min_vouches: i64,
+    pub vouches: Vec<Vouch>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct VouchPolicyRequest {
+    /// 0 turns the requirement off
+    pub min_vouches: i64,
+}
+
+/// Vouches in a tribe, oldest first, optionally only those for one user
+pub(crate) async fn tribe_vouches(
+    db: &DbPool,
+    tribe: &str,
+    vouchee_id: Option<i64>,
+) -> Result<Vec<Vouch>, sqlx::Error> {
+    let sql = format!(
+        "{} WHERE v.tribe = ? AND (? IS NULL OR v.vouchee_id = ?) ORDER BY v.created_at, v.id",
+        VOUCH_SELECT_SQL
+    );
+    sqlx::query_as(&sql)
+        .bind(tribe)
+        .bind(vouchee_id)
+        .bind(vouchee_id)
+        .fetch_all(db)
+        .await
+}
+
+async fn vouch_by_id(db: &DbPool, id: &str) -> Result<Option<Vouch>, sqlx::Error> {
+    let sql = format!("{} WHERE v.id = ?", VOUCH_SELECT_SQL);
+    sqlx::query_as(&sql).bind(id).fetch_optional(db).await
+}
+
+/// A user's vouches from current members of a tribe, and how many the tribe
+/// requires. Vouches from members who have left or been kicked don't count.
+pub async fn vouch_standing<'e, E>(
+    executor: E,
+    tribe: &str,
+    user_id: i64,
+) -> Result<(i64, i64), sqlx::Error>
+where
+    E: sqlx::Executor<'e, Database = sqlx::Sqlite>,
+{
+    // Flagged vouches (see `VOUCH_SELECT_SQL`) don't count even after the voucher rejoins
+    let standing: Option<(i64, i64)> = sqlx::query_as(
+        r#"
+        SELECT
+            (SELECT COUNT(*) FROM vouches v
+             JOIN user_tribes ut ON ut.user_id = v.voucher_id AND ut.tribe = v.tribe
+             WHERE v.tribe = t.name AND v.vouchee_id = ?
+               AND NOT EXISTS (
+                   SELECT 1 FROM tribe_membership_history h
+                   WHERE h.user_id = v.voucher_id AND h.tribe = v.tribe
+                     AND h.left_by IS NOT NULL AND h.left_by <> v.voucher_id
+                     AND h.left_at >= strftime('%Y-%m-%dT%H:%M:%fZ', v.created_at)
+               )),
+            t.min_vouches
+        FROM tribes t WHERE t.name = ?
+        "#,
+    )
+    .bind(user_id)
+    .bind(tribe)
+    .fetch_optional(executor)
+    .await?;
+    Ok(standing.unwrap_or_default())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/vouches",
+    request_body = CreateVouchRequest,
+    responses(
+        (status = 201, description = "Vouch recorded", body = Vouch),
+        (status = 400, description = "Vouching for yourself, or invalid justification"),
+        (status = 403, description = "Not a member of the tribe, or wallet re-verification required"),
+        (status = 404, description = "User not found"),
+        (status = 409, description = "You already vouch for this user in this tribe")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn create_vouch(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+    Json(payload): Json<CreateVouchRequest>,
+) -> impl IntoResponse {
+    let justification = payload.justification.trim();
+    if justification.is_empty() || justification.chars().count() > MAX_JUSTIFICATION_CHARS {
+        return (
+            StatusCode::BAD_REQUEST,
+            "Justification must be 1 to 500 characters",
+        )
+            .into_response();
+    }
+
+    let is_member: Option<i64> =
+        match sqlx::query_scalar("SELECT 1 FROM user_tribes WHERE user_id = ? AND tribe = ?")
+            .bind(auth_user.user_id)
+            .bind(&payload.tribe)
+            .fetch_optional(&state.db)
+            .await
+        {
+            Ok(m) => m,
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+        };
+    if is_member.is_none() {
+        return (
+            StatusCode::FORBIDDEN,
+            "Access denied: You are not in the specified tribe",
+        )
+            .into_response();
+    }
+    match stale_enforced_tribes(&state.db, auth_user.user_id).await {
+        Ok(stale) if stale.contains(&payload.tribe) => {
+            return (StatusCode::FORBIDDEN, STALE_WALLET_MESSAGE).into_response()
+        }
+        Ok(_) => {}
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    }
+
+    let vouchee = match get_user_by_discord_id(&state.db, &payload.discord_id).await {
+        Ok(Some(u)) => u,
+        Ok(None) => return (StatusCode::NOT_FOUND, "User not found").into_response(),
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+    if vouchee.id == auth_user.user_id {
+        return (StatusCode::BAD_REQUEST, "You can't vouch for yourself").into_response();
+    }
+
+    let id = Uuid::new_v4().to_string();
+    let result = sqlx::query(
+        "INSERT INTO vouches (id, tribe, voucher_id, vouchee_id, justification, created_at) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(&id)
+    .bind(&payload.tribe)
+    .bind(auth_user.user_id)
+    .bind(vouchee.id)
+    .bind(justification)
+    .bind(Utc::now())
+    .execute(&state.db)
+    .await;
+    match result {
+        Ok(_) => {}
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => {
+            return (
+                StatusCode::CONFLICT,
+                "You already vouch for this user in this tribe",
+            )
+                .into_response()
+        }
+        Err(e) => {
+            eprintln!("Failed to create vouch: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+        }
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::VouchCreate,
+        auth_user.user_id,
+        Some(vouchee.id),
+        &format!(
+            "Vouched for {} in tribe {}",
+            vouchee.username, payload.tribe
+        ),
+    )
+    .await;
+
+    match vouch_by_id(&state.db, &id).await {
+        Ok(Some(vouch)) => (StatusCode::CREATED, Json(vouch)).into_response(),
+        _ => (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/me/vouches",
+    responses(
+        (status = 200, description = "Vouches the current user has given, newest first", body = Vec<Vouch>)
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn list_my_vouches(
+    auth_user: AuthenticatedUser,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let sql = format!(
+        "{} WHERE v.voucher_id = ? ORDER BY v.created_at DESC, v.id",
+        VOUCH_SELECT_SQL
+    );
+    match sqlx::query_as::<_, Vouch>(&sql)
+        .bind(auth_user.user_id)
+        .fetch_all(&state.db)
+        .await
+    {
+        Ok(vouches) => Json(vouches).into_response(),
+        Err(e) => {
+            eprintln!("Failed to list vouches: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/vouches/{id}",
+    params(
+        ("id" = String, Path, description = "Vouch ID")
+    ),
+    responses(
+        (status = 200, description = "Vouch retracted"),
+        (status = 404, description = "No vouch of yours with this ID")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn retract_vouch(
+    auth_user: AuthenticatedUser,
+    Path(id): Path<String>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let vouch: Option<(String, i64, String)> = match sqlx::query_as(
+        "SELECT v.tribe, v.vouchee_id, u.username FROM vouches v JOIN users u ON u.id = v.vouchee_id WHERE v.id = ? AND v.voucher_id = ?",
+    )
+    .bind(&id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.db)
+    .await
+    {
+        Ok(v) => v,
+        Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+    };
+    let Some((tribe, vouchee_id, vouchee_name)) = vouch else {
+        return (StatusCode::NOT_FOUND, "Vouch not found").into_response();
+    };
+
+    if let Err(e) = sqlx::query("DELETE FROM vouches WHERE id = ?")
+        .bind(&id)
+        .execute(&state.db)
+        .await
+    {
+        eprintln!("Failed to retract vouch: {}", e);
+        return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::VouchRetract,
+        auth_user.user_id,
+        Some(vouchee_id),
+        &format!("Retracted vouch for {} in tribe {}", vouchee_name, tribe),
+    )
+    .await;
+
+    StatusCode::OK.into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/roster/vouches",
+    params(VouchQuery),
+    responses(
+        (status = 200, description = "The tribe's vouches and vouch requirement", body = TribeVouches),
+        (status = 403, description = "Forbidden: Missing admin.grant"),
+        (status = 404, description = "User not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn list_tribe_vouches(
+    access: TribeAccess<perm::AdminGrant>,
+    Query(query): Query<VouchQuery>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let vouchee_id = match query.discord_id.as_deref() {
+        None => None,
+        Some(discord_id) => match get_user_by_discord_id(&state.db, discord_id).await {
+            Ok(Some(u)) => Some(u.id),
+            Ok(None) => return (StatusCode::NOT_FOUND, "User not found").into_response(),
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+        },
+    };
+
+    let min_vouches: Option<i64> =
+        match sqlx::query_scalar("SELECT min_vouches FROM tribes WHERE name = ?")
+            .bind(&access.tribe)
+            .fetch_optional(&state.db)
+            .await
+        {
+            Ok(m) => m,
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response(),
+        };
+    match tribe_vouches(&state.db, &access.tribe, vouchee_id).await {
+        Ok(vouches) => Json(TribeVouches {
+            min_vouches: min_vouches.unwrap_or(0),
+            vouches,
+        })
+        .into_response(),
+        Err(e) => {
+            eprintln!("Failed to list tribe vouches: {}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response()
+        }
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/roster/vouches/policy",
+    params(
+        ("tribe" = Option<String>, Query, description = "Tribe to configure")
+    ),
+    request_body = VouchPolicyRequest,
+    responses(
+        (status = 200, description = "Requirement updated"),
+        (status = 400, description = "min_vouches out of range"),
+        (status = 403, description = "Forbidden: Missing admin.grant, or step-up required"),
+        (status = 404, description = "Tribe not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn set_vouch_policy(
+    access: TribeAccess<perm::AdminGrant>,
+    State(state): State<AppState>,
+    _step_up: StepUp,
+    Json(payload): Json<VouchPolicyRequest>,
+) -> impl IntoResponse {
+    if !(0..=MAX_MIN_VOUCHES).contains(&payload.min_vouches) {
+        return (StatusCode::BAD_REQUEST, "min_vouches must be 0 to 10").into_response();
+    }
+    match sqlx::query("UPDATE tribes SET min_vouches = ? WHERE name = ?")
+        .bind(payload.min_vouches)
+        .bind(&access.tribe)
+        .execute(&state.db)
+        .await
+    {
+        Ok(r) if r.rows_affected() == 0 => {
+            return (StatusCode::NOT_FOUND, "Tribe not found").into_response()
+        }
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Failed to update vouch policy: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Database error").into_response();
+        }
+    }
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::VouchPolicyUpdate,
+        access.user.id,
+        None,
+        &format!(
+            "Set minimum vouches for tribe {} to {}",
+            access.tribe, payload.min_vouches
+        ),
+    )
+    .await;
+
+    StatusCode::OK.into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        roster::kick_member,
+        test_support::{access, add_members, insert_users, setup_db},
+    };
+
+    async fn setup() -> AppState {
+        let db = setup_db().await;
+        insert_users(
+            &db,
+            &[
+                (1, "111", "Chief"),
+                (2, "222", "Member"),
+                (3, "333", "Other"),
+                (4, "444", "Applicant"),
+            ],
+        )
+        .await;
+        sqlx::query("INSERT INTO tribes (name) VALUES ('Fire')")
+            .execute(&db)
+            .await
+            .unwrap();
+        add_members(
+            &db,
+            &[(1, "Fire", true), (2, "Fire", false), (3, "Fire", false)],
+        )
+        .await;
+        AppState::new(db)
+    }
+
+    async fn vouch(state: &AppState, by: i64, discord_id: &str) -> axum::response::Response {
+        create_vouch(
+            AuthenticatedUser { user_id: by },
+            State(state.clone()),
+            Json(CreateVouchRequest {
+                tribe: "Fire".to_string(),
+                discord_id: discord_id.to_string(),
+                justification: "Flew with them for months".to_string(),
+            }),
+        )
+        .await
+        .into_response()
+    }
+
+    #[tokio::test]
+    async fn test_vouching() {
+        let state = setup().await;
+        assert_eq!(vouch(&state, 2, "444").await.status(), StatusCode::CREATED);
+        assert_eq!(vouch(&state, 2, "444").await.status(), StatusCode::CONFLICT);
+        assert_eq!(
+            vouch(&state, 2, "222").await.status(),
+            StatusCode::BAD_REQUEST
+        );
+        // Only members may vouch
+        assert_eq!(
+            vouch(&state, 4, "222").await.status(),
+            StatusCode::FORBIDDEN
+        );
+
+        let res = vouch(&state, 3, "444").await;
+        assert_eq!(res.status(), StatusCode::CREATED);
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let other_vouch: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let other_id = other_vouch["id"].as_str().unwrap().to_string();
+        assert_eq!(other_vouch["voucherKicked"], false);
+        assert_eq!(vouch_standing(&state.db, "Fire", 4).await.unwrap(), (2, 0));
+
+        // Someone else can't retract it
+        let res = retract_vouch(
+            AuthenticatedUser { user_id: 2 },
+            Path(other_id.clone()),
+            State(state.clone()),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        // Kicking a voucher flags their vouch and stops it counting
+        let res = kick_member(
+            access(&state.db, 1, "Fire").await,
+            StepUp,
+            Path("333".to_string()),
+            State(state.clone()),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        let vouches = tribe_vouches(&state.db, "Fire", Some(4)).await.unwrap();
+        assert_eq!(vouches.len(), 2);
+        let flagged: Vec<_> = vouches.iter().map(|v| v.voucher_kicked).collect();
+        assert_eq!(flagged, [false, true]);
+        assert_eq!(vouch_standing(&state.db, "Fire", 4).await.unwrap(), (1, 0));
+
+        // Rejoining keeps the flag, and the vouch still doesn't count
+        add_members(&state.db, &[(3, "Fire", false)]).await;
+        let vouches = tribe_vouches(&state.db, "Fire", Some(4)).await.unwrap();
+        assert!(vouches[1].voucher_kicked);
+        assert_eq!(vouch_standing(&state.db, "Fire", 4).await.unwrap(), (1, 0));
+
+        // Leaving of your own accord isn't a kick
+        sqlx::query("DELETE FROM user_tribes WHERE user_id = 2")
+            .execute(&state.db)
+            .await
+            .unwrap();
+        let vouches = tribe_vouches(&state.db, "Fire", Some(4)).await.unwrap();
+        assert!(!vouches[0].voucher_kicked);
+        assert_eq!(vouch_standing(&state.db, "Fire", 4).await.unwrap(), (0, 0));
+
+        let res = retract_vouch(
+            AuthenticatedUser { user_id: 3 },
+            Path(other_id),
+            State(state.clone()),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(
+            tribe_vouches(&state.db, "Fire", None).await.unwrap().len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_min_vouches_gate_tribe_admission() {
+        let state = setup().await;
+        let res = set_vouch_policy(
+            access(&state.db, 1, "Fire").await,
+            State(state.clone()),
+            StepUp,
+            Json(VouchPolicyRequest { min_vouches: 1 }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let add = || {
+            crate::admin::add_user_to_tribe(
+                State(state.clone()),
+                crate::middleware::admin::RequireSuperAdmin {
+                    discord_id: "111".to_string(),
+                },
+                Path("Fire".to_string()),
+                Json(crate::admin::AddUserToTribeRequest {
+                    username: "Applicant".to_string(),
+                    expires_at: None,
+                }),
+            )
+        };
+        assert_eq!(
+            add().await.into_response().status(),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(vouch(&state, 2, "444").await.status(), StatusCode::CREATED);
+        assert_eq!(add().await.into_response().status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_min_vouches_gate_admin_grant_to_non_member() {
+        let state = setup().await;
+        let res = set_vouch_policy(
+            access(&state.db, 1, "Fire").await,
+            State(state.clone()),
+            StepUp,
+            Json(VouchPolicyRequest { min_vouches: 1 }),
+        )
+        .await
+        .into_response();
+        assert_eq!(res.status(), StatusCode::OK);
+        sqlx::query("INSERT INTO wallets (id, user_id, address, verified_at) VALUES ('w4', 4, '0xapplicant', CURRENT_TIMESTAMP)")
+            .execute(&state.db)
+            .await
+            .unwrap();
+
+        let grant = || async {
+            crate::roster::grant_admin(
+                Path("444".to_string()),
+                State(state.clone()),
+                access(&state.db, 1, "Fire").await,
+                StepUp,
+                Json(crate::roster::GrantAdminRequest {
+                    wallet_id: Some("w4".to_string()),
+                    expires_at: None,
+                }),
+            )
+            .await
+            .into_response()
+            .status()
+        };
+        assert_eq!(grant().await, StatusCode::BAD_REQUEST);
+        assert_eq!(vouch(&state, 2, "444").await.status(), StatusCode::CREATED);
+        assert_eq!(grant().await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_vouches_follow_tribe_rename() {
+        let state = setup().await;
+        assert_eq!(vouch(&state, 2, "444").await.status(), StatusCode::CREATED);
+
+        let res = crate::admin::apply_update_tribe(
+            &state,
+            &crate::middleware::admin::RequireSuperAdmin {
+                discord_id: "111".to_string(),
+            },
+            "Fire".to_string(),
+            "Flame".to_string(),
+        )
+        .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let vouches = tribe_vouches(&state.db, "Flame", Some(4)).await.unwrap();
+        assert_eq!(vouches.len(), 1);
+        assert_eq!(vouches[0].tribe, "Flame");
+        assert_eq!(vouch_standing(&state.db, "Flame", 4).await.unwrap().0, 1);
+    }
+}
@@ -4,6 +4,8 @@ use axum::{
     response::IntoResponse,
 };
 use serde::{Deserialize, Serialize};
+use sqlx::SqliteExecutor;
+use std::collections::HashSet;
 use std::str::FromStr;
 
 use crate::auth::AuthenticatedUser;
@@ -38,6 +40,73 @@ pub struct VerifyRequest {
     network: Option<String>,
 }
 
+#[derive(Deserialize, ToSchema)]
+pub struct ReverifyRequest {
+    signature: String,
+}
+
+/// Longest label a user can give a wallet
+const MAX_LABEL_LENGTH: usize = 32;
+
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateWalletRequest {
+    /// New label; an empty string clears it
+    label: Option<String>,
+    /// `true` makes this the primary wallet for its network
+    is_primary: Option<bool>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ReorderWalletsRequest {
+    /// Every active wallet of the user, in the desired order
+    wallet_ids: Vec<String>,
+}
+
+/// Promote a wallet to primary on every network where the user has active wallets
+/// but no primary one, picking the first wallet in the user's order.
+pub(crate) async fn ensure_primary_wallets(
+    executor: impl SqliteExecutor<'_>,
+    user_id: i64,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE wallets SET is_primary = TRUE WHERE id IN (
+            SELECT w.id FROM wallets w
+            WHERE w.user_id = ? AND w.deleted_at IS NULL
+              AND NOT EXISTS (
+                SELECT 1 FROM wallets p
+                WHERE p.user_id = w.user_id AND p.network = w.network
+                  AND p.is_primary AND p.deleted_at IS NULL
+              )
+              AND NOT EXISTS (
+                SELECT 1 FROM wallets e
+                WHERE e.user_id = w.user_id AND e.network = w.network AND e.deleted_at IS NULL
+                  AND (e.sort_order < w.sort_order OR (e.sort_order = w.sort_order AND e.id < w.id))
+              )
+        )",
+    )
+    .bind(user_id)
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// The wallet a new tribe membership is attributed to by default: the user's primary
+/// wallet, preferring mainnet.
+pub(crate) async fn primary_wallet_id(
+    executor: impl SqliteExecutor<'_>,
+    user_id: i64,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT id FROM wallets
+         WHERE user_id = ? AND is_primary AND deleted_at IS NULL
+         ORDER BY network != 'mainnet', sort_order
+         LIMIT 1",
+    )
+    .bind(user_id)
+    .fetch_optional(executor)
+    .await
+}
+
 #[utoipa::path(
     post,
     path = "/api/wallets/link-nonce",
@@ -73,51 +142,11 @@ struct PersonalMessage<'a> {
     message: &'a [u8],
 }
 
-#[utoipa::path(
-    post,
-    path = "/api/wallets/link-verify",
-    request_body = VerifyRequest,
-    responses(
-        (status = 200, description = "Wallet linked successfully"),
-        (status = 400, description = "Invalid signature or wallet already linked")
-    ),
-    security(
-        ("jwt" = [])
-    )
-)]
-pub async fn link_verify(
-    State(state): State<AppState>,
-    auth_user: AuthenticatedUser,
-    Json(payload): Json<VerifyRequest>,
-) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let address_str = payload.address.to_lowercase();
-
-    // Check if denylisted
-    let wallet_hash = crate::auth::hash_identity(&address_str, &state.identity_hash_pepper);
-    let denylisted: Option<(String,)> =
-        sqlx::query_as("SELECT hash FROM identity_hashes WHERE hash = ?")
-            .bind(&wallet_hash)
-            .fetch_optional(&state.db)
-            .await
-            .map_err(|e| {
-                eprintln!("Database error inserting wallet: {}", e);
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "Internal server error".to_string(),
-                )
-            })?;
-
-    if denylisted.is_some() {
-        return Err((
-            StatusCode::FORBIDDEN,
-            "This wallet has been denylisted due to account deletion".into(),
-        ));
-    }
-
-    // Check Nonce and validate TTL
+/// Consume the pending nonce for an address, rejecting it if missing or older than 5 minutes.
+fn take_nonce(state: &AppState, address: &str) -> Result<String, (StatusCode, String)> {
     let stored_nonce = {
         let mut nonces = state.wallet_nonces.lock().unwrap();
-        nonces.remove(&address_str)
+        nonces.remove(address)
     }
     .ok_or((StatusCode::BAD_REQUEST, "Nonce invalid or expired".into()))?;
 
@@ -126,11 +155,17 @@ pub async fn link_verify(
         return Err((StatusCode::BAD_REQUEST, "Nonce expired".into()));
     }
 
-    let stored_nonce = stored_nonce.0; // Extract the actual nonce string
+    Ok(stored_nonce.0)
+}
 
-    // Verify Signature
+/// Verify a base64 Sui personal-message signature over `nonce` for `address`.
+fn verify_personal_message(
+    address: &str,
+    signature: &str,
+    nonce: &str,
+) -> Result<(), (StatusCode, String)> {
     let sig_bytes = STANDARD
-        .decode(&payload.signature)
+        .decode(signature)
         .map_err(|e| (StatusCode::BAD_REQUEST, format!("Invalid base64: {}", e)))?;
 
     let sig = Signature::from_bytes(&sig_bytes).map_err(|e| {
@@ -140,17 +175,15 @@ pub async fn link_verify(
         )
     })?;
 
-    let sui_address = SuiAddress::from_str(&address_str).map_err(|e| {
+    let sui_address = SuiAddress::from_str(address).map_err(|e| {
         (
             StatusCode::BAD_REQUEST,
             format!("Invalid address format: {}", e),
         )
     })?;
 
-    let message_bytes = stored_nonce.as_bytes();
-
     let msg_struct = PersonalMessage {
-        message: message_bytes,
+        message: nonce.as_bytes(),
     };
 
     let intent = Intent::personal_message();
@@ -158,9 +191,6 @@ pub async fn link_verify(
 
     let result = sig.verify_secure(&intent_msg, sui_address, sig.scheme());
 
-    // If verify_secure expects the Struct, we might need a wrapper.
-    // Assuming verify_secure<T>(value: &T, intent, author)
-
     if result.is_err() {
         return Err((
             StatusCode::BAD_REQUEST,
@@ -168,6 +198,53 @@ pub async fn link_verify(
         ));
     }
 
+    Ok(())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/wallets/link-verify",
+    request_body = VerifyRequest,
+    responses(
+        (status = 200, description = "Wallet linked successfully"),
+        (status = 400, description = "Invalid signature or wallet already linked")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn link_verify(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Json(payload): Json<VerifyRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let address_str = payload.address.to_lowercase();
+
+    // Check if denylisted
+    let wallet_hash = crate::auth::hash_identity(&address_str, &state.identity_hash_pepper);
+    let denylisted: Option<(String,)> =
+        sqlx::query_as("SELECT hash FROM identity_hashes WHERE hash = ?")
+            .bind(&wallet_hash)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|e| {
+                eprintln!("Database error inserting wallet: {}", e);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "Internal server error".to_string(),
+                )
+            })?;
+
+    if denylisted.is_some() {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "This wallet has been denylisted due to account deletion".into(),
+        ));
+    }
+
+    let stored_nonce = take_nonce(&state, &address_str)?;
+    verify_personal_message(&address_str, &payload.signature, &stored_nonce)?;
+
     let network = payload.network.unwrap_or_else(|| "mainnet".to_string());
 
     // Check availability (including soft-deleted)
@@ -183,18 +260,24 @@ pub async fn link_verify(
             return Err((StatusCode::BAD_REQUEST, "Wallet already linked".into()));
         }
 
-        // Re-link: Update user_id, network, and clear deleted_at
+        // Re-link: Update user_id, network, and clear deleted_at and the previous owner's
+        // character and presentation settings
         sqlx::query(
-            "UPDATE wallets SET user_id = ?, verified_at = ?, deleted_at = NULL, network = ? WHERE id = ?",
+            "UPDATE wallets SET user_id = ?, verified_at = ?, deleted_at = NULL, stale_at = NULL, character_name = NULL, character_name_refreshed_at = NULL, network = ?, is_primary = FALSE, label = NULL, sort_order = (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM wallets WHERE user_id = ?) WHERE id = ?",
         )
         .bind(auth_user.user_id)
         .bind(Utc::now())
         .bind(&network)
+        .bind(auth_user.user_id)
         .bind(&w.id)
         .execute(&state.db)
         .await
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+        ensure_primary_wallets(&state.db, auth_user.user_id)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
         // Audit log for re-linking
         let _ = log_audit(
             &state.db,
@@ -204,25 +287,32 @@ pub async fn link_verify(
             &format!("Re-linked wallet {}", address_str),
         )
         .await;
+        crate::sanctions::screen_wallet(&state.db, auth_user.user_id, &address_str).await;
 
         return Ok(Json(
             serde_json::json!({ "message": "Wallet re-linked successfully" }),
         ));
     }
 
-    // Link new wallet
+    // Link new wallet at the end of the user's order; it becomes primary if it's the
+    // first on its network
     let _ = sqlx::query(
-        "INSERT INTO wallets (id, user_id, address, verified_at, network) VALUES (?, ?, ?, ?, ?)",
+        "INSERT INTO wallets (id, user_id, address, verified_at, network, sort_order) VALUES (?, ?, ?, ?, ?, (SELECT COALESCE(MAX(sort_order) + 1, 0) FROM wallets WHERE user_id = ?))",
     )
     .bind(Uuid::new_v4().to_string())
     .bind(auth_user.user_id)
     .bind(&address_str)
     .bind(Utc::now())
     .bind(&network)
+    .bind(auth_user.user_id)
     .execute(&state.db)
     .await
     .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
 
+    ensure_primary_wallets(&state.db, auth_user.user_id)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
     // Audit log
     let _ = log_audit(
         &state.db,
@@ -232,12 +322,256 @@ pub async fn link_verify(
         &format!("Linked wallet {}", address_str),
     )
     .await;
+    crate::sanctions::screen_wallet(&state.db, auth_user.user_id, &address_str).await;
 
     Ok(Json(
         serde_json::json!({ "message": "Wallet linked successfully" }),
     ))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/wallets/{id}/reverify",
+    params(
+        ("id" = String, Path, description = "Wallet ID")
+    ),
+    request_body = ReverifyRequest,
+    responses(
+        (status = 200, description = "Wallet re-verified"),
+        (status = 400, description = "Invalid signature or nonce"),
+        (status = 404, description = "Wallet not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn reverify_wallet(
+    Path(wallet_id): Path<String>,
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Json(payload): Json<ReverifyRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    // The challenge is a regular link nonce, requested for the wallet's address
+    let wallet: FlatLinkedWallet = sqlx::query_as(
+        "SELECT *, NULL as tribe FROM wallets WHERE id = ? AND user_id = ? AND deleted_at IS NULL",
+    )
+    .bind(&wallet_id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or((
+        StatusCode::NOT_FOUND,
+        "Wallet not found or not owned by user".to_string(),
+    ))?;
+
+    let stored_nonce = take_nonce(&state, &wallet.address)?;
+    verify_personal_message(&wallet.address, &payload.signature, &stored_nonce)?;
+
+    sqlx::query("UPDATE wallets SET verified_at = ?, stale_at = NULL WHERE id = ?")
+        .bind(Utc::now())
+        .bind(&wallet.id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::WalletReverify,
+        auth_user.user_id,
+        None,
+        &format!(
+            "Re-verified wallet {}{}",
+            wallet.address,
+            if wallet.stale_at.is_some() {
+                " (was stale)"
+            } else {
+                ""
+            }
+        ),
+    )
+    .await;
+
+    Ok(Json(
+        serde_json::json!({ "message": "Wallet re-verified successfully" }),
+    ))
+}
+
+#[utoipa::path(
+    patch,
+    path = "/api/wallets/{id}",
+    params(
+        ("id" = String, Path, description = "Wallet ID")
+    ),
+    request_body = UpdateWalletRequest,
+    responses(
+        (status = 200, description = "Wallet updated"),
+        (status = 400, description = "Invalid label or primary flag"),
+        (status = 404, description = "Wallet not found")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn update_wallet(
+    Path(wallet_id): Path<String>,
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Json(payload): Json<UpdateWalletRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let label = payload.label.map(|l| l.trim().to_string());
+    if label
+        .as_ref()
+        .is_some_and(|l| l.chars().count() > MAX_LABEL_LENGTH)
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!(
+                "Label exceeds maximum length ({} characters)",
+                MAX_LABEL_LENGTH
+            ),
+        ));
+    }
+    if payload.is_primary == Some(false) {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Mark another wallet as primary instead".into(),
+        ));
+    }
+
+    let wallet: FlatLinkedWallet = sqlx::query_as(
+        "SELECT *, NULL as tribe FROM wallets WHERE id = ? AND user_id = ? AND deleted_at IS NULL",
+    )
+    .bind(&wallet_id)
+    .bind(auth_user.user_id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+    .ok_or((
+        StatusCode::NOT_FOUND,
+        "Wallet not found or not owned by user".to_string(),
+    ))?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    let mut changes = Vec::new();
+
+    if let Some(label) = &label {
+        sqlx::query("UPDATE wallets SET label = ? WHERE id = ?")
+            .bind(if label.is_empty() { None } else { Some(label) })
+            .bind(&wallet.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        changes.push(format!("label '{}'", label));
+    }
+
+    if payload.is_primary == Some(true) && !wallet.is_primary {
+        // Demote the current primary first so the one-per-network index holds
+        sqlx::query(
+            "UPDATE wallets SET is_primary = FALSE WHERE user_id = ? AND network = ? AND is_primary",
+        )
+        .bind(auth_user.user_id)
+        .bind(&wallet.network)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+        sqlx::query("UPDATE wallets SET is_primary = TRUE WHERE id = ?")
+            .bind(&wallet.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+        changes.push(format!("primary on {}", wallet.network));
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    if !changes.is_empty() {
+        let _ = log_audit(
+            &state.db,
+            AuditAction::WalletUpdate,
+            auth_user.user_id,
+            None,
+            &format!("Updated wallet {}: {}", wallet.address, changes.join(", ")),
+        )
+        .await;
+    }
+
+    Ok(Json(serde_json::json!({ "message": "Wallet updated" })))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/wallets/order",
+    request_body = ReorderWalletsRequest,
+    responses(
+        (status = 200, description = "Wallets reordered"),
+        (status = 400, description = "List doesn't match the user's active wallets")
+    ),
+    security(
+        ("jwt" = [])
+    )
+)]
+pub async fn reorder_wallets(
+    State(state): State<AppState>,
+    auth_user: AuthenticatedUser,
+    Json(payload): Json<ReorderWalletsRequest>,
+) -> Result<impl IntoResponse, (StatusCode, String)> {
+    let active: Vec<String> =
+        sqlx::query_scalar("SELECT id FROM wallets WHERE user_id = ? AND deleted_at IS NULL")
+            .bind(auth_user.user_id)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let requested: HashSet<&String> = payload.wallet_ids.iter().collect();
+    if requested.len() != payload.wallet_ids.len()
+        || requested != active.iter().collect::<HashSet<_>>()
+    {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Wallet order must list each active wallet exactly once".into(),
+        ));
+    }
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    for (position, id) in payload.wallet_ids.iter().enumerate() {
+        sqlx::query("UPDATE wallets SET sort_order = ? WHERE id = ? AND user_id = ?")
+            .bind(position as i64)
+            .bind(id)
+            .bind(auth_user.user_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    }
+    tx.commit()
+        .await
+        .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+
+    let _ = log_audit(
+        &state.db,
+        AuditAction::WalletUpdate,
+        auth_user.user_id,
+        None,
+        &format!("Reordered {} wallets", payload.wallet_ids.len()),
+    )
+    .await;
+
+    Ok(Json(
+        serde_json::json!({ "message": "Wallets reordered successfully" }),
+    ))
+}
+
 #[utoipa::path(
     delete,
     path = "/api/wallets/{id}",
@@ -284,7 +618,7 @@ pub async fn unlink_wallet(
         .execute(&state.db)
         .await;
 
-    let result = sqlx::query("UPDATE wallets SET deleted_at = CURRENT_TIMESTAMP WHERE id = ? AND user_id = ? AND deleted_at IS NULL")
+    let result = sqlx::query("UPDATE wallets SET deleted_at = CURRENT_TIMESTAMP, is_primary = FALSE WHERE id = ? AND user_id = ? AND deleted_at IS NULL")
         .bind(&wallet_id)
         .bind(auth_user.user_id)
         .execute(&state.db)
@@ -292,6 +626,11 @@ pub async fn unlink_wallet(
 
     match result {
         Ok(r) if r.rows_affected() > 0 => {
+            // Hand the primary flag to the next wallet on the same network
+            if let Err(e) = ensure_primary_wallets(&state.db, auth_user.user_id).await {
+                eprintln!("Failed to promote primary wallet after unlink: {}", e);
+            }
+
             // Audit log
             let _ = log_audit(
                 &state.db,
@@ -544,4 +883,122 @@ mod tests {
 
         assert!(wallet.is_none());
     }
+
+    async fn insert_wallet(db: &crate::db::DbPool, id: &str, network: &str, sort_order: i64) {
+        sqlx::query("INSERT INTO wallets (id, user_id, address, verified_at, network, sort_order) VALUES (?, ?, ?, ?, ?, ?)")
+            .bind(id)
+            .bind(1001_i64)
+            .bind(format!("0x{}", id))
+            .bind(Utc::now())
+            .bind(network)
+            .bind(sort_order)
+            .execute(db)
+            .await
+            .unwrap();
+    }
+
+    async fn primary_ids(db: &crate::db::DbPool) -> Vec<String> {
+        sqlx::query_scalar(
+            "SELECT id FROM wallets WHERE is_primary AND deleted_at IS NULL ORDER BY id",
+        )
+        .fetch_all(db)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_ensure_primary_wallets_per_network() {
+        let db = setup_db().await;
+        insert_wallet(&db, "main-b", "mainnet", 1).await;
+        insert_wallet(&db, "main-a", "mainnet", 0).await;
+        insert_wallet(&db, "test-a", "testnet", 2).await;
+
+        ensure_primary_wallets(&db, 1001).await.unwrap();
+        assert_eq!(primary_ids(&db).await, vec!["main-a", "test-a"]);
+        assert_eq!(
+            primary_wallet_id(&db, 1001).await.unwrap(),
+            Some("main-a".to_string())
+        );
+
+        // Losing the primary hands the flag to the next wallet on that network
+        sqlx::query("UPDATE wallets SET deleted_at = CURRENT_TIMESTAMP, is_primary = FALSE WHERE id = 'main-a'")
+            .execute(&db)
+            .await
+            .unwrap();
+        ensure_primary_wallets(&db, 1001).await.unwrap();
+        assert_eq!(primary_ids(&db).await, vec!["main-b", "test-a"]);
+    }
+
+    #[tokio::test]
+    async fn test_update_wallet_primary_and_label() {
+        let db = setup_db().await;
+        insert_wallet(&db, "w1", "mainnet", 0).await;
+        insert_wallet(&db, "w2", "mainnet", 1).await;
+        ensure_primary_wallets(&db, 1001).await.unwrap();
+        let state = AppState::new(db.clone());
+
+        let res = update_wallet(
+            Path("w2".to_string()),
+            State(state.clone()),
+            AuthenticatedUser { user_id: 1001 },
+            Json(UpdateWalletRequest {
+                label: Some("  Main  ".to_string()),
+                is_primary: Some(true),
+            }),
+        )
+        .await;
+        assert!(res.is_ok());
+        assert_eq!(primary_ids(&db).await, vec!["w2"]);
+        let label: Option<String> = sqlx::query_scalar("SELECT label FROM wallets WHERE id = 'w2'")
+            .fetch_one(&db)
+            .await
+            .unwrap();
+        assert_eq!(label, Some("Main".to_string()));
+
+        // Primary can only move, not be switched off
+        let res = update_wallet(
+            Path("w2".to_string()),
+            State(state),
+            AuthenticatedUser { user_id: 1001 },
+            Json(UpdateWalletRequest {
+                label: None,
+                is_primary: Some(false),
+            }),
+        )
+        .await;
+        assert_eq!(res.err().unwrap().0, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_reorder_wallets() {
+        let db = setup_db().await;
+        insert_wallet(&db, "w1", "mainnet", 0).await;
+        insert_wallet(&db, "w2", "mainnet", 1).await;
+        let state = AppState::new(db.clone());
+
+        let partial = reorder_wallets(
+            State(state.clone()),
+            AuthenticatedUser { user_id: 1001 },
+            Json(ReorderWalletsRequest {
+                wallet_ids: vec!["w2".to_string()],
+            }),
+        )
+        .await;
+        assert_eq!(partial.err().unwrap().0, StatusCode::BAD_REQUEST);
+
+        let res = reorder_wallets(
+            State(state),
+            AuthenticatedUser { user_id: 1001 },
+            Json(ReorderWalletsRequest {
+                wallet_ids: vec!["w2".to_string(), "w1".to_string()],
+            }),
+        )
+        .await;
+        assert!(res.is_ok());
+        let order: Vec<String> = sqlx::query_scalar("SELECT id FROM wallets ORDER BY sort_order")
+            .fetch_all(&db)
+            .await
+            .unwrap();
+        assert_eq!(order, vec!["w2", "w1"]);
+    }
 }
@@ -90,8 +90,9 @@ class ServerAuthenticatorI(Murmur.ServerAuthenticator):
                 data = response.json()
                 user_id = data.get("user_id", -1)
                 new_name = data.get("username", name)
+                groups = data.get("groups", [])
                 logger.info(f"Authentication successful for {name} (ID: {user_id})")
-                return user_id, new_name, [] # ID, Name, Groups
+                return user_id, new_name, groups # ID, Name, Groups
             else:
                 logger.warning(f"Authentication failed for {name}: Backend returned {response.status_code}")
                 return -1, None, []